@@ -28,6 +28,9 @@ pub fn get_allowed_child_refs(obj_type: ObjectType, version: VtVersion) -> Vec<O
         ObjectType::WindowMask => WindowMask::get_allowed_child_refs(version),
         ObjectType::KeyGroup => KeyGroup::get_allowed_child_refs(version),
         ObjectType::Animation => Animation::get_allowed_child_refs(version),
+        ObjectType::ExternalObjectDefinition => {
+            ExternalObjectDefinition::get_allowed_child_refs(version)
+        }
         ObjectType::ObjectLabelReferenceList => {
             ObjectLabelReferenceList::get_allowed_child_refs(version)
         }
@@ -383,6 +386,36 @@ impl AllowedChildRefs for Animation {
     }
 }
 
+impl AllowedChildRefs for ExternalObjectDefinition {
+    fn get_allowed_child_refs(version: VtVersion) -> Vec<ObjectType> {
+        let mut allowed_objects = vec![];
+        if version >= VtVersion::Version5 {
+            allowed_objects.extend_from_slice(&[
+                ObjectType::Container,
+                ObjectType::OutputString,
+                ObjectType::OutputNumber,
+                ObjectType::OutputList,
+                ObjectType::OutputLine,
+                ObjectType::OutputRectangle,
+                ObjectType::OutputEllipse,
+                ObjectType::OutputPolygon,
+                ObjectType::OutputMeter,
+                ObjectType::OutputLinearBarGraph,
+                ObjectType::OutputArchedBarGraph,
+                ObjectType::GraphicsContext,
+                ObjectType::PictureGraphic,
+                ObjectType::ObjectPointer,
+                ObjectType::Animation,
+            ]);
+        }
+        if version >= VtVersion::Version6 {
+            allowed_objects.push(ObjectType::ScaledGraphic);
+        }
+
+        allowed_objects
+    }
+}
+
 impl AllowedChildRefs for ObjectLabelReferenceList {
     fn get_allowed_child_refs(version: VtVersion) -> Vec<ObjectType> {
         let mut allowed_objects = vec![];
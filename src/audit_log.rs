@@ -0,0 +1,186 @@
+//! Copyright 2024 - The Open-Agriculture Developers
+//! SPDX-License-Identifier: GPL-3.0-or-later
+//! Authors: Daan Steenbergen
+
+use crate::pool_diff::AttributeChange;
+use serde::Serialize;
+use web_time::{SystemTime, UNIX_EPOCH};
+
+/// Most entries kept, the oldest entries are dropped first
+const MAX_ENTRIES: usize = 10_000;
+
+/// Changes of the same object made within this time of each other are merged into one entry,
+/// e.g. the edits made every frame while a slider is dragged
+const MERGE_WINDOW_MS: u64 = 2_000;
+
+/// A single change made to the project during the session
+#[derive(Serialize, Clone)]
+pub struct AuditEntry {
+    /// Milliseconds since the unix epoch at which the change was made
+    pub timestamp_ms: u64,
+    /// Kind of change, e.g. the name of the pool command
+    pub command: String,
+    pub summary: String,
+    /// ID of the changed object, if the change is about a single object
+    pub object_id: Option<u16>,
+    pub old_value: Option<String>,
+    pub new_value: Option<String>,
+    /// The changed attributes, if the change is an edit of an object
+    pub attributes: Vec<AttributeChange>,
+}
+
+impl AuditEntry {
+    /// The old value, or the old values of the changed attributes
+    fn old_text(&self) -> String {
+        match &self.old_value {
+            Some(value) => value.clone(),
+            None => attributes_text(&self.attributes, |change| &change.old),
+        }
+    }
+
+    /// The new value, or the new values of the changed attributes
+    fn new_text(&self) -> String {
+        match &self.new_value {
+            Some(value) => value.clone(),
+            None => attributes_text(&self.attributes, |change| &change.new),
+        }
+    }
+}
+
+/// Log of the changes made to the project during a session, for change traceability.
+/// Only the latest changes are kept to bound its size.
+#[derive(Default, Clone)]
+pub struct AuditLog {
+    entries: Vec<AuditEntry>,
+}
+
+impl AuditLog {
+    pub(crate) fn record(
+        &mut self,
+        command: &str,
+        summary: String,
+        object_id: Option<u16>,
+        old_value: Option<String>,
+        new_value: Option<String>,
+    ) {
+        self.push(AuditEntry {
+            timestamp_ms: now_ms(),
+            command: command.to_string(),
+            summary,
+            object_id,
+            old_value,
+            new_value,
+            attributes: Vec::new(),
+        });
+    }
+
+    /// Record the changed attributes of an object. Consecutive changes of the same object are
+    /// merged into one entry keeping the first old and the last new value of every attribute.
+    pub(crate) fn record_attributes(
+        &mut self,
+        command: &str,
+        summary: String,
+        object_id: u16,
+        attributes: Vec<AttributeChange>,
+    ) {
+        if attributes.is_empty() {
+            return;
+        }
+        let timestamp_ms = now_ms();
+        if let Some(last) = self.entries.last_mut().filter(|last| {
+            last.command == command
+                && last.object_id == Some(object_id)
+                && !last.attributes.is_empty()
+                && timestamp_ms.saturating_sub(last.timestamp_ms) <= MERGE_WINDOW_MS
+        }) {
+            for change in attributes {
+                match last
+                    .attributes
+                    .iter_mut()
+                    .find(|merged| merged.attribute == change.attribute)
+                {
+                    Some(merged) => merged.new = change.new,
+                    None => last.attributes.push(change),
+                }
+            }
+            last.attributes.retain(|merged| merged.old != merged.new);
+            if last.attributes.is_empty() {
+                self.entries.pop();
+            } else {
+                last.timestamp_ms = timestamp_ms;
+                last.summary = summary;
+            }
+            return;
+        }
+        self.push(AuditEntry {
+            timestamp_ms,
+            command: command.to_string(),
+            summary,
+            object_id: Some(object_id),
+            old_value: None,
+            new_value: None,
+            attributes,
+        });
+    }
+
+    fn push(&mut self, entry: AuditEntry) {
+        self.entries.push(entry);
+        if self.entries.len() > MAX_ENTRIES {
+            self.entries.drain(..self.entries.len() - MAX_ENTRIES);
+        }
+    }
+
+    /// The changes in the order they were made
+    pub fn entries(&self) -> &[AuditEntry] {
+        &self.entries
+    }
+
+    pub fn to_json(&self) -> Result<Vec<u8>, serde_json::Error> {
+        serde_json::to_vec_pretty(&self.entries)
+    }
+
+    /// Export as CSV with a header row, values are quoted where needed
+    pub fn to_csv(&self) -> Vec<u8> {
+        let mut csv = String::from("timestamp_ms,command,summary,object_id,old_value,new_value\n");
+        for entry in &self.entries {
+            let fields = [
+                entry.timestamp_ms.to_string(),
+                entry.command.clone(),
+                entry.summary.clone(),
+                entry.object_id.map(|id| id.to_string()).unwrap_or_default(),
+                entry.old_text(),
+                entry.new_text(),
+            ];
+            let line: Vec<String> = fields.iter().map(|field| csv_field(field)).collect();
+            csv.push_str(&line.join(","));
+            csv.push('\n');
+        }
+        csv.into_bytes()
+    }
+}
+
+fn now_ms() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map_or(0, |time| time.as_millis() as u64)
+}
+
+/// The attributes with one of their values, e.g. "width: 100; height: 50"
+fn attributes_text(
+    attributes: &[AttributeChange],
+    value: impl Fn(&AttributeChange) -> &String,
+) -> String {
+    attributes
+        .iter()
+        .map(|change| format!("{}: {}", change.attribute, value(change)))
+        .collect::<Vec<String>>()
+        .join("; ")
+}
+
+fn csv_field(value: &str) -> String {
+    if value.contains([',', '"', '\n', '\r']) {
+        format!("\"{}\"", value.replace('"', "\"\""))
+    } else {
+        value.to_string()
+    }
+}
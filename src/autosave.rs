@@ -0,0 +1,406 @@
+//! Copyright 2024 - The Open-Agriculture Developers
+//! SPDX-License-Identifier: GPL-3.0-or-later
+//! Authors: Daan Steenbergen
+
+use crate::settings::AutosaveSettings;
+use crate::EditorProject;
+use serde::{Deserialize, Serialize};
+use std::path::PathBuf;
+use std::time::Duration;
+use uuid::Uuid;
+use web_time::{Instant, SystemTime, UNIX_EPOCH};
+
+/// Most autosaves looked for when removing a rotation, well above the largest rotation size
+const MAX_ROTATION_SIZE: usize = 100;
+
+/// Information stored alongside the autosaved project
+#[derive(Serialize, Deserialize, Clone)]
+pub struct AutosaveMetadata {
+    /// File the project was loaded from or last saved to, `None` for a project never saved
+    pub source_path: Option<String>,
+
+    /// Seconds since the unix epoch at which the autosave was written
+    pub saved_at: u64,
+}
+
+impl AutosaveMetadata {
+    /// Describe how long ago the autosave was written, e.g. "5 minutes ago"
+    pub fn age(&self) -> String {
+        describe_age(self.saved_at)
+    }
+}
+
+/// Describe how long ago a moment given in seconds since the unix epoch was, e.g. "5 minutes ago"
+pub fn describe_age(unix_seconds: u64) -> String {
+    let seconds = unix_time().saturating_sub(unix_seconds);
+    match seconds {
+        0..=59 => "less than a minute ago".to_string(),
+        60..=3599 => format!("{} minutes ago", seconds / 60),
+        3600..=86399 => format!("{} hours ago", seconds / 3600),
+        _ => format!("{} days ago", seconds / 86400),
+    }
+}
+
+/// An autosaved project, the project data is in the format of a project file (.aitp)
+#[derive(Serialize, Deserialize, Clone)]
+pub struct Autosave {
+    pub metadata: AutosaveMetadata,
+    pub project: String,
+    /// Name the autosave is stored under
+    #[serde(skip)]
+    file_name: String,
+}
+
+pub(crate) fn unix_time() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map_or(0, |time| time.as_secs())
+}
+
+/// Name of the autosave file of a project.
+/// Projects with a source file get a name derived from its path, so reopening the project finds
+/// its autosave; other projects get a name unique to this session.
+fn autosave_file_name(source_path: Option<&str>, session: &Uuid) -> String {
+    match source_path {
+        Some(path) => {
+            // FNV-1a, stable between runs unlike the hasher of the standard library
+            let hash = path.bytes().fold(0xcbf29ce484222325u64, |hash, byte| {
+                (hash ^ byte as u64).wrapping_mul(0x100000001b3)
+            });
+            format!("{:016x}.autosave.json", hash)
+        }
+        None => format!("untitled-{}.autosave.json", session),
+    }
+}
+
+/// Name of an older autosave kept by the rotation, age 0 being the latest autosave
+fn rotated_file_name(file_name: &str, age: usize) -> String {
+    match age {
+        0 => file_name.to_string(),
+        _ => file_name.replace(".autosave.json", &format!(".autosave.{}.json", age)),
+    }
+}
+
+/// Default directory holding the autosaves, in the application data directory or else the temp
+/// directory
+#[cfg(not(target_arch = "wasm32"))]
+pub fn default_autosave_dir() -> PathBuf {
+    crate::settings::app_data_dir()
+        .unwrap_or_else(|| std::env::temp_dir().join("AgIsoTerminalDesigner"))
+        .join("autosave")
+}
+
+/// Autosaves are not stored in a directory on the web
+#[cfg(target_arch = "wasm32")]
+pub fn default_autosave_dir() -> PathBuf {
+    PathBuf::new()
+}
+
+/// Autosaves are files in the autosave directory on native targets
+#[cfg(not(target_arch = "wasm32"))]
+mod storage {
+    use super::rotated_file_name;
+    use std::path::{Path, PathBuf};
+
+    /// Write the autosave, moving the earlier autosaves up in a rotation of `keep` autosaves
+    pub fn write(dir: PathBuf, file_name: String, data: String, keep: usize) {
+        // Written on a separate thread so large projects don't stall the UI
+        std::thread::spawn(move || {
+            let path = dir.join(&file_name);
+            let temp_path = path.with_extension("tmp");
+            let result = std::fs::create_dir_all(&dir)
+                // Write next to the autosave first, so a crash never leaves a partial file
+                .and_then(|_| std::fs::write(&temp_path, data))
+                .and_then(|_| {
+                    for age in (1..keep).rev() {
+                        let older = dir.join(rotated_file_name(&file_name, age - 1));
+                        if older.exists() {
+                            std::fs::rename(older, dir.join(rotated_file_name(&file_name, age)))?;
+                        }
+                    }
+                    std::fs::rename(&temp_path, &path)
+                });
+            match result {
+                Ok(()) => log::info!("Autosaved project to {}", path.display()),
+                Err(e) => log::error!("Failed to autosave project: {}", e),
+            }
+        });
+    }
+
+    pub fn read(dir: &Path, file_name: &str) -> Option<String> {
+        let path = dir.join(file_name);
+        match std::fs::read_to_string(&path) {
+            Ok(data) => Some(data),
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => None,
+            Err(e) => {
+                log::error!("Failed to read autosave {}: {}", path.display(), e);
+                None
+            }
+        }
+    }
+
+    /// Remove the autosave, returns whether it existed
+    pub fn remove(dir: &Path, file_name: &str) -> Result<bool, String> {
+        let path = dir.join(file_name);
+        match std::fs::remove_file(&path) {
+            Ok(()) => Ok(true),
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(false),
+            Err(e) => Err(format!(
+                "Failed to remove autosave {}: {}",
+                path.display(),
+                e
+            )),
+        }
+    }
+
+    pub fn list(dir: &Path) -> Vec<String> {
+        let Ok(entries) = std::fs::read_dir(dir) else {
+            return Vec::new();
+        };
+        entries
+            .filter_map(|entry| entry.ok()?.file_name().into_string().ok())
+            .collect()
+    }
+
+    /// Seconds since the unix epoch at which the file was last written
+    pub fn modified_at(path: &str) -> Option<u64> {
+        let modified = std::fs::metadata(path).ok()?.modified().ok()?;
+        Some(
+            modified
+                .duration_since(std::time::UNIX_EPOCH)
+                .ok()?
+                .as_secs(),
+        )
+    }
+}
+
+/// Autosaves are kept in the local storage of the browser on the web, the directory is ignored
+#[cfg(target_arch = "wasm32")]
+mod storage {
+    use super::rotated_file_name;
+    use std::path::{Path, PathBuf};
+
+    const KEY_PREFIX: &str = "autosave/";
+
+    fn local_storage() -> Option<web_sys::Storage> {
+        web_sys::window()?.local_storage().ok()?
+    }
+
+    fn key(file_name: &str) -> String {
+        format!("{}{}", KEY_PREFIX, file_name)
+    }
+
+    /// Write the autosave, moving the earlier autosaves up in a rotation of `keep` autosaves
+    pub fn write(_dir: PathBuf, file_name: String, data: String, keep: usize) {
+        let result = local_storage().map(|storage| {
+            for age in (1..keep).rev() {
+                if let Ok(Some(older)) =
+                    storage.get_item(&key(&rotated_file_name(&file_name, age - 1)))
+                {
+                    storage.set_item(&key(&rotated_file_name(&file_name, age)), &older)?;
+                }
+            }
+            storage.set_item(&key(&file_name), &data)
+        });
+        match result {
+            Some(Ok(())) => log::info!("Autosaved project to local storage"),
+            Some(Err(e)) => log::error!("Failed to autosave project: {:?}", e),
+            None => log::error!("Failed to autosave project: no local storage"),
+        }
+    }
+
+    pub fn read(_dir: &Path, file_name: &str) -> Option<String> {
+        local_storage()?.get_item(&key(file_name)).ok()?
+    }
+
+    /// Remove the autosave, returns whether it existed
+    pub fn remove(_dir: &Path, file_name: &str) -> Result<bool, String> {
+        let Some(storage) = local_storage() else {
+            return Ok(false);
+        };
+        let existed = matches!(storage.get_item(&key(file_name)), Ok(Some(_)));
+        storage
+            .remove_item(&key(file_name))
+            .map_err(|e| format!("Failed to remove autosave {}: {:?}", file_name, e))?;
+        Ok(existed)
+    }
+
+    pub fn list(_dir: &Path) -> Vec<String> {
+        let Some(storage) = local_storage() else {
+            return Vec::new();
+        };
+        let length = storage.length().unwrap_or(0);
+        (0..length)
+            .filter_map(|idx| storage.key(idx).ok()?)
+            .filter_map(|key| key.strip_prefix(KEY_PREFIX).map(str::to_string))
+            .collect()
+    }
+
+    /// Files can't be inspected on the web
+    pub fn modified_at(_path: &str) -> Option<u64> {
+        None
+    }
+}
+
+/// Periodically writes the open project to its own autosave file
+pub struct Autosaver {
+    /// Identifies the autosaves of projects without a source file made by this session
+    session: Uuid,
+    last_autosave: Instant,
+    /// Generation of the pool that was autosaved last
+    autosaved_generation: Option<u64>,
+    /// Time between autosaves of a modified project
+    interval: Duration,
+    directory: PathBuf,
+    /// Number of autosaves kept per project, older ones are overwritten
+    keep: usize,
+}
+
+impl Default for Autosaver {
+    fn default() -> Self {
+        let settings = AutosaveSettings::default();
+        let mut autosaver = Autosaver {
+            session: Uuid::new_v4(),
+            last_autosave: Instant::now(),
+            autosaved_generation: None,
+            interval: Duration::ZERO,
+            directory: PathBuf::new(),
+            keep: 1,
+        };
+        autosaver.configure(&settings);
+        autosaver
+    }
+}
+
+impl Autosaver {
+    /// Take over the interval, location and rotation from the autosave settings
+    pub fn configure(&mut self, settings: &AutosaveSettings) {
+        self.interval = Duration::from_secs(settings.interval_secs.max(1) as u64);
+        self.directory = settings
+            .directory
+            .as_ref()
+            .map_or_else(default_autosave_dir, PathBuf::from);
+        self.keep = settings.keep.max(1) as usize;
+    }
+
+    /// Autosave the project if it has unsaved changes that are not autosaved yet and the
+    /// autosave interval has passed.
+    /// Returns the time until the pending changes will be autosaved, if there are any.
+    pub fn update(
+        &mut self,
+        project: &EditorProject,
+        source_path: Option<&str>,
+    ) -> Option<Duration> {
+        if !project.is_modified()
+            || self.autosaved_generation == Some(project.get_pool_generation())
+        {
+            return None;
+        }
+        let elapsed = self.last_autosave.elapsed();
+        if elapsed < self.interval {
+            return Some(self.interval - elapsed);
+        }
+        self.last_autosave = Instant::now();
+        self.autosaved_generation = Some(project.get_pool_generation());
+
+        let project_data = match project.to_project_bytes() {
+            Ok(data) => String::from_utf8_lossy(&data).into_owned(),
+            Err(e) => {
+                log::error!("Failed to serialize project for autosave: {}", e);
+                return None;
+            }
+        };
+        let autosave = Autosave {
+            metadata: AutosaveMetadata {
+                source_path: source_path.map(str::to_string),
+                saved_at: unix_time(),
+            },
+            project: project_data,
+            file_name: String::new(),
+        };
+        self.write(autosave_file_name(source_path, &self.session), autosave);
+        None
+    }
+
+    /// Forget the autosave state, to be called when another project is opened
+    pub fn reset(&mut self) {
+        self.last_autosave = Instant::now();
+        self.autosaved_generation = None;
+    }
+
+    fn write(&self, file_name: String, autosave: Autosave) {
+        match serde_json::to_string(&autosave) {
+            Ok(data) => storage::write(self.directory.clone(), file_name, data, self.keep),
+            Err(e) => log::error!("Failed to autosave project: {}", e),
+        }
+    }
+
+    fn read(&self, file_name: &str) -> Option<Autosave> {
+        let data = storage::read(&self.directory, file_name)?;
+        match serde_json::from_str::<Autosave>(&data) {
+            Ok(mut autosave) => {
+                autosave.file_name = file_name.to_string();
+                Some(autosave)
+            }
+            Err(e) => {
+                log::error!("Failed to parse autosave {}: {}", file_name, e);
+                None
+            }
+        }
+    }
+
+    /// Find the autosave of the project stored at the given path
+    pub fn find(&self, source_path: &str) -> Option<Autosave> {
+        self.read(&autosave_file_name(Some(source_path), &self.session))
+            // A different path with the same hash is not the same project
+            .filter(|autosave| autosave.metadata.source_path.as_deref() == Some(source_path))
+    }
+
+    /// Find the most recent autosave with work that was never saved explicitly, left behind by
+    /// an earlier session that didn't end normally
+    pub fn find_recoverable(&self) -> Option<Autosave> {
+        storage::list(&self.directory)
+            .iter()
+            // Only the latest autosave of a project, not the earlier ones of the rotation
+            .filter(|file_name| {
+                file_name
+                    .strip_suffix(".autosave.json")
+                    .is_some_and(|stem| !stem.contains(".autosave."))
+            })
+            .filter_map(|file_name| self.read(file_name))
+            .filter(|autosave| match &autosave.metadata.source_path {
+                // Saving the project after the autosave made the autosave obsolete
+                Some(path) => storage::modified_at(path)
+                    .is_none_or(|modified_at| modified_at < autosave.metadata.saved_at),
+                None => true,
+            })
+            .max_by_key(|autosave| autosave.metadata.saved_at)
+    }
+
+    /// Remove the autosaves of a project, e.g. after it is saved or the autosave is discarded
+    pub fn discard(&self, source_path: Option<&str>) {
+        self.remove_rotation(&autosave_file_name(source_path, &self.session));
+    }
+
+    /// Remove an autosave that was found earlier, also if it was made by another session
+    pub fn discard_autosave(&self, autosave: &Autosave) {
+        self.remove_rotation(&autosave.file_name);
+    }
+
+    /// Remove the latest autosave and the earlier ones of its rotation
+    fn remove_rotation(&self, file_name: &str) {
+        // Continue past the rotation size while autosaves exist, the rotation may have been
+        // larger before
+        for age in 0..MAX_ROTATION_SIZE {
+            match storage::remove(&self.directory, &rotated_file_name(file_name, age)) {
+                Ok(existed) if existed || age < self.keep => {}
+                Ok(_) => break,
+                // The older autosaves are likely to fail as well
+                Err(e) => {
+                    log::error!("{}", e);
+                    break;
+                }
+            }
+        }
+    }
+}
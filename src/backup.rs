@@ -0,0 +1,92 @@
+//! Copyright 2024 - The Open-Agriculture Developers
+//! SPDX-License-Identifier: GPL-3.0-or-later
+//! Authors: Daan Steenbergen
+
+use crate::autosave::unix_time;
+
+/// Result of the backup made before a bulk operation, shown when the operation is done
+#[derive(Clone)]
+pub struct BackupNotice {
+    /// Description of the bulk operation, e.g. "Merge duplicate pictures"
+    pub operation: String,
+    /// Path (or storage key on the web) of the backup, or why it couldn't be made
+    pub location: Result<String, String>,
+}
+
+/// Date and time in UTC as "YYYYMMDD-HHMMSS", used to order backups by name
+fn timestamp(unix_seconds: u64) -> String {
+    let days = (unix_seconds / 86400) as i64;
+    let seconds = unix_seconds % 86400;
+
+    // Civil date from the days since the epoch, after Howard Hinnant's days_from_civil inverse
+    let z = days + 719468;
+    let era = z.div_euclid(146097);
+    let day_of_era = z.rem_euclid(146097);
+    let year_of_era =
+        (day_of_era - day_of_era / 1460 + day_of_era / 36524 - day_of_era / 146096) / 365;
+    let day_of_year = day_of_era - (365 * year_of_era + year_of_era / 4 - year_of_era / 100);
+    let mp = (5 * day_of_year + 2) / 153;
+    let day = day_of_year - (153 * mp + 2) / 5 + 1;
+    let month = if mp < 10 { mp + 3 } else { mp - 9 };
+    let year = year_of_era + era * 400 + if month <= 2 { 1 } else { 0 };
+
+    format!(
+        "{:04}{:02}{:02}-{:02}{:02}{:02}",
+        year,
+        month,
+        day,
+        seconds / 3600,
+        seconds / 60 % 60,
+        seconds % 60
+    )
+}
+
+/// Name of the backup file, the time of the backup and the operation it was made for
+fn backup_file_name(operation: &str) -> String {
+    let operation: String = operation
+        .chars()
+        .map(|c| {
+            if c.is_alphanumeric() {
+                c.to_ascii_lowercase()
+            } else {
+                '-'
+            }
+        })
+        .collect();
+    format!("{}-{}.aitp", timestamp(unix_time()), operation)
+}
+
+/// Store a copy of the project (in the project file format) made before a bulk operation.
+/// Returns where the backup is stored.
+pub fn write_backup(project: &[u8], operation: &str) -> Result<String, String> {
+    storage::write(&backup_file_name(operation), project)
+}
+
+/// Backups are files in the backups directory on native targets
+#[cfg(not(target_arch = "wasm32"))]
+mod storage {
+    pub fn write(file_name: &str, data: &[u8]) -> Result<String, String> {
+        let dir = crate::settings::app_data_dir()
+            .unwrap_or_else(|| std::env::temp_dir().join("AgIsoTerminalDesigner"))
+            .join("backups");
+        let path = dir.join(file_name);
+        std::fs::create_dir_all(&dir)
+            .and_then(|_| std::fs::write(&path, data))
+            .map_err(|e| e.to_string())?;
+        Ok(path.display().to_string())
+    }
+}
+
+/// Backups are kept in the local storage of the browser on the web
+#[cfg(target_arch = "wasm32")]
+mod storage {
+    pub fn write(file_name: &str, data: &[u8]) -> Result<String, String> {
+        let key = format!("backup/{}", file_name);
+        web_sys::window()
+            .and_then(|window| window.local_storage().ok()?)
+            .ok_or_else(|| "No local storage".to_string())?
+            .set_item(&key, &String::from_utf8_lossy(data))
+            .map_err(|e| format!("{:?}", e))?;
+        Ok(key)
+    }
+}
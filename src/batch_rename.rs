@@ -0,0 +1,67 @@
+//! Copyright 2024 - The Open-Agriculture Developers
+//! SPDX-License-Identifier: GPL-3.0-or-later
+//! Authors: Daan Steenbergen
+
+use crate::NameReplace;
+
+/// Renaming of several objects at once. The steps are applied in order: replacing text,
+/// the pattern, then the prefix and suffix.
+#[derive(Default, Clone)]
+pub struct BatchRename {
+    pub find: String,
+    pub replace: String,
+    /// New name with `{name}` for the current name and `{n}` for a sequential number, which is
+    /// padded with zeros by `{n:02}`. Empty to keep the name.
+    pub pattern: String,
+    /// Number of the first object
+    pub start: usize,
+    pub prefix: String,
+    pub suffix: String,
+}
+
+impl BatchRename {
+    /// The new name of the object at the given position in the renamed objects.
+    /// Returns an error if the pattern is invalid.
+    pub fn apply(&self, name: &str, position: usize) -> Result<String, String> {
+        let mut name = name.to_string();
+        if !self.find.is_empty() {
+            let replace = NameReplace::new(&self.find, &self.replace, false, true)?;
+            if let Some(replaced) = replace.apply(&name) {
+                name = replaced;
+            }
+        }
+        if !self.pattern.is_empty() {
+            name = expand_pattern(&self.pattern, &name, self.start + position)?;
+        }
+        Ok(format!("{}{}{}", self.prefix, name, self.suffix))
+    }
+}
+
+/// Replace the placeholders of the pattern by the name and number
+fn expand_pattern(pattern: &str, name: &str, number: usize) -> Result<String, String> {
+    let mut result = String::new();
+    let mut rest = pattern;
+    while let Some(open) = rest.find('{') {
+        result.push_str(&rest[..open]);
+        let close = rest[open..]
+            .find('}')
+            .map(|close| open + close)
+            .ok_or_else(|| format!("Missing '}}' after '{}'", &rest[open..]))?;
+        let placeholder = &rest[open + 1..close];
+        match placeholder.split_once(':') {
+            None if placeholder == "name" => result.push_str(name),
+            None if placeholder == "n" => result.push_str(&number.to_string()),
+            Some(("n", width)) => {
+                let width: usize = width
+                    .trim_start_matches('0')
+                    .parse()
+                    .map_err(|_| format!("Invalid number width '{}'", width))?;
+                result.push_str(&format!("{:0width$}", number, width = width));
+            }
+            _ => return Err(format!("Unknown placeholder '{{{}}}'", placeholder)),
+        }
+        rest = &rest[close + 1..];
+    }
+    result.push_str(rest);
+    Ok(result)
+}
@@ -0,0 +1,150 @@
+//! Copyright 2024 - The Open-Agriculture Developers
+//! SPDX-License-Identifier: GPL-3.0-or-later
+//! Authors: Daan Steenbergen
+
+use ag_iso_stack::object_pool::object::Object;
+use ag_iso_stack::object_pool::{ObjectId, ObjectPool};
+
+/// Macro command moving a child relative to where it is
+const CHANGE_CHILD_LOCATION: u8 = 0xA5;
+/// Macro command moving a child to a position in its parent
+const CHANGE_CHILD_POSITION: u8 = 0xB4;
+/// Offset of the relative change of Change Child Location, 127 means no change
+const LOCATION_CHANGE_OFFSET: i32 = 127;
+
+/// The macro commands moving a child of a mask or container at runtime
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum ChildMoveCommand {
+    /// Change Child Location, moving the child by at most -127 to +128 pixels
+    Location,
+    /// Change Child Position, placing the child at a position in its parent
+    Position,
+}
+
+impl ChildMoveCommand {
+    pub const ALL: [ChildMoveCommand; 2] = [ChildMoveCommand::Location, ChildMoveCommand::Position];
+
+    pub fn name(self) -> &'static str {
+        match self {
+            ChildMoveCommand::Location => "Change Child Location",
+            ChildMoveCommand::Position => "Change Child Position",
+        }
+    }
+}
+
+/// A child picked on the canvas and the position it is dragged to, relative to its parent
+#[derive(Clone, PartialEq, Debug)]
+pub struct ChildMoveTarget {
+    pub parent: ObjectId,
+    pub child: ObjectId,
+    /// Position of the parent on the mask, to draw the child where it is moved to
+    pub parent_position: (i16, i16),
+    /// Position of the child in its parent in the pool
+    pub from: (i16, i16),
+    /// Position of the child in its parent after the command
+    pub to: (i16, i16),
+}
+
+impl ChildMoveTarget {
+    /// The bytes of the macro command moving the child, or why the command can't move it there
+    pub fn command_bytes(&self, command: ChildMoveCommand) -> Result<Vec<u8>, String> {
+        let parent = self.parent.value().to_le_bytes();
+        let child = self.child.value().to_le_bytes();
+        match command {
+            ChildMoveCommand::Location => {
+                let change = |from: i16, to: i16| {
+                    u8::try_from(to as i32 - from as i32 + LOCATION_CHANGE_OFFSET).map_err(|_| {
+                        format!(
+                            "Moving {} pixels is more than Change Child Location can, use Change \
+                            Child Position instead",
+                            to as i32 - from as i32
+                        )
+                    })
+                };
+                Ok(vec![
+                    CHANGE_CHILD_LOCATION,
+                    parent[0],
+                    parent[1],
+                    child[0],
+                    child[1],
+                    change(self.from.0, self.to.0)?,
+                    change(self.from.1, self.to.1)?,
+                    0xFF,
+                ])
+            }
+            ChildMoveCommand::Position => {
+                let [x0, x1] = self.to.0.to_le_bytes();
+                let [y0, y1] = self.to.1.to_le_bytes();
+                Ok(vec![
+                    CHANGE_CHILD_POSITION,
+                    parent[0],
+                    parent[1],
+                    child[0],
+                    child[1],
+                    x0,
+                    x1,
+                    y0,
+                    y1,
+                ])
+            }
+        }
+    }
+}
+
+/// Building a command of a macro moving a child by picking it on the canvas
+#[derive(Clone, PartialEq, Debug)]
+pub struct ChildMovePick {
+    pub macro_id: ObjectId,
+    pub command: ChildMoveCommand,
+    /// `None` until the child is picked
+    pub target: Option<ChildMoveTarget>,
+}
+
+/// Find where the object is shown on the mask: its parent, the position of the parent on the
+/// mask and the position of the object in the parent. The first occurrence is used.
+pub fn find_child_move_target(
+    pool: &ObjectPool,
+    mask: &Object,
+    child: ObjectId,
+) -> Option<ChildMoveTarget> {
+    find_in(pool, mask, (0, 0), child, 0)
+}
+
+/// Masks and containers nest only a few levels deep, this guards against circular references
+const MAX_DEPTH: usize = 16;
+
+fn find_in(
+    pool: &ObjectPool,
+    parent: &Object,
+    parent_position: (i16, i16),
+    child: ObjectId,
+    depth: usize,
+) -> Option<ChildMoveTarget> {
+    let object_refs = match parent {
+        Object::DataMask(mask) => &mask.object_refs,
+        Object::AlarmMask(mask) => &mask.object_refs,
+        Object::Container(container) => &container.object_refs,
+        _ => return None,
+    };
+    if depth > MAX_DEPTH {
+        return None;
+    }
+    if let Some(object_ref) = object_refs.iter().find(|object_ref| object_ref.id == child) {
+        let position = (object_ref.offset.x, object_ref.offset.y);
+        return Some(ChildMoveTarget {
+            parent: parent.id(),
+            child,
+            parent_position,
+            from: position,
+            to: position,
+        });
+    }
+    object_refs.iter().find_map(|object_ref| {
+        let nested = pool.object_by_id(object_ref.id)?;
+        let position = (
+            parent_position.0 + object_ref.offset.x,
+            parent_position.1 + object_ref.offset.y,
+        );
+        find_in(pool, nested, position, child, depth + 1)
+    })
+}
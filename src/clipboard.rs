@@ -0,0 +1,113 @@
+//! Copyright 2024 - The Open-Agriculture Developers
+//! SPDX-License-Identifier: GPL-3.0-or-later
+//! Authors: Daan Steenbergen
+
+use ag_iso_stack::object_pool::object::Object;
+use ag_iso_stack::object_pool::ObjectPool;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+
+/// Marks clipboard text as objects copied by the designer
+const CLIPBOARD_FORMAT: &str = "ag-iso-terminal-designer/objects";
+const CLIPBOARD_VERSION: u32 = 1;
+
+/// Objects copied to the system clipboard, as JSON so they can be pasted into another running
+/// designer or inspected in a text editor
+#[derive(Serialize, Deserialize)]
+struct ClipboardContent {
+    format: String,
+    version: u32,
+    /// Readable summary of the copied objects, not used when pasting
+    objects: Vec<ClipboardObject>,
+    /// The copied objects in IOP format, hex encoded
+    iop: String,
+}
+
+#[derive(Serialize, Deserialize)]
+struct ClipboardObject {
+    id: u16,
+    object_type: String,
+    /// Custom name of the object in the designer
+    name: Option<String>,
+    /// IDs of the objects it references
+    references: Vec<u16>,
+}
+
+fn to_hex(data: &[u8]) -> String {
+    data.iter().map(|byte| format!("{:02x}", byte)).collect()
+}
+
+fn from_hex(text: &str) -> Result<Vec<u8>, String> {
+    if text.len() % 2 != 0 {
+        return Err("Invalid object data length".to_string());
+    }
+    // Bytes instead of string slices, a slice could split a multi-byte character
+    let digit = |byte: u8| char::from(byte).to_digit(16);
+    text.as_bytes()
+        .chunks(2)
+        .map(|pair| match pair {
+            [high, low] => digit(*high)
+                .zip(digit(*low))
+                .map(|(high, low)| (high * 16 + low) as u8)
+                .ok_or_else(|| "Invalid object data".to_string()),
+            _ => Err("Invalid object data length".to_string()),
+        })
+        .collect()
+}
+
+/// Encode objects with their custom names as clipboard text
+pub fn encode_objects(objects: &[(Object, Option<String>)]) -> String {
+    let mut pool = ObjectPool::default();
+    for (object, _) in objects {
+        pool.add(object.clone());
+    }
+    let content = ClipboardContent {
+        format: CLIPBOARD_FORMAT.to_string(),
+        version: CLIPBOARD_VERSION,
+        objects: objects
+            .iter()
+            .map(|(object, name)| ClipboardObject {
+                id: object.id().value(),
+                object_type: format!("{:?}", object.object_type()),
+                name: name.clone(),
+                references: object
+                    .referenced_objects()
+                    .iter()
+                    .map(|id| id.value())
+                    .collect(),
+            })
+            .collect(),
+        iop: to_hex(&pool.as_iop()),
+    };
+    serde_json::to_string_pretty(&content).unwrap_or_default()
+}
+
+/// Decode objects with their custom names from clipboard text.
+/// Returns an error if the text doesn't hold objects copied by the designer.
+pub fn decode_objects(text: &str) -> Result<Vec<(Object, Option<String>)>, String> {
+    let content: ClipboardContent = serde_json::from_str(text)
+        .map_err(|_| "The clipboard doesn't contain designer objects".to_string())?;
+    if content.format != CLIPBOARD_FORMAT {
+        return Err("The clipboard doesn't contain designer objects".to_string());
+    }
+    if content.version > CLIPBOARD_VERSION {
+        return Err(format!(
+            "The objects were copied by a newer designer (version {})",
+            content.version
+        ));
+    }
+    let names: HashMap<u16, Option<String>> = content
+        .objects
+        .into_iter()
+        .map(|object| (object.id, object.name))
+        .collect();
+    let pool = ObjectPool::from_iop(from_hex(&content.iop)?);
+    Ok(pool
+        .objects()
+        .iter()
+        .map(|object| {
+            let name = names.get(&object.id().value()).cloned().flatten();
+            (object.clone(), name)
+        })
+        .collect())
+}
@@ -0,0 +1,97 @@
+//! Copyright 2024 - The Open-Agriculture Developers
+//! SPDX-License-Identifier: GPL-3.0-or-later
+//! Authors: Daan Steenbergen
+
+use crate::EditorProject;
+use ag_iso_stack::object_pool::object::Object;
+use ag_iso_stack::object_pool::{Colour, ObjectId, ObjectPool};
+
+/// Lowest contrast ratio between text and its background for the text to be readable, as
+/// required by WCAG for normal text
+pub const MIN_CONTRAST_RATIO: f32 = 4.5;
+
+/// Deepest nesting followed when looking for the background behind an object
+const MAX_BACKGROUND_DEPTH: usize = 32;
+
+/// Relative luminance of a colour, from 0 for black to 1 for white
+fn relative_luminance(colour: &Colour) -> f32 {
+    let linear = |channel: u8| {
+        let value = channel as f32 / 255.0;
+        if value <= 0.03928 {
+            value / 12.92
+        } else {
+            ((value + 0.055) / 1.055).powf(2.4)
+        }
+    };
+    0.2126 * linear(colour.r) + 0.7152 * linear(colour.g) + 0.0722 * linear(colour.b)
+}
+
+/// Contrast ratio between two colours, from 1 for equal colours to 21 for black on white
+pub fn contrast_ratio(a: &Colour, b: &Colour) -> f32 {
+    let (a, b) = (relative_luminance(a), relative_luminance(b));
+    (a.max(b) + 0.05) / (a.min(b) + 0.05)
+}
+
+/// The colour index shown behind an object: the background of the first mask, key or button
+/// it is placed on, looking through containers and object pointers. `None` if the object isn't
+/// placed on anything with a background.
+pub fn background_behind(project: &EditorProject, id: ObjectId) -> Option<u8> {
+    let mut current = id;
+    for _ in 0..MAX_BACKGROUND_DEPTH {
+        let parents = project.get_parents(current);
+        let parent = parents.first()?;
+        match parent {
+            Object::WorkingSet(o) => return Some(o.background_colour),
+            Object::DataMask(o) => return Some(o.background_colour),
+            Object::AlarmMask(o) => return Some(o.background_colour),
+            Object::WindowMask(o) => return Some(o.background_colour),
+            Object::Key(o) => return Some(o.background_colour),
+            Object::Button(o) => return Some(o.background_colour),
+            Object::Container(_) | Object::ObjectPointer(_) => current = parent.id(),
+            _ => return None,
+        }
+    }
+    None
+}
+
+/// Colour indices close to `colour` that contrast enough with `background`, the closest first
+pub fn suggest_contrasting_colours(
+    pool: &ObjectPool,
+    colour: u8,
+    background: u8,
+    count: usize,
+) -> Vec<u8> {
+    let original = pool.color_by_index(colour);
+    let background = pool.color_by_index(background);
+    let distance = |candidate: &Colour| {
+        let dr = candidate.r as i32 - original.r as i32;
+        let dg = candidate.g as i32 - original.g as i32;
+        let db = candidate.b as i32 - original.b as i32;
+        dr * dr + dg * dg + db * db
+    };
+
+    let mut candidates: Vec<(i32, u8)> = (0..=u8::MAX)
+        .filter(|index| *index != colour)
+        .map(|index| (index, pool.color_by_index(index)))
+        .filter(|(_, candidate)| contrast_ratio(candidate, &background) >= MIN_CONTRAST_RATIO)
+        .map(|(index, candidate)| (distance(&candidate), index))
+        .collect();
+    candidates.sort_unstable();
+
+    let mut suggestions: Vec<u8> = Vec::new();
+    for (_, index) in candidates {
+        // Palettes often repeat colours, suggest each colour once
+        let candidate = pool.color_by_index(index);
+        if suggestions
+            .iter()
+            .any(|suggestion| pool.color_by_index(*suggestion) == candidate)
+        {
+            continue;
+        }
+        suggestions.push(index);
+        if suggestions.len() == count {
+            break;
+        }
+    }
+    suggestions
+}
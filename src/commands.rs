@@ -0,0 +1,283 @@
+//! Copyright 2024 - The Open-Agriculture Developers
+//! SPDX-License-Identifier: GPL-3.0-or-later
+//! Authors: Daan Steenbergen
+
+use crate::references::{redirect_references, remap_references};
+use ag_iso_stack::object_pool::object::Object;
+use ag_iso_stack::object_pool::{ObjectId, ObjectPool, ObjectRef};
+use std::collections::{HashMap, HashSet};
+use std::fmt;
+use std::rc::Rc;
+
+/// A change to the object pool.
+/// All edits of the pool go through a command, both from the UI and from scripts, so every edit
+/// is undoable, marks the project as modified and is checked by the validation hooks.
+#[derive(Clone, Debug)]
+pub enum PoolCommand {
+    /// Add a new object, its ID must not be in use yet
+    AddObject(Object),
+    /// Remove an object, references to it are left as they are
+    RemoveObject(ObjectId),
+    /// Replace an object by an edited version of it, the edit may change its ID
+    SetObject { id: ObjectId, object: Object },
+    /// Move a child reference of an object to another position in its list of children
+    MoveRef {
+        parent: ObjectId,
+        from: usize,
+        to: usize,
+    },
+    /// Reorder the objects of the pool, objects that are not listed keep their relative order
+    /// after the listed ones
+    ReorderObjects(Vec<ObjectId>),
+    /// Make all objects reference `to` wherever they reference `from`
+    RedirectReferences { from: ObjectId, to: ObjectId },
+    /// Give objects a new ID and update all references to them, all at once so IDs can be
+    /// swapped
+    RenumberObjects(Vec<(ObjectId, ObjectId)>),
+}
+
+impl PoolCommand {
+    /// Name of the kind of command
+    pub fn name(&self) -> &'static str {
+        match self {
+            PoolCommand::AddObject(_) => "AddObject",
+            PoolCommand::RemoveObject(_) => "RemoveObject",
+            PoolCommand::SetObject { .. } => "SetObject",
+            PoolCommand::MoveRef { .. } => "MoveRef",
+            PoolCommand::ReorderObjects(_) => "ReorderObjects",
+            PoolCommand::RedirectReferences { .. } => "RedirectReferences",
+            PoolCommand::RenumberObjects(_) => "RenumberObjects",
+        }
+    }
+
+    /// The objects the command changes, all of them must be editable for the command to run
+    pub fn targets(&self, pool: &ObjectPool) -> Vec<ObjectId> {
+        let referrers = |ids: &[ObjectId]| {
+            pool.objects()
+                .iter()
+                .filter(|object| {
+                    object
+                        .referenced_objects()
+                        .iter()
+                        .any(|id| ids.contains(id))
+                })
+                .map(|object| object.id())
+                .collect::<Vec<ObjectId>>()
+        };
+        match self {
+            PoolCommand::AddObject(object) => vec![object.id()],
+            PoolCommand::RemoveObject(id) => vec![*id],
+            PoolCommand::SetObject { id, .. } => vec![*id],
+            PoolCommand::MoveRef { parent, .. } => vec![*parent],
+            PoolCommand::ReorderObjects(order) => order.clone(),
+            PoolCommand::RedirectReferences { from, .. } => referrers(&[*from]),
+            PoolCommand::RenumberObjects(ids) => {
+                let from: Vec<ObjectId> = ids.iter().map(|(from, _)| *from).collect();
+                let mut targets = referrers(&from);
+                targets.retain(|id| !from.contains(id));
+                targets.extend(from);
+                targets
+            }
+        }
+    }
+
+    /// The IDs of the objects that may differ after the command, under their old and new IDs,
+    /// and whether the order or the IDs of the objects in the pool may change
+    pub fn changes(&self, pool: &ObjectPool) -> (Vec<ObjectId>, bool) {
+        let mut ids = self.targets(pool);
+        let reorders = match self {
+            PoolCommand::SetObject { id, object } if *id != object.id() => {
+                ids.push(object.id());
+                true
+            }
+            PoolCommand::RenumberObjects(renumbered) => {
+                ids.extend(renumbered.iter().map(|(_, to)| *to));
+                true
+            }
+            PoolCommand::AddObject(_)
+            | PoolCommand::RemoveObject(_)
+            | PoolCommand::ReorderObjects(_) => true,
+            PoolCommand::SetObject { .. }
+            | PoolCommand::MoveRef { .. }
+            | PoolCommand::RedirectReferences { .. } => false,
+        };
+        (ids, reorders)
+    }
+
+    /// Short human readable description of the command
+    pub fn summary(&self) -> String {
+        match self {
+            PoolCommand::AddObject(object) => format!("Add object {}", object.id().value()),
+            PoolCommand::RemoveObject(id) => format!("Remove object {}", id.value()),
+            PoolCommand::SetObject { id, object } if *id != object.id() => format!(
+                "Change object {} and its ID to {}",
+                id.value(),
+                object.id().value()
+            ),
+            PoolCommand::SetObject { id, .. } => format!("Change object {}", id.value()),
+            PoolCommand::MoveRef { parent, from, to } => format!(
+                "Move child reference of object {} from position {} to {}",
+                parent.value(),
+                from,
+                to
+            ),
+            PoolCommand::ReorderObjects(order) => format!("Reorder {} objects", order.len()),
+            PoolCommand::RedirectReferences { from, to } => format!(
+                "Redirect references from object {} to {}",
+                from.value(),
+                to.value()
+            ),
+            PoolCommand::RenumberObjects(ids) => format!("Renumber {} objects", ids.len()),
+        }
+    }
+}
+
+/// Reason a command could not be executed
+#[derive(Clone, Debug)]
+pub enum CommandError {
+    ObjectNotFound(ObjectId),
+    DuplicateId(ObjectId),
+    NoChildReferences(ObjectId),
+    IndexOutOfRange { parent: ObjectId, index: usize },
+    Rejected(String),
+}
+
+impl fmt::Display for CommandError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            CommandError::ObjectNotFound(id) => write!(f, "Object {} not found", id.value()),
+            CommandError::DuplicateId(id) => {
+                write!(f, "Object ID {} is already in use", id.value())
+            }
+            CommandError::NoChildReferences(id) => {
+                write!(f, "Object {} has no list of child references", id.value())
+            }
+            CommandError::IndexOutOfRange { parent, index } => write!(
+                f,
+                "Object {} has no child reference at index {}",
+                parent.value(),
+                index
+            ),
+            CommandError::Rejected(reason) => write!(f, "Rejected: {}", reason),
+        }
+    }
+}
+
+/// Check run before a command is executed, returning an error rejects the command
+pub type ValidationHook = Rc<dyn Fn(&ObjectPool, &PoolCommand) -> Result<(), String>>;
+
+/// The list of positioned child references of an object, if it has one
+pub(crate) fn object_refs_mut(object: &mut Object) -> Option<&mut Vec<ObjectRef>> {
+    match object {
+        Object::DataMask(o) => Some(&mut o.object_refs),
+        Object::AlarmMask(o) => Some(&mut o.object_refs),
+        Object::Container(o) => Some(&mut o.object_refs),
+        Object::Key(o) => Some(&mut o.object_refs),
+        Object::Button(o) => Some(&mut o.object_refs),
+        Object::WindowMask(o) => Some(&mut o.object_refs),
+        Object::AuxiliaryFunctionType2(o) => Some(&mut o.object_refs),
+        Object::AuxiliaryInputType2(o) => Some(&mut o.object_refs),
+        _ => None,
+    }
+}
+
+/// Check if the command can be applied to the pool, without changing it
+pub(crate) fn check(pool: &ObjectPool, command: &PoolCommand) -> Result<(), CommandError> {
+    match command {
+        PoolCommand::AddObject(object) => {
+            if pool.object_by_id(object.id()).is_some() {
+                return Err(CommandError::DuplicateId(object.id()));
+            }
+        }
+        PoolCommand::RemoveObject(id) => {
+            if pool.object_by_id(*id).is_none() {
+                return Err(CommandError::ObjectNotFound(*id));
+            }
+        }
+        PoolCommand::SetObject { id, object } => {
+            if pool.object_by_id(*id).is_none() {
+                return Err(CommandError::ObjectNotFound(*id));
+            }
+            if object.id() != *id && pool.object_by_id(object.id()).is_some() {
+                return Err(CommandError::DuplicateId(object.id()));
+            }
+        }
+        PoolCommand::MoveRef { parent, .. } => {
+            if pool.object_by_id(*parent).is_none() {
+                return Err(CommandError::ObjectNotFound(*parent));
+            }
+        }
+        PoolCommand::ReorderObjects(_) => {}
+        PoolCommand::RedirectReferences { to, .. } => {
+            if pool.object_by_id(*to).is_none() {
+                return Err(CommandError::ObjectNotFound(*to));
+            }
+        }
+        PoolCommand::RenumberObjects(ids) => {
+            let renumbered: HashMap<ObjectId, ObjectId> = ids.iter().copied().collect();
+            let mut used = HashSet::new();
+            for object in pool.objects() {
+                let id = renumbered.get(&object.id()).copied().unwrap_or(object.id());
+                if !used.insert(id) {
+                    return Err(CommandError::DuplicateId(id));
+                }
+            }
+            if let Some((from, _)) = ids
+                .iter()
+                .find(|(from, _)| pool.object_by_id(*from).is_none())
+            {
+                return Err(CommandError::ObjectNotFound(*from));
+            }
+        }
+    }
+    Ok(())
+}
+
+/// Apply a command to the pool, the command must have passed `check`.
+/// The pool is left unchanged if an error is returned.
+pub(crate) fn apply(pool: &mut ObjectPool, command: PoolCommand) -> Result<(), CommandError> {
+    match command {
+        PoolCommand::AddObject(object) => pool.add(object),
+        PoolCommand::RemoveObject(id) => pool.remove(id),
+        PoolCommand::SetObject { id, object } => {
+            if let Some(target) = pool.object_mut_by_id(id) {
+                *target = object;
+            }
+        }
+        PoolCommand::MoveRef { parent, from, to } => {
+            let refs = pool
+                .object_mut_by_id(parent)
+                .and_then(object_refs_mut)
+                .ok_or(CommandError::NoChildReferences(parent))?;
+            if let Some(index) = [from, to].into_iter().find(|index| *index >= refs.len()) {
+                return Err(CommandError::IndexOutOfRange { parent, index });
+            }
+            let object_ref = refs.remove(from);
+            refs.insert(to, object_ref);
+        }
+        PoolCommand::ReorderObjects(order) => {
+            let ranks: HashMap<ObjectId, usize> = order
+                .into_iter()
+                .enumerate()
+                .map(|(rank, id)| (id, rank))
+                .collect();
+            pool.objects_mut()
+                .sort_by_key(|obj| ranks.get(&obj.id()).copied().unwrap_or(usize::MAX));
+        }
+        PoolCommand::RedirectReferences { from, to } => {
+            for object in pool.objects_mut() {
+                redirect_references(object, from, to);
+            }
+        }
+        PoolCommand::RenumberObjects(ids) => {
+            let renumbered: HashMap<ObjectId, ObjectId> = ids.into_iter().collect();
+            for object in pool.objects_mut() {
+                remap_references(object, &renumbered);
+                if let Some(id) = renumbered.get(&object.id()) {
+                    object.mut_id().set_value(id.value()).ok();
+                }
+            }
+        }
+    }
+    Ok(())
+}
@@ -0,0 +1,221 @@
+//! Copyright 2024 - The Open-Agriculture Developers
+//! SPDX-License-Identifier: GPL-3.0-or-later
+//! Authors: Daan Steenbergen
+
+use ag_iso_stack::object_pool::ObjectType;
+use eframe::egui;
+use serde::{Deserialize, Serialize};
+
+use crate::EditorProject;
+
+/// Delay of a newly added step in milliseconds
+const DEFAULT_STEP_DELAY_MS: u32 = 1000;
+
+/// What a step of a demo script does on the simulated VT
+#[derive(Serialize, Deserialize, Clone, PartialEq, Debug)]
+pub enum DemoAction {
+    /// Set a number variable, like the implement application would
+    SetNumber { variable: u16, value: u32 },
+    /// Set a string variable, like the implement application would
+    SetString { variable: u16, value: String },
+    /// Press and release a soft key or button
+    PressKey { key: u16 },
+    /// Show a data or alarm mask
+    ActivateMask { mask: u16 },
+}
+
+impl DemoAction {
+    /// An action of every kind, targeting no object yet
+    const KINDS: [DemoAction; 4] = [
+        DemoAction::SetNumber {
+            variable: u16::MAX,
+            value: 0,
+        },
+        DemoAction::SetString {
+            variable: u16::MAX,
+            value: String::new(),
+        },
+        DemoAction::PressKey { key: u16::MAX },
+        DemoAction::ActivateMask { mask: u16::MAX },
+    ];
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            DemoAction::SetNumber { .. } => "Set number",
+            DemoAction::SetString { .. } => "Set string",
+            DemoAction::PressKey { .. } => "Press key",
+            DemoAction::ActivateMask { .. } => "Activate mask",
+        }
+    }
+
+    /// The ID of the object the action is done on
+    pub fn target(&self) -> u16 {
+        match self {
+            DemoAction::SetNumber { variable, .. } | DemoAction::SetString { variable, .. } => {
+                *variable
+            }
+            DemoAction::PressKey { key } => *key,
+            DemoAction::ActivateMask { mask } => *mask,
+        }
+    }
+
+    fn target_mut(&mut self) -> &mut u16 {
+        match self {
+            DemoAction::SetNumber { variable, .. } | DemoAction::SetString { variable, .. } => {
+                variable
+            }
+            DemoAction::PressKey { key } => key,
+            DemoAction::ActivateMask { mask } => mask,
+        }
+    }
+
+    /// The types of objects the action can be done on
+    fn target_types(&self) -> &'static [ObjectType] {
+        match self {
+            DemoAction::SetNumber { .. } => &[ObjectType::NumberVariable],
+            DemoAction::SetString { .. } => &[ObjectType::StringVariable],
+            DemoAction::PressKey { .. } => &[ObjectType::Key, ObjectType::Button],
+            DemoAction::ActivateMask { .. } => &[ObjectType::DataMask, ObjectType::AlarmMask],
+        }
+    }
+}
+
+/// A step of a demo script, done once the delay after the previous step has passed
+#[derive(Serialize, Deserialize, Clone, PartialEq, Debug)]
+pub struct DemoStep {
+    pub delay_ms: u32,
+    pub action: DemoAction,
+}
+
+/// Timed steps replayed in the simulation, e.g. a loop showing the pool at a trade fair.
+/// The script is stored in the project.
+#[derive(Serialize, Deserialize, Clone, PartialEq, Debug, Default)]
+pub struct DemoScript {
+    pub steps: Vec<DemoStep>,
+    /// Start again from the first step after the last one
+    pub looping: bool,
+}
+
+impl DemoScript {
+    /// Render the editor of the steps, marking the step that is being played.
+    /// Returns true if the script changed.
+    pub fn render_editor(
+        &mut self,
+        ui: &mut egui::Ui,
+        project: &EditorProject,
+        playing: Option<usize>,
+    ) -> bool {
+        let before = self.clone();
+        let mut moved = None;
+        let mut removed = None;
+
+        ui.checkbox(&mut self.looping, "Loop")
+            .on_hover_text("Start again from the first step after the last one");
+        egui::Grid::new("demo_script")
+            .striped(true)
+            .num_columns(6)
+            .show(ui, |ui| {
+                ui.label("");
+                ui.strong("Delay (ms)");
+                ui.strong("Action");
+                ui.strong("Object");
+                ui.strong("Value");
+                ui.label("");
+                ui.end_row();
+
+                let count = self.steps.len();
+                for (idx, step) in self.steps.iter_mut().enumerate() {
+                    if playing == Some(idx) {
+                        ui.strong(format!("\u{25B6} {}", idx + 1));
+                    } else {
+                        ui.label((idx + 1).to_string());
+                    }
+                    ui.add(egui::DragValue::new(&mut step.delay_ms).speed(10.0));
+
+                    egui::ComboBox::from_id_salt(("demo_action", idx))
+                        .selected_text(step.action.name())
+                        .show_ui(ui, |ui| {
+                            for kind in DemoAction::KINDS {
+                                let same_kind = std::mem::discriminant(&kind)
+                                    == std::mem::discriminant(&step.action);
+                                if ui.selectable_label(same_kind, kind.name()).clicked()
+                                    && !same_kind
+                                {
+                                    step.action = kind;
+                                }
+                            }
+                        });
+
+                    let target = step.action.target();
+                    let objects = project.get_objects_by_types(step.action.target_types());
+                    let name = |id: u16| {
+                        objects
+                            .iter()
+                            .find(|object| object.id().value() == id)
+                            .map_or("Choose an object".to_string(), |object| {
+                                format!(
+                                    "{}: {}",
+                                    id,
+                                    project.get_object_info(object).get_name(object)
+                                )
+                            })
+                    };
+                    egui::ComboBox::from_id_salt(("demo_target", idx))
+                        .selected_text(name(target))
+                        .show_ui(ui, |ui| {
+                            for object in &objects {
+                                let id = object.id().value();
+                                ui.selectable_value(step.action.target_mut(), id, name(id));
+                            }
+                        });
+
+                    match &mut step.action {
+                        DemoAction::SetNumber { value, .. } => {
+                            ui.add(egui::DragValue::new(value));
+                        }
+                        DemoAction::SetString { value, .. } => {
+                            ui.text_edit_singleline(value);
+                        }
+                        DemoAction::PressKey { .. } | DemoAction::ActivateMask { .. } => {
+                            ui.label("");
+                        }
+                    }
+
+                    ui.horizontal(|ui| {
+                        if ui
+                            .add_enabled(idx > 0, egui::Button::new("\u{23F6}"))
+                            .on_hover_text("Move up")
+                            .clicked()
+                        {
+                            moved = Some((idx, idx - 1));
+                        }
+                        if ui
+                            .add_enabled(idx + 1 < count, egui::Button::new("\u{23F7}"))
+                            .on_hover_text("Move down")
+                            .clicked()
+                        {
+                            moved = Some((idx, idx + 1));
+                        }
+                        if ui.button("\u{1F5D9}").on_hover_text("Remove").clicked() {
+                            removed = Some(idx);
+                        }
+                    });
+                    ui.end_row();
+                }
+            });
+
+        if let Some((from, to)) = moved {
+            self.steps.swap(from, to);
+        }
+        if let Some(idx) = removed {
+            self.steps.remove(idx);
+        }
+        if ui.button("Add step").clicked() {
+            self.steps.push(DemoStep {
+                delay_ms: DEFAULT_STEP_DELAY_MS,
+                action: DemoAction::KINDS[0].clone(),
+            });
+        }
+        *self != before
+    }
+}
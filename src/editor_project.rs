@@ -2,23 +2,109 @@
 //! SPDX-License-Identifier: GPL-3.0-or-later
 //! Authors: Daan Steenbergen
 
-use std::{cell::RefCell, collections::HashMap};
+use std::{
+    cell::RefCell,
+    collections::{HashMap, HashSet, VecDeque},
+    sync::Arc,
+};
 
 use ag_iso_stack::object_pool::{
-    object::Object, NullableObjectId, ObjectId, ObjectPool, ObjectType,
+    object::{ColourPalette, Object, ObjectPointer},
+    object_attributes::{PictureGraphicFormat, Point},
+    vt_version::VtVersion,
+    Colour, NullableObjectId, ObjectId, ObjectPool, ObjectRef, ObjectType,
 };
 
-use crate::{project_file::ProjectFile, smart_naming, ObjectInfo};
+use crate::{
+    allowed_object_relationships::get_allowed_child_refs,
+    audit_log::AuditLog,
+    backup::{self, BackupNotice},
+    batch_rename::BatchRename,
+    child_move::ChildMovePick,
+    clipboard,
+    commands::{self, CommandError, PoolCommand, ValidationHook},
+    demo_script::DemoScript,
+    font_substitution,
+    graphics_commands::{self, GraphicsCommand},
+    guidelines::GuidelineSettings,
+    image_import::ImageSource,
+    layout_presets::LayoutPreset,
+    localization,
+    name_replace::NameReplace,
+    object_configuring::would_create_circular_reference,
+    object_defaults::default_object,
+    object_list::{self, ImportColumn, ObjectListChange},
+    offscreen_rendering,
+    picture_scaling::{self, ScaledVariantKind},
+    pool_delta::PoolDelta,
+    pool_diff::attribute_changes,
+    pool_index::PoolIndex,
+    pool_merge::{MergeChoice, MergeItem, MergeKind},
+    project_events::{self, EventListener, EventListeners, ProjectEvent, SubscriptionId},
+    project_file::{NamedSnapshot, ProjectFile, ProjectSettings},
+    references::{redirect_references, remap_references, remove_references},
+    settings::{IdRange, IdSettings},
+    simulation::AuxAssignment,
+    smart_naming::{self, NamingRules, SmartRename},
+    templates::Template,
+    vt_compatibility::{required_vt_version, vt_version, BASE_VT_VERSION},
+    ObjectInfo,
+};
 
 const MAX_UNDO_REDO_POOL: usize = 10;
 const MAX_UNDO_REDO_SELECTED: usize = 20;
 
+/// Maximum width/height in pixels of the generated object thumbnails
+const THUMBNAIL_SIZE: f32 = 32.0;
+
+/// Amount of recently picked objects that are remembered for the object pickers
+const MAX_RECENTLY_PICKED: usize = 8;
+
+/// Maximum amount of candidates shown at the top of an object picker
+const MAX_FEATURED_CANDIDATES: usize = 5;
+
+/// Amount of recently used colours that are remembered for the colour selector
+const MAX_RECENT_COLOURS: usize = 16;
+
+/// Immutable version of the pool, shared instead of copied.
+/// Simulation, exports and analysis keep working on their snapshot while editing continues.
+pub type PoolSnapshot = Arc<ObjectPool>;
+
+/// A step in the undo or redo history: the changes back to the pool of the step, with a
+/// description of the change between it and the current pool
+#[derive(Clone)]
+struct HistoryStep {
+    delta: PoolDelta,
+    label: String,
+    /// Custom names of the objects renamed in the change, as they were in this step
+    names: Vec<(ObjectId, Option<String>)>,
+}
+
+/// An object that uses another object, directly or shown through an object pointer
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub struct ObjectUsage {
+    /// The referencing object
+    pub id: ObjectId,
+    /// The object pointer it references the object through, `None` if it is referenced directly
+    pub via: Option<ObjectId>,
+}
+
 #[derive(Default, Clone)]
 pub struct EditorProject {
-    pool: ObjectPool,
+    pool: PoolSnapshot,
+    /// The pool the commands of this frame are executed on. It is still a `RefCell` that
+    /// [`Self::execute`] borrows mutably, so a command executed while the pool is borrowed
+    /// panics; commands don't produce the next snapshot directly yet.
     mut_pool: RefCell<ObjectPool>,
-    undo_pool_history: Vec<ObjectPool>,
-    redo_pool_history: Vec<ObjectPool>,
+    undo_pool_history: Vec<HistoryStep>,
+    redo_pool_history: Vec<HistoryStep>,
+    /// Kind and target of the commands executed this frame, to label the next undo step
+    pending_actions: RefCell<Vec<(&'static str, Option<ObjectId>)>>,
+    /// Custom names of the objects renamed since the last undo step, before they were renamed
+    pending_renames: RefCell<Vec<(ObjectId, Option<String>)>>,
+    /// IDs of the objects the commands of this frame may have changed and whether they may have
+    /// changed the order, so only those are compared when the next undo step is made
+    pending_changes: RefCell<(Vec<ObjectId>, bool)>,
     selected_object: NullableObjectId,
     mut_selected_object: RefCell<NullableObjectId>,
     undo_selected_history: Vec<NullableObjectId>,
@@ -29,20 +115,235 @@ pub struct EditorProject {
 
     /// Used to keep track of the object that is being renamed
     renaming_object: RefCell<Option<(eframe::egui::Id, ObjectId, String)>>,
+    /// Macro command being built by picking a child on the canvas
+    child_move_pick: RefCell<Option<ChildMovePick>>,
+
+    /// Set when renaming of the selected object is requested (e.g. by pressing F2),
+    /// the first widget showing the selected object picks up the request
+    rename_selected_requested: RefCell<bool>,
 
     /// Cached next available ID for efficient allocation
     next_available_id: RefCell<u16>,
 
+    /// ID ranges of the object types numbered on their own, from the designer settings
+    id_settings: RefCell<IdSettings>,
+
+    /// Cached next ID to try in the range of each object type, by the number of the type
+    next_ids_by_type: RefCell<HashMap<u8, u16>>,
+
+    /// Patterns of the names given by smart naming, from the designer settings
+    naming_rules: RefCell<NamingRules>,
+
+    /// Smart names are preceded by the name of the mask or container showing the object, a
+    /// setting of the project
+    parent_naming: RefCell<bool>,
+
+    /// Number of the VT version the pool is made for, a setting of the project
+    vt_version: RefCell<u8>,
+
     /// Cached default object names for efficient lookup
     default_object_names: RefCell<HashMap<ObjectId, String>>,
 
     /// Request to open image file dialog for PictureGraphic object
     image_load_request: RefCell<Option<ObjectId>>,
+
+    /// Request to change the format of a PictureGraphic object
+    picture_format_request: RefCell<Option<(ObjectId, PictureGraphicFormat)>>,
+
+    /// Backup made before the last bulk operation, to be shown to the user
+    backup_notice: RefCell<Option<BackupNotice>>,
+
+    /// Cached thumbnail textures of objects, removed when the object or one of its children changes
+    thumbnail_cache: RefCell<HashMap<ObjectId, Option<eframe::egui::TextureHandle>>>,
+
+    /// Objects most recently picked in an object picker, most recent first
+    recently_picked: RefCell<Vec<ObjectId>>,
+
+    /// Lookup tables of the pool, updated with the changed objects whenever the pool changes
+    index: PoolIndex,
+
+    /// The last query of the object list and the objects it found, with the name generation
+    /// of the index it was found with
+    name_matches: RefCell<Option<(u64, String, Vec<ObjectId>)>>,
+
+    /// Incremented whenever the pool changes, to find out if derived data is outdated
+    pool_generation: RefCell<u64>,
+
+    /// Colour indices most recently picked in the colour selector, most recent first
+    recent_colours: RefCell<Vec<u8>>,
+
+    /// Colour indices the user added to the palette of this project
+    palette_colours: RefCell<Vec<u8>>,
+
+    /// Expanded nodes of the hierarchy, as paths of object IDs from the working set
+    expanded_nodes: RefCell<HashSet<Vec<u16>>>,
+
+    /// Auxiliary input assignments used by the simulation
+    aux_assignments: RefCell<Vec<AuxAssignment>>,
+    /// Demo script replayed in the simulation
+    demo_script: RefCell<DemoScript>,
+    /// HMI guideline rules checked for the pool
+    guideline_settings: RefCell<GuidelineSettings>,
+
+    /// Standard colour palette of the VT, used for the colours a custom palette doesn't define
+    standard_colour_palette: Vec<Colour>,
+
+    /// Checks run before every command, see [`EditorProject::execute`]
+    validation_hooks: RefCell<Vec<ValidationHook>>,
+
+    /// Whether the pool changed since the project was loaded or saved
+    modified: RefCell<bool>,
+
+    /// Listeners notified of the changes to the pool and the selection
+    listeners: RefCell<EventListeners>,
+
+    /// Language the masks are previewed in, `None` to show the text of the pool itself
+    preview_language: RefCell<Option<String>>,
+
+    /// Every change made to the project during this session
+    audit_log: RefCell<AuditLog>,
+
+    /// Named copies of the pool, stored in the project file
+    snapshots: RefCell<Vec<NamedSnapshot>>,
+
+    /// Restricted objects unlocked for editing during this session
+    unlocked_objects: RefCell<HashSet<ObjectId>>,
+
+    /// Pool translated to the preview language, with the generation of the pool it was made of.
+    /// Cleared whenever a translation changes.
+    preview_pool: RefCell<Option<(u64, PoolSnapshot)>>,
+
+    /// Parsed drawing commands previewed per graphics context.
+    /// Cleared whenever the commands change.
+    graphics_previews: RefCell<Option<Arc<HashMap<ObjectId, Vec<GraphicsCommand>>>>>,
+
+    /// Objects shown blinking in the preview. Cleared whenever an object starts or stops
+    /// blinking.
+    blinking_objects: RefCell<Option<Arc<HashSet<ObjectId>>>>,
+}
+
+/// Find the colour palette in effect: the one selected by the working set special controls,
+/// or else the first colour palette in the pool
+pub fn get_active_colour_palette(pool: &ObjectPool) -> Option<&ColourPalette> {
+    let selected = pool
+        .objects_by_type(ObjectType::WorkingSetSpecialControls)
+        .into_iter()
+        .find_map(|obj| match obj {
+            Object::WorkingSetSpecialControls(o) => o.id_of_colour_palette.0,
+            _ => None,
+        });
+    let palette = match selected {
+        Some(id) => pool.object_by_id(id),
+        None => pool
+            .objects_by_type(ObjectType::ColourPalette)
+            .into_iter()
+            .next(),
+    };
+    match palette {
+        Some(Object::ColourPalette(palette)) => Some(palette),
+        _ => None,
+    }
+}
+
+/// Position in the child references of the object of the reference at `index` in
+/// [`Object::referenced_objects`], which also lists e.g. attributes and the soft key mask
+fn child_ref_position(object: &mut Object, index: usize) -> Option<usize> {
+    let referenced = object.referenced_objects();
+    let id = *referenced.get(index)?;
+    let occurrence = referenced[..index]
+        .iter()
+        .filter(|other| **other == id)
+        .count();
+    commands::object_refs_mut(object)?
+        .iter()
+        .enumerate()
+        .filter(|(_, object_ref)| object_ref.id == id)
+        .nth(occurrence)
+        .map(|(position, _)| position)
+}
+
+/// List object IDs for a message, e.g. "12, 13, 40"
+fn join_ids(ids: impl IntoIterator<Item = u16>) -> String {
+    ids.into_iter()
+        .map(|id| id.to_string())
+        .collect::<Vec<_>>()
+        .join(", ")
+}
+
+/// Objects meant to be shared by the objects referencing them, rather than belonging to one
+fn is_shared_resource(object_type: ObjectType) -> bool {
+    matches!(
+        object_type,
+        ObjectType::WorkingSet
+            | ObjectType::DataMask
+            | ObjectType::AlarmMask
+            | ObjectType::SoftKeyMask
+            | ObjectType::WindowMask
+            | ObjectType::KeyGroup
+            | ObjectType::FontAttributes
+            | ObjectType::LineAttributes
+            | ObjectType::FillAttributes
+            | ObjectType::InputAttributes
+            | ObjectType::ExtendedInputAttributes
+            | ObjectType::Macro
+            | ObjectType::ColourMap
+            | ObjectType::ColourPalette
+            | ObjectType::PictureGraphic
+            | ObjectType::GraphicData
+            | ObjectType::ObjectLabelReferenceList
+            | ObjectType::WorkingSetSpecialControls
+    )
+}
+
+/// Objects the VT finds by their type instead of through a reference, the roots of the pool
+fn is_root_object(object_type: ObjectType) -> bool {
+    matches!(
+        object_type,
+        ObjectType::WorkingSet
+            | ObjectType::WorkingSetSpecialControls
+            | ObjectType::AuxiliaryFunctionType1
+            | ObjectType::AuxiliaryInputType1
+            | ObjectType::AuxiliaryFunctionType2
+            | ObjectType::AuxiliaryInputType2
+    )
+}
+
+/// Make the colours of the pool follow its custom colour palette, if there is one
+fn apply_colour_palette(pool: &mut ObjectPool, standard_palette: &[Colour]) {
+    let mut colours = pool.get_colour_palette();
+    for (colour, standard) in colours.iter_mut().zip(standard_palette) {
+        *colour = *standard;
+    }
+    if let Some(palette) = get_active_colour_palette(pool) {
+        for (colour, custom) in colours.iter_mut().zip(&palette.colours) {
+            *colour = *custom;
+        }
+    }
+    if pool.get_colour_palette() != colours {
+        pool.set_colour_palette(colours);
+    }
+}
+
+/// Content of the linked image of a picture graphic, if it can be read
+#[cfg(not(target_arch = "wasm32"))]
+fn read_image_source(
+    source: &ImageSource,
+    project_dir: Option<&std::path::Path>,
+) -> Option<Vec<u8>> {
+    std::fs::read(source.resolve(project_dir)).ok()
+}
+
+/// Linked images can't be read in the browser
+#[cfg(target_arch = "wasm32")]
+fn read_image_source(_: &ImageSource, _: Option<&std::path::Path>) -> Option<Vec<u8>> {
+    None
 }
 
 impl From<ObjectPool> for EditorProject {
-    fn from(pool: ObjectPool) -> Self {
+    fn from(mut pool: ObjectPool) -> Self {
         let (mask_size, soft_key_size) = pool.get_minimum_mask_sizes();
+        let standard_colour_palette = pool.get_colour_palette().to_vec();
+        apply_colour_palette(&mut pool, &standard_colour_palette);
 
         // Find the highest ID in use to initialize next_available_id
         let max_id = pool
@@ -51,12 +352,17 @@ impl From<ObjectPool> for EditorProject {
             .map(|obj| obj.id().value())
             .max()
             .unwrap_or(0);
+        let index = PoolIndex::new(&pool);
+        let required_version = required_vt_version(&pool);
 
         EditorProject {
             mut_pool: RefCell::new(pool.clone()),
-            pool,
+            pool: Arc::new(pool),
             undo_pool_history: Default::default(),
             redo_pool_history: Default::default(),
+            pending_actions: RefCell::new(Vec::new()),
+            pending_renames: RefCell::new(Vec::new()),
+            pending_changes: RefCell::new((Vec::new(), false)),
             selected_object: NullableObjectId::default(),
             mut_selected_object: RefCell::new(NullableObjectId::default()),
             undo_selected_history: Default::default(),
@@ -65,9 +371,40 @@ impl From<ObjectPool> for EditorProject {
             soft_key_size,
             object_info: RefCell::new(HashMap::new()),
             renaming_object: RefCell::new(None),
+            child_move_pick: RefCell::new(None),
+            rename_selected_requested: RefCell::new(false),
             next_available_id: RefCell::new(max_id.saturating_add(1)),
+            id_settings: RefCell::new(IdSettings::default()),
+            next_ids_by_type: RefCell::new(HashMap::new()),
+            naming_rules: RefCell::new(NamingRules::default()),
+            parent_naming: RefCell::new(false),
+            vt_version: RefCell::new(required_version),
             default_object_names: RefCell::new(HashMap::new()),
             image_load_request: RefCell::new(None),
+            picture_format_request: RefCell::new(None),
+            backup_notice: RefCell::new(None),
+            thumbnail_cache: RefCell::new(HashMap::new()),
+            recently_picked: RefCell::new(Vec::new()),
+            index,
+            name_matches: RefCell::new(None),
+            pool_generation: RefCell::new(0),
+            recent_colours: RefCell::new(Vec::new()),
+            palette_colours: RefCell::new(Vec::new()),
+            expanded_nodes: RefCell::new(HashSet::new()),
+            aux_assignments: RefCell::new(Vec::new()),
+            demo_script: RefCell::new(DemoScript::default()),
+            guideline_settings: RefCell::new(GuidelineSettings::default()),
+            standard_colour_palette,
+            validation_hooks: RefCell::new(Vec::new()),
+            listeners: RefCell::new(EventListeners::default()),
+            modified: RefCell::new(false),
+            audit_log: RefCell::new(AuditLog::default()),
+            snapshots: RefCell::new(Vec::new()),
+            unlocked_objects: RefCell::new(HashSet::new()),
+            preview_language: RefCell::new(None),
+            preview_pool: RefCell::new(None),
+            graphics_previews: RefCell::new(None),
+            blinking_objects: RefCell::new(None),
         }
     }
 }
@@ -78,6 +415,11 @@ impl EditorProject {
         &self.pool
     }
 
+    /// Get a snapshot of the current object pool, which is not affected by later edits
+    pub fn snapshot(&self) -> PoolSnapshot {
+        self.pool.clone()
+    }
+
     /// Allocate a new unique object ID efficiently
     pub fn allocate_object_id(&self) -> ObjectId {
         let mut next_id = self.next_available_id.borrow_mut();
@@ -116,6 +458,50 @@ impl EditorProject {
         allocated_id
     }
 
+    /// Allocate a new unique object ID for an object of the given type. The ID is taken from
+    /// the range of the type if one is configured, else it is the next available ID.
+    pub fn allocate_object_id_for_type(&self, object_type: ObjectType) -> ObjectId {
+        let Some(range) = self
+            .id_settings
+            .borrow()
+            .range(object_type)
+            .map(IdRange::ids)
+        else {
+            return self.allocate_object_id();
+        };
+
+        // IDs handed out before are skipped until the pool is updated and the cache reset
+        let mut next_ids = self.next_ids_by_type.borrow_mut();
+        let next = next_ids.entry(object_type as u8).or_insert(*range.start());
+        let free = ((*next).max(*range.start())..=*range.end())
+            .filter_map(|id| ObjectId::new(id).ok())
+            .find(|id| self.pool.object_by_id(*id).is_none());
+        match free {
+            Some(id) => {
+                *next = id.value().saturating_add(1);
+                id
+            }
+            None => {
+                log::warn!(
+                    "No free ID left in {} to {} for {:?}, using the next available ID",
+                    range.start(),
+                    range.end(),
+                    object_type
+                );
+                drop(next_ids);
+                self.allocate_object_id()
+            }
+        }
+    }
+
+    /// Use the ID ranges of the settings for new objects
+    pub fn set_id_settings(&self, settings: &IdSettings) {
+        if *self.id_settings.borrow() != *settings {
+            self.id_settings.replace(settings.clone());
+            self.next_ids_by_type.borrow_mut().clear();
+        }
+    }
+
     /// Update the next available ID cache based on the current pool
     fn update_next_available_id(&self) {
         let max_id = self
@@ -126,6 +512,7 @@ impl EditorProject {
             .max()
             .unwrap_or(0);
         self.next_available_id.replace(max_id.saturating_add(1));
+        self.next_ids_by_type.borrow_mut().clear();
     }
 
     /// Get the current selected object
@@ -133,11 +520,112 @@ impl EditorProject {
         self.selected_object
     }
 
-    /// Get the current mutating object pool
-    /// This is used to make changes to the pool in the next frame
-    /// without affecting the current pool
-    pub fn get_mut_pool(&self) -> &RefCell<ObjectPool> {
-        &self.mut_pool
+    /// Execute a command on the pool. This is the only way to change the pool, the changes of
+    /// all commands executed during a frame become a single undo entry in [`Self::update_pool`].
+    pub fn execute(&self, command: PoolCommand) -> Result<(), CommandError> {
+        self.check_editable(std::slice::from_ref(&command))?;
+        let mut pool = self.mut_pool.borrow_mut();
+        commands::check(&pool, &command)?;
+        for hook in self.validation_hooks.borrow().iter() {
+            hook(&pool, &command).map_err(CommandError::Rejected)?;
+        }
+
+        let (changed, reorders) = command.changes(&pool);
+        let target = match command.targets(&pool).as_slice() {
+            [id] => Some(*id),
+            _ => None,
+        };
+        let (name, summary) = (command.name(), command.summary());
+        // Only the changed attributes are logged, whole objects can hold e.g. picture data
+        let attributes = match &command {
+            PoolCommand::SetObject { id, object } => pool
+                .object_by_id(*id)
+                .map(|old| attribute_changes(old, object)),
+            _ => None,
+        };
+        commands::apply(&mut pool, command)?;
+        self.pending_actions.borrow_mut().push((name, target));
+        let mut pending_changes = self.pending_changes.borrow_mut();
+        pending_changes.0.extend(changed);
+        pending_changes.1 |= reorders;
+        let mut audit_log = self.audit_log.borrow_mut();
+        match (attributes, target) {
+            (Some(attributes), Some(target)) => {
+                audit_log.record_attributes(name, summary, target.value(), attributes)
+            }
+            _ => audit_log.record(name, summary, target.map(|id| id.value()), None, None),
+        }
+        Ok(())
+    }
+
+    /// Check that none of the commands changes a restricted object that is not unlocked, so a
+    /// sequence of commands can be rejected before any of them is executed
+    fn check_editable(&self, commands: &[PoolCommand]) -> Result<(), CommandError> {
+        let pool = self.mut_pool.borrow();
+        let restricted = commands
+            .iter()
+            .flat_map(|command| command.targets(&pool))
+            .find_map(|target| self.get_restricting_object(target));
+        match restricted {
+            Some(restricted) => Err(CommandError::Rejected(format!(
+                "Object {} is restricted, unlock it to edit",
+                restricted.value()
+            ))),
+            None => Ok(()),
+        }
+    }
+
+    /// Get the log of all changes made to the project during this session
+    pub fn get_audit_log(&self) -> std::cell::Ref<'_, AuditLog> {
+        self.audit_log.borrow()
+    }
+
+    /// Add a check that is run before every command, it can reject the command by returning an error
+    pub fn add_validation_hook(&self, hook: ValidationHook) {
+        self.validation_hooks.borrow_mut().push(hook);
+    }
+
+    /// Get a copy of an object including the changes of this frame, to edit it and store it again
+    /// with [`PoolCommand::SetObject`]
+    pub fn get_editable_object(&self, id: ObjectId) -> Option<Object> {
+        self.mut_pool.borrow().object_by_id(id).cloned()
+    }
+
+    /// Call the listener for every change committed to the pool or the selection, so an
+    /// application embedding the editor doesn't have to poll [`Self::update_pool`]
+    pub fn subscribe(&self, listener: EventListener) -> SubscriptionId {
+        self.listeners.borrow_mut().subscribe(listener)
+    }
+
+    pub fn unsubscribe(&self, id: SubscriptionId) {
+        self.listeners.borrow_mut().unsubscribe(id);
+    }
+
+    fn notify(&self, event: &ProjectEvent) {
+        // Copied first, listeners may subscribe or unsubscribe while handling the event
+        let listeners = self.listeners.borrow().snapshot();
+        for listener in listeners {
+            listener(event);
+        }
+    }
+
+    fn notify_pool_changes(&self, old: &ObjectPool, new: &ObjectPool) {
+        if self.listeners.borrow().is_empty() {
+            return;
+        }
+        for event in project_events::pool_changes(old, new) {
+            self.notify(&event);
+        }
+    }
+
+    /// Whether the pool changed since the project was loaded or saved
+    pub fn is_modified(&self) -> bool {
+        *self.modified.borrow()
+    }
+
+    /// Mark the project as having unsaved changes, e.g. when it is restored from an autosave
+    pub fn set_modified(&self) {
+        self.modified.replace(true);
     }
 
     /// Set the mutating selected object
@@ -147,40 +635,208 @@ impl EditorProject {
         &self.mut_selected_object
     }
 
-    /// If the mutating pool is different from the current pool, add the current pool to the history
-    /// and update the current pool with the mutated pool.
+    /// If commands changed the mutating pool, add the changes back to the current pool to the
+    /// history and update the current pool with the mutated pool. Only the objects the commands
+    /// of this frame changed are compared.
     /// Returns true if the pool was updated
     pub fn update_pool(&mut self) -> bool {
-        if self.mut_pool.borrow().to_owned() != self.pool {
-            self.redo_pool_history.clear();
-            self.undo_pool_history.push(self.pool.clone());
-            if self.undo_pool_history.len() > MAX_UNDO_REDO_POOL {
-                self.undo_pool_history
-                    .drain(..self.undo_pool_history.len() - MAX_UNDO_REDO_POOL);
-            }
-            self.pool = self.mut_pool.borrow().clone();
-            // Clear the default names cache since objects may have changed
-            self.default_object_names.borrow_mut().clear();
-            return true;
+        let actions = self.pending_actions.take();
+        let names = self.pending_renames.take();
+        let (changed, reordered) = self.pending_changes.take();
+        if changed.is_empty() && !reordered && names.is_empty() {
+            return false;
         }
-        false
+        apply_colour_palette(
+            &mut self.mut_pool.borrow_mut(),
+            &self.standard_colour_palette,
+        );
+        let new_pool = self.mut_pool.borrow().clone();
+        let delta = PoolDelta::between_changed(&new_pool, &self.pool, &changed, reordered);
+        if delta.is_empty() && names.is_empty() {
+            return false;
+        }
+
+        let label = self.history_label(&actions);
+        let changed: Vec<ObjectId> = delta.changed_ids().collect();
+        let reordered = delta.reorders();
+        self.redo_pool_history.clear();
+        self.undo_pool_history.push(HistoryStep {
+            delta,
+            label,
+            names,
+        });
+        if self.undo_pool_history.len() > MAX_UNDO_REDO_POOL {
+            self.undo_pool_history
+                .drain(..self.undo_pool_history.len() - MAX_UNDO_REDO_POOL);
+        }
+        self.index.update(&new_pool, &changed, reordered);
+        self.notify_pool_changes(&self.pool, &new_pool);
+        self.pool = Arc::new(new_pool);
+        self.invalidate_caches();
+        self.invalidate_thumbnails(changed);
+        self.modified.replace(true);
+        true
+    }
+
+    /// Describe the commands that make up an undo step, e.g. "Edit Button_1" or "Delete 3 objects"
+    fn history_label(&self, actions: &[(&'static str, Option<ObjectId>)]) -> String {
+        let verb = |name: &str| match name {
+            "AddObject" => "Add",
+            "RemoveObject" => "Delete",
+            "SetObject" => "Edit",
+            "MoveRef" => "Move child of",
+            "ReorderObjects" => "Reorder objects",
+            "RedirectReferences" => "Redirect references",
+            "RenumberObjects" => "Renumber objects",
+            "RenameObjects" => "Rename",
+            _ => "Change",
+        };
+        let object_name = |id: ObjectId| {
+            let mut_pool = self.mut_pool.borrow();
+            match mut_pool.object_by_id(id).or(self.pool.object_by_id(id)) {
+                Some(object) => self.get_object_info(object).get_name(object),
+                None => format!("object {}", id.value()),
+            }
+        };
+
+        let Some((first, _)) = actions.first() else {
+            return "Edit".to_string();
+        };
+        let mut targets: Vec<ObjectId> = actions.iter().filter_map(|(_, id)| *id).collect();
+        targets.dedup();
+        if actions.iter().any(|(name, _)| name != first) {
+            return format!("{} changes", actions.len());
+        }
+        match targets.as_slice() {
+            [] => verb(first).to_string(),
+            [id] => format!("{} {}", verb(first), object_name(*id)),
+            _ => format!("{} {} objects", verb(first), targets.len()),
+        }
+    }
+
+    /// Descriptions of the undo steps, oldest first
+    pub fn get_undo_labels(&self) -> Vec<String> {
+        self.undo_pool_history
+            .iter()
+            .map(|step| step.label.clone())
+            .collect()
+    }
+
+    /// Descriptions of the redo steps, next redo first
+    pub fn get_redo_labels(&self) -> Vec<String> {
+        self.redo_pool_history
+            .iter()
+            .rev()
+            .map(|step| step.label.clone())
+            .collect()
+    }
+
+    /// Undo or redo until the given number of steps is undone, 0 being the current state of the
+    /// history with nothing undone
+    pub fn jump_in_history(&mut self, undo_steps: isize) {
+        for _ in 0..undo_steps.max(0) {
+            self.undo();
+        }
+        for _ in 0..(-undo_steps).max(0) {
+            self.redo();
+        }
+    }
+
+    /// Clear all cached data derived from the pool, since objects may have changed
+    fn invalidate_caches(&self) {
+        self.default_object_names.borrow_mut().clear();
+        *self.pool_generation.borrow_mut() += 1;
+    }
+
+    /// Forget the thumbnails of the changed objects and of the objects showing them, the other
+    /// thumbnails stay cached so e.g. a hierarchy full of thumbnails isn't rendered again on
+    /// every edit
+    fn invalidate_thumbnails(&self, changed: Vec<ObjectId>) {
+        let mut thumbnail_cache = self.thumbnail_cache.borrow_mut();
+        // Every object may use a changed colour palette
+        if changed.iter().any(|id| {
+            self.pool
+                .object_by_id(*id)
+                .is_some_and(|object| object.object_type() == ObjectType::ColourPalette)
+        }) {
+            thumbnail_cache.clear();
+            return;
+        }
+        let mut visited = HashSet::new();
+        let mut pending = changed;
+        while let Some(id) = pending.pop() {
+            if visited.insert(id) {
+                thumbnail_cache.remove(&id);
+                pending.extend(self.index.parents(id));
+            }
+        }
+    }
+
+    /// Get the generation of the pool, which changes whenever the pool changes
+    pub fn get_pool_generation(&self) -> u64 {
+        *self.pool_generation.borrow()
     }
 
     /// Undo the last action
     pub fn undo(&mut self) {
-        if let Some(pool) = self.undo_pool_history.pop() {
-            self.redo_pool_history.push(self.pool.clone());
+        if let Some(HistoryStep {
+            delta,
+            label,
+            names,
+        }) = self.undo_pool_history.pop()
+        {
+            self.pending_actions.borrow_mut().clear();
+            self.pending_changes.replace((Vec::new(), false));
+            let names = self.swap_names(names);
+            let delta = self.apply_history_delta(delta);
+            self.redo_pool_history.push(HistoryStep {
+                delta,
+                label,
+                names,
+            });
+            self.audit_log
+                .borrow_mut()
+                .record("Undo", "Undo".to_string(), None, None, None);
+        }
+    }
 
-            // Both need to be replaced here because otherwise it will be added to the undo history
-            self.pool = pool.clone();
-            self.mut_pool.replace(pool);
+    /// Bring the pool to the state of a history step, returning the changes back to the current
+    /// pool. Changes of this frame that aren't in the history yet are dropped.
+    fn apply_history_delta(&mut self, delta: PoolDelta) -> PoolDelta {
+        let mut pool = ObjectPool::clone(&self.pool);
+        let reverse = delta.apply(&mut pool);
+        let changed: Vec<ObjectId> = reverse.changed_ids().collect();
 
-            // Update next_available_id based on the new pool state
-            self.update_next_available_id();
+        // Both need to be replaced here because otherwise it will be added to the history
+        self.index.update(&pool, &changed, reverse.reorders());
+        self.notify_pool_changes(&self.pool, &pool);
+        self.mut_pool.replace(pool.clone());
+        self.pool = Arc::new(pool);
+        self.modified.replace(true);
 
-            // Clear the default names cache since objects may have changed
-            self.default_object_names.borrow_mut().clear();
-        }
+        // Update next_available_id based on the new pool state
+        self.update_next_available_id();
+
+        self.invalidate_caches();
+        self.invalidate_thumbnails(changed);
+        reverse
+    }
+
+    /// Give the objects the custom names of a history step, returning the names they had
+    fn swap_names(
+        &self,
+        names: Vec<(ObjectId, Option<String>)>,
+    ) -> Vec<(ObjectId, Option<String>)> {
+        let ids: Vec<ObjectId> = names.iter().map(|(id, _)| *id).collect();
+        self.index.forget_names(&ids);
+        let mut object_info = self.object_info.borrow_mut();
+        names
+            .into_iter()
+            .filter_map(|(id, name)| {
+                let info = object_info.get_mut(&id)?;
+                Some((id, std::mem::replace(&mut info.name, name)))
+            })
+            .collect()
     }
 
     /// Check if there are actions available to undo
@@ -190,17 +846,24 @@ impl EditorProject {
 
     /// Redo the last undone action
     pub fn redo(&mut self) {
-        if let Some(pool) = self.redo_pool_history.pop() {
-            self.undo_pool_history.push(self.pool.clone());
-            // Both need to be replaced here because otherwise the redo history will be cleared
-            self.pool = pool.clone();
-            self.mut_pool.replace(pool);
-
-            // Update next_available_id based on the new pool state
-            self.update_next_available_id();
-
-            // Clear the default names cache since objects may have changed
-            self.default_object_names.borrow_mut().clear();
+        if let Some(HistoryStep {
+            delta,
+            label,
+            names,
+        }) = self.redo_pool_history.pop()
+        {
+            self.pending_actions.borrow_mut().clear();
+            self.pending_changes.replace((Vec::new(), false));
+            let names = self.swap_names(names);
+            let delta = self.apply_history_delta(delta);
+            self.undo_pool_history.push(HistoryStep {
+                delta,
+                label,
+                names,
+            });
+            self.audit_log
+                .borrow_mut()
+                .record("Redo", "Redo".to_string(), None, None, None);
         }
     }
 
@@ -223,6 +886,7 @@ impl EditorProject {
                 }
             }
             self.selected_object = mut_selected;
+            self.notify(&ProjectEvent::SelectionChanged(mut_selected));
             return true;
         }
         false
@@ -235,6 +899,7 @@ impl EditorProject {
             // Both need to be replaced here because otherwise it will be added to the undo history
             self.selected_object = selected.clone();
             self.mut_selected_object.replace(selected);
+            self.notify(&ProjectEvent::SelectionChanged(selected));
         }
     }
 
@@ -245,7 +910,102 @@ impl EditorProject {
             // Both need to be replaced here because otherwise the redo history will be cleared
             self.selected_object = selected.clone();
             self.mut_selected_object.replace(selected);
+            self.notify(&ProjectEvent::SelectionChanged(selected));
+        }
+    }
+
+    /// The range of IDs the objects of a type are numbered in when renumbering: the configured
+    /// range of the type, or else a block of 1000 IDs per type, so the type of an object can be
+    /// told from its ID
+    pub fn object_id_range(&self, object_type: ObjectType) -> std::ops::RangeInclusive<u16> {
+        if let Some(range) = self.id_settings.borrow().range(object_type) {
+            return range.ids();
+        }
+        let start = (object_type as u16).saturating_mul(1000);
+        start..=start.saturating_add(999).min(u16::MAX - 1)
+    }
+
+    /// New IDs for the objects of the given types, numbered from the start of the range of
+    /// their type in the order of their current IDs. Objects keeping their ID are left out.
+    pub fn plan_renumbering(
+        &self,
+        object_types: &[ObjectType],
+    ) -> Result<Vec<(ObjectId, ObjectId)>, String> {
+        let mut renumbered = Vec::new();
+        for object_type in object_types {
+            let mut ids = self.index.ids_by_type(*object_type).to_vec();
+            ids.sort_by_key(|id| id.value());
+            let range = self.object_id_range(*object_type);
+            if ids.len() > range.len() {
+                return Err(format!(
+                    "{} objects of type {:?} don't fit in IDs {} to {}",
+                    ids.len(),
+                    object_type,
+                    range.start(),
+                    range.end()
+                ));
+            }
+            for (id, new_id) in ids.into_iter().zip(range) {
+                let new_id =
+                    ObjectId::new(new_id).map_err(|_| format!("Invalid object ID {}", new_id))?;
+                if id != new_id {
+                    renumbered.push((id, new_id));
+                }
+            }
+        }
+
+        // The new IDs may be in use by objects that are not renumbered
+        let moved: HashSet<ObjectId> = renumbered.iter().map(|(from, _)| *from).collect();
+        for (_, to) in &renumbered {
+            if let Some(object) = self.get_object(*to).filter(|o| !moved.contains(&o.id())) {
+                return Err(format!(
+                    "ID {} is used by {:?} {}, renumber its type as well",
+                    to.value(),
+                    object.object_type(),
+                    self.get_object_info(object).get_name(object)
+                ));
+            }
+        }
+        Ok(renumbered)
+    }
+
+    /// Give objects new IDs, fixing every reference to them. Their names and other details
+    /// move along, so the exported header keeps the same names for the new IDs.
+    pub fn remap_object_references(
+        &self,
+        renumbered: Vec<(ObjectId, ObjectId)>,
+    ) -> Result<(), CommandError> {
+        if renumbered.is_empty() {
+            return Ok(());
+        }
+        self.backup_before("Renumber objects");
+        self.execute(PoolCommand::RenumberObjects(renumbered.clone()))?;
+
+        let mut object_info = self.object_info.borrow_mut();
+        let moved: Vec<(ObjectId, Option<ObjectInfo>)> = renumbered
+            .iter()
+            .map(|(from, to)| (*to, object_info.remove(from)))
+            .collect();
+        for (to, info) in moved {
+            if let Some(info) = info {
+                object_info.insert(to, info);
+            }
+        }
+        drop(object_info);
+        let ids: Vec<ObjectId> = renumbered
+            .iter()
+            .flat_map(|(from, to)| [*from, *to])
+            .collect();
+        self.index.forget_names(&ids);
+
+        let mut selected = self.mut_selected_object.borrow_mut();
+        if let Some((_, to)) = renumbered
+            .iter()
+            .find(|(from, _)| selected.0 == Some(*from))
+        {
+            selected.0 = Some(*to);
         }
+        Ok(())
     }
 
     /// Change an object id in the object info hashmap
@@ -254,6 +1014,71 @@ impl EditorProject {
         if let Some(info) = object_info.remove(&old_id) {
             object_info.insert(new_id, info);
         }
+        self.index.forget_names(&[old_id, new_id]);
+    }
+
+    /// Bring the object info in line with the pool: remove the info of objects that no longer
+    /// exist, add info for objects without it and give objects sharing a unique identifier new
+    /// ones. Returns a description of every fix, empty if the info was consistent.
+    pub fn check_object_info(&self) -> Vec<String> {
+        let mut fixes = Vec::new();
+        let mut object_info = self.object_info.borrow_mut();
+
+        let mut stale: Vec<ObjectId> = object_info
+            .keys()
+            .filter(|id| self.pool.object_by_id(**id).is_none())
+            .copied()
+            .collect();
+        if !stale.is_empty() {
+            stale.sort_by_key(|id| id.value());
+            for id in &stale {
+                object_info.remove(id);
+            }
+            fixes.push(format!(
+                "Removed the info of {} objects that no longer exist: {}",
+                stale.len(),
+                join_ids(stale.iter().map(|id| id.value()))
+            ));
+        }
+
+        let mut missing = Vec::new();
+        for object in self.pool.objects() {
+            if !object_info.contains_key(&object.id()) {
+                object_info.insert(object.id(), ObjectInfo::new(object));
+                missing.push(object.id().value());
+            }
+        }
+        if !missing.is_empty() {
+            fixes.push(format!(
+                "Added info for {} objects without it: {}",
+                missing.len(),
+                join_ids(missing)
+            ));
+        }
+
+        let mut seen = HashSet::new();
+        let mut duplicates = Vec::new();
+        for object in self.pool.objects() {
+            if let Some(info) = object_info.get_mut(&object.id()) {
+                if !seen.insert(info.get_unique_id()) {
+                    info.renew_unique_id();
+                    duplicates.push(object.id().value());
+                }
+            }
+        }
+        if !duplicates.is_empty() {
+            fixes.push(format!(
+                "Gave {} objects sharing a unique identifier with another object a new one: {}",
+                duplicates.len(),
+                join_ids(duplicates)
+            ));
+        }
+
+        drop(object_info);
+        if !fixes.is_empty() {
+            self.index.forget_all_names();
+        }
+        fixes
     }
 
     /// Get the object info for an object id
@@ -273,6 +1098,15 @@ impl EditorProject {
 
     /// Get the current name of the object that is being renamed
     /// Returns None if no object is being renamed
+    /// Start or, with `None`, stop building a macro command by picking a child on the canvas
+    pub fn set_child_move_pick(&self, pick: Option<ChildMovePick>) {
+        self.child_move_pick.replace(pick);
+    }
+
+    pub fn get_child_move_pick(&self) -> Option<ChildMovePick> {
+        self.child_move_pick.borrow().clone()
+    }
+
     pub fn get_renaming_object(&self) -> Option<(eframe::egui::Id, ObjectId, String)> {
         self.renaming_object.borrow().clone()
     }
@@ -286,86 +1120,1511 @@ impl EditorProject {
                 if let Some(info) = object_info.get_mut(&renaming_object.1) {
                     info.set_name(renaming_object.2.clone());
                 }
+                self.index.forget_names(&[renaming_object.1]);
             }
         }
         self.renaming_object.replace(None);
     }
 
-    pub fn sort_objects_by<F>(&mut self, cmp: F)
-    where
-        F: Fn(&Object, &Object) -> std::cmp::Ordering,
-    {
-        self.mut_pool.borrow_mut().objects_mut().sort_by(cmp);
-    }
-
-    /// Get all existing object names for validation
-    pub fn get_all_object_names(&self) -> HashMap<String, ObjectType> {
-        let mut names = HashMap::new();
+    /// The custom names that change when the replacement is applied, as (object, current name,
+    /// new name) in the order of the pool
+    pub fn preview_name_replace(&self, replace: &NameReplace) -> Vec<(ObjectId, String, String)> {
         let object_info = self.object_info.borrow();
-        let mut default_names_cache = self.default_object_names.borrow_mut();
+        self.pool
+            .objects()
+            .iter()
+            .filter_map(|object| {
+                let name = object_info.get(&object.id())?.name.as_ref()?;
+                let new_name = replace.apply(name)?;
+                (new_name != *name).then(|| (object.id(), name.clone(), new_name))
+            })
+            .collect()
+    }
 
-        for object in self.pool.objects() {
-            let name = if let Some(info) = object_info.get(&object.id()) {
-                info.get_name(object)
-            } else {
-                // Use cached default name if available, otherwise generate and cache it
-                default_names_cache
-                    .entry(object.id())
-                    .or_insert_with(|| {
-                        format!(
-                            "Object {} ({})",
-                            object.id().value(),
-                            smart_naming::get_object_type_name(object.object_type())
-                        )
-                    })
-                    .clone()
+    /// The names of the objects after the batch rename, as (object, current name, new name) in
+    /// the given order. Unchanged names are left out.
+    pub fn preview_batch_rename(
+        &self,
+        ids: &[ObjectId],
+        rename: &BatchRename,
+    ) -> Result<Vec<(ObjectId, String, String)>, String> {
+        let mut renamed = Vec::new();
+        for (position, id) in ids.iter().enumerate() {
+            let Some(object) = self.get_object(*id) else {
+                continue;
             };
-            names.entry(name).or_insert(object.object_type());
+            let name = self.get_object_info(object).get_name(object);
+            let new_name = rename.apply(&name, position)?;
+            if new_name != name && !new_name.is_empty() {
+                renamed.push((*id, name, new_name));
+            }
         }
-        names
-    }
-
-    /// Generate a smart default name for a new object
-    pub fn generate_smart_name_for_new_object(&self, object_type: ObjectType) -> String {
-        let existing_names = self.get_all_object_names();
-        smart_naming::generate_smart_default_name(object_type, &existing_names)
+        Ok(renamed)
     }
 
-    /// Apply smart naming to all objects efficiently
-    pub fn apply_smart_naming_to_all_objects(&self) {
+    /// Give objects a new name at once, e.g. after a find and replace. The renaming is a single
+    /// undo step, together with the other changes of the frame.
+    pub fn rename_objects(&self, names: &[(ObjectId, String)]) {
         let mut object_info = self.object_info.borrow_mut();
-
-        // Build existing names map once for all remaining objects
-        let mut existing_names = HashMap::new();
-        for (id, info) in object_info.iter() {
-            if let Some(name) = &info.name {
-                if let Some(object) = self.pool.object_by_id(*id) {
-                    existing_names
-                        .entry(name.clone())
-                        .or_insert(object.object_type());
-                }
+        let mut pending_renames = self.pending_renames.borrow_mut();
+        let mut_pool = self.mut_pool.borrow();
+        for (id, name) in names {
+            // Objects of an imported pool have no info until it is asked for
+            let Some(object) = mut_pool.object_by_id(*id) else {
+                continue;
+            };
+            let info = object_info
+                .entry(*id)
+                .or_insert_with(|| ObjectInfo::new(object));
+            if name.is_empty() || info.name.as_ref() == Some(name) {
+                continue;
+            }
+            if !pending_renames.iter().any(|(renamed, _)| renamed == id) {
+                pending_renames.push((*id, info.name.clone()));
             }
+            info.set_name(name.clone());
+            self.pending_actions
+                .borrow_mut()
+                .push(("RenameObjects", Some(*id)));
         }
+        drop(object_info);
+        let ids: Vec<ObjectId> = names.iter().map(|(id, _)| *id).collect();
+        self.index.forget_names(&ids);
+        self.modified.replace(true);
+    }
 
-        // Generate names for remaining objects
-        for object in self.pool.objects() {
-            let new_name =
-                smart_naming::generate_smart_default_name(object.object_type(), &existing_names);
+    /// Request renaming of the currently selected object
+    pub fn request_rename_selected(&self) {
+        self.rename_selected_requested.replace(true);
+    }
 
-            // Update the count for the new name to ensure uniqueness
-            existing_names
-                .entry(new_name.clone())
-                .or_insert(object.object_type());
+    /// Take the pending rename request, returns true if renaming was requested
+    pub fn take_rename_selected_request(&self) -> bool {
+        self.rename_selected_requested.replace(false)
+    }
 
-            let info = object_info
-                .entry(object.id())
-                .or_insert_with(|| ObjectInfo::new(object));
-            info.set_name(new_name);
+    pub fn sort_objects_by<F>(&self, cmp: F)
+    where
+        F: Fn(&Object, &Object) -> std::cmp::Ordering,
+    {
+        let mut objects: Vec<&Object> = self.pool.objects().iter().collect();
+        objects.sort_by(|a, b| cmp(a, b));
+        let order = objects.iter().map(|obj| obj.id()).collect();
+        if let Err(e) = self.execute(PoolCommand::ReorderObjects(order)) {
+            log::error!("Failed to sort objects: {}", e);
         }
     }
 
+    /// Keep the original in place of its duplicates: all references to the duplicates are
+    /// redirected to the original and the duplicates are removed
+    pub fn merge_duplicates(
+        &self,
+        original: ObjectId,
+        duplicates: &[ObjectId],
+    ) -> Result<(), CommandError> {
+        let commands: Vec<PoolCommand> = duplicates
+            .iter()
+            .flat_map(|duplicate| {
+                [
+                    PoolCommand::RedirectReferences {
+                        from: *duplicate,
+                        to: original,
+                    },
+                    PoolCommand::RemoveObject(*duplicate),
+                ]
+            })
+            .collect();
+        self.check_editable(&commands)?;
+        self.backup_before("Merge duplicate pictures");
+        for command in commands {
+            self.execute(command)?;
+        }
+        Ok(())
+    }
+
+    /// Make the text objects use the font `to` instead of `from`, only those shown on the mask
+    /// if a scope is given. Returns the number of objects that were changed.
+    pub fn substitute_font(
+        &self,
+        from: ObjectId,
+        to: ObjectId,
+        scope: Option<ObjectId>,
+    ) -> Result<usize, CommandError> {
+        let Some(Object::FontAttributes(font)) = self.get_object(to) else {
+            return Err(CommandError::ObjectNotFound(to));
+        };
+        let users = font_substitution::preview_font_substitution(&self.pool, from, font, scope);
+        if users.is_empty() {
+            return Ok(0);
+        }
+        let commands = match scope {
+            None => vec![PoolCommand::RedirectReferences { from, to }],
+            Some(_) => users
+                .iter()
+                .filter_map(|user| {
+                    let mut object = self.get_editable_object(user.object)?;
+                    redirect_references(&mut object, from, to);
+                    Some(PoolCommand::SetObject {
+                        id: user.object,
+                        object,
+                    })
+                })
+                .collect(),
+        };
+        self.check_editable(&commands)?;
+        self.backup_before("Substitute font");
+        for command in commands {
+            self.execute(command)?;
+        }
+        Ok(users.len())
+    }
+
+    /// Set the enabled flag of the input objects among `ids`, e.g. to make a read-only demo
+    /// variant of the pool. Other objects are skipped. All changes are a single undo step.
+    /// Returns the inputs that were changed, in the order of `ids`.
+    pub fn set_inputs_enabled(
+        &self,
+        ids: &[ObjectId],
+        enabled: bool,
+    ) -> Result<Vec<ObjectId>, CommandError> {
+        let mut changes: Vec<(ObjectId, Object)> = Vec::new();
+        for id in ids {
+            let Some(mut object) = self.get_editable_object(*id) else {
+                continue;
+            };
+            let flag = match &mut object {
+                Object::InputBoolean(o) => &mut o.enabled,
+                Object::InputString(o) => &mut o.enabled,
+                Object::InputNumber(o) => &mut o.options2.enabled,
+                Object::InputList(o) => &mut o.options.enabled,
+                _ => continue,
+            };
+            if *flag != enabled {
+                *flag = enabled;
+                changes.push((*id, object));
+            }
+        }
+        if changes.is_empty() {
+            return Ok(Vec::new());
+        }
+
+        self.backup_before(if enabled {
+            "Enable inputs"
+        } else {
+            "Disable inputs"
+        });
+        let mut changed = Vec::new();
+        for (id, object) in changes {
+            self.execute(PoolCommand::SetObject { id, object })?;
+            changed.push(id);
+        }
+        Ok(changed)
+    }
+
+    /// Apply the changes of an imported object list, see
+    /// [`crate::preview_object_list_import`]. All changes are a single undo step.
+    /// Returns the number of changed objects.
+    pub fn import_object_list(&self, changes: &[ObjectListChange]) -> Result<usize, CommandError> {
+        if changes.is_empty() {
+            return Ok(0);
+        }
+        self.backup_before("Import object list");
+        let mut changed: Vec<ObjectId> = Vec::new();
+        for change in changes {
+            if !changed.contains(&change.object) {
+                changed.push(change.object);
+            }
+        }
+        let names: Vec<(ObjectId, String)> = changes
+            .iter()
+            .filter(|change| change.column == ImportColumn::Name)
+            .map(|change| (change.object, change.new.clone()))
+            .collect();
+        for id in &changed {
+            let attributes: Vec<&ObjectListChange> = changes
+                .iter()
+                .filter(|change| change.object == *id && change.column != ImportColumn::Name)
+                .collect();
+            if attributes.is_empty() {
+                continue;
+            }
+            let mut object = self
+                .get_editable_object(*id)
+                .ok_or(CommandError::ObjectNotFound(*id))?;
+            for change in attributes {
+                object_list::set_attribute(&mut object, change.column, &change.new)
+                    .map_err(CommandError::Rejected)?;
+            }
+            self.execute(PoolCommand::SetObject { id: *id, object })?;
+        }
+        self.rename_objects(&names);
+        Ok(changed.len())
+    }
+
+    /// Objects that can't be reached from the working set or the auxiliary objects, in the
+    /// order of the pool. They are never shown and only add to the size of the pool.
+    pub fn find_orphans(&self) -> Vec<ObjectId> {
+        let mut reachable = HashSet::new();
+        let mut pending: Vec<ObjectId> = self
+            .pool
+            .objects()
+            .iter()
+            .filter(|object| is_root_object(object.object_type()))
+            .map(|object| object.id())
+            .collect();
+        while let Some(id) = pending.pop() {
+            if !reachable.insert(id) {
+                continue;
+            }
+            if let Some(object) = self.get_object(id) {
+                pending.extend(object.referenced_objects());
+            }
+        }
+        self.pool
+            .objects()
+            .iter()
+            .map(|object| object.id())
+            .filter(|id| !reachable.contains(id))
+            .collect()
+    }
+
+    /// Remove the orphaned objects, other objects referencing them are orphans as well so no
+    /// references are left dangling
+    pub fn remove_orphans(&self, ids: &[ObjectId]) -> Result<(), CommandError> {
+        self.backup_before("Remove orphan objects");
+        for id in ids {
+            self.execute(PoolCommand::RemoveObject(*id))?;
+        }
+        Ok(())
+    }
+
+    /// Remove macros that never run, see [`crate::MacroUsage::is_dead`]
+    pub fn remove_dead_macros(&self, ids: &[ObjectId]) -> Result<(), CommandError> {
+        self.backup_before("Remove unused macros");
+        for id in ids {
+            self.execute(PoolCommand::RemoveObject(*id))?;
+        }
+        Ok(())
+    }
+
+    /// References to objects that are not in the pool, with the objects referencing them.
+    /// Sorted by the ID of the missing object.
+    pub fn find_broken_references(&self) -> Vec<(ObjectId, Vec<ObjectId>)> {
+        let mut broken: Vec<(ObjectId, Vec<ObjectId>)> = self
+            .index
+            .missing()
+            .into_iter()
+            .map(|missing| {
+                let mut parents = self.index.parents(missing).to_vec();
+                parents.sort_by_key(|id| id.value());
+                parents.dedup();
+                (missing, parents)
+            })
+            .collect();
+        broken.sort_by_key(|(missing, _)| missing.value());
+        broken
+    }
+
+    /// Types of child objects the objects referencing a missing object allow, to suggest what
+    /// the missing object could be replaced by
+    pub fn get_expected_types(&self, missing: ObjectId) -> Vec<ObjectType> {
+        let mut types: Vec<ObjectType> = Vec::new();
+        for parent in self.index.parents(missing) {
+            let Some(object) = self.get_object(*parent) else {
+                continue;
+            };
+            for object_type in get_allowed_child_refs(object.object_type(), self.get_vt_version()) {
+                if !types.contains(&object_type) {
+                    types.push(object_type);
+                }
+            }
+        }
+        types
+    }
+
+    /// Add a default object of the given type with the ID of a missing object, so the
+    /// references to it are valid again
+    pub fn create_placeholder(
+        &self,
+        missing: ObjectId,
+        object_type: ObjectType,
+    ) -> Result<(), CommandError> {
+        let mut placeholder = default_object(object_type);
+        placeholder.mut_id().set_value(missing.value()).ok();
+        self.execute(PoolCommand::AddObject(placeholder))
+    }
+
+    /// Remove the references to a missing object where possible.
+    /// Returns the number of required references that are left, they need another object.
+    pub fn remove_broken_references(&self, missing: ObjectId) -> Result<usize, CommandError> {
+        let mut parents = self.index.parents(missing).to_vec();
+        parents.dedup();
+        let mut left = 0;
+        for parent in parents {
+            let Some(mut object) = self.get_editable_object(parent) else {
+                continue;
+            };
+            let references = object
+                .referenced_objects()
+                .iter()
+                .filter(|id| **id == missing)
+                .count();
+            let removed = remove_references(&mut object, missing);
+            left += references - removed;
+            if removed > 0 {
+                self.execute(PoolCommand::SetObject { id: parent, object })?;
+            }
+        }
+        Ok(left)
+    }
+
+    /// Replace the duplicates by object pointers to the original, so their IDs stay valid for
+    /// the application using the pool
+    pub fn replace_duplicates_with_pointers(
+        &self,
+        original: ObjectId,
+        duplicates: &[ObjectId],
+    ) -> Result<(), CommandError> {
+        self.backup_before("Convert duplicate pictures to pointers");
+        for duplicate in duplicates {
+            self.execute(PoolCommand::SetObject {
+                id: *duplicate,
+                object: Object::ObjectPointer(ObjectPointer {
+                    id: *duplicate,
+                    value: NullableObjectId(Some(original)),
+                }),
+            })?;
+        }
+        Ok(())
+    }
+
+    /// Create a variant of a picture graphic per target width, keeping its aspect ratio.
+    /// The variants are named after the picture and their size; scaled graphics link to the
+    /// picture itself. Returns the IDs of the new objects.
+    pub fn create_scaled_variants(
+        &self,
+        picture: ObjectId,
+        widths: &[u16],
+        kind: ScaledVariantKind,
+    ) -> Result<Vec<ObjectId>, CommandError> {
+        let Some(object) = self.get_editable_object(picture) else {
+            return Err(CommandError::ObjectNotFound(picture));
+        };
+        self.backup_before("Create scaled picture variants");
+        let base_name = self.get_object_info(&object).get_name(&object);
+        let Object::PictureGraphic(original) = object else {
+            return Err(CommandError::ObjectNotFound(picture));
+        };
+
+        let mut created = Vec::new();
+        for &width in widths {
+            let id = self.allocate_object_id_for_type(match kind {
+                ScaledVariantKind::Resampled => ObjectType::PictureGraphic,
+                ScaledVariantKind::ScaledGraphic => ObjectType::ScaledGraphic,
+            });
+            let height = picture_scaling::scaled_height(&original, width);
+            let object = match kind {
+                ScaledVariantKind::Resampled => {
+                    let mut scaled = picture_scaling::resample_picture(&original, width);
+                    scaled.id = id;
+                    Object::PictureGraphic(scaled)
+                }
+                ScaledVariantKind::ScaledGraphic => {
+                    let mut scaled = default_object(ObjectType::ScaledGraphic);
+                    if let Object::ScaledGraphic(o) = &mut scaled {
+                        o.id = id;
+                        o.width = width;
+                        o.height = height;
+                        o.value = NullableObjectId(Some(picture));
+                    }
+                    scaled
+                }
+            };
+            self.execute(PoolCommand::AddObject(object.clone()))?;
+            self.object_info
+                .borrow_mut()
+                .entry(id)
+                .or_insert_with(|| ObjectInfo::new(&object))
+                .set_name(format!("{} {}x{}", base_name, width, height));
+            created.push(id);
+        }
+        self.index.forget_names(&created);
+        Ok(created)
+    }
+
+    /// Store a named copy of the current pool in the project
+    pub fn create_snapshot(&self, name: String) {
+        self.snapshots
+            .borrow_mut()
+            .push(NamedSnapshot::new(name, &self.pool));
+        self.modified.replace(true);
+    }
+
+    /// Name and creation time of the snapshots, oldest first
+    pub fn get_snapshots(&self) -> Vec<(String, u64)> {
+        self.snapshots
+            .borrow()
+            .iter()
+            .map(|snapshot| (snapshot.name.clone(), snapshot.created_at))
+            .collect()
+    }
+
+    pub fn delete_snapshot(&self, index: usize) {
+        let mut snapshots = self.snapshots.borrow_mut();
+        if index < snapshots.len() {
+            snapshots.remove(index);
+            self.modified.replace(true);
+        }
+    }
+
+    /// Changes from the snapshot to the current pool
+    pub fn diff_snapshot(&self, index: usize) -> Vec<ProjectEvent> {
+        match self.snapshots.borrow().get(index) {
+            Some(snapshot) => project_events::pool_changes(&snapshot.load_pool(), &self.pool),
+            None => Vec::new(),
+        }
+    }
+
+    /// Make the pool equal to the snapshot again, as a single undoable step
+    pub fn restore_snapshot(&self, index: usize) -> Result<(), CommandError> {
+        let Some(snapshot) = self
+            .snapshots
+            .borrow()
+            .get(index)
+            .map(NamedSnapshot::load_pool)
+        else {
+            return Ok(());
+        };
+        self.backup_before("Restore snapshot");
+        let current = self.pool.clone();
+        for object in current.objects() {
+            if snapshot.object_by_id(object.id()).is_none() {
+                self.execute(PoolCommand::RemoveObject(object.id()))?;
+            }
+        }
+        for object in snapshot.objects() {
+            match current.object_by_id(object.id()) {
+                Some(existing) if existing == object => {}
+                Some(_) => self.execute(PoolCommand::SetObject {
+                    id: object.id(),
+                    object: object.clone(),
+                })?,
+                None => self.execute(PoolCommand::AddObject(object.clone()))?,
+            }
+        }
+        self.execute(PoolCommand::ReorderObjects(
+            snapshot
+                .objects()
+                .iter()
+                .map(|object| object.id())
+                .collect(),
+        ))
+    }
+
+    /// Bring the changes of another copy of the project into this one, as a single undoable
+    /// step, see [`crate::pool_merge::plan_merge`]. Conflicts are resolved by the choices,
+    /// keeping my version without a choice. Their objects added with an ID that is also added
+    /// here get a new ID, updating their references to it. Objects added from their copy get
+    /// their names. Returns the IDs of their objects that got a new ID and what it became.
+    pub fn apply_merge(
+        &self,
+        items: &[MergeItem],
+        choices: &HashMap<ObjectId, MergeChoice>,
+        their_names: &HashMap<ObjectId, String>,
+    ) -> Result<Vec<(ObjectId, ObjectId)>, CommandError> {
+        self.backup_before("Merge");
+        let pool = self.mut_pool.borrow().clone();
+        let their_ids: HashSet<ObjectId> = items
+            .iter()
+            .filter_map(|item| item.theirs.as_ref().map(Object::id))
+            .collect();
+
+        let mut new_ids = HashMap::new();
+        for item in items
+            .iter()
+            .filter(|item| item.kind == MergeKind::IdConflict)
+        {
+            let Some(object_type) = item.theirs.as_ref().map(Object::object_type) else {
+                continue;
+            };
+            let mut new_id = self.allocate_object_id_for_type(object_type);
+            while pool.object_by_id(new_id).is_some()
+                || their_ids.contains(&new_id)
+                || new_ids.values().any(|id| *id == new_id)
+            {
+                new_id = self.allocate_object_id_for_type(object_type);
+            }
+            new_ids.insert(item.id, new_id);
+        }
+
+        let mut added = Vec::new();
+        for item in items {
+            if item.kind == MergeKind::Conflict
+                && choices.get(&item.id).copied().unwrap_or(MergeChoice::Mine) == MergeChoice::Mine
+            {
+                continue;
+            }
+            let Some(theirs) = &item.theirs else {
+                if pool.object_by_id(item.id).is_some() {
+                    self.execute(PoolCommand::RemoveObject(item.id))?;
+                }
+                continue;
+            };
+            let mut object = theirs.clone();
+            remap_references(&mut object, &new_ids);
+            if let Some(new_id) = new_ids.get(&item.id) {
+                object.mut_id().set_value(new_id.value()).ok();
+            }
+            if object.id() == item.id && pool.object_by_id(item.id).is_some() {
+                self.execute(PoolCommand::SetObject {
+                    id: item.id,
+                    object,
+                })?;
+            } else {
+                added.push((item.id, object.clone()));
+                self.execute(PoolCommand::AddObject(object))?;
+            }
+        }
+
+        for (their_id, object) in &added {
+            if let Some(name) = their_names.get(their_id) {
+                self.object_info
+                    .borrow_mut()
+                    .entry(object.id())
+                    .or_insert_with(|| ObjectInfo::new(object))
+                    .set_name(name.clone());
+            }
+        }
+        let added: Vec<ObjectId> = added.iter().map(|(_, object)| object.id()).collect();
+        self.index.forget_names(&added);
+        Ok(new_ids.into_iter().collect())
+    }
+
+    /// Whether editing the object and its children requires unlocking it first
+    pub fn is_restricted(&self, id: ObjectId) -> bool {
+        self.object_info
+            .borrow()
+            .get(&id)
+            .is_some_and(|info| info.restricted)
+    }
+
+    pub fn set_restricted(&self, id: ObjectId, restricted: bool) {
+        let Some(object) = self.get_object(id) else {
+            return;
+        };
+        self.object_info
+            .borrow_mut()
+            .entry(id)
+            .or_insert_with(|| ObjectInfo::new(object))
+            .restricted = restricted;
+        self.modified.replace(true);
+    }
+
+    pub fn is_unlocked(&self, id: ObjectId) -> bool {
+        self.unlocked_objects.borrow().contains(&id)
+    }
+
+    /// Allow or disallow editing a restricted object and its children for this session
+    pub fn set_unlocked(&self, id: ObjectId, unlocked: bool) {
+        let mut unlocked_objects = self.unlocked_objects.borrow_mut();
+        if unlocked {
+            unlocked_objects.insert(id);
+        } else {
+            unlocked_objects.remove(&id);
+        }
+    }
+
+    /// The locked restricted object that prevents editing the given object, either the object
+    /// itself or one of the objects showing it
+    pub fn get_restricting_object(&self, id: ObjectId) -> Option<ObjectId> {
+        let mut visited = HashSet::new();
+        let mut pending = vec![id];
+        while let Some(id) = pending.pop() {
+            if !visited.insert(id) {
+                continue;
+            }
+            if self.is_restricted(id) && !self.is_unlocked(id) {
+                return Some(id);
+            }
+            pending.extend(self.index.parents(id).iter().copied());
+        }
+        None
+    }
+
+    /// Changes from the given pool, e.g. a released IOP file, to the pool of the project
+    pub fn compare_with_pool(&self, pool: &ObjectPool) -> Vec<ProjectEvent> {
+        project_events::pool_changes(pool, &self.pool)
+    }
+
+    /// Language codes of the working set, the languages the text of the pool can be translated to
+    pub fn get_language_codes(&self) -> Vec<String> {
+        self.pool
+            .working_set_object()
+            .map(|working_set| working_set.language_codes.clone())
+            .unwrap_or_default()
+    }
+
+    /// Get the text of an object in the given language, if it is translated
+    pub fn get_translation(&self, id: ObjectId, language: &str) -> Option<String> {
+        self.object_info
+            .borrow()
+            .get(&id)
+            .and_then(|info| info.get_translation(language))
+            .map(str::to_string)
+    }
+
+    /// Set or, with `None`, remove the text of an object in the given language
+    pub fn set_translation(&self, id: ObjectId, language: &str, text: Option<String>) {
+        let Some(object) = self.get_object(id) else {
+            return;
+        };
+        let old_text = self.get_translation(id, language);
+        self.audit_log.borrow_mut().record(
+            "SetTranslation",
+            format!("Change {} text of object {}", language, id.value()),
+            Some(id.value()),
+            old_text,
+            text.clone(),
+        );
+        self.object_info
+            .borrow_mut()
+            .entry(id)
+            .or_insert_with(|| ObjectInfo::new(object))
+            .set_translation(language, text);
+        self.modified.replace(true);
+        self.preview_pool.replace(None);
+    }
+
+    /// Language the masks are previewed in, `None` if the text of the pool itself is shown
+    pub fn get_preview_language(&self) -> Option<String> {
+        self.preview_language.borrow().clone()
+    }
+
+    pub fn set_preview_language(&self, language: Option<String>) {
+        self.preview_language.replace(language);
+        self.preview_pool.replace(None);
+    }
+
+    /// The pool to preview the masks with, translated to the preview language if one is set
+    pub fn get_preview_pool(&self) -> PoolSnapshot {
+        let Some(language) = self.get_preview_language() else {
+            return self.pool.clone();
+        };
+        let generation = self.get_pool_generation();
+        let mut preview_pool = self.preview_pool.borrow_mut();
+        match preview_pool.as_ref() {
+            Some((pool_generation, pool)) if *pool_generation == generation => pool.clone(),
+            _ => {
+                let pool = Arc::new(self.localized_pool(&language));
+                *preview_pool = Some((generation, pool.clone()));
+                pool
+            }
+        }
+    }
+
+    /// Drawing commands previewed on a graphics context, one command per line
+    pub fn get_graphics_commands(&self, id: ObjectId) -> String {
+        self.object_info
+            .borrow()
+            .get(&id)
+            .map(|info| info.graphics_commands.clone())
+            .unwrap_or_default()
+    }
+
+    pub fn set_graphics_commands(&self, id: ObjectId, commands: String) {
+        let Some(object) = self.get_object(id) else {
+            return;
+        };
+        self.object_info
+            .borrow_mut()
+            .entry(id)
+            .or_insert_with(|| ObjectInfo::new(object))
+            .graphics_commands = commands;
+        self.modified.replace(true);
+        self.graphics_previews.replace(None);
+    }
+
+    /// The valid drawing commands previewed per graphics context
+    pub fn get_graphics_previews(&self) -> Arc<HashMap<ObjectId, Vec<GraphicsCommand>>> {
+        self.graphics_previews
+            .borrow_mut()
+            .get_or_insert_with(|| {
+                Arc::new(
+                    self.object_info
+                        .borrow()
+                        .iter()
+                        .filter(|(_, info)| !info.graphics_commands.is_empty())
+                        .map(|(id, info)| {
+                            (
+                                *id,
+                                graphics_commands::parse_commands(&info.graphics_commands).0,
+                            )
+                        })
+                        .collect(),
+                )
+            })
+            .clone()
+    }
+
+    /// Duplicate an object with the objects it shows, e.g. to build repeated screen sections.
+    /// Objects shared between objects, like attributes, macros and pictures, are referenced by
+    /// the duplicate instead of duplicated. The duplicate is placed below the original in
+    /// `parent`, or else in the first parent of the original.
+    /// Returns the ID of the duplicate.
+    pub fn duplicate_with_children(
+        &self,
+        id: ObjectId,
+        parent: Option<ObjectId>,
+    ) -> Result<ObjectId, String> {
+        let pool = self.mut_pool.borrow().clone();
+        let mut subtree = Vec::new();
+        let mut pending = vec![id];
+        while let Some(id) = pending.pop() {
+            if subtree.contains(&id) {
+                continue;
+            }
+            let Some(object) = pool.object_by_id(id) else {
+                continue;
+            };
+            if !subtree.is_empty() && is_shared_resource(object.object_type()) {
+                continue;
+            }
+            subtree.push(id);
+            pending.extend(object.referenced_objects().iter().rev());
+        }
+        if subtree.is_empty() {
+            return Err(format!("Object {} not found", id.value()));
+        }
+
+        let mut new_ids = HashMap::new();
+        for old_id in &subtree {
+            let Some(object_type) = pool.object_by_id(*old_id).map(|o| o.object_type()) else {
+                continue;
+            };
+            let mut new_id = self.allocate_object_id_for_type(object_type);
+            while pool.object_by_id(new_id).is_some() {
+                new_id = self.allocate_object_id_for_type(object_type);
+            }
+            new_ids.insert(*old_id, new_id);
+        }
+
+        for old_id in &subtree {
+            let Some(original) = pool.object_by_id(*old_id) else {
+                continue;
+            };
+            let mut object = original.clone();
+            for (from, to) in &new_ids {
+                redirect_references(&mut object, *from, *to);
+            }
+            object.mut_id().set_value(new_ids[old_id].value()).ok();
+            self.execute(PoolCommand::AddObject(object.clone()))
+                .map_err(|e| e.to_string())?;
+            let name = self
+                .object_info
+                .borrow()
+                .get(old_id)
+                .and_then(|info| info.name.as_ref().map(|name| format!("{} (copy)", name)));
+            if let Some(name) = name {
+                self.object_info
+                    .borrow_mut()
+                    .entry(object.id())
+                    .or_insert_with(|| ObjectInfo::new(&object))
+                    .set_name(name);
+            }
+        }
+
+        // Place the duplicate below the original in the parent
+        let duplicate = new_ids[&id];
+        let parent = parent.or_else(|| self.index.parents(id).first().copied());
+        if let Some(mut parent_object) =
+            parent.and_then(|parent| pool.object_by_id(parent).cloned())
+        {
+            let (_, height) = pool
+                .object_by_id(id)
+                .map_or((0, 0), |object| pool.content_size(object));
+            if let Some(refs) = commands::object_refs_mut(&mut parent_object) {
+                if let Some(idx) = refs.iter().position(|object_ref| object_ref.id == id) {
+                    let mut offset = refs[idx].offset;
+                    offset.y = offset.y.saturating_add(height.min(i16::MAX as u16) as i16);
+                    refs.insert(
+                        idx + 1,
+                        ObjectRef {
+                            id: duplicate,
+                            offset,
+                        },
+                    );
+                    self.execute(PoolCommand::SetObject {
+                        id: parent_object.id(),
+                        object: parent_object,
+                    })
+                    .map_err(|e| e.to_string())?;
+                }
+            }
+        }
+        Ok(duplicate)
+    }
+
+    /// Add an empty container for every region of the layout preset to the data mask, named
+    /// after the region. Returns the IDs of the containers.
+    pub fn apply_layout_preset(
+        &self,
+        mask: ObjectId,
+        preset: LayoutPreset,
+        margin: u16,
+        gap: u16,
+    ) -> Result<Vec<ObjectId>, CommandError> {
+        let Some(Object::DataMask(mut data_mask)) = self.get_editable_object(mask) else {
+            return Err(CommandError::ObjectNotFound(mask));
+        };
+
+        let mut created = Vec::new();
+        for region in preset.regions(self.mask_size, margin, gap) {
+            let id = self.allocate_object_id_for_type(ObjectType::Container);
+            let mut container = default_object(ObjectType::Container);
+            if let Object::Container(o) = &mut container {
+                o.id = id;
+                o.width = region.width;
+                o.height = region.height;
+            }
+            self.execute(PoolCommand::AddObject(container.clone()))?;
+            self.object_info
+                .borrow_mut()
+                .entry(id)
+                .or_insert_with(|| ObjectInfo::new(&container))
+                .set_name(region.name.to_string());
+            data_mask.object_refs.push(ObjectRef {
+                id,
+                offset: Point {
+                    x: region.x,
+                    y: region.y,
+                },
+            });
+            created.push(id);
+        }
+        self.execute(PoolCommand::SetObject {
+            id: mask,
+            object: Object::DataMask(data_mask),
+        })?;
+        self.index.forget_names(&created);
+        Ok(created)
+    }
+
+    /// Move the child reference at `from_index` of `from_parent` to `to_parent`, before or after
+    /// (when the flag is set) its child at the index of `to_sibling`, or else after its last
+    /// child. Indexes are positions in [`Object::referenced_objects`], as in the hierarchy.
+    /// Moving within a parent changes the z-order. Moving to another parent keeps the offset of
+    /// the reference and is only allowed for child types the parent allows, without creating a
+    /// circular reference.
+    pub fn move_child(
+        &self,
+        from_parent: ObjectId,
+        from_index: usize,
+        to_parent: ObjectId,
+        to_sibling: Option<(usize, bool)>,
+    ) -> Result<(), CommandError> {
+        let mut source = self
+            .get_editable_object(from_parent)
+            .ok_or(CommandError::ObjectNotFound(from_parent))?;
+        let from =
+            child_ref_position(&mut source, from_index).ok_or(CommandError::IndexOutOfRange {
+                parent: from_parent,
+                index: from_index,
+            })?;
+        let mut target = if to_parent == from_parent {
+            source.clone()
+        } else {
+            self.get_editable_object(to_parent)
+                .ok_or(CommandError::ObjectNotFound(to_parent))?
+        };
+        let target_len = commands::object_refs_mut(&mut target)
+            .ok_or(CommandError::NoChildReferences(to_parent))?
+            .len();
+        let to = to_sibling
+            .and_then(|(index, after)| {
+                child_ref_position(&mut target, index).map(|position| position + after as usize)
+            })
+            .unwrap_or(target_len);
+
+        if to_parent == from_parent {
+            // The reference is removed before it is inserted again
+            let to = if to > from { to - 1 } else { to };
+            if to == from {
+                return Ok(());
+            }
+            return self.execute(PoolCommand::MoveRef {
+                parent: from_parent,
+                from,
+                to,
+            });
+        }
+
+        let refs = commands::object_refs_mut(&mut source)
+            .ok_or(CommandError::NoChildReferences(from_parent))?;
+        let child = refs[from].id;
+        let child_type = self
+            .get_object(child)
+            .ok_or(CommandError::ObjectNotFound(child))?
+            .object_type();
+        if !get_allowed_child_refs(target.object_type(), self.get_vt_version())
+            .contains(&child_type)
+        {
+            return Err(CommandError::Rejected(format!(
+                "{:?} objects can't be children of {:?} objects",
+                child_type,
+                target.object_type()
+            )));
+        }
+        if would_create_circular_reference(&self.pool, to_parent, child) {
+            return Err(CommandError::Rejected(format!(
+                "Object {} contains object {}",
+                child.value(),
+                to_parent.value()
+            )));
+        }
+
+        let object_ref = refs.remove(from);
+        if let Some(refs) = commands::object_refs_mut(&mut target) {
+            refs.insert(to, object_ref);
+        }
+        self.execute(PoolCommand::SetObject {
+            id: from_parent,
+            object: source,
+        })?;
+        self.execute(PoolCommand::SetObject {
+            id: to_parent,
+            object: target,
+        })
+    }
+
+    /// Clipboard text holding the objects with their custom names, see [`Self::paste_objects`]
+    pub fn copy_objects(&self, ids: &[ObjectId]) -> String {
+        let object_info = self.object_info.borrow();
+        let objects: Vec<(Object, Option<String>)> = ids
+            .iter()
+            .filter_map(|id| self.pool.object_by_id(*id))
+            .map(|object| {
+                let name = object_info
+                    .get(&object.id())
+                    .and_then(|info| info.name.clone());
+                (object.clone(), name)
+            })
+            .collect();
+        clipboard::encode_objects(&objects)
+    }
+
+    /// Add the objects of clipboard text made by [`Self::copy_objects`], possibly in another
+    /// designer. Objects whose ID is in use get a new ID, references between the pasted objects
+    /// follow the new IDs. Returns the IDs of the pasted objects.
+    pub fn paste_objects(&self, text: &str) -> Result<Vec<ObjectId>, String> {
+        let objects = clipboard::decode_objects(text)?;
+        let pasted_ids: HashSet<ObjectId> = objects.iter().map(|(object, _)| object.id()).collect();
+        let pool = self.mut_pool.borrow();
+
+        // New IDs must neither be in use nor be the ID of another pasted object, so redirecting
+        // a reference never redirects it a second time
+        let mut new_ids = HashMap::new();
+        for (object, _) in &objects {
+            if pool.object_by_id(object.id()).is_some() {
+                let mut id = self.allocate_object_id_for_type(object.object_type());
+                while pasted_ids.contains(&id) || pool.object_by_id(id).is_some() {
+                    id = self.allocate_object_id_for_type(object.object_type());
+                }
+                new_ids.insert(object.id(), id);
+            }
+        }
+        drop(pool);
+
+        let mut added = Vec::new();
+        for (mut object, name) in objects {
+            for (from, to) in &new_ids {
+                redirect_references(&mut object, *from, *to);
+            }
+            if let Some(id) = new_ids.get(&object.id()) {
+                object.mut_id().set_value(id.value()).ok();
+            }
+            let id = object.id();
+            self.execute(PoolCommand::AddObject(object.clone()))
+                .map_err(|e| e.to_string())?;
+            if let Some(name) = name {
+                self.object_info
+                    .borrow_mut()
+                    .entry(id)
+                    .or_insert_with(|| ObjectInfo::new(&object))
+                    .set_name(name);
+            }
+            added.push(id);
+        }
+        Ok(added)
+    }
+
+    /// The object with all objects it references directly or indirectly, the object first.
+    /// Masks referenced further down, e.g. by macros, are left out.
+    pub fn referenced_subtree(&self, id: ObjectId) -> Vec<ObjectId> {
+        let mut subtree = Vec::new();
+        let mut pending = vec![id];
+        while let Some(id) = pending.pop() {
+            if subtree.contains(&id) {
+                continue;
+            }
+            let Some(object) = self.pool.object_by_id(id) else {
+                continue;
+            };
+            if !subtree.is_empty()
+                && matches!(
+                    object.object_type(),
+                    ObjectType::WorkingSet | ObjectType::DataMask | ObjectType::AlarmMask
+                )
+            {
+                continue;
+            }
+            subtree.push(id);
+            pending.extend(object.referenced_objects().iter().rev());
+        }
+        subtree
+    }
+
+    /// The object with everything it references as a pool of its own, e.g. to share a widget
+    /// between projects. With `renumber` the objects get IDs from 0 in the order of
+    /// [`Self::referenced_subtree`], else they keep their IDs.
+    pub fn subtree_pool(&self, id: ObjectId, renumber: bool) -> ObjectPool {
+        let subtree = self.referenced_subtree(id);
+        let new_ids: HashMap<ObjectId, ObjectId> = if renumber {
+            subtree
+                .iter()
+                .zip(0..)
+                .filter_map(|(id, new_id)| ObjectId::new(new_id).ok().map(|new_id| (*id, new_id)))
+                .collect()
+        } else {
+            HashMap::new()
+        };
+
+        let mut pool = ObjectPool::default();
+        for id in &subtree {
+            let Some(mut object) = self.pool.object_by_id(*id).cloned() else {
+                continue;
+            };
+            if let Some(new_id) = new_ids.get(id) {
+                remap_references(&mut object, &new_ids);
+                object.mut_id().set_value(new_id.value()).ok();
+            }
+            pool.add(object);
+        }
+        pool
+    }
+
+    /// Make a template of the object and everything it references
+    pub fn create_template(&self, id: ObjectId, name: String) -> Option<Template> {
+        let root_type = self.pool.object_by_id(id)?.object_type();
+        let subtree = self.referenced_subtree(id);
+        Some(Template {
+            name,
+            root_type: format!("{:?}", root_type),
+            object_count: subtree.len(),
+            objects: self.copy_objects(&subtree),
+        })
+    }
+
+    /// Add the objects of a template, with new IDs where they are in use.
+    /// Returns the ID of the root object of the inserted template.
+    pub fn insert_template(&self, template: &Template) -> Result<ObjectId, String> {
+        self.paste_objects(&template.objects)?
+            .first()
+            .copied()
+            .ok_or_else(|| "The template is empty".to_string())
+    }
+
+    /// Whether the object is shown blinking in the preview
+    pub fn is_blinking(&self, id: ObjectId) -> bool {
+        self.object_info
+            .borrow()
+            .get(&id)
+            .is_some_and(|info| info.blinking)
+    }
+
+    pub fn set_blinking(&self, id: ObjectId, blinking: bool) {
+        let Some(object) = self.get_object(id) else {
+            return;
+        };
+        self.object_info
+            .borrow_mut()
+            .entry(id)
+            .or_insert_with(|| ObjectInfo::new(object))
+            .blinking = blinking;
+        self.modified.replace(true);
+        self.blinking_objects.replace(None);
+    }
+
+    /// The objects shown blinking in the preview
+    pub fn get_blinking_objects(&self) -> Arc<HashSet<ObjectId>> {
+        self.blinking_objects
+            .borrow_mut()
+            .get_or_insert_with(|| {
+                Arc::new(
+                    self.object_info
+                        .borrow()
+                        .iter()
+                        .filter(|(_, info)| info.blinking)
+                        .map(|(id, _)| *id)
+                        .collect(),
+                )
+            })
+            .clone()
+    }
+
+    /// The identifier used instead of the name of the object in exported headers and code
+    pub fn get_export_alias(&self, id: ObjectId) -> Option<String> {
+        self.object_info
+            .borrow()
+            .get(&id)
+            .and_then(|info| info.export_alias.clone())
+    }
+
+    /// Set the export alias of the object, an empty alias removes it
+    pub fn set_export_alias(&self, id: ObjectId, alias: Option<String>) {
+        let Some(object) = self.get_object(id) else {
+            return;
+        };
+        self.object_info
+            .borrow_mut()
+            .entry(id)
+            .or_insert_with(|| ObjectInfo::new(object))
+            .export_alias = alias.filter(|alias| !alias.trim().is_empty());
+        self.modified.replace(true);
+    }
+
+    /// The notes of the object, empty if it has none
+    pub fn get_notes(&self, id: ObjectId) -> String {
+        self.object_info
+            .borrow()
+            .get(&id)
+            .map(|info| info.notes.clone())
+            .unwrap_or_default()
+    }
+
+    pub fn set_notes(&self, id: ObjectId, notes: String) {
+        let Some(object) = self.get_object(id) else {
+            return;
+        };
+        self.object_info
+            .borrow_mut()
+            .entry(id)
+            .or_insert_with(|| ObjectInfo::new(object))
+            .notes = notes;
+        self.modified.replace(true);
+    }
+
+    /// The tags of the object, in the order they were added
+    pub fn get_tags(&self, id: ObjectId) -> Vec<String> {
+        self.object_info
+            .borrow()
+            .get(&id)
+            .map(|info| info.tags.clone())
+            .unwrap_or_default()
+    }
+
+    pub fn set_tags(&self, id: ObjectId, tags: Vec<String>) {
+        let Some(object) = self.get_object(id) else {
+            return;
+        };
+        self.object_info
+            .borrow_mut()
+            .entry(id)
+            .or_insert_with(|| ObjectInfo::new(object))
+            .tags = tags;
+        self.index.forget_names(&[id]);
+        self.modified.replace(true);
+    }
+
+    /// All tags used in the project, sorted
+    pub fn get_all_tags(&self) -> Vec<String> {
+        let mut tags: Vec<String> = self
+            .object_info
+            .borrow()
+            .values()
+            .flat_map(|info| info.tags.iter().cloned())
+            .collect();
+        tags.sort();
+        tags.dedup();
+        tags
+    }
+
+    /// The image file the picture graphic was imported from, if it is linked
+    pub fn get_image_source(&self, id: ObjectId) -> Option<ImageSource> {
+        self.object_info
+            .borrow()
+            .get(&id)
+            .and_then(|info| info.image_source.clone())
+    }
+
+    pub fn set_image_source(&self, id: ObjectId, source: Option<ImageSource>) {
+        let Some(object) = self.get_object(id) else {
+            return;
+        };
+        self.object_info
+            .borrow_mut()
+            .entry(id)
+            .or_insert_with(|| ObjectInfo::new(object))
+            .image_source = source;
+        self.modified.replace(true);
+    }
+
+    /// Import the linked image of every picture graphic again if the file changed on disk since
+    /// it was imported. Relative links are resolved against the project directory.
+    /// Returns the updated pictures, and a message per picture that couldn't be updated.
+    #[cfg(not(target_arch = "wasm32"))]
+    pub fn reimport_changed_images(
+        &self,
+        project_dir: Option<&std::path::Path>,
+    ) -> (Vec<ObjectId>, Vec<String>) {
+        let mut errors = Vec::new();
+        let mut changed: Vec<(ObjectId, ImageSource, std::path::PathBuf, u64)> = self
+            .object_info
+            .borrow()
+            .iter()
+            .filter_map(|(id, info)| {
+                let source = info.image_source.clone()?;
+                let path = source.resolve(project_dir);
+                match crate::image_import::modified_time(&path) {
+                    Some(modified) if modified > source.modified => {
+                        Some((*id, source, path, modified))
+                    }
+                    Some(_) => None,
+                    None => {
+                        errors.push(format!(
+                            "Object {}: {} not found",
+                            id.value(),
+                            path.display()
+                        ));
+                        None
+                    }
+                }
+            })
+            .collect();
+        changed.sort_by_key(|(id, ..)| id.value());
+        if changed.is_empty() {
+            return (Vec::new(), errors);
+        }
+
+        self.backup_before("Re-import changed images");
+        let palette = self.pool.get_colour_palette();
+        let mut updated = Vec::new();
+        for (id, source, path, modified) in changed {
+            let Some(Object::PictureGraphic(mut picture)) = self.get_editable_object(id) else {
+                continue;
+            };
+            let result = std::fs::read(&path)
+                .map_err(|e| e.to_string())
+                .and_then(|content| {
+                    let format = picture.format;
+                    crate::image_import::import_image_as(&mut picture, &content, format, &palette)
+                })
+                .and_then(|_| {
+                    self.execute(PoolCommand::SetObject {
+                        id,
+                        object: Object::PictureGraphic(picture),
+                    })
+                    .map_err(|e| e.to_string())
+                });
+            match result {
+                Ok(()) => {
+                    self.set_image_source(id, Some(ImageSource { modified, ..source }));
+                    updated.push(id);
+                }
+                Err(e) => errors.push(format!("Object {}: {}", id.value(), e)),
+            }
+        }
+        (updated, errors)
+    }
+
+    /// Copy of the pool with the text of the objects in the given language
+    pub fn localized_pool(&self, language: &str) -> ObjectPool {
+        let mut pool = ObjectPool::clone(&self.pool);
+        localization::apply_translations(&mut pool, &self.object_info.borrow(), language);
+        pool
+    }
+
+    /// Get all existing object names for validation
+    pub fn get_all_object_names(&self) -> HashMap<String, ObjectType> {
+        let mut names = HashMap::new();
+        let object_info = self.object_info.borrow();
+        let mut default_names_cache = self.default_object_names.borrow_mut();
+
+        for object in self.pool.objects() {
+            let name = if let Some(info) = object_info.get(&object.id()) {
+                info.get_name(object)
+            } else {
+                // Use cached default name if available, otherwise generate and cache it
+                default_names_cache
+                    .entry(object.id())
+                    .or_insert_with(|| {
+                        format!(
+                            "Object {} ({})",
+                            object.id().value(),
+                            smart_naming::get_object_type_name(object.object_type())
+                        )
+                    })
+                    .clone()
+            };
+            names.entry(name).or_insert(object.object_type());
+        }
+        names
+    }
+
+    /// Use the naming rules of the settings for smart naming
+    pub fn set_naming_rules(&self, rules: &NamingRules) {
+        if *self.naming_rules.borrow() != *rules {
+            self.naming_rules.replace(rules.clone());
+        }
+    }
+
+    /// Generate a smart default name for a new object
+    pub fn generate_smart_name_for_new_object(&self, object_type: ObjectType) -> String {
+        let existing_names = self.get_all_object_names();
+        smart_naming::generate_smart_default_name(
+            object_type,
+            &existing_names,
+            &self.naming_rules.borrow(),
+        )
+    }
+
+    /// Propose smart names for all objects, e.g. after importing a pool. Names that look
+    /// intentional are marked, they are kept by default and the proposed names don't collide
+    /// with them. Objects whose name wouldn't change are left out.
+    pub fn preview_smart_naming(&self) -> Vec<SmartRename> {
+        let rules = self.naming_rules.borrow();
+        let parent_naming = *self.parent_naming.borrow();
+        let object_info = self.object_info.borrow();
+        let current_name = |id: ObjectId| object_info.get(&id).and_then(|info| info.name.clone());
+        let context_object = |id: ObjectId| {
+            parent_naming
+                .then(|| self.naming_context_object(id))
+                .flatten()
+        };
+        let is_intentional = |object: &Object, name: &str| {
+            let context = context_object(object.id()).and_then(current_name);
+            smart_naming::looks_intentional(name, object.object_type(), context.as_deref(), &rules)
+        };
+
+        // Build existing names map once with the names that are kept
+        let mut existing_names = HashMap::new();
+        for object in self.pool.objects() {
+            if let Some(name) = current_name(object.id()) {
+                if is_intentional(object, &name) {
+                    existing_names.insert(name, object.object_type());
+                }
+            }
+        }
+
+        // Masks and containers first, so their children are named after their new names
+        let mut objects: Vec<&Object> = self.pool.objects().iter().collect();
+        if parent_naming {
+            objects.sort_by_key(|object| match object.object_type() {
+                ObjectType::Container => 1,
+                object_type if smart_naming::CONTEXT_OBJECT_TYPES.contains(&object_type) => 0,
+                _ => 2,
+            });
+        }
+        let mut context_names: HashMap<ObjectId, String> = HashMap::new();
+
+        let mut renames = Vec::new();
+        for object in objects {
+            let context = context_object(object.id()).and_then(|context| {
+                context_names
+                    .get(&context)
+                    .cloned()
+                    .or_else(|| current_name(context))
+            });
+            let new_name = match &context {
+                Some(context) => smart_naming::generate_smart_name_in_context(
+                    object.object_type(),
+                    context,
+                    &existing_names,
+                    &rules,
+                ),
+                None => smart_naming::generate_smart_default_name(
+                    object.object_type(),
+                    &existing_names,
+                    &rules,
+                ),
+            };
+            // Update the count for the new name to ensure uniqueness
+            existing_names
+                .entry(new_name.clone())
+                .or_insert(object.object_type());
+
+            let name = current_name(object.id());
+            let intentional = name
+                .as_ref()
+                .is_some_and(|name| is_intentional(object, name));
+            if smart_naming::CONTEXT_OBJECT_TYPES.contains(&object.object_type()) {
+                let kept = name.clone().filter(|_| intentional);
+                context_names.insert(object.id(), kept.unwrap_or_else(|| new_name.clone()));
+            }
+            if name.as_ref() == Some(&new_name) {
+                continue;
+            }
+            renames.push(SmartRename {
+                object: object.id(),
+                intentional,
+                name: name.unwrap_or_else(|| ObjectInfo::new(object).get_name(object)),
+                new_name,
+            });
+        }
+        renames.sort_by_key(|rename| self.index.position(rename.object));
+        renames
+    }
+
+    /// The nearest mask or container showing the object, whose name precedes the name of the
+    /// object when naming in the context of the parent
+    fn naming_context_object(&self, id: ObjectId) -> Option<ObjectId> {
+        let mut visited = HashSet::new();
+        let mut pending: VecDeque<ObjectId> = self.index.parents(id).iter().copied().collect();
+        while let Some(parent) = pending.pop_front() {
+            if !visited.insert(parent) {
+                continue;
+            }
+            let is_context = self.pool.object_by_id(parent).is_some_and(|object| {
+                smart_naming::CONTEXT_OBJECT_TYPES.contains(&object.object_type())
+            });
+            if is_context {
+                return Some(parent);
+            }
+            pending.extend(self.index.parents(parent));
+        }
+        None
+    }
+
+    /// Whether generated names are preceded by the name of the mask or container showing the
+    /// object, e.g. "Main Button 2"
+    pub fn is_parent_naming(&self) -> bool {
+        *self.parent_naming.borrow()
+    }
+
+    pub fn set_parent_naming(&self, parent_naming: bool) {
+        if self.parent_naming.replace(parent_naming) != parent_naming {
+            self.modified.replace(true);
+        }
+    }
+
+    /// The VT version the pool is made for, objects and attributes of later versions are not offered
+    pub fn get_vt_version(&self) -> VtVersion {
+        vt_version(*self.vt_version.borrow())
+    }
+
+    pub fn set_vt_version(&self, number: u8) {
+        if self.vt_version.replace(number) != number {
+            self.modified.replace(true);
+        }
+    }
+
+    /// Whether the VT version the pool is made for has what was introduced in `required`
+    pub fn supports_vt_version(&self, required: VtVersion) -> bool {
+        self.get_vt_version() >= required
+    }
+
     /// Apply smart naming to an existing object if it doesn't have a custom name
     pub fn apply_smart_naming_to_object(&self, object: &Object) {
+        self.index.forget_names(&[object.id()]);
         let mut object_info = self.object_info.borrow_mut();
 
         // Check if the object already has a name
@@ -398,8 +2657,24 @@ impl EditorProject {
             existing_names.entry(name).or_insert(obj.object_type());
         }
 
-        let new_name =
-            smart_naming::generate_smart_default_name(object.object_type(), &existing_names);
+        let context = self
+            .is_parent_naming()
+            .then(|| self.naming_context_object(object.id()))
+            .flatten()
+            .and_then(|context| object_info.get(&context)?.name.clone());
+        let new_name = match context {
+            Some(context) => smart_naming::generate_smart_name_in_context(
+                object.object_type(),
+                &context,
+                &existing_names,
+                &self.naming_rules.borrow(),
+            ),
+            None => smart_naming::generate_smart_default_name(
+                object.object_type(),
+                &existing_names,
+                &self.naming_rules.borrow(),
+            ),
+        };
 
         let info = object_info
             .entry(object.id())
@@ -409,6 +2684,13 @@ impl EditorProject {
 
     /// Save the project to a file
     pub fn save_project(&self) -> Result<Vec<u8>, serde_json::Error> {
+        let data = self.to_project_bytes()?;
+        self.modified.replace(false);
+        Ok(data)
+    }
+
+    /// Serialize the project in the project file format, without marking it as saved
+    pub fn to_project_bytes(&self) -> Result<Vec<u8>, serde_json::Error> {
         // Make sure we're saving the current state
         let object_info = self.object_info.borrow();
         let selected = if self.mut_selected_object.borrow().0.is_some() {
@@ -417,24 +2699,82 @@ impl EditorProject {
             self.selected_object.0
         };
 
-        let project = ProjectFile::new(&self.pool, &object_info, self.mask_size, selected);
+        let mut expanded_nodes: Vec<Vec<u16>> =
+            self.expanded_nodes.borrow().iter().cloned().collect();
+        expanded_nodes.sort();
+        let settings = ProjectSettings {
+            mask_size: self.mask_size,
+            last_selected: selected.map(|id| id.value()),
+            recent_colours: self.recent_colours.borrow().clone(),
+            palette_colours: self.palette_colours.borrow().clone(),
+            aux_assignments: self.aux_assignments.borrow().clone(),
+            demo_script: self.demo_script.borrow().clone(),
+            guidelines: self.guideline_settings.borrow().clone(),
+            expanded_nodes,
+            parent_naming: *self.parent_naming.borrow(),
+            vt_version: Some(*self.vt_version.borrow()),
+        };
+
+        let project = ProjectFile::new(&self.pool, &object_info, settings)
+            .with_snapshots(self.snapshots.borrow().clone());
         project.to_bytes()
     }
 
     /// Load a project from file data
-    pub fn load_project(data: Vec<u8>) -> Result<Self, String> {
-        let project = ProjectFile::from_bytes(&data)
+    pub fn load_project(data: &[u8]) -> Result<Self, String> {
+        let project = ProjectFile::from_bytes(data)
             .map_err(|e| format!("Failed to parse project file: {}", e))?;
+        Self::from_project_file(&project)
+    }
+
+    /// Load what can be recovered of project file data that can't be loaded, see
+    /// [`ProjectFile::recover`]. Returns the project with a description of the parts that were
+    /// left out. The project is marked as modified, since it differs from the file.
+    pub fn recover_project(data: &[u8]) -> Result<(Self, Vec<String>), String> {
+        let (project, dropped) = ProjectFile::recover(data)?;
+        let editor_project = Self::from_project_file(&project)?;
+        editor_project.set_modified();
+        Ok((editor_project, dropped))
+    }
+
+    fn from_project_file(project: &ProjectFile) -> Result<Self, String> {
         let pool = project.load_pool()?;
         let settings = project.get_settings();
 
         let mut editor_project = EditorProject::from(pool);
         editor_project.mask_size = settings.mask_size;
+        editor_project
+            .recent_colours
+            .replace(settings.recent_colours.clone());
+        editor_project
+            .palette_colours
+            .replace(settings.palette_colours.clone());
+        editor_project
+            .aux_assignments
+            .replace(settings.aux_assignments.clone());
+        editor_project
+            .demo_script
+            .replace(settings.demo_script.clone());
+        editor_project
+            .guideline_settings
+            .replace(settings.guidelines.clone());
+        editor_project
+            .expanded_nodes
+            .replace(settings.expanded_nodes.iter().cloned().collect());
+        editor_project
+            .snapshots
+            .replace(project.get_snapshots().to_vec());
+        editor_project.parent_naming.replace(settings.parent_naming);
+        editor_project.vt_version.replace(
+            settings
+                .vt_version
+                .unwrap_or(BASE_VT_VERSION)
+                .max(required_vt_version(&editor_project.pool)),
+        );
 
         // Restore object metadata
         let metadata = project.get_metadata();
         let mut object_info = editor_project.object_info.borrow_mut();
-        let mut_pool = editor_project.get_mut_pool();
         for object in editor_project.pool.objects() {
             if let Some(meta) = metadata.get(&object.id().value()) {
                 let info = object_info
@@ -443,9 +2783,22 @@ impl EditorProject {
                 if let Some(name) = &meta.name {
                     info.set_name(name.clone());
                 }
+                info.export_alias = meta.export_alias.clone();
+                info.notes = meta.notes.clone().unwrap_or_default();
+                info.tags = meta.tags.clone();
+                info.restricted = meta.restricted;
+                info.graphics_commands = meta.graphics_commands.clone();
+                info.blinking = meta.blinking;
+                info.image_source = meta.image_source.clone();
+                for (language, text) in &meta.translations {
+                    info.set_translation(language, Some(text.clone()));
+                }
             }
         }
         drop(object_info);
+        for fix in editor_project.check_object_info() {
+            log::info!("{}", fix);
+        }
 
         // Apply smart naming to objects without custom names
         for object in editor_project.pool.objects() {
@@ -474,4 +2827,332 @@ impl EditorProject {
     pub fn take_image_load_request(&self) -> Option<ObjectId> {
         self.image_load_request.replace(None)
     }
+
+    /// Request to change the format of a PictureGraphic object, which needs the project directory
+    /// to find its linked image
+    pub fn request_picture_format(&self, object_id: ObjectId, format: PictureGraphicFormat) {
+        self.picture_format_request
+            .replace(Some((object_id, format)));
+    }
+
+    /// Take and clear the picture format request if any
+    pub fn take_picture_format_request(&self) -> Option<(ObjectId, PictureGraphicFormat)> {
+        self.picture_format_request.replace(None)
+    }
+
+    /// Change the format of a picture graphic. The linked image is quantized again when it is
+    /// available, instead of converting the pixels that were already reduced to the old format.
+    /// Relative links are resolved against the project directory.
+    pub fn change_picture_format(
+        &self,
+        id: ObjectId,
+        format: PictureGraphicFormat,
+        project_dir: Option<&std::path::Path>,
+    ) -> Result<(), String> {
+        let Some(Object::PictureGraphic(mut picture)) = self.get_editable_object(id) else {
+            return Err(format!("Object {} is not a picture graphic", id.value()));
+        };
+        let palette = self.pool.get_colour_palette();
+        let source = self
+            .get_image_source(id)
+            .and_then(|source| read_image_source(&source, project_dir));
+        match source {
+            Some(content) => {
+                crate::image_import::import_image_as(&mut picture, &content, format, &palette)?
+            }
+            None => picture_scaling::convert_format(&mut picture, format, &palette),
+        }
+        self.execute(PoolCommand::SetObject {
+            id,
+            object: Object::PictureGraphic(picture),
+        })
+        .map_err(|e| e.to_string())
+    }
+
+    /// Store a backup of the project before a bulk operation changes it, independent of the
+    /// undo history. The result is kept to be shown once the operation is done.
+    pub fn backup_before(&self, operation: &str) {
+        let location = self
+            .to_project_bytes()
+            .map_err(|e| e.to_string())
+            .and_then(|data| backup::write_backup(&data, operation));
+        match &location {
+            Ok(location) => log::info!("Backed up project to {} before: {}", location, operation),
+            Err(e) => log::error!("Failed to back up project before {}: {}", operation, e),
+        }
+        self.backup_notice.replace(Some(BackupNotice {
+            operation: operation.to_string(),
+            location,
+        }));
+    }
+
+    /// Take and clear the backup made before the last bulk operation, if any
+    pub fn take_backup_notice(&self) -> Option<BackupNotice> {
+        self.backup_notice.replace(None)
+    }
+
+    /// Remember a colour that was picked in the colour selector
+    pub fn note_colour_used(&self, colour: u8) {
+        let mut recent = self.recent_colours.borrow_mut();
+        recent.retain(|c| *c != colour);
+        recent.insert(0, colour);
+        recent.truncate(MAX_RECENT_COLOURS);
+    }
+
+    /// Whether the node of the hierarchy at the path of object IDs is expanded
+    pub fn is_node_expanded(&self, path: &[u16]) -> bool {
+        self.expanded_nodes.borrow().contains(path)
+    }
+
+    /// Remember whether the node of the hierarchy at the path of object IDs is expanded, to
+    /// restore the hierarchy when the project is opened again
+    pub fn set_node_expanded(&self, path: &[u16], expanded: bool) {
+        let mut expanded_nodes = self.expanded_nodes.borrow_mut();
+        if expanded {
+            if !expanded_nodes.contains(path) {
+                expanded_nodes.insert(path.to_vec());
+            }
+        } else {
+            expanded_nodes.remove(path);
+        }
+    }
+
+    /// Get the recently used colours, most recent first
+    pub fn get_recent_colours(&self) -> Vec<u8> {
+        self.recent_colours.borrow().clone()
+    }
+
+    /// Get the colours in the project palette
+    pub fn get_palette_colours(&self) -> Vec<u8> {
+        self.palette_colours.borrow().clone()
+    }
+
+    /// Add a colour to the project palette, if it is not in there already
+    pub fn add_palette_colour(&self, colour: u8) {
+        let mut palette = self.palette_colours.borrow_mut();
+        if !palette.contains(&colour) {
+            palette.push(colour);
+        }
+    }
+
+    /// Remove a colour from the project palette
+    pub fn remove_palette_colour(&self, colour: u8) {
+        self.palette_colours.borrow_mut().retain(|c| *c != colour);
+    }
+
+    /// Get the standard colour palette of the VT, without the custom palette of the pool applied
+    pub fn get_standard_colour_palette(&self) -> &[Colour] {
+        &self.standard_colour_palette
+    }
+
+    /// Get the HMI guideline rules checked for the pool
+    pub fn get_guideline_settings(&self) -> GuidelineSettings {
+        self.guideline_settings.borrow().clone()
+    }
+
+    pub fn set_guideline_settings(&self, settings: GuidelineSettings) {
+        if *self.guideline_settings.borrow() != settings {
+            self.guideline_settings.replace(settings);
+            self.modified.replace(true);
+        }
+    }
+
+    /// Get the auxiliary input assignments used by the simulation
+    pub fn get_aux_assignments(&self) -> Vec<AuxAssignment> {
+        self.aux_assignments.borrow().clone()
+    }
+
+    /// Store the auxiliary input assignments used by the simulation
+    pub fn set_aux_assignments(&self, assignments: Vec<AuxAssignment>) {
+        self.aux_assignments.replace(assignments);
+    }
+
+    /// Get the demo script replayed in the simulation
+    pub fn get_demo_script(&self) -> DemoScript {
+        self.demo_script.borrow().clone()
+    }
+
+    /// Store the demo script replayed in the simulation
+    pub fn set_demo_script(&self, script: DemoScript) {
+        self.demo_script.replace(script);
+        self.modified.replace(true);
+    }
+
+    /// Get a thumbnail texture of an object, rendering it if it is not cached yet
+    /// Returns None if the object has nothing to show (e.g. zero size)
+    pub fn get_object_thumbnail(
+        &self,
+        ctx: &eframe::egui::Context,
+        object: &Object,
+    ) -> Option<eframe::egui::TextureHandle> {
+        self.thumbnail_cache
+            .borrow_mut()
+            .entry(object.id())
+            .or_insert_with(|| {
+                offscreen_rendering::render_object_thumbnail(object, &self.pool, THUMBNAIL_SIZE)
+                    .map(|image| {
+                        ctx.load_texture(
+                            format!("thumbnail_{}", object.id().value()),
+                            image,
+                            Default::default(),
+                        )
+                    })
+            })
+            .clone()
+    }
+
+    /// Remember that an object was picked in an object picker
+    pub fn note_object_picked(&self, id: ObjectId) {
+        let mut recently_picked = self.recently_picked.borrow_mut();
+        recently_picked.retain(|&picked| picked != id);
+        recently_picked.insert(0, id);
+        recently_picked.truncate(MAX_RECENTLY_PICKED);
+    }
+
+    /// Get the amount of objects referencing the given object
+    pub fn get_reference_count(&self, id: ObjectId) -> usize {
+        self.index.parents(id).len()
+    }
+
+    /// Get the objects referencing the given object, listed once for every reference
+    pub fn get_parents(&self, id: ObjectId) -> Vec<&Object> {
+        self.index
+            .parents(id)
+            .iter()
+            .filter_map(|parent| self.get_object(*parent))
+            .collect()
+    }
+
+    /// Every object using the given object, once per object. Objects showing it through an
+    /// object pointer are listed with the pointer, pointers to pointers are followed.
+    pub fn find_usages(&self, id: ObjectId) -> Vec<ObjectUsage> {
+        let mut usages: Vec<ObjectUsage> = Vec::new();
+        let mut visited = HashSet::from([id]);
+        let mut queue = vec![(id, None)];
+        while let Some((used, via)) = queue.pop() {
+            for &parent in self.index.parents(used) {
+                if usages
+                    .iter()
+                    .any(|usage| usage.id == parent && usage.via == via)
+                {
+                    continue;
+                }
+                usages.push(ObjectUsage { id: parent, via });
+                let is_pointer = matches!(self.get_object(parent), Some(Object::ObjectPointer(_)));
+                if is_pointer && visited.insert(parent) {
+                    queue.push((parent, Some(parent)));
+                }
+            }
+        }
+        usages
+    }
+
+    /// Look up an object through the index instead of searching the pool
+    pub fn get_object(&self, id: ObjectId) -> Option<&Object> {
+        self.index
+            .position(id)
+            .and_then(|position| self.pool.objects().get(position))
+    }
+
+    /// Get all objects of the given type, in the order of the pool
+    pub fn get_objects_by_type(&self, object_type: ObjectType) -> Vec<&Object> {
+        self.index
+            .ids_by_type(object_type)
+            .iter()
+            .filter_map(|id| self.get_object(*id))
+            .collect()
+    }
+
+    /// Get all objects of the given types, in the order of the pool
+    pub fn get_objects_by_types(&self, object_types: &[ObjectType]) -> Vec<&Object> {
+        let mut objects: Vec<&Object> = object_types
+            .iter()
+            .flat_map(|object_type| self.get_objects_by_type(*object_type))
+            .collect();
+        objects.sort_by_key(|obj| self.index.position(obj.id()));
+        objects
+    }
+
+    /// Objects whose name contains the words of the query, ignoring case. Words like
+    /// `tag:page1` only match objects with the tag, `-tag:page1` only objects without it.
+    /// The result of the last query is cached until a name changes.
+    pub fn find_objects_by_name(&self, query: &str) -> Vec<&Object> {
+        let mut name_matches = self.name_matches.borrow_mut();
+        let generation = self.index.name_generation();
+        if let Some((_, _, ids)) = name_matches
+            .as_ref()
+            .filter(|(last_generation, last, _)| *last_generation == generation && last == query)
+        {
+            return ids.iter().filter_map(|id| self.get_object(*id)).collect();
+        }
+
+        let mut name_words = Vec::new();
+        let mut tag_filters = Vec::new();
+        for word in query.split_whitespace() {
+            if let Some(tag) = word.strip_prefix("tag:") {
+                tag_filters.push((tag.to_lowercase(), true));
+            } else if let Some(tag) = word.strip_prefix("-tag:") {
+                tag_filters.push((tag.to_lowercase(), false));
+            } else {
+                name_words.push(word);
+            }
+        }
+        let original_query = query;
+        let query = if tag_filters.is_empty() {
+            query.to_lowercase()
+        } else {
+            name_words.join(" ").to_lowercase()
+        };
+
+        let has_tag = |id: ObjectId, tag: &str| {
+            self.object_info
+                .borrow()
+                .get(&id)
+                .is_some_and(|info| info.tags.iter().any(|t| t.to_lowercase() == tag))
+        };
+        let ids = self.index.filter_by_name(
+            &self.pool,
+            |obj| self.get_object_info(obj).get_name(obj),
+            |id, name| {
+                name.contains(&query)
+                    && tag_filters
+                        .iter()
+                        .all(|(tag, wanted)| has_tag(id, tag) == *wanted)
+            },
+        );
+        let objects = ids.iter().filter_map(|id| self.get_object(*id)).collect();
+        *name_matches = Some((generation, original_query.to_string(), ids));
+        objects
+    }
+
+    /// Select the candidates that should be listed at the top of an object picker:
+    /// first the recently picked ones, then the ones referenced most throughout the pool.
+    /// Returns an empty list if the candidate list is short enough to not need it.
+    pub fn get_featured_candidates<'a>(&self, candidates: &[&'a Object]) -> Vec<&'a Object> {
+        if candidates.len() <= MAX_FEATURED_CANDIDATES {
+            return vec![];
+        }
+
+        let mut featured: Vec<&'a Object> = vec![];
+        for id in self.recently_picked.borrow().iter() {
+            if let Some(candidate) = candidates.iter().find(|c| c.id() == *id) {
+                featured.push(*candidate);
+            }
+        }
+
+        let mut most_used: Vec<(&'a Object, usize)> = candidates
+            .iter()
+            .map(|c| (*c, self.get_reference_count(c.id())))
+            .filter(|(_, count)| *count > 1)
+            .collect();
+        most_used.sort_by(|a, b| b.1.cmp(&a.1));
+        for (candidate, _) in most_used {
+            if !featured.iter().any(|f| f.id() == candidate.id()) {
+                featured.push(candidate);
+            }
+        }
+
+        featured.truncate(MAX_FEATURED_CANDIDATES);
+        featured
+    }
 }
@@ -0,0 +1,125 @@
+//! Copyright 2024 - The Open-Agriculture Developers
+//! SPDX-License-Identifier: GPL-3.0-or-later
+//! Authors: Daan Steenbergen
+
+use ag_iso_stack::object_pool::object::{FontAttributes, Object};
+use ag_iso_stack::object_pool::object_attributes::FontSize;
+use ag_iso_stack::object_pool::{NullableObjectId, ObjectId, ObjectPool};
+use std::collections::HashSet;
+
+/// Average width of a character of a proportional font, relative to its height
+const PROPORTIONAL_CHARACTER_WIDTH: f32 = 0.6;
+
+/// A text object using the replaced font, with a warning if its text no longer fits
+#[derive(Clone)]
+pub struct FontUse {
+    pub object: ObjectId,
+    pub warning: Option<String>,
+}
+
+/// The objects using the font, optionally only those shown on the given mask, each with a
+/// warning if its text wouldn't fit with the other font. Objects are in the order of the pool.
+pub fn preview_font_substitution(
+    pool: &ObjectPool,
+    from: ObjectId,
+    to: &FontAttributes,
+    scope: Option<ObjectId>,
+) -> Vec<FontUse> {
+    let in_scope = scope.map(|mask| shown_objects(pool, mask));
+    pool.objects()
+        .iter()
+        .filter(|object| object.referenced_objects().contains(&from))
+        .filter(|object| {
+            in_scope
+                .as_ref()
+                .is_none_or(|ids| ids.contains(&object.id()))
+        })
+        .map(|object| FontUse {
+            object: object.id(),
+            warning: fit_warning(pool, object, to),
+        })
+        .collect()
+}
+
+/// The objects shown on the mask, following the references of every object
+fn shown_objects(pool: &ObjectPool, mask: ObjectId) -> HashSet<ObjectId> {
+    let mut shown = HashSet::new();
+    let mut pending = vec![mask];
+    while let Some(id) = pending.pop() {
+        if !shown.insert(id) {
+            continue;
+        }
+        if let Some(object) = pool.object_by_id(id) {
+            pending.extend(object.referenced_objects());
+        }
+    }
+    shown
+}
+
+/// Width and height of a character in the font
+fn character_size(font: &FontAttributes) -> (f32, f32) {
+    match font.font_size {
+        FontSize::NonProportional(size) => (size.width() as f32, size.height() as f32),
+        FontSize::Proportional(height) => {
+            (height as f32 * PROPORTIONAL_CHARACTER_WIDTH, height as f32)
+        }
+    }
+}
+
+/// The text shown by a string object, the value of its variable if it has one
+fn shown_text(pool: &ObjectPool, value: &str, variable: NullableObjectId) -> String {
+    match variable.0.and_then(|id| pool.object_by_id(id)) {
+        Some(Object::StringVariable(s)) => s.value.clone(),
+        _ => value.to_string(),
+    }
+}
+
+/// Check whether the text of the object fits in its area with the font, an estimate for
+/// proportional fonts
+fn fit_warning(pool: &ObjectPool, object: &Object, font: &FontAttributes) -> Option<String> {
+    let (character_width, character_height) = character_size(font);
+    let (text, auto_wrap) = match object {
+        Object::OutputString(o) => (
+            shown_text(pool, &o.value, o.variable_reference),
+            o.options.auto_wrap,
+        ),
+        Object::InputString(o) => (
+            shown_text(pool, &o.value, o.variable_reference),
+            o.options.auto_wrap,
+        ),
+        _ => (String::new(), false),
+    };
+    let (width, height) = pool.content_size(object);
+    let (width, height) = (width as f32, height as f32);
+
+    if character_height > height {
+        return Some(format!(
+            "Characters of {} pixels are higher than the object of {} pixels",
+            character_height, height
+        ));
+    }
+
+    let text = text.trim_end();
+    if text.is_empty() {
+        return None;
+    }
+    let characters_per_line = (width / character_width.max(1.0)).floor().max(1.0) as usize;
+    let mut lines = 0;
+    for line in text.split(['\r', '\n']) {
+        let length = line.chars().count();
+        if !auto_wrap && length > characters_per_line {
+            return Some(format!(
+                "A line of {} characters doesn't fit, {} fit in {} pixels",
+                length, characters_per_line, width
+            ));
+        }
+        lines += length.div_ceil(characters_per_line).max(1);
+    }
+    let lines_fitting = (height / character_height).floor() as usize;
+    (lines > lines_fitting).then(|| {
+        format!(
+            "{} lines of text don't fit, {} fit in {} pixels",
+            lines, lines_fitting, height
+        )
+    })
+}
@@ -0,0 +1,126 @@
+//! Copyright 2024 - The Open-Agriculture Developers
+//! SPDX-License-Identifier: GPL-3.0-or-later
+//! Authors: Daan Steenbergen
+
+/// A drawing command an ECU can send to a graphics context at runtime, used to preview what the
+/// canvas will show. Positions are in canvas pixels.
+#[derive(Debug, Clone, PartialEq)]
+pub enum GraphicsCommand {
+    SetCursor {
+        x: i16,
+        y: i16,
+    },
+    MoveCursor {
+        dx: i16,
+        dy: i16,
+    },
+    SetForeground(u8),
+    SetBackground(u8),
+    /// Line from the graphics cursor to the given position, the cursor moves to its end
+    DrawLine {
+        x: i16,
+        y: i16,
+    },
+    /// Rectangle at the graphics cursor, the cursor moves to its bottom right corner
+    DrawRectangle {
+        width: u16,
+        height: u16,
+        filled: bool,
+    },
+    /// Ellipse in the rectangle at the graphics cursor, the cursor moves to its bottom right
+    /// corner
+    DrawEllipse {
+        width: u16,
+        height: u16,
+        filled: bool,
+    },
+    /// Fill a rectangle at the graphics cursor with the background colour
+    EraseRectangle {
+        width: u16,
+        height: u16,
+    },
+    /// Text at the graphics cursor, the cursor doesn't move
+    DrawText(String),
+}
+
+/// Syntax of the commands, shown as help next to the command editor
+pub const COMMAND_SYNTAX: &str = "\
+cursor X Y          set the graphics cursor
+move DX DY          move the graphics cursor
+foreground COLOUR   set the foreground colour index
+background COLOUR   set the background colour index
+line X Y            draw a line to X Y
+rectangle W H       draw a rectangle, add 'fill' to fill it
+ellipse W H         draw an ellipse, add 'fill' to fill it
+erase W H           fill a rectangle with the background colour
+text TEXT           draw text
+# comment";
+
+fn parse_number<T: std::str::FromStr>(argument: Option<&str>, name: &str) -> Result<T, String> {
+    let argument = argument.ok_or_else(|| format!("missing {}", name))?;
+    argument
+        .parse()
+        .map_err(|_| format!("invalid {} '{}'", name, argument))
+}
+
+fn parse_command(line: &str) -> Result<GraphicsCommand, String> {
+    let (keyword, rest) = line.split_once(char::is_whitespace).unwrap_or((line, ""));
+    let keyword = keyword.to_ascii_lowercase();
+    let mut arguments = rest.split_whitespace();
+    let mut next = |name: &str| parse_number::<i32>(arguments.next(), name);
+    let command = match keyword.as_str() {
+        "cursor" => GraphicsCommand::SetCursor {
+            x: next("x")? as i16,
+            y: next("y")? as i16,
+        },
+        "move" => GraphicsCommand::MoveCursor {
+            dx: next("dx")? as i16,
+            dy: next("dy")? as i16,
+        },
+        "foreground" => GraphicsCommand::SetForeground(next("colour")?.clamp(0, 255) as u8),
+        "background" => GraphicsCommand::SetBackground(next("colour")?.clamp(0, 255) as u8),
+        "line" => GraphicsCommand::DrawLine {
+            x: next("x")? as i16,
+            y: next("y")? as i16,
+        },
+        "rectangle" | "ellipse" | "erase" => {
+            let width = next("width")?.clamp(0, u16::MAX as i32) as u16;
+            let height = next("height")?.clamp(0, u16::MAX as i32) as u16;
+            let filled = rest.split_whitespace().nth(2) == Some("fill");
+            match keyword.as_str() {
+                "rectangle" => GraphicsCommand::DrawRectangle {
+                    width,
+                    height,
+                    filled,
+                },
+                "ellipse" => GraphicsCommand::DrawEllipse {
+                    width,
+                    height,
+                    filled,
+                },
+                _ => GraphicsCommand::EraseRectangle { width, height },
+            }
+        }
+        "text" => GraphicsCommand::DrawText(rest.trim().to_string()),
+        _ => return Err(format!("unknown command '{}'", keyword)),
+    };
+    Ok(command)
+}
+
+/// Parse the commands, one per line. Empty lines and lines starting with '#' are skipped.
+/// Returns the valid commands and the errors of the invalid lines with their line number.
+pub fn parse_commands(text: &str) -> (Vec<GraphicsCommand>, Vec<(usize, String)>) {
+    let mut commands = Vec::new();
+    let mut errors = Vec::new();
+    for (idx, line) in text.lines().enumerate() {
+        let line = line.trim();
+        if line.is_empty() || line.starts_with('#') {
+            continue;
+        }
+        match parse_command(line) {
+            Ok(command) => commands.push(command),
+            Err(e) => errors.push((idx + 1, e)),
+        }
+    }
+    (commands, errors)
+}
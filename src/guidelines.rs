@@ -0,0 +1,135 @@
+//! Copyright 2024 - The Open-Agriculture Developers
+//! SPDX-License-Identifier: GPL-3.0-or-later
+//! Authors: Daan Steenbergen
+
+use crate::AnalysisIssue;
+use ag_iso_stack::object_pool::object::Object;
+use ag_iso_stack::object_pool::object_attributes::FontSize;
+use ag_iso_stack::object_pool::{ObjectPool, ObjectType};
+use serde::{Deserialize, Serialize};
+
+/// Character height per viewing distance for text to be readable at a glance, about 20 minutes
+/// of arc as recommended for operator displays
+const CHARACTER_HEIGHT_PER_DISTANCE: f32 = 0.0058;
+
+/// HMI guideline rules checked for a project, following ISO 15077 and the AEF guidelines.
+/// A rule is turned off when its limit is `None`.
+#[derive(Serialize, Deserialize, Clone, PartialEq, Debug)]
+pub struct GuidelineSettings {
+    /// Physical width of the data mask on the terminal, to convert millimetres to pixels
+    pub screen_width_mm: f32,
+    /// Minimum width and height of buttons and input objects, to be hit with a gloved finger
+    pub min_touch_target_mm: Option<f32>,
+    /// Distance from the operator to the terminal, text must be readable from there
+    pub viewing_distance_mm: Option<f32>,
+    /// Maximum number of high priority alarm masks, so the operator isn't flooded with alarms
+    pub max_high_priority_alarms: Option<usize>,
+}
+
+impl Default for GuidelineSettings {
+    fn default() -> Self {
+        GuidelineSettings {
+            screen_width_mm: 150.0,
+            min_touch_target_mm: None,
+            viewing_distance_mm: None,
+            max_high_priority_alarms: None,
+        }
+    }
+}
+
+impl GuidelineSettings {
+    /// The rules of ISO 15077 and the AEF guidelines with their recommended limits
+    pub fn recommended(screen_width_mm: f32) -> Self {
+        GuidelineSettings {
+            screen_width_mm,
+            min_touch_target_mm: Some(10.0),
+            viewing_distance_mm: Some(700.0),
+            max_high_priority_alarms: Some(5),
+        }
+    }
+
+    pub fn any_enabled(&self) -> bool {
+        self.min_touch_target_mm.is_some()
+            || self.viewing_distance_mm.is_some()
+            || self.max_high_priority_alarms.is_some()
+    }
+
+    /// Amount of pixels in a millimetre on a data mask of the given size
+    fn pixels_per_mm(&self, mask_size: u16) -> f32 {
+        mask_size as f32 / self.screen_width_mm.max(1.0)
+    }
+}
+
+/// Check the pool against the enabled guideline rules
+pub fn check_guidelines(
+    pool: &ObjectPool,
+    settings: &GuidelineSettings,
+    mask_size: u16,
+) -> Vec<AnalysisIssue> {
+    let mut issues = Vec::new();
+    let pixels_per_mm = settings.pixels_per_mm(mask_size);
+
+    if let Some(min_mm) = settings.min_touch_target_mm {
+        let min_pixels = (min_mm * pixels_per_mm).ceil() as u16;
+        for object in pool.objects_by_types(&[
+            ObjectType::Button,
+            ObjectType::InputBoolean,
+            ObjectType::InputString,
+            ObjectType::InputNumber,
+            ObjectType::InputList,
+        ]) {
+            let (width, height) = pool.content_size(object);
+            if width < min_pixels || height < min_pixels {
+                issues.push(AnalysisIssue {
+                    object: Some(object.id()),
+                    message: format!(
+                        "Touch target of {}x{} pixels is smaller than {} mm ({} pixels)",
+                        width, height, min_mm, min_pixels
+                    ),
+                });
+            }
+        }
+    }
+
+    if let Some(distance_mm) = settings.viewing_distance_mm {
+        let min_pixels = (distance_mm * CHARACTER_HEIGHT_PER_DISTANCE * pixels_per_mm).ceil();
+        for object in pool.objects_by_type(ObjectType::FontAttributes) {
+            let Object::FontAttributes(font) = object else {
+                continue;
+            };
+            let height = match font.font_size {
+                FontSize::NonProportional(size) => size.height() as f32,
+                FontSize::Proportional(height) => height as f32,
+            };
+            if height < min_pixels {
+                issues.push(AnalysisIssue {
+                    object: Some(object.id()),
+                    message: format!(
+                        "Font height of {} pixels is too small to read from {} mm, use at \
+                        least {} pixels",
+                        height, distance_mm, min_pixels
+                    ),
+                });
+            }
+        }
+    }
+
+    if let Some(max_alarms) = settings.max_high_priority_alarms {
+        let high_priority = pool
+            .objects_by_type(ObjectType::AlarmMask)
+            .into_iter()
+            .filter(|object| matches!(object, Object::AlarmMask(mask) if mask.priority == 0))
+            .count();
+        if high_priority > max_alarms {
+            issues.push(AnalysisIssue {
+                object: None,
+                message: format!(
+                    "{} high priority alarm masks, more than the {} the operator can handle",
+                    high_priority, max_alarms
+                ),
+            });
+        }
+    }
+
+    issues
+}
@@ -0,0 +1,177 @@
+//! Copyright 2024 - The Open-Agriculture Developers
+//! SPDX-License-Identifier: GPL-3.0-or-later
+//! Authors: Daan Steenbergen
+
+use crate::picture_scaling::{closest_palette_index, colour_count, store_pixels};
+use ag_iso_stack::object_pool::object::PictureGraphic;
+use ag_iso_stack::object_pool::object_attributes::PictureGraphicFormat;
+use ag_iso_stack::object_pool::Colour;
+use serde::{Deserialize, Serialize};
+use std::path::{Path, PathBuf};
+
+/// The image file a picture graphic was imported from, so it can be imported again when the
+/// artwork changes
+#[derive(Serialize, Deserialize, Clone, PartialEq, Debug)]
+pub struct ImageSource {
+    /// Path of the image, relative to the directory of the project file when the project was
+    /// saved at the time of importing, absolute otherwise
+    pub path: String,
+    /// Modification time of the image when it was imported, in seconds since the unix epoch
+    pub modified: u64,
+}
+
+impl ImageSource {
+    /// Link an image file, relative to the project directory if it is inside it
+    pub fn new(image_path: &Path, project_dir: Option<&Path>) -> Self {
+        let path = project_dir
+            .and_then(|dir| image_path.strip_prefix(dir).ok())
+            .unwrap_or(image_path);
+        ImageSource {
+            path: path.display().to_string(),
+            modified: modified_time(image_path).unwrap_or(0),
+        }
+    }
+
+    /// Location of the image on disk
+    pub fn resolve(&self, project_dir: Option<&Path>) -> PathBuf {
+        let path = Path::new(&self.path);
+        match project_dir {
+            Some(dir) if path.is_relative() => dir.join(path),
+            _ => path.to_path_buf(),
+        }
+    }
+}
+
+/// Modification time of a file in seconds since the unix epoch
+pub fn modified_time(path: &Path) -> Option<u64> {
+    let modified = std::fs::metadata(path).ok()?.modified().ok()?;
+    modified
+        .duration_since(std::time::UNIX_EPOCH)
+        .ok()
+        .map(|duration| duration.as_secs())
+}
+
+/// Find the closest color index in the palette for a given RGB value
+fn find_closest_color_index(r: u8, g: u8, b: u8) -> u8 {
+    fn quantize_channel(c: u8) -> u8 {
+        // ((c + 25) / 51) in integer math, capped to 0..5
+        let v = (c as u16 + 25) / 51;
+        v.min(5) as u8
+    }
+    let rq = quantize_channel(r);
+    let gq = quantize_channel(g);
+    let bq = quantize_channel(b);
+
+    16 + 36 * rq + 6 * gq + bq
+}
+
+/// Brightness from which a pixel of a monochrome picture gets the brighter of its two colours
+pub const DEFAULT_MONOCHROME_THRESHOLD: u8 = 128;
+
+/// Perceived brightness of a colour, from 0 to 255
+fn brightness(r: u8, g: u8, b: u8) -> u8 {
+    ((r as u32 * 299 + g as u32 * 587 + b as u32 * 114) / 1000) as u8
+}
+
+/// Replace the pixel data of the picture with an image file (PNG, JPEG, ...), quantized to the
+/// 8-bit VT palette. The raw or run-length encoding is chosen, whichever is smaller.
+pub fn import_image(picture: &mut PictureGraphic, content: &[u8]) -> Result<(), String> {
+    // Set format by default to 8-bit color, user can change it in UI
+    import_image_as(picture, content, PictureGraphicFormat::EightBit, &[])
+}
+
+/// Replace the pixel data of the picture with an image file, quantized to the colours of the
+/// given format, see [`DecodedImage::convert`]
+pub fn import_image_as(
+    picture: &mut PictureGraphic,
+    content: &[u8],
+    format: PictureGraphicFormat,
+    palette: &[Colour],
+) -> Result<(), String> {
+    let image = DecodedImage::decode(content)?;
+    image.convert(picture, format, palette, DEFAULT_MONOCHROME_THRESHOLD);
+    Ok(())
+}
+
+/// An image file decoded once, so it can be converted to several formats, e.g. to preview them
+pub struct DecodedImage {
+    rgba: image::RgbaImage,
+}
+
+impl DecodedImage {
+    pub fn decode(content: &[u8]) -> Result<Self, String> {
+        let img = image::load_from_memory(content)
+            .map_err(|e| format!("Failed to decode image: {}", e))?;
+        if img.width() > u16::MAX as u32 || img.height() > u16::MAX as u32 {
+            return Err(format!(
+                "Image dimensions exceed maximum size of {}x{}",
+                u16::MAX,
+                u16::MAX
+            ));
+        }
+        Ok(DecodedImage {
+            rgba: img.to_rgba8(),
+        })
+    }
+
+    /// Replace the pixel data of the picture with the image, quantized to the colours of the
+    /// given format. 4-bit pictures use the closest of the first colours of the palette, 8-bit
+    /// pictures the colour cube of the VT palette. Monochrome pictures use the brighter of the
+    /// first two colours for pixels at least as bright as `threshold`.
+    /// The raw or run-length encoding is chosen, whichever is smaller.
+    pub fn convert(
+        &self,
+        picture: &mut PictureGraphic,
+        format: PictureGraphicFormat,
+        palette: &[Colour],
+        threshold: u8,
+    ) {
+        let (w, h) = self.rgba.dimensions();
+        picture.actual_width = w as u16;
+        picture.actual_height = h as u16;
+        if picture.width == 0 {
+            picture.width = picture.actual_width;
+        }
+        picture.format = format;
+
+        // The darker and brighter colour of a monochrome picture
+        let monochrome = match palette {
+            [first, second, ..]
+                if brightness(first.r, first.g, first.b)
+                    > brightness(second.r, second.g, second.b) =>
+            {
+                (1, 0)
+            }
+            _ => (0, 1),
+        };
+        let quantize = |r: u8, g: u8, b: u8| match picture.format {
+            PictureGraphicFormat::EightBit => find_closest_color_index(r, g, b),
+            PictureGraphicFormat::Monochrome if brightness(r, g, b) >= threshold => monochrome.1,
+            PictureGraphicFormat::Monochrome => monochrome.0,
+            _ => closest_palette_index(palette, colour_count(&picture.format), r, g, b),
+        };
+        let opaque: Vec<Option<u8>> = self
+            .rgba
+            .pixels()
+            .map(|p| (p[3] != 0).then(|| quantize(p[0], p[1], p[2])))
+            .collect();
+
+        picture.transparency_colour = match picture.format {
+            // We set transparent color to 1 (arbitrary choice) as we
+            // only use index 15..255 for actual colors
+            PictureGraphicFormat::EightBit => 1,
+            // The smaller formats need all their colours, take one the image doesn't use
+            _ => (0..colour_count(&picture.format) as u8)
+                .find(|index| !opaque.contains(&Some(*index)))
+                .unwrap_or(0),
+        };
+        picture.options.transparent =
+            picture.format == PictureGraphicFormat::EightBit || opaque.iter().any(Option::is_none);
+
+        let pixels: Vec<u8> = opaque
+            .iter()
+            .map(|index| index.unwrap_or(picture.transparency_colour))
+            .collect();
+        store_pixels(picture, &pixels);
+    }
+}
@@ -2,7 +2,7 @@
 //! SPDX-License-Identifier: GPL-3.0-or-later
 //! Authors: Daan Steenbergen
 
-use crate::RenderableObject;
+use crate::{CanvasTheme, FocusStyle, RenderableObject};
 use ag_iso_stack::object_pool::object_attributes::Point;
 use ag_iso_stack::object_pool::{object::Object, ObjectId, ObjectPool};
 use eframe::egui;
@@ -11,6 +11,10 @@ use eframe::egui;
 pub struct InteractiveMaskRenderer<'a> {
     pub object: &'a Object,
     pub pool: &'a ObjectPool,
+    pub selected: Option<ObjectId>,
+    /// Input object that has focus, painted in the given style and colour
+    pub focused: Option<(ObjectId, FocusStyle, egui::Color32)>,
+    pub theme: CanvasTheme,
     pub selected_callback: Box<dyn FnMut(ObjectId) + 'a>,
 }
 
@@ -29,6 +33,39 @@ impl<'a> egui::Widget for InteractiveMaskRenderer<'a> {
             self.object
                 .render(&mut child_ui, self.pool, Point::default());
 
+            // Outline the mask area
+            ui.painter().rect_stroke(
+                rect,
+                0.0,
+                egui::Stroke::new(1.0, self.theme.guide_colour()),
+                egui::epaint::StrokeKind::Outside,
+            );
+
+            // Indicate the focused input like the VT would
+            if let Some((id, style, colour)) = self.focused {
+                if let Some(object_rect) = self.find_object_rect(self.object, Point::default(), id)
+                {
+                    style.paint(
+                        ui.painter(),
+                        object_rect.translate(rect.min.to_vec2()),
+                        colour,
+                    );
+                }
+            }
+
+            // Highlight the selected object
+            if let Some(object_rect) = self
+                .selected
+                .and_then(|id| self.find_object_rect(self.object, Point::default(), id))
+            {
+                ui.painter().rect_stroke(
+                    object_rect.translate(rect.min.to_vec2()),
+                    0.0,
+                    egui::Stroke::new(2.0, self.theme.selection_colour()),
+                    egui::epaint::StrokeKind::Outside,
+                );
+            }
+
             // Handle interaction - check if pointer is interacting with this widget
             if let Some(pointer_pos) = ui.ctx().pointer_hover_pos() {
                 // Check if the pointer is within our allocated rect
@@ -47,10 +84,7 @@ impl<'a> egui::Widget for InteractiveMaskRenderer<'a> {
                         ui.painter().rect_stroke(
                             screen_rect,
                             0.0,
-                            egui::Stroke::new(
-                                2.0,
-                                egui::Color32::from_rgba_premultiplied(255, 255, 0, 200),
-                            ),
+                            egui::Stroke::new(2.0, self.theme.hover_colour()),
                             egui::epaint::StrokeKind::Middle,
                         );
 
@@ -58,10 +92,7 @@ impl<'a> egui::Widget for InteractiveMaskRenderer<'a> {
                         ui.painter().circle_stroke(
                             pointer_pos,
                             10.0,
-                            egui::Stroke::new(
-                                2.0,
-                                egui::Color32::from_rgba_premultiplied(255, 255, 255, 128),
-                            ),
+                            egui::Stroke::new(2.0, self.theme.hover_colour().gamma_multiply(0.5)),
                         );
 
                         if response.clicked() {
@@ -83,6 +114,37 @@ impl<'a> InteractiveMaskRenderer<'a> {
         self.find_object_recursive(self.object, Point::default(), pos)
     }
 
+    /// Find the rect (relative to widget) of the first occurrence of an object in the mask
+    fn find_object_rect(
+        &self,
+        object: &Object,
+        offset: Point<i16>,
+        id: ObjectId,
+    ) -> Option<egui::Rect> {
+        if object.id() == id {
+            let (width, height) = self.pool.content_size(object);
+            return Some(egui::Rect::from_min_size(
+                egui::pos2(offset.x as f32, offset.y as f32),
+                egui::vec2(width as f32, height as f32),
+            ));
+        }
+
+        let object_refs = match object {
+            Object::DataMask(mask) => &mask.object_refs,
+            Object::AlarmMask(mask) => &mask.object_refs,
+            Object::Container(container) => &container.object_refs,
+            _ => return None,
+        };
+        object_refs.iter().find_map(|obj_ref| {
+            let child = self.pool.object_by_id(obj_ref.id)?;
+            let child_offset = Point {
+                x: offset.x + obj_ref.offset.x,
+                y: offset.y + obj_ref.offset.y,
+            };
+            self.find_object_rect(child, child_offset, id)
+        })
+    }
+
     fn find_object_recursive(
         &self,
         object: &Object,
@@ -0,0 +1,45 @@
+//! Copyright 2024 - The Open-Agriculture Developers
+//! SPDX-License-Identifier: GPL-3.0-or-later
+//! Authors: Daan Steenbergen
+
+use ag_iso_stack::object_pool::object::Object;
+use ag_iso_stack::object_pool::ObjectPool;
+use web_time::Instant;
+
+/// Parses an IOP file object by object like [`ObjectPool::from_iop`], so the progress can be
+/// shown and the parsing can be spread over several frames where there are no threads
+pub struct IopParser {
+    data: std::vec::IntoIter<u8>,
+    size: usize,
+    pool: ObjectPool,
+}
+
+impl IopParser {
+    pub fn new(content: Vec<u8>) -> Self {
+        IopParser {
+            size: content.len(),
+            data: content.into_iter(),
+            pool: ObjectPool::default(),
+        }
+    }
+
+    /// Parse objects until the file ends or the deadline, if any, passes.
+    /// Returns the pool once the whole file is parsed.
+    pub fn parse(&mut self, deadline: Option<Instant>) -> Option<ObjectPool> {
+        loop {
+            match Object::read(&mut self.data) {
+                Ok(object) => self.pool.add(object),
+                // Like from_iop, the objects before the first one that can't be read are kept
+                Err(_) => return Some(std::mem::take(&mut self.pool)),
+            }
+            if deadline.is_some_and(|deadline| Instant::now() >= deadline) {
+                return None;
+            }
+        }
+    }
+
+    /// Number of bytes of the file parsed so far
+    pub fn parsed_bytes(&self) -> usize {
+        self.size - self.data.len()
+    }
+}
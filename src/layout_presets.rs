@@ -0,0 +1,88 @@
+//! Copyright 2024 - The Open-Agriculture Developers
+//! SPDX-License-Identifier: GPL-3.0-or-later
+//! Authors: Daan Steenbergen
+
+/// Common screen structures, scaffolded as containers inside a data mask
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum LayoutPreset {
+    HeaderContentFooter,
+    NavigationContent,
+    Dashboard,
+}
+
+/// An area of a layout preset, filled by a container
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub struct LayoutRegion {
+    pub name: &'static str,
+    pub x: i16,
+    pub y: i16,
+    pub width: u16,
+    pub height: u16,
+}
+
+impl LayoutPreset {
+    pub const ALL: [LayoutPreset; 3] = [
+        LayoutPreset::HeaderContentFooter,
+        LayoutPreset::NavigationContent,
+        LayoutPreset::Dashboard,
+    ];
+
+    pub fn name(self) -> &'static str {
+        match self {
+            LayoutPreset::HeaderContentFooter => "Header + content + footer",
+            LayoutPreset::NavigationContent => "Left navigation + content",
+            LayoutPreset::Dashboard => "2\u{00D7}2 dashboard",
+        }
+    }
+
+    /// The regions of the layout in a mask of the given size. `margin` is kept free along the
+    /// edges of the mask as a safe area, `gap` between the regions.
+    pub fn regions(self, mask_size: u16, margin: u16, gap: u16) -> Vec<LayoutRegion> {
+        let start = margin.min(mask_size / 2);
+        let area = mask_size.saturating_sub(2 * start);
+        let region = |name, x: u16, y: u16, width: u16, height: u16| LayoutRegion {
+            name,
+            x: (start + x) as i16,
+            y: (start + y) as i16,
+            width,
+            height,
+        };
+        // Split a length into a part of `first` and the rest, with the gap between them
+        let split = |length: u16, first: u16| {
+            let first = first.min(length);
+            (first, length.saturating_sub(first).saturating_sub(gap))
+        };
+
+        match self {
+            LayoutPreset::HeaderContentFooter => {
+                let bar = area / 6;
+                let (header, rest) = split(area, bar);
+                let (content, _) = split(rest, rest.saturating_sub(bar + gap));
+                let content_y = header + gap;
+                let footer_y = content_y + content + gap;
+                vec![
+                    region("Header", 0, 0, area, header),
+                    region("Content", 0, content_y, area, content),
+                    region("Footer", 0, footer_y, area, area.saturating_sub(footer_y)),
+                ]
+            }
+            LayoutPreset::NavigationContent => {
+                let (navigation, content) = split(area, area / 4);
+                vec![
+                    region("Navigation", 0, 0, navigation, area),
+                    region("Content", navigation + gap, 0, content, area),
+                ]
+            }
+            LayoutPreset::Dashboard => {
+                let (first, second) = split(area, area.saturating_sub(gap) / 2);
+                let offset = first + gap;
+                vec![
+                    region("Top left", 0, 0, first, first),
+                    region("Top right", offset, 0, second, first),
+                    region("Bottom left", 0, offset, first, second),
+                    region("Bottom right", offset, offset, second, second),
+                ]
+            }
+        }
+    }
+}
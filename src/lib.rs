@@ -3,19 +3,118 @@
 //! Authors: Daan Steenbergen
 
 mod allowed_object_relationships;
+mod audit_log;
+mod autosave;
+mod backup;
+mod batch_rename;
+mod child_move;
+mod clipboard;
+mod colour_contrast;
+mod commands;
+mod demo_script;
 mod editor_project;
+mod font_substitution;
+mod graphics_commands;
+mod guidelines;
+mod image_import;
 mod interactive_rendering_simple;
+mod iop_parser;
+mod layout_presets;
+mod localization;
+mod macro_usage;
+mod mask_size;
+mod mask_usage;
+mod name_replace;
 mod object_configuring;
 mod object_defaults;
 mod object_info;
+mod object_list;
 mod object_rendering;
+mod offscreen_rendering;
+mod picture_scaling;
+mod pool_analysis;
+mod pool_delta;
+mod pool_diff;
+mod pool_index;
+mod pool_manifest;
+mod pool_merge;
 mod possible_events;
+mod profiler;
+mod project_events;
 mod project_file;
+mod references;
+mod settings;
+mod share_link;
+mod simulation;
 mod smart_naming;
+mod starter_templates;
+mod templates;
+mod unsupported_objects;
+mod vt_compatibility;
 
-pub use editor_project::EditorProject;
+pub use audit_log::{AuditEntry, AuditLog};
+pub use autosave::{describe_age, Autosave, AutosaveMetadata, Autosaver};
+pub use backup::BackupNotice;
+pub use batch_rename::BatchRename;
+pub use child_move::{find_child_move_target, ChildMoveCommand, ChildMovePick, ChildMoveTarget};
+pub use colour_contrast::{
+    background_behind, contrast_ratio, suggest_contrasting_colours, MIN_CONTRAST_RATIO,
+};
+pub use commands::{CommandError, PoolCommand, ValidationHook};
+pub use demo_script::{DemoAction, DemoScript, DemoStep};
+pub use editor_project::{EditorProject, ObjectUsage, PoolSnapshot};
+pub use font_substitution::{preview_font_substitution, FontUse};
+pub use graphics_commands::{parse_commands, GraphicsCommand, COMMAND_SYNTAX};
+pub use guidelines::{check_guidelines, GuidelineSettings};
+pub use image_import::{import_image, DecodedImage, ImageSource, DEFAULT_MONOCHROME_THRESHOLD};
 pub use interactive_rendering_simple::InteractiveMaskRenderer;
-pub use object_configuring::ConfigurableObject;
+pub use iop_parser::IopParser;
+pub use layout_presets::{LayoutPreset, LayoutRegion};
+pub use localization::{translatable_text, TRANSLATABLE_TYPES};
+pub use macro_usage::{analyse_macro_usage, MacroTrigger, MacroUsage};
+pub use mask_size::{
+    check_mask_size, mask_size_name, mask_size_selector, MASK_SIZE_PRESETS, MAX_MASK_SIZE,
+    MIN_MASK_SIZE,
+};
+pub use mask_usage::{analyse_mask_usage, MaskUsage, TypeUsage, UsageCategory, UsageRegion};
+pub use name_replace::NameReplace;
+pub use object_configuring::{render_object_thumbnail, ConfigurableObject};
 pub use object_defaults::default_object;
 pub use object_info::ObjectInfo;
-pub use object_rendering::RenderableObject;
+pub use object_list::{
+    object_list_csv, preview_object_list_import, ImportColumn, ObjectListChange,
+};
+pub use object_rendering::{
+    decimal_separator_for_language, paint_graphics_commands, set_animations_enabled,
+    set_blinking_objects_preview, set_decimal_separator_preview, set_graphics_commands_preview,
+    window_mask_cell_size, window_mask_cells, RenderableObject,
+};
+pub use offscreen_rendering::{
+    encode_png, frame_image, render_object_to_image, render_soft_key_mask_to_image,
+};
+pub use picture_scaling::{parse_widths, ScaledVariantKind};
+pub use pool_analysis::{
+    AnalysisIssue, AnalysisReport, DuplicatePictures, PoolAnalyzer, TypeStatistics,
+};
+pub use pool_diff::{attribute_changes, compare_pools, AttributeChange, ObjectDiff, PoolDiff};
+pub use pool_manifest::{ManifestObject, PoolManifest};
+pub use pool_merge::{choose_all, plan_merge, MergeChoice, MergeItem, MergeKind};
+pub use profiler::{FrameProfiler, ProfileSection};
+pub use project_events::{EventListener, ProjectEvent, SubscriptionId};
+pub use settings::{
+    CanvasTheme, DesignerSettings, HeaderStyle, IdRange, IdSettings, SettingsDialog,
+    FILTER_SHORTCUT, GO_TO_SHORTCUT, PERFORMANCE_HUD_SHORTCUT, REDO_SHORTCUT, RENAME_SHORTCUT,
+    UNDO_SHORTCUT,
+};
+pub use share_link::{decode_share_fragment, encode_share_fragment};
+pub use simulation::{FocusStyle, MaskTransition, Simulation};
+pub use smart_naming::{CaseConvention, NamingRules, NumberingStyle, SmartRename};
+pub use starter_templates::{StarterTemplate, STARTER_TEMPLATES};
+pub use templates::{delete_template, list_templates, save_template, template_exists, Template};
+pub use unsupported_objects::{
+    find_unsupported_objects, unsupported_objects_report, UnsupportedObject,
+};
+pub use vt_compatibility::{
+    object_type_vt_version, required_vt_version, vt_version, vt_version_number, BASE_VT_VERSION,
+    LATEST_VT_VERSION,
+};
@@ -0,0 +1,54 @@
+//! Copyright 2024 - The Open-Agriculture Developers
+//! SPDX-License-Identifier: GPL-3.0-or-later
+//! Authors: Daan Steenbergen
+
+use crate::ObjectInfo;
+use ag_iso_stack::object_pool::object::Object;
+use ag_iso_stack::object_pool::{ObjectId, ObjectPool, ObjectType};
+use std::collections::HashMap;
+
+/// Objects holding text that can be translated
+pub const TRANSLATABLE_TYPES: &[ObjectType] = &[
+    ObjectType::OutputString,
+    ObjectType::InputString,
+    ObjectType::StringVariable,
+];
+
+/// The text of an object that can be translated
+pub fn translatable_text(object: &Object) -> Option<&String> {
+    match object {
+        Object::OutputString(o) => Some(&o.value),
+        Object::InputString(o) => Some(&o.value),
+        Object::StringVariable(o) => Some(&o.value),
+        _ => None,
+    }
+}
+
+fn translatable_text_mut(object: &mut Object) -> Option<&mut String> {
+    match object {
+        Object::OutputString(o) => Some(&mut o.value),
+        Object::InputString(o) => Some(&mut o.value),
+        Object::StringVariable(o) => Some(&mut o.value),
+        _ => None,
+    }
+}
+
+/// Replace the text of the objects by their translation in the given language.
+/// Objects without a translation keep the text of the pool.
+pub fn apply_translations(
+    pool: &mut ObjectPool,
+    object_info: &HashMap<ObjectId, ObjectInfo>,
+    language: &str,
+) {
+    for object in pool.objects_mut() {
+        let Some(translation) = object_info
+            .get(&object.id())
+            .and_then(|info| info.get_translation(language))
+        else {
+            continue;
+        };
+        if let Some(text) = translatable_text_mut(object) {
+            *text = translation.to_string();
+        }
+    }
+}
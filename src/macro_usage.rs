@@ -0,0 +1,139 @@
+//! Copyright 2024 - The Open-Agriculture Developers
+//! SPDX-License-Identifier: GPL-3.0-or-later
+//! Authors: Daan Steenbergen
+
+use ag_iso_stack::object_pool::object::Object;
+use ag_iso_stack::object_pool::object_attributes::{Event, MacroRef};
+use ag_iso_stack::object_pool::{ObjectId, ObjectPool, ObjectType};
+
+/// Command of a macro executing a macro with an 8-bit ID
+const EXECUTE_MACRO: u8 = 0xBE;
+/// Command of a macro executing a macro with a 16-bit ID
+const EXECUTE_EXTENDED_MACRO: u8 = 0xBC;
+/// Command of a macro changing a string value, the only command with a variable length
+const CHANGE_STRING_VALUE: u8 = 0xB3;
+/// Command of a macro moving a child to a position, one byte longer than the others
+const CHANGE_CHILD_POSITION: u8 = 0xB4;
+/// Length of the other macro commands
+const COMMAND_LENGTH: usize = 8;
+
+/// What makes a macro run
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub enum MacroTrigger {
+    /// An event of the object
+    Event(ObjectId, Event),
+    /// A command of another macro
+    Macro(ObjectId),
+}
+
+/// A macro of the pool and what makes it run
+#[derive(Clone)]
+pub struct MacroUsage {
+    pub macro_id: ObjectId,
+    pub triggers: Vec<MacroTrigger>,
+}
+
+impl MacroUsage {
+    /// The macro never runs, e.g. a leftover of an earlier version of the pool
+    pub fn is_dead(&self) -> bool {
+        self.triggers.is_empty()
+    }
+}
+
+/// Every macro of the pool with the events and macros running it, in the order of the pool
+pub fn analyse_macro_usage(pool: &ObjectPool) -> Vec<MacroUsage> {
+    let mut usages: Vec<MacroUsage> = pool
+        .objects_by_type(ObjectType::Macro)
+        .into_iter()
+        .map(|object| MacroUsage {
+            macro_id: object.id(),
+            triggers: Vec::new(),
+        })
+        .collect();
+
+    for object in pool.objects() {
+        let mut triggers: Vec<(u16, MacroTrigger)> = macro_refs(object)
+            .iter()
+            .map(|macro_ref| {
+                (
+                    macro_ref.macro_id as u16,
+                    MacroTrigger::Event(object.id(), macro_ref.event_id),
+                )
+            })
+            .collect();
+        if let Object::Macro(m) = object {
+            triggers.extend(
+                executed_macros(&m.commands)
+                    .into_iter()
+                    .map(|id| (id, MacroTrigger::Macro(object.id()))),
+            );
+        }
+        for (id, trigger) in triggers {
+            if let Some(usage) = usages.iter_mut().find(|usage| usage.macro_id.value() == id) {
+                usage.triggers.push(trigger);
+            }
+        }
+    }
+    usages
+}
+
+/// The macros the object runs on its events
+pub fn macro_refs(object: &Object) -> &[MacroRef] {
+    match object {
+        Object::WorkingSet(o) => &o.macro_refs,
+        Object::DataMask(o) => &o.macro_refs,
+        Object::AlarmMask(o) => &o.macro_refs,
+        Object::Container(o) => &o.macro_refs,
+        Object::WindowMask(o) => &o.macro_refs,
+        Object::SoftKeyMask(o) => &o.macro_refs,
+        Object::Key(o) => &o.macro_refs,
+        Object::Button(o) => &o.macro_refs,
+        Object::InputBoolean(o) => &o.macro_refs,
+        Object::InputString(o) => &o.macro_refs,
+        Object::InputNumber(o) => &o.macro_refs,
+        Object::InputList(o) => &o.macro_refs,
+        Object::OutputString(o) => &o.macro_refs,
+        Object::OutputNumber(o) => &o.macro_refs,
+        Object::OutputList(o) => &o.macro_refs,
+        Object::OutputLine(o) => &o.macro_refs,
+        Object::OutputRectangle(o) => &o.macro_refs,
+        Object::OutputEllipse(o) => &o.macro_refs,
+        Object::OutputPolygon(o) => &o.macro_refs,
+        Object::OutputMeter(o) => &o.macro_refs,
+        Object::OutputLinearBarGraph(o) => &o.macro_refs,
+        Object::OutputArchedBarGraph(o) => &o.macro_refs,
+        Object::PictureGraphic(o) => &o.macro_refs,
+        Object::FontAttributes(o) => &o.macro_refs,
+        Object::LineAttributes(o) => &o.macro_refs,
+        Object::FillAttributes(o) => &o.macro_refs,
+        Object::InputAttributes(o) => &o.macro_refs,
+        _ => &[],
+    }
+}
+
+/// IDs of the macros executed by the commands of a macro
+fn executed_macros(commands: &[u8]) -> Vec<u16> {
+    let mut executed = Vec::new();
+    let mut remaining = commands;
+    while let Some(&code) = remaining.first() {
+        let length = match code {
+            CHANGE_STRING_VALUE if remaining.len() >= 5 => {
+                5 + u16::from_le_bytes([remaining[3], remaining[4]]) as usize
+            }
+            CHANGE_CHILD_POSITION => COMMAND_LENGTH + 1,
+            _ => COMMAND_LENGTH,
+        };
+        if remaining.len() < length {
+            break;
+        }
+        match code {
+            EXECUTE_MACRO => executed.push(remaining[1] as u16),
+            EXECUTE_EXTENDED_MACRO => {
+                executed.push(u16::from_le_bytes([remaining[1], remaining[2]]))
+            }
+            _ => {}
+        }
+        remaining = &remaining[length..];
+    }
+    executed
+}
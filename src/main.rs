@@ -9,30 +9,480 @@ use ag_iso_stack::object_pool::NullableObjectId;
 use ag_iso_stack::object_pool::ObjectId;
 use ag_iso_stack::object_pool::ObjectPool;
 use ag_iso_stack::object_pool::ObjectType;
+use ag_iso_terminal_designer::attribute_changes;
+use ag_iso_terminal_designer::choose_all;
+use ag_iso_terminal_designer::plan_merge;
+use ag_iso_terminal_designer::render_object_thumbnail;
+use ag_iso_terminal_designer::Autosave;
+use ag_iso_terminal_designer::Autosaver;
+use ag_iso_terminal_designer::BackupNotice;
+use ag_iso_terminal_designer::BatchRename;
+use ag_iso_terminal_designer::CanvasTheme;
+use ag_iso_terminal_designer::ChildMovePick;
 use ag_iso_terminal_designer::ConfigurableObject;
+use ag_iso_terminal_designer::DecodedImage;
+use ag_iso_terminal_designer::DesignerSettings;
+use ag_iso_terminal_designer::DuplicatePictures;
 use ag_iso_terminal_designer::EditorProject;
+use ag_iso_terminal_designer::FocusStyle;
+use ag_iso_terminal_designer::FrameProfiler;
+use ag_iso_terminal_designer::GuidelineSettings;
+use ag_iso_terminal_designer::HeaderStyle;
+use ag_iso_terminal_designer::ImageSource;
 use ag_iso_terminal_designer::InteractiveMaskRenderer;
+use ag_iso_terminal_designer::IopParser;
+use ag_iso_terminal_designer::LayoutPreset;
+use ag_iso_terminal_designer::MacroTrigger;
+use ag_iso_terminal_designer::MaskTransition;
+use ag_iso_terminal_designer::MergeChoice;
+use ag_iso_terminal_designer::MergeItem;
+use ag_iso_terminal_designer::MergeKind;
+use ag_iso_terminal_designer::NameReplace;
+use ag_iso_terminal_designer::PoolAnalyzer;
+use ag_iso_terminal_designer::PoolCommand;
+use ag_iso_terminal_designer::PoolManifest;
+use ag_iso_terminal_designer::ProfileSection;
+use ag_iso_terminal_designer::ProjectEvent;
 use ag_iso_terminal_designer::RenderableObject;
+use ag_iso_terminal_designer::SettingsDialog;
+use ag_iso_terminal_designer::Simulation;
+use ag_iso_terminal_designer::SmartRename;
+use ag_iso_terminal_designer::Template;
+use ag_iso_terminal_designer::UnsupportedObject;
+use ag_iso_terminal_designer::UsageCategory;
+use ag_iso_terminal_designer::STARTER_TEMPLATES;
+use ag_iso_terminal_designer::{
+    analyse_macro_usage, analyse_mask_usage, check_guidelines, check_mask_size,
+    find_child_move_target, find_unsupported_objects, mask_size_selector, object_list_csv,
+    preview_font_substitution, preview_object_list_import, required_vt_version,
+    unsupported_objects_report, vt_version_number, ObjectListChange,
+};
+#[cfg(target_arch = "wasm32")]
+use ag_iso_terminal_designer::{decode_share_fragment, encode_share_fragment};
+use ag_iso_terminal_designer::{
+    BASE_VT_VERSION, DEFAULT_MONOCHROME_THRESHOLD, FILTER_SHORTCUT, GO_TO_SHORTCUT,
+    LATEST_VT_VERSION, PERFORMANCE_HUD_SHORTCUT, REDO_SHORTCUT, RENAME_SHORTCUT, UNDO_SHORTCUT,
+};
 use eframe::egui;
+use std::collections::{HashMap, HashSet};
 use std::future::Future;
+#[cfg(not(target_arch = "wasm32"))]
+use std::sync::atomic::{AtomicUsize, Ordering};
 use std::sync::mpsc::Receiver;
 use std::sync::mpsc::Sender;
+#[cfg(not(target_arch = "wasm32"))]
+use std::sync::Arc;
+use web_time::Instant;
 
 const OBJECT_HIERARCHY_ID: &str = "object_hierarchy_ui";
 
+/// Size of the thumbnails in the hierarchy and the object list, fitting in a row
+const LIST_THUMBNAIL_SIZE: f32 = 16.0;
+/// Most objects listed in the go to object dialog
+const MAX_GO_TO_CANDIDATES: usize = 50;
+/// Margin along the edges of the mask and gap between the regions of a new layout preset
+const DEFAULT_LAYOUT_SPACING: u16 = 4;
+/// Size of the preview of a layout preset
+const LAYOUT_PREVIEW_SIZE: f32 = 160.0;
+/// Size of the preview of every format an image can be imported in
+const IMAGE_IMPORT_PREVIEW_SIZE: f32 = 128.0;
+/// Formats an image can be imported in, with their name
+const IMAGE_IMPORT_FORMATS: [(PictureGraphicFormat, &str); 3] = [
+    (PictureGraphicFormat::Monochrome, "Monochrome"),
+    (PictureGraphicFormat::FourBit, "4-bit (16 colours)"),
+    (PictureGraphicFormat::EightBit, "8-bit (256 colours)"),
+];
+/// Pixels per VT pixel offered when exporting the selected object as PNG
+const PNG_EXPORT_SCALES: [u8; 4] = [1, 2, 4, 8];
+/// Time the backup made before a bulk operation is shown
+const BACKUP_TOAST_DURATION: std::time::Duration = std::time::Duration::from_secs(10);
+/// Shortest time between repaints for changes over time, like timers of the simulation, in
+/// low-power mode
+const LOW_POWER_REPAINT_INTERVAL: std::time::Duration = std::time::Duration::from_millis(100);
+
 enum FileDialogReason {
     LoadPool,
     LoadProject,
+    CompareWithPool,
+    MergeOriginal,
+    MergeTheirs,
+    ImportObjectList,
     OpenImagePictureGraphics(ObjectId),
 }
 
+/// State of the find and replace dialog for object names
+#[derive(Default)]
+struct FindReplaceDialog {
+    find: String,
+    replace: String,
+    use_regex: bool,
+    case_sensitive: bool,
+}
+
+/// State of the dialog renaming the chosen objects at once
+#[derive(Default)]
+struct BatchRenameDialog {
+    /// Objects to rename, numbered in the order of the pool
+    selected: HashSet<ObjectId>,
+    /// Only list the objects whose name contains this text
+    filter: String,
+    rename: BatchRename,
+}
+
+/// Input object types that can be enabled or disabled
+const INPUT_TYPES: [ObjectType; 4] = [
+    ObjectType::InputBoolean,
+    ObjectType::InputString,
+    ObjectType::InputNumber,
+    ObjectType::InputList,
+];
+
+/// State of the dialog enabling or disabling several input objects at once
+#[derive(Default)]
+struct InputEnableDialog {
+    selected: HashSet<ObjectId>,
+    /// Objects changed by the last enable or disable, with whether they were enabled
+    changed: Option<(Vec<ObjectId>, bool)>,
+}
+
+/// State of the dialog reviewing the smart names proposed for all objects
+struct SmartNamingDialog {
+    renames: Vec<SmartRename>,
+    /// Objects that keep their name, initially those whose name looks intentional
+    keep: HashSet<ObjectId>,
+}
+
+impl SmartNamingDialog {
+    fn new(renames: Vec<SmartRename>) -> Self {
+        let keep = renames
+            .iter()
+            .filter(|rename| rename.intentional)
+            .map(|rename| rename.object)
+            .collect();
+        SmartNamingDialog { renames, keep }
+    }
+}
+
+/// State of the tool replacing a font attributes object by another one
+#[derive(Default)]
+struct FontSubstitution {
+    from: Option<ObjectId>,
+    to: Option<ObjectId>,
+    /// Only replace the font of the objects shown on this mask
+    scope: Option<ObjectId>,
+    /// Result of the last substitution, shown until the next one
+    message: Option<String>,
+}
+
+/// State of the dialog listing the objects unreachable from the working set
+#[derive(Default)]
+struct OrphanCleanup {
+    /// Orphans checked for removal
+    selected: HashSet<ObjectId>,
+}
+
+/// State of the report listing the macros with what runs them
+#[derive(Default)]
+struct MacroReport {
+    /// Unused macros checked for removal
+    selected: HashSet<ObjectId>,
+}
+
+/// State of the dialog renumbering objects into a range of IDs per type
+struct RenumberDialog {
+    /// Renumber the objects of every type, instead of only the chosen types
+    all_types: bool,
+    object_types: HashSet<ObjectType>,
+}
+
+impl Default for RenumberDialog {
+    fn default() -> Self {
+        RenumberDialog {
+            all_types: true,
+            object_types: HashSet::new(),
+        }
+    }
+}
+
+/// State of the window showing how much of a mask is covered by text, graphics and inputs
+#[derive(Default)]
+struct MaskUsageView {
+    /// The mask to show, the active mask of the working set if none is chosen
+    mask: Option<ObjectId>,
+}
+
+/// State of the wizard repairing references to objects missing from the pool
+#[derive(Default)]
+struct RepairWizard {
+    /// Object chosen to take the place of each missing object
+    remap_targets: HashMap<ObjectId, ObjectId>,
+    /// Type chosen for the placeholder of each missing object
+    placeholder_types: HashMap<ObjectId, ObjectType>,
+    /// Result of the last repair, shown until the next one
+    message: Option<String>,
+}
+
+/// Categories of object types the flat object list can be filtered on
+#[derive(Clone, Copy, PartialEq, Eq, Hash)]
+enum ObjectCategory {
+    Masks,
+    Keys,
+    Inputs,
+    Outputs,
+    Attributes,
+    Variables,
+    Pictures,
+    Macros,
+}
+
+impl ObjectCategory {
+    const ALL: [ObjectCategory; 8] = [
+        ObjectCategory::Masks,
+        ObjectCategory::Keys,
+        ObjectCategory::Inputs,
+        ObjectCategory::Outputs,
+        ObjectCategory::Attributes,
+        ObjectCategory::Variables,
+        ObjectCategory::Pictures,
+        ObjectCategory::Macros,
+    ];
+
+    fn name(self) -> &'static str {
+        match self {
+            ObjectCategory::Masks => "Masks",
+            ObjectCategory::Keys => "Keys",
+            ObjectCategory::Inputs => "Inputs",
+            ObjectCategory::Outputs => "Outputs",
+            ObjectCategory::Attributes => "Attributes",
+            ObjectCategory::Variables => "Variables",
+            ObjectCategory::Pictures => "Pictures",
+            ObjectCategory::Macros => "Macros",
+        }
+    }
+
+    fn contains(self, object_type: ObjectType) -> bool {
+        match self {
+            ObjectCategory::Masks => matches!(
+                object_type,
+                ObjectType::DataMask
+                    | ObjectType::AlarmMask
+                    | ObjectType::SoftKeyMask
+                    | ObjectType::WindowMask
+            ),
+            ObjectCategory::Keys => matches!(
+                object_type,
+                ObjectType::Key | ObjectType::KeyGroup | ObjectType::Button
+            ),
+            ObjectCategory::Inputs => matches!(
+                object_type,
+                ObjectType::InputBoolean
+                    | ObjectType::InputString
+                    | ObjectType::InputNumber
+                    | ObjectType::InputList
+            ),
+            ObjectCategory::Outputs => matches!(
+                object_type,
+                ObjectType::OutputString
+                    | ObjectType::OutputNumber
+                    | ObjectType::OutputList
+                    | ObjectType::OutputLine
+                    | ObjectType::OutputRectangle
+                    | ObjectType::OutputEllipse
+                    | ObjectType::OutputPolygon
+                    | ObjectType::OutputMeter
+                    | ObjectType::OutputLinearBarGraph
+                    | ObjectType::OutputArchedBarGraph
+            ),
+            ObjectCategory::Attributes => matches!(
+                object_type,
+                ObjectType::FontAttributes
+                    | ObjectType::LineAttributes
+                    | ObjectType::FillAttributes
+                    | ObjectType::InputAttributes
+                    | ObjectType::ExtendedInputAttributes
+            ),
+            ObjectCategory::Variables => matches!(
+                object_type,
+                ObjectType::NumberVariable | ObjectType::StringVariable
+            ),
+            ObjectCategory::Pictures => matches!(
+                object_type,
+                ObjectType::PictureGraphic | ObjectType::ScaledGraphic | ObjectType::Animation
+            ),
+            ObjectCategory::Macros => object_type == ObjectType::Macro,
+        }
+    }
+}
+
+/// Open dialog of the template library
+enum TemplateDialog {
+    /// Save the object and everything it references as a template with the entered name
+    Save { root: ObjectId, name: String },
+    /// Insert one of the templates of the library
+    Insert { templates: Vec<Template> },
+}
+
 pub struct DesignerApp {
     project: Option<EditorProject>,
     file_dialog_reason: Option<FileDialogReason>,
-    file_channel: (Sender<Vec<u8>>, Receiver<Vec<u8>>),
+    /// Contents and, on native targets, path of the files picked in a file dialog
+    file_channel: (
+        Sender<(Vec<u8>, Option<String>)>,
+        Receiver<(Vec<u8>, Option<String>)>,
+    ),
+    /// Paths the project is saved to
+    saved_path_channel: (Sender<String>, Receiver<String>),
+    /// File the project was loaded from or last saved to
+    project_path: Option<String>,
+    autosaver: Autosaver,
+    /// Autosave found for the project that was just opened, offered to be restored
+    autosave_offer: Option<Autosave>,
+    /// Objects of the opened pool the designer can't edit or render yet
+    unsupported_objects: Vec<UnsupportedObject>,
     show_development_popup: bool,
     new_object_dialog: Option<(ObjectType, String)>,
-    apply_smart_naming_on_import: bool,
+    template_dialog: Option<TemplateDialog>,
+    find_replace_dialog: Option<FindReplaceDialog>,
+    /// Open go to object dialog, with the typed name, header identifier or ID
+    go_to: Option<String>,
+    batch_rename_dialog: Option<BatchRenameDialog>,
+    input_enable_dialog: Option<InputEnableDialog>,
+    smart_naming_dialog: Option<SmartNamingDialog>,
+    font_substitution: Option<FontSubstitution>,
+    orphan_cleanup: Option<OrphanCleanup>,
+    macro_report: Option<MacroReport>,
+    repair_wizard: Option<RepairWizard>,
+    mask_usage: Option<MaskUsageView>,
+    renumber_dialog: Option<RenumberDialog>,
+    /// Backup made before the last bulk operation, shown until the time has passed
+    backup_toast: Option<(BackupNotice, Instant)>,
+    /// Pictures updated by the last re-import of changed images, and the errors
+    image_reimport: Option<(Vec<ObjectId>, Vec<String>)>,
+    /// Fixes made by the last check of the object info
+    object_info_check: Option<Vec<String>>,
+    /// Link sharing the project made last, or why it couldn't be made or opened
+    share_link: Option<Result<String, String>>,
+    settings: DesignerSettings,
+    settings_dialog: Option<SettingsDialog>,
+    simulation: Option<Simulation>,
+    profiler: FrameProfiler,
+    analyzer: PoolAnalyzer,
+    show_analysis: bool,
+    show_history: bool,
+    /// Open snapshot manager, with the name of the next snapshot and the compared snapshot
+    snapshot_manager: Option<(String, Option<usize>)>,
+    drift_check: Option<DriftCheck>,
+    merge_dialog: Option<MergeDialog>,
+    layout_preset_dialog: Option<LayoutPresetDialog>,
+    image_import_dialog: Option<ImageImportDialog>,
+    object_list_import: Option<ObjectListImport>,
+    pool_loading: Option<PoolLoading>,
+    /// Name of a project file that was recovered, why it couldn't be loaded and the parts that
+    /// were left out
+    recovery_report: Option<(String, String, Vec<String>)>,
+}
+
+/// An IOP file parsed in the background, so opening a big pool doesn't freeze the UI
+struct PoolLoading {
+    file_name: String,
+    path: Option<String>,
+    size: usize,
+    started: Instant,
+    parsing: PoolParsing,
+}
+
+/// How an IOP file is parsed without freezing the UI
+enum PoolParsing {
+    /// On another thread, which sends the pool when it is parsed
+    #[cfg(not(target_arch = "wasm32"))]
+    Thread {
+        /// Number of bytes parsed so far
+        parsed: Arc<AtomicUsize>,
+        receiver: Receiver<ObjectPool>,
+    },
+    /// A part of the file every frame, the web has no threads
+    #[cfg(target_arch = "wasm32")]
+    Frames(IopParser),
+}
+
+impl PoolParsing {
+    /// Number of bytes parsed so far
+    fn parsed_bytes(&self) -> usize {
+        match self {
+            #[cfg(not(target_arch = "wasm32"))]
+            PoolParsing::Thread { parsed, .. } => parsed.load(Ordering::Relaxed),
+            #[cfg(target_arch = "wasm32")]
+            PoolParsing::Frames(parser) => parser.parsed_bytes(),
+        }
+    }
+}
+
+/// How often the progress of parsing an IOP file is updated
+const POOL_LOADING_PROGRESS_INTERVAL: std::time::Duration = std::time::Duration::from_millis(250);
+
+/// Time spent parsing an IOP file every frame where there are no threads
+#[cfg(target_arch = "wasm32")]
+const POOL_LOADING_FRAME_TIME: std::time::Duration = std::time::Duration::from_millis(12);
+
+/// An edited object list to import, with the changes it makes to the project
+struct ObjectListImport {
+    file_name: String,
+    csv: String,
+    /// Generation of the project pool the changes were found for
+    generation: u64,
+    changes: Result<Vec<ObjectListChange>, String>,
+}
+
+/// State of the dialog choosing the format an image file is imported in
+struct ImageImportDialog {
+    id: ObjectId,
+    path: Option<String>,
+    image: DecodedImage,
+    format: PictureGraphicFormat,
+    /// Brightness from which pixels get the brighter colour in monochrome
+    threshold: u8,
+    /// The converted picture with its preview per format, made for `preview_threshold`
+    previews: Vec<(PictureGraphic, egui::TextureHandle)>,
+    preview_threshold: Option<u8>,
+}
+
+/// State of the dialog adding the containers of a layout preset to a data mask
+struct LayoutPresetDialog {
+    mask: ObjectId,
+    preset: LayoutPreset,
+    /// Safe area kept free along the edges of the mask
+    margin: u16,
+    gap: u16,
+}
+
+/// State of the dialog merging their copy of the project into the opened one, both edited from
+/// the same original
+#[derive(Default)]
+struct MergeDialog {
+    /// File name and pool of the original
+    original: Option<(String, ObjectPool)>,
+    /// File name, pool and object names of their copy
+    theirs: Option<(String, ObjectPool, HashMap<ObjectId, String>)>,
+    /// Generation of the project pool the items were found for
+    generation: u64,
+    items: Vec<MergeItem>,
+    choices: HashMap<ObjectId, MergeChoice>,
+}
+
+/// Comparison of the project with an IOP or project file, e.g. the one released to machines or
+/// an update of a vendor
+struct DriftCheck {
+    file_name: String,
+    pool: ObjectPool,
+    /// Names of the objects in the compared file, only known for project files
+    names: HashMap<ObjectId, String>,
+    /// The difference navigated to
+    current: Option<usize>,
+    /// Generation of the project pool the differences were found for
+    generation: u64,
+    differences: Vec<ProjectEvent>,
+    /// Whether the IOP file is byte for byte equal to the export of the project
+    identical: bool,
 }
 
 impl DesignerApp {
@@ -106,14 +556,68 @@ impl DesignerApp {
         //     .unwrap()
         //     .insert(0, "iso_greek".to_owned());
 
-        Self {
+        let settings = DesignerSettings::load();
+
+        // Offer the work of a session that ended without saving it
+        let mut autosaver = Autosaver::default();
+        autosaver.configure(&settings.autosave);
+        let autosave_offer = if settings.autosave.enabled {
+            autosaver.find_recoverable()
+        } else {
+            None
+        };
+
+        let mut app = Self {
             project: None,
             file_dialog_reason: None,
             file_channel: std::sync::mpsc::channel(),
-            show_development_popup: true,
+            saved_path_channel: std::sync::mpsc::channel(),
+            project_path: None,
+            autosaver,
+            autosave_offer,
+            unsupported_objects: Vec::new(),
+            show_development_popup: settings.general.show_development_popup,
             new_object_dialog: None,
-            apply_smart_naming_on_import: true, // Default to true for better UX
+            template_dialog: None,
+            find_replace_dialog: None,
+            go_to: None,
+            batch_rename_dialog: None,
+            input_enable_dialog: None,
+            smart_naming_dialog: None,
+            font_substitution: None,
+            orphan_cleanup: None,
+            macro_report: None,
+            repair_wizard: None,
+            mask_usage: None,
+            renumber_dialog: None,
+            backup_toast: None,
+            image_reimport: None,
+            object_info_check: None,
+            share_link: None,
+            settings,
+            settings_dialog: None,
+            simulation: None,
+            profiler: FrameProfiler::default(),
+            analyzer: PoolAnalyzer::default(),
+            show_analysis: false,
+            show_history: false,
+            snapshot_manager: None,
+            drift_check: None,
+            merge_dialog: None,
+            layout_preset_dialog: None,
+            image_import_dialog: None,
+            object_list_import: None,
+            pool_loading: None,
+            recovery_report: None,
+        };
+        // Restoring unsaved work takes precedence over reopening the last project
+        #[cfg(not(target_arch = "wasm32"))]
+        if app.settings.general.restore_last_session && app.autosave_offer.is_none() {
+            app.restore_session(&cc.egui_ctx);
         }
+        #[cfg(target_arch = "wasm32")]
+        app.open_shared_project();
+        app
     }
 }
 
@@ -142,330 +646,4279 @@ impl DesignerApp {
             let file = task.await;
             if let Some(file) = file {
                 let content = file.read().await;
-                let _ = sender.send(content);
+                let _ = sender.send((content, file_path(&file)));
             }
             ctx.request_repaint();
         });
     }
 
     /// Handle a file loaded in the file dialog
-    fn handle_file_loaded(&mut self) {
-        if let Ok(content) = self.file_channel.1.try_recv() {
+    fn handle_file_loaded(&mut self, ctx: &egui::Context) {
+        if let Ok((content, path)) = self.file_channel.1.try_recv() {
             match self.file_dialog_reason {
-                Some(FileDialogReason::LoadPool) => {
-                    let project = EditorProject::from(ObjectPool::from_iop(content));
-                    // Apply smart naming to all objects that don't have custom names (if enabled)
-                    if self.apply_smart_naming_on_import {
-                        project.apply_smart_naming_to_all_objects();
+                Some(FileDialogReason::LoadPool) => self.load_pool_file(content, path, ctx),
+                Some(FileDialogReason::LoadProject) => self.load_project_file(content, path),
+                Some(FileDialogReason::CompareWithPool) => {
+                    let (pool, names) = read_pool_file(content);
+                    self.drift_check = Some(DriftCheck {
+                        file_name: file_name(&path),
+                        pool,
+                        names,
+                        current: None,
+                        generation: u64::MAX,
+                        differences: Vec::new(),
+                        identical: false,
+                    });
+                }
+                Some(FileDialogReason::MergeOriginal) => {
+                    if let Some(merge) = &mut self.merge_dialog {
+                        merge.original = Some((file_name(&path), read_pool_file(content).0));
+                        merge.generation = u64::MAX;
                     }
-                    self.project = Some(project);
                 }
-                Some(FileDialogReason::LoadProject) => {
-                    match EditorProject::load_project(content) {
-                        Ok(project) => {
-                            self.project = Some(project);
-                        }
-                        Err(e) => {
-                            log::error!("Failed to load project: {}", e);
-                            // TODO: Show error dialog
-                        }
+                Some(FileDialogReason::MergeTheirs) => {
+                    if let Some(merge) = &mut self.merge_dialog {
+                        let (pool, names) = read_pool_file(content);
+                        merge.theirs = Some((file_name(&path), pool, names));
+                        merge.generation = u64::MAX;
                     }
                 }
+                Some(FileDialogReason::ImportObjectList) => {
+                    let file_name = path
+                        .as_deref()
+                        .and_then(|path| std::path::Path::new(path).file_name())
+                        .map_or("CSV file".to_string(), |name| {
+                            name.to_string_lossy().into_owned()
+                        });
+                    self.object_list_import = Some(ObjectListImport {
+                        file_name,
+                        csv: String::from_utf8_lossy(&content).into_owned(),
+                        generation: u64::MAX,
+                        changes: Ok(Vec::new()),
+                    });
+                }
                 Some(FileDialogReason::OpenImagePictureGraphics(id)) => {
-                    if let Some(pool) = &mut self.project {
-                        if let Some(obj) = pool.get_mut_pool().borrow_mut().object_mut_by_id(id) {
-                            match obj {
-                                Object::PictureGraphic(o) => {
-                                    if let Ok(img) = image::load_from_memory(&content) {
-                                        // Update dimensions based on the new picture
-                                        let w = img.width();
-                                        let h = img.height();
-
-                                        if w > u16::MAX as u32 || h > u16::MAX as u32 {
-                                            log::error!(
-                                                "Image dimensions exceed maximum size of {}x{}",
-                                                u16::MAX,
-                                                u16::MAX
-                                            );
-                                            return;
-                                        }
+                    match DecodedImage::decode(&content) {
+                        Ok(image) => {
+                            self.image_import_dialog = Some(ImageImportDialog {
+                                id,
+                                path,
+                                image,
+                                format: PictureGraphicFormat::EightBit,
+                                threshold: DEFAULT_MONOCHROME_THRESHOLD,
+                                previews: Vec::new(),
+                                preview_threshold: None,
+                            });
+                        }
+                        Err(e) => log::error!("{}", e),
+                    }
+                }
+                _ => (),
+            }
+        }
+    }
 
-                                        o.actual_width = w as u16;
-                                        o.actual_height = h as u16;
-                                        if o.width == 0 {
-                                            o.width = o.actual_width;
-                                        }
+    /// Parse an IOP file in the background, it is opened as a new project when it is parsed
+    fn load_pool_file(&mut self, content: Vec<u8>, path: Option<String>, ctx: &egui::Context) {
+        let file_name = path
+            .as_deref()
+            .and_then(|path| std::path::Path::new(path).file_name())
+            .map_or("IOP file".to_string(), |name| {
+                name.to_string_lossy().into_owned()
+            });
+        let size = content.len();
 
-                                        // Set format by default to 8-bit color, user can change it in UI
-                                        o.format = PictureGraphicFormat::EightBit;
+        #[cfg(not(target_arch = "wasm32"))]
+        let parsing = {
+            let (sender, receiver) = std::sync::mpsc::channel();
+            let parsed = Arc::new(AtomicUsize::new(0));
+            let progress = parsed.clone();
+            let ctx = ctx.clone();
+            execute(async move {
+                let mut parser = IopParser::new(content);
+                let pool = loop {
+                    let deadline = Instant::now() + POOL_LOADING_PROGRESS_INTERVAL;
+                    if let Some(pool) = parser.parse(Some(deadline)) {
+                        break pool;
+                    }
+                    // Only this task holds the progress anymore if loading was cancelled
+                    if Arc::strong_count(&progress) == 1 {
+                        return;
+                    }
+                    progress.store(parser.parsed_bytes(), Ordering::Relaxed);
+                };
+                // The receiver is gone if loading was cancelled
+                _ = sender.send(pool);
+                ctx.request_repaint();
+            });
+            PoolParsing::Thread { parsed, receiver }
+        };
+        #[cfg(target_arch = "wasm32")]
+        let parsing = {
+            ctx.request_repaint();
+            PoolParsing::Frames(IopParser::new(content))
+        };
 
-                                        // We set transparent color to 1 (arbitrary choice) as we
-                                        // only use index 15..255 for actual colors
-                                        o.transparency_colour = 1;
-                                        o.options.transparent = true;
+        self.pool_loading = Some(PoolLoading {
+            file_name,
+            path,
+            size,
+            started: Instant::now(),
+            parsing,
+        });
+    }
 
-                                        let rgba = if let Some(view) = img.as_rgba8() {
-                                            // Borrowed view (no allocation)
-                                            std::borrow::Cow::Borrowed(view)
-                                        } else {
-                                            // Allocates once if the image isn't already RGBA8
-                                            std::borrow::Cow::Owned(img.to_rgba8())
-                                        };
+    /// Show the progress of parsing an IOP file, which can be cancelled, and open the pool when
+    /// it is parsed
+    fn show_pool_loading(&mut self, ctx: &egui::Context) {
+        let Some(loading) = &mut self.pool_loading else {
+            return;
+        };
+        let parsed = match &mut loading.parsing {
+            #[cfg(not(target_arch = "wasm32"))]
+            PoolParsing::Thread { receiver, .. } => match receiver.try_recv() {
+                Ok(pool) => Some(pool),
+                Err(std::sync::mpsc::TryRecvError::Disconnected) => {
+                    log::error!("Parsing {} stopped without a result", loading.file_name);
+                    self.pool_loading = None;
+                    return;
+                }
+                Err(std::sync::mpsc::TryRecvError::Empty) => None,
+            },
+            #[cfg(target_arch = "wasm32")]
+            PoolParsing::Frames(parser) => {
+                let pool = parser.parse(Some(Instant::now() + POOL_LOADING_FRAME_TIME));
+                ctx.request_repaint();
+                pool
+            }
+        };
+        if let Some(pool) = parsed {
+            let path = loading.path.clone();
+            self.pool_loading = None;
+            self.open_pool(pool, path);
+            return;
+        }
 
-                                        // Build raw and run-length encoded data
-                                        let pixel_count = (w as usize) * (h as usize);
+        let mut cancel = false;
+        egui::Modal::new(egui::Id::new("pool_loading")).show(ctx, |ui| {
+            ui.set_width(320.0);
+            ui.strong(format!("Opening {}", loading.file_name));
+            let parsed = loading.parsing.parsed_bytes();
+            ui.add(
+                egui::ProgressBar::new(parsed as f32 / loading.size.max(1) as f32)
+                    .show_percentage(),
+            );
+            ui.label(format!(
+                "Parsed {} of {} kB of object pool data, {} s elapsed",
+                parsed.div_ceil(1024),
+                loading.size.div_ceil(1024),
+                loading.started.elapsed().as_secs()
+            ));
+            if ui.button("Cancel").clicked() {
+                cancel = true;
+            }
+        });
+        if cancel {
+            self.pool_loading = None;
+        } else {
+            // The parsing wakes the UI when it is done, this keeps the progress up to date
+            ctx.request_repaint_after(POOL_LOADING_PROGRESS_INTERVAL);
+        }
+    }
 
-                                        // Worst case: raw = N, rle = 2*N
-                                        let mut raw = Vec::with_capacity(pixel_count);
-                                        let mut rle = Vec::with_capacity(pixel_count * 2);
+    /// Open a parsed pool as a new project
+    fn open_pool(&mut self, pool: ObjectPool, path: Option<String>) {
+        let mut project = EditorProject::from(pool);
+        project.mask_size = project.mask_size.max(self.settings.terminal.data_mask_size);
+        project.set_naming_rules(&self.settings.naming.rules);
+        // Propose smart names for the objects of the pool (if enabled), applied after review
+        let renames = self
+            .settings
+            .naming
+            .apply_smart_naming_on_import
+            .then(|| project.preview_smart_naming());
+        self.open_project(project, path);
+        self.smart_naming_dialog = renames
+            .filter(|renames| !renames.is_empty())
+            .map(SmartNamingDialog::new);
+    }
 
-                                        let mut have_run = false;
-                                        let mut run_value: u8 = 0;
-                                        let mut run_count: u8 = 0;
+    /// Open a project file, or else what can be recovered of it as a new unsaved project
+    fn load_project_file(&mut self, content: Vec<u8>, path: Option<String>) {
+        let error = match EditorProject::load_project(&content) {
+            Ok(project) => {
+                self.open_project(project, path);
+                return;
+            }
+            Err(e) => e,
+        };
+        log::error!("Failed to load project: {}", error);
+        match EditorProject::recover_project(&content) {
+            Ok((project, dropped)) => {
+                self.open_project(project, None);
+                let file_name = path
+                    .as_deref()
+                    .and_then(|path| std::path::Path::new(path).file_name())
+                    .map_or("The project file".to_string(), |name| {
+                        name.to_string_lossy().into_owned()
+                    });
+                self.recovery_report = Some((file_name, error, dropped));
+            }
+            Err(e) => log::error!("Failed to recover project: {}", e),
+        }
+    }
 
-                                        for p in rgba.pixels() {
-                                            let idx = if p[3] == 0 {
-                                                o.transparency_colour
-                                            } else {
-                                                find_closest_color_index(p[0], p[1], p[2])
-                                            };
+    /// Report the parts of a project file that were left out when recovering it
+    fn show_recovery_report(&mut self, ctx: &egui::Context) {
+        let Some((file_name, error, dropped)) = &self.recovery_report else {
+            return;
+        };
+        let mut close = false;
+        egui::Modal::new(egui::Id::new("recovery_report")).show(ctx, |ui| {
+            ui.set_width(400.0);
+            ui.heading("Project partly recovered");
+            ui.label(format!("{} could not be loaded: {}", file_name, error));
+            ui.label(
+                "What could be read is opened as a new, unsaved project. Save it under another \
+                name to keep the original file.",
+            );
+            if dropped.is_empty() {
+                ui.label("Nothing was left out.");
+            } else {
+                ui.strong("Left out:");
+                for part in dropped {
+                    ui.label(format!("\u{2022} {}", part));
+                }
+            }
+            ui.separator();
+            close = ui.button("OK").clicked();
+        });
+        if close {
+            self.recovery_report = None;
+        }
+    }
 
-                                            raw.push(idx);
+    /// Open a recently opened pool or project again
+    #[cfg(not(target_arch = "wasm32"))]
+    fn open_recent_file(&mut self, path: &str, ctx: &egui::Context) {
+        match std::fs::read(path) {
+            Ok(content) if path.to_ascii_lowercase().ends_with(".iop") => {
+                self.load_pool_file(content, Some(path.to_string()), ctx)
+            }
+            Ok(content) => self.load_project_file(content, Some(path.to_string())),
+            Err(e) => {
+                log::error!("Failed to open {}: {}", path, e);
+                self.settings.remove_recent_file(path);
+                self.settings.save();
+            }
+        }
+    }
 
-                                            if !have_run {
-                                                have_run = true;
-                                                run_value = idx;
-                                                run_count = 1;
-                                                continue;
-                                            }
+    /// Reopen the project of the last session with its selected object and mask size
+    #[cfg(not(target_arch = "wasm32"))]
+    fn restore_session(&mut self, ctx: &egui::Context) {
+        let session = self.settings.session.clone();
+        let Some(path) = &session.project_path else {
+            return;
+        };
+        self.open_recent_file(path, ctx);
+        if let Some(project) = &mut self.project {
+            if let Some(mask_size) = session.mask_size {
+                project.mask_size = mask_size;
+            }
+            if let Some(id) = session
+                .selected_object
+                .and_then(|id| ObjectId::new(id).ok())
+                .filter(|id| project.get_object(*id).is_some())
+            {
+                project
+                    .get_mut_selected()
+                    .replace(NullableObjectId(Some(id)));
+            }
+        }
+    }
 
-                                            if idx == run_value && run_count < u8::MAX {
-                                                run_count += 1;
-                                            } else {
-                                                rle.push(run_count);
-                                                rle.push(run_value);
-                                                run_value = idx;
-                                                run_count = 1;
-                                            }
-                                        }
+    /// Keep the session state in the settings up to date, so it can be restored on the next
+    /// start. Stored once nothing is being dragged, e.g. a panel edge or the mask size slider.
+    fn record_session(&mut self, ctx: &egui::Context, panel_widths: (Option<f32>, Option<f32>)) {
+        let mut session = self.settings.session.clone();
+        session.project_path = self.project_path.clone().filter(|_| self.project.is_some());
+        if let Some(project) = &self.project {
+            session.selected_object = project.get_selected().0.map(|id| id.value());
+            session.mask_size = Some(project.mask_size);
+        }
+        session.left_panel_width = panel_widths.0.or(session.left_panel_width);
+        session.right_panel_width = panel_widths.1.or(session.right_panel_width);
+        if session != self.settings.session && !ctx.input(|i| i.pointer.any_down()) {
+            self.settings.session = session;
+            self.settings.save();
+        }
+    }
 
-                                        // flush final run
-                                        if have_run {
-                                            rle.push(run_count);
-                                            rle.push(run_value);
-                                        }
+    /// Make a loaded project the open project, offering to restore its autosave if there is one
+    fn open_project(&mut self, project: EditorProject, path: Option<String>) {
+        self.unsupported_objects = find_unsupported_objects(project.get_pool());
+        self.project = Some(project);
+        self.simulation = None;
+        self.smart_naming_dialog = None;
+        self.merge_dialog = None;
+        self.layout_preset_dialog = None;
+        self.image_import_dialog = None;
+        self.input_enable_dialog = None;
+        self.analyzer = PoolAnalyzer::default();
+        self.autosaver.reset();
+        self.autosave_offer = path.as_deref().and_then(|path| self.autosaver.find(path));
+        if let Some(path) = &path {
+            self.settings.add_recent_file(path);
+            self.settings.save();
+        }
+        self.project_path = path;
+    }
 
-                                        // Choose the best encoding
-                                        if rle.len() < raw.len() {
-                                            o.data = rle;
-                                            o.options.data_code_type = DataCodeType::RunLength;
-                                            log::info!(
-                                            "Selected run-length encoding ({} bytes) over raw ({} bytes)",
-                                            o.data.len(),
-                                            raw.len()
-                                        );
-                                        } else {
-                                            o.data = raw;
-                                            o.options.data_code_type = DataCodeType::Raw;
-                                            log::info!(
-                                            "Selected raw encoding ({} bytes) over run-length ({} bytes)",
-                                            o.data.len(),
-                                            rle.len()
-                                        );
+    /// List the objects of the opened pool the designer can't edit or render yet, so they don't
+    /// go unnoticed. Shown once the autosave offer is answered.
+    fn show_unsupported_objects(&mut self, ctx: &egui::Context) {
+        if self.unsupported_objects.is_empty() || self.autosave_offer.is_some() {
+            return;
+        }
+        let Some(project) = &self.project else {
+            self.unsupported_objects.clear();
+            return;
+        };
+
+        let mut open = true;
+        let mut close = false;
+        egui::Window::new("Unsupported objects")
+            .open(&mut open)
+            .default_width(420.0)
+            .show(ctx, |ui| {
+                ui.label(format!(
+                    "The pool contains {} objects the designer can't fully work with yet. \
+                    They are kept as they are when the pool is saved.",
+                    self.unsupported_objects.len()
+                ));
+                ui.add_space(5.0);
+                egui::ScrollArea::vertical()
+                    .max_height(300.0)
+                    .show(ui, |ui| {
+                        egui::Grid::new("unsupported_objects")
+                            .striped(true)
+                            .num_columns(3)
+                            .show(ui, |ui| {
+                                for object in &self.unsupported_objects {
+                                    let label = match project.get_object(object.id) {
+                                        Some(obj) => format!(
+                                            "{}: {}",
+                                            object.id.value(),
+                                            project.get_object_info(obj).get_name(obj)
+                                        ),
+                                        None => object.id.value().to_string(),
+                                    };
+                                    if ui.link(label).on_hover_text("Select the object").clicked() {
+                                        project
+                                            .get_mut_selected()
+                                            .replace(NullableObjectId(Some(object.id)));
+                                    }
+                                    ui.label(format!("{:?}", object.object_type));
+                                    ui.weak(object.limitation);
+                                    ui.end_row();
+                                }
+                            });
+                    });
+                ui.add_space(5.0);
+                ui.horizontal(|ui| {
+                    if ui
+                        .button("Copy report")
+                        .on_hover_text("Copy a summary to paste into an issue")
+                        .clicked()
+                    {
+                        ui.ctx()
+                            .copy_text(unsupported_objects_report(&self.unsupported_objects));
+                    }
+                    close = ui.button("Close").clicked();
+                });
+            });
+        if !open || close {
+            self.unsupported_objects.clear();
+        }
+    }
+
+    /// Show the dialog to save a template or insert one from the library
+    fn show_template_dialog(&mut self, ctx: &egui::Context) {
+        let (Some(dialog), Some(project)) = (&mut self.template_dialog, &self.project) else {
+            self.template_dialog = None;
+            return;
+        };
+
+        let mut open = true;
+        let mut close = false;
+        match dialog {
+            TemplateDialog::Save { root, name } => {
+                egui::Window::new("Save as template")
+                    .collapsible(false)
+                    .resizable(false)
+                    .open(&mut open)
+                    .show(ctx, |ui| {
+                        ui.label(
+                            "The object is saved with all objects it references, so the \
+                            template can be inserted into any project.",
+                        );
+                        ui.horizontal(|ui| {
+                            ui.label("Name:");
+                            ui.text_edit_singleline(name);
+                        });
+                        let exists = ag_iso_terminal_designer::template_exists(name);
+                        if exists {
+                            ui.colored_label(
+                                egui::Color32::YELLOW,
+                                "A template with this name is replaced",
+                            );
+                        }
+                        ui.horizontal(|ui| {
+                            if ui
+                                .add_enabled(!name.trim().is_empty(), egui::Button::new("Save"))
+                                .clicked()
+                            {
+                                match project.create_template(*root, name.trim().to_string()) {
+                                    Some(template) => {
+                                        match ag_iso_terminal_designer::save_template(&template) {
+                                            Ok(()) => close = true,
+                                            Err(e) => {
+                                                log::error!("Failed to save template: {}", e)
+                                            }
                                         }
-                                    } else {
-                                        log::error!("Failed to decode image");
                                     }
+                                    None => close = true,
                                 }
-                                _ => (),
                             }
+                            close |= ui.button("Cancel").clicked();
+                        });
+                    });
+            }
+            TemplateDialog::Insert { templates } => {
+                let mut deleted = None;
+                egui::Window::new("Insert template")
+                    .open(&mut open)
+                    .default_width(360.0)
+                    .show(ctx, |ui| {
+                        if templates.is_empty() {
+                            ui.weak(
+                                "No templates yet, save one with Templates > Save selected \
+                                as template",
+                            );
                         }
+                        egui::ScrollArea::vertical()
+                            .max_height(300.0)
+                            .show(ui, |ui| {
+                                egui::Grid::new("templates")
+                                    .striped(true)
+                                    .num_columns(3)
+                                    .show(ui, |ui| {
+                                        for (idx, template) in templates.iter().enumerate() {
+                                            ui.label(&template.name);
+                                            ui.weak(format!(
+                                                "{}, {} objects",
+                                                template.root_type, template.object_count
+                                            ));
+                                            ui.horizontal(|ui| {
+                                                if ui
+                                                    .button("Insert")
+                                                    .on_hover_text(
+                                                        "Add the objects of the template, with \
+                                                        new IDs where they are in use",
+                                                    )
+                                                    .clicked()
+                                                {
+                                                    match project.insert_template(template) {
+                                                        Ok(id) => {
+                                                            project.get_mut_selected().replace(
+                                                                NullableObjectId(Some(id)),
+                                                            );
+                                                            close = true;
+                                                        }
+                                                        Err(e) => log::error!(
+                                                            "Failed to insert template: {}",
+                                                            e
+                                                        ),
+                                                    }
+                                                }
+                                                if ui.button("Delete").clicked() {
+                                                    deleted = Some(idx);
+                                                }
+                                            });
+                                            ui.end_row();
+                                        }
+                                    });
+                            });
+                    });
+                if let Some(idx) = deleted {
+                    let template = templates.remove(idx);
+                    if let Err(e) = ag_iso_terminal_designer::delete_template(&template.name) {
+                        log::error!("Failed to delete template: {}", e);
                     }
                 }
-                _ => (),
             }
         }
-    }
-
-    /// Open a file dialog to save a pool file
-    fn save_pool(&mut self) {
-        if let Some(pool) = &self.project {
-            let task = rfd::AsyncFileDialog::new()
-                .set_file_name("object_pool.iop")
-                .save_file();
-            let contents = pool.get_pool().as_iop();
-            execute(async move {
-                let file = task.await;
-                if let Some(file) = file {
-                    _ = file.write(&contents).await;
-                }
-            });
+        if !open || close {
+            self.template_dialog = None;
         }
     }
 
-    /// Open a file dialog to save a project file
-    fn save_project(&mut self) {
-        if let Some(project) = &self.project {
-            match project.save_project() {
-                Ok(contents) => {
-                    let task = rfd::AsyncFileDialog::new()
-                        .set_file_name("project.aitp")
-                        .add_filter("AgIsoTerminal Project", &["aitp"])
-                        .save_file();
-                    execute(async move {
-                        let file = task.await;
-                        if let Some(file) = file {
-                            _ = file.write(&contents).await;
+    /// Show the result of re-importing the changed linked images
+    fn show_image_reimport(&mut self, ctx: &egui::Context) {
+        let Some((updated, errors)) = &self.image_reimport else {
+            return;
+        };
+        let mut open = true;
+        let mut close = false;
+        let mut navigate_to = None;
+        egui::Window::new("Re-import changed images")
+            .open(&mut open)
+            .collapsible(false)
+            .show(ctx, |ui| {
+                if updated.is_empty() && errors.is_empty() {
+                    ui.label("All linked images are up to date.");
+                } else {
+                    ui.label(format!("{} pictures updated", updated.len()));
+                }
+                if let Some(project) = &self.project {
+                    for id in updated {
+                        if let Some(object) = project.get_object(*id) {
+                            let name = project.get_object_info(object).get_name(object);
+                            if ui.link(name).clicked() {
+                                navigate_to = Some(*id);
+                            }
                         }
-                    });
+                    }
                 }
-                Err(e) => {
-                    log::error!("Failed to save project: {}", e);
-                    // TODO: Show error dialog
+                for error in errors {
+                    ui.colored_label(egui::Color32::RED, error);
                 }
-            }
+                ui.separator();
+                close = ui.button("Close").clicked();
+            });
+        if let (Some(id), Some(project)) = (navigate_to, &self.project) {
+            project
+                .get_mut_selected()
+                .replace(NullableObjectId(Some(id)));
+        }
+        if !open || close {
+            self.image_reimport = None;
         }
     }
 
-    /// Convert a string to a valid C identifier
-    fn to_c_identifier(name: &str) -> String {
-        name.chars()
-            .map(|c| match c {
-                'a'..='z' | 'A'..='Z' | '0'..='9' => c.to_ascii_uppercase(),
-                _ => '_',
-            })
-            .collect()
+    /// Open the project shared in the URL of the page, if the page was opened with a share link
+    #[cfg(target_arch = "wasm32")]
+    fn open_shared_project(&mut self) {
+        let Some(window) = web_sys::window() else {
+            return;
+        };
+        let Some(shared) = window
+            .location()
+            .hash()
+            .ok()
+            .and_then(|hash| decode_share_fragment(&hash))
+        else {
+            return;
+        };
+        // Remove the project from the address, so reloading the page doesn't open it again over
+        // the changes made since
+        if let (Ok(history), Ok(href)) = (window.history(), window.location().href()) {
+            let page = href.split('#').next().unwrap_or_default();
+            let _ = history.replace_state_with_url(
+                &eframe::wasm_bindgen::JsValue::NULL,
+                "",
+                Some(page),
+            );
+        }
+        match shared {
+            Ok(content) => self.load_project_file(content, None),
+            Err(e) => self.share_link = Some(Err(e)),
+        }
     }
 
-    /// Open a file dialog to save a C header file with object IDs
-    fn save_header(&mut self) {
-        if let Some(project) = &self.project {
-            let pool = project.get_pool();
-
-            // Start with the header
-            let mut header = String::from("// Object IDs for the objects in the object pool.\n\n");
-            header.push_str("#pragma once\n");
-            header.push_str("#define UNDEFINED 65535\n");
+    /// Make a link to the page holding the project, so it opens in the same state elsewhere
+    #[cfg(target_arch = "wasm32")]
+    fn make_share_link(project: &EditorProject) -> Result<String, String> {
+        let href = web_sys::window()
+            .and_then(|window| window.location().href().ok())
+            .ok_or("The address of the page is unknown")?;
+        let page = href.split('#').next().unwrap_or_default();
+        let content = project.to_project_bytes().map_err(|e| e.to_string())?;
+        Ok(format!("{}#{}", page, encode_share_fragment(&content)?))
+    }
 
-            // Collect all objects with their names and IDs
-            let mut objects: Vec<(String, u16)> = pool
-                .objects()
-                .iter()
-                .map(|obj| {
-                    let name = project.get_object_info(obj).get_name(obj);
-                    let c_name = Self::to_c_identifier(&name);
-                    let id = u16::from(obj.id());
-                    (c_name, id)
-                })
-                .collect();
+    /// Show the link sharing the project, or why it couldn't be made or opened
+    fn show_share_link(&mut self, ctx: &egui::Context) {
+        let Some(share_link) = &self.share_link else {
+            return;
+        };
+        let mut open = true;
+        let mut close = false;
+        egui::Window::new("Share link")
+            .open(&mut open)
+            .collapsible(false)
+            .show(ctx, |ui| {
+                match share_link {
+                    Ok(link) => {
+                        ui.label(
+                            "The link is copied to the clipboard. It holds the whole project, \
+                            opening it shows the project as it is now.",
+                        );
+                        ui.weak(format!("{} kB", link.len() / 1024));
+                    }
+                    Err(e) => {
+                        ui.colored_label(egui::Color32::RED, e);
+                    }
+                }
+                ui.separator();
+                close = ui.button("Close").clicked();
+            });
+        if !open || close {
+            self.share_link = None;
+        }
+    }
+
+    /// Show the fixes made by checking the object info against the pool
+    fn show_object_info_check(&mut self, ctx: &egui::Context) {
+        let Some(fixes) = &self.object_info_check else {
+            return;
+        };
+        let mut open = true;
+        let mut close = false;
+        egui::Window::new("Check object info")
+            .open(&mut open)
+            .collapsible(false)
+            .show(ctx, |ui| {
+                if fixes.is_empty() {
+                    ui.label("The names and other info of all objects match the pool.");
+                }
+                for fix in fixes {
+                    ui.label(fix);
+                }
+                ui.separator();
+                close = ui.button("Close").clicked();
+            });
+        if !open || close {
+            self.object_info_check = None;
+        }
+    }
+
+    /// Show the objects changed in their copy of the project, with a choice between my and their
+    /// version for conflicting objects. Nothing is merged until confirmed.
+    fn show_merge_dialog(&mut self, ctx: &egui::Context) {
+        let (Some(merge), Some(project)) = (&mut self.merge_dialog, &self.project) else {
+            self.merge_dialog = None;
+            return;
+        };
+        // Planned again whenever the project changes
+        if let (Some((_, original)), Some((_, theirs, _))) = (&merge.original, &merge.theirs) {
+            if merge.generation != project.get_pool_generation() {
+                merge.generation = project.get_pool_generation();
+                merge.items = plan_merge(original, project.get_pool(), theirs);
+                let conflicts: HashSet<ObjectId> = merge
+                    .items
+                    .iter()
+                    .filter(|item| item.kind == MergeKind::Conflict)
+                    .map(|item| item.id)
+                    .collect();
+                merge.choices.retain(|id, _| conflicts.contains(id));
+            }
+        }
+
+        let mut open = true;
+        let mut close = false;
+        let mut pick_file = None;
+        egui::Window::new("Merge project")
+            .open(&mut open)
+            .default_width(480.0)
+            .show(ctx, |ui| {
+                egui::Grid::new("merge_files")
+                    .num_columns(2)
+                    .show(ui, |ui| {
+                        ui.label("Original");
+                        let original = merge.original.as_ref().map(|(name, _)| name.as_str());
+                        if ui
+                            .button(original.unwrap_or("Choose\u{2026}"))
+                            .on_hover_text("The file both copies were edited from")
+                            .clicked()
+                        {
+                            pick_file = Some(FileDialogReason::MergeOriginal);
+                        }
+                        ui.end_row();
+                        ui.label("Their copy");
+                        let theirs = merge.theirs.as_ref().map(|(name, _, _)| name.as_str());
+                        if ui
+                            .button(theirs.unwrap_or("Choose\u{2026}"))
+                            .on_hover_text("The copy with the changes to bring into this project")
+                            .clicked()
+                        {
+                            pick_file = Some(FileDialogReason::MergeTheirs);
+                        }
+                        ui.end_row();
+                    });
+                if merge.original.is_none() || merge.theirs.is_none() {
+                    return;
+                }
+                ui.separator();
+                if merge.items.is_empty() {
+                    ui.label("Their copy has no changes that aren't in this project.");
+                    return;
+                }
+
+                let conflicts = merge
+                    .items
+                    .iter()
+                    .filter(|item| item.kind == MergeKind::Conflict)
+                    .count();
+                ui.label(format!(
+                    "{} objects changed in their copy, {} of them also changed here",
+                    merge.items.len(),
+                    conflicts
+                ));
+                if conflicts > 0 {
+                    ui.horizontal(|ui| {
+                        if ui.button("Keep all mine").clicked() {
+                            merge.choices = choose_all(&merge.items, MergeChoice::Mine);
+                        }
+                        if ui.button("Take all theirs").clicked() {
+                            merge.choices = choose_all(&merge.items, MergeChoice::Theirs);
+                        }
+                    });
+                }
+                egui::ScrollArea::vertical()
+                    .max_height(360.0)
+                    .show(ui, |ui| {
+                        egui::Grid::new("merge_items")
+                            .striped(true)
+                            .num_columns(3)
+                            .show(ui, |ui| {
+                                for item in &merge.items {
+                                    let object = item.mine.as_ref().or(item.theirs.as_ref());
+                                    let name = match (project.get_object(item.id), object) {
+                                        (Some(object), _) => {
+                                            project.get_object_info(object).get_name(object)
+                                        }
+                                        (None, Some(object)) => {
+                                            format!("{:?}", object.object_type())
+                                        }
+                                        (None, None) => String::new(),
+                                    };
+                                    if ui.link(format!("{}: {}", item.id.value(), name)).clicked()
+                                        && project.get_object(item.id).is_some()
+                                    {
+                                        project
+                                            .get_mut_selected()
+                                            .replace(NullableObjectId(Some(item.id)));
+                                    }
+                                    match item.kind {
+                                        MergeKind::Theirs => {
+                                            ui.label(match (&item.mine, &item.theirs) {
+                                                (None, _) => "Added in their copy",
+                                                (_, None) => "Removed in their copy",
+                                                _ => "Changed in their copy",
+                                            });
+                                            ui.label("");
+                                        }
+                                        MergeKind::IdConflict => {
+                                            ui.colored_label(
+                                                egui::Color32::ORANGE,
+                                                "Added in both copies",
+                                            )
+                                            .on_hover_text(
+                                                "Their object is added with a new ID, the \
+                                                references of their objects are updated",
+                                            );
+                                            ui.label("");
+                                        }
+                                        MergeKind::Conflict => {
+                                            let description = match (&item.mine, &item.theirs) {
+                                                (None, _) => "Removed here, changed in their copy",
+                                                (_, None) => "Changed here, removed in their copy",
+                                                _ => "Changed in both copies",
+                                            };
+                                            let label =
+                                                ui.colored_label(egui::Color32::RED, description);
+                                            if let (Some(mine), Some(theirs)) =
+                                                (&item.mine, &item.theirs)
+                                            {
+                                                label.on_hover_ui(|ui| {
+                                                    for change in attribute_changes(mine, theirs) {
+                                                        ui.label(format!(
+                                                            "{}: {} \u{2192} {}",
+                                                            change.attribute,
+                                                            change.old,
+                                                            change.new
+                                                        ));
+                                                    }
+                                                });
+                                            }
+                                            let choice = merge
+                                                .choices
+                                                .entry(item.id)
+                                                .or_insert(MergeChoice::Mine);
+                                            ui.horizontal(|ui| {
+                                                ui.radio_value(choice, MergeChoice::Mine, "Mine");
+                                                ui.radio_value(
+                                                    choice,
+                                                    MergeChoice::Theirs,
+                                                    "Theirs",
+                                                );
+                                            });
+                                        }
+                                    }
+                                    ui.end_row();
+                                }
+                            });
+                    });
+                ui.separator();
+                if ui.button("Merge").clicked() {
+                    let names = merge
+                        .theirs
+                        .as_ref()
+                        .map(|(_, _, names)| names.clone())
+                        .unwrap_or_default();
+                    match project.apply_merge(&merge.items, &merge.choices, &names) {
+                        Ok(new_ids) => {
+                            for (from, to) in new_ids {
+                                log::info!(
+                                    "Object {} of their copy is merged as object {}",
+                                    from.value(),
+                                    to.value()
+                                );
+                            }
+                        }
+                        Err(e) => log::error!("Failed to merge the project: {}", e),
+                    }
+                    close = true;
+                }
+            });
+        if let Some(reason) = pick_file {
+            self.open_file_dialog(reason, ctx);
+        }
+        if !open || close {
+            self.merge_dialog = None;
+        }
+    }
+
+    /// Show the image being imported in every picture format, it is stored in the picture
+    /// graphic in the chosen format once confirmed
+    fn show_image_import_dialog(&mut self, ctx: &egui::Context) {
+        let project_dir = self
+            .project_path
+            .as_deref()
+            .and_then(|path| std::path::Path::new(path).parent());
+        let link_image = self.settings.general.link_imported_images;
+        let (Some(dialog), Some(project)) = (&mut self.image_import_dialog, &self.project) else {
+            self.image_import_dialog = None;
+            return;
+        };
+        let Some(Object::PictureGraphic(picture)) = project.get_editable_object(dialog.id) else {
+            self.image_import_dialog = None;
+            return;
+        };
+
+        if dialog.preview_threshold != Some(dialog.threshold) {
+            let pool = project.get_pool();
+            let palette = pool.get_colour_palette();
+            dialog.previews = IMAGE_IMPORT_FORMATS
+                .iter()
+                .map(|(format, _)| {
+                    let mut converted = picture.clone();
+                    dialog
+                        .image
+                        .convert(&mut converted, *format, &palette, dialog.threshold);
+                    let object = Object::PictureGraphic(converted.clone());
+                    let (width, height) = pool.content_size(&object);
+                    let scale = (IMAGE_IMPORT_PREVIEW_SIZE / width.max(1) as f32)
+                        .min(IMAGE_IMPORT_PREVIEW_SIZE / height.max(1) as f32)
+                        .min(1.0);
+                    let image =
+                        ag_iso_terminal_designer::render_object_to_image(&object, pool, scale);
+                    let texture = ctx.load_texture(
+                        format!("image_import_{:?}", format),
+                        image,
+                        Default::default(),
+                    );
+                    (converted, texture)
+                })
+                .collect();
+            dialog.preview_threshold = Some(dialog.threshold);
+        }
+
+        let mut open = true;
+        let mut close = false;
+        egui::Window::new("Import image")
+            .open(&mut open)
+            .collapsible(false)
+            .resizable(false)
+            .show(ctx, |ui| {
+                ui.horizontal(|ui| {
+                    for ((format, name), (converted, texture)) in
+                        IMAGE_IMPORT_FORMATS.iter().zip(&dialog.previews)
+                    {
+                        ui.vertical(|ui| {
+                            ui.set_width(IMAGE_IMPORT_PREVIEW_SIZE);
+                            ui.add(egui::Image::new(texture));
+                            ui.radio_value(&mut dialog.format, *format, *name);
+                            let encoding = match converted.options.data_code_type {
+                                DataCodeType::RunLength => "run-length",
+                                _ => "raw",
+                            };
+                            ui.weak(format!("{} bytes, {}", converted.data.len(), encoding));
+                        });
+                    }
+                });
+                ui.add_enabled(
+                    dialog.format == PictureGraphicFormat::Monochrome,
+                    egui::Slider::new(&mut dialog.threshold, 0..=255).text("Monochrome threshold"),
+                )
+                .on_hover_text("Brightness from which pixels get the brighter colour");
+
+                ui.separator();
+                ui.horizontal(|ui| {
+                    if ui.button("Import").clicked() {
+                        let converted = IMAGE_IMPORT_FORMATS
+                            .iter()
+                            .zip(&dialog.previews)
+                            .find(|((format, _), _)| *format == dialog.format)
+                            .map(|(_, (converted, _))| converted.clone());
+                        if let Some(converted) = converted {
+                            match project.execute(PoolCommand::SetObject {
+                                id: dialog.id,
+                                object: Object::PictureGraphic(converted),
+                            }) {
+                                Ok(()) => {
+                                    // Without a link, re-importing would bring back the
+                                    // previous image
+                                    let source =
+                                        dialog.path.as_ref().filter(|_| link_image).map(|path| {
+                                            ImageSource::new(
+                                                std::path::Path::new(path),
+                                                project_dir,
+                                            )
+                                        });
+                                    project.set_image_source(dialog.id, source);
+                                }
+                                Err(e) => log::error!("Failed to store the loaded image: {}", e),
+                            }
+                        }
+                        close = true;
+                    }
+                    close |= ui.button("Cancel").clicked();
+                });
+            });
+        if !open || close {
+            self.image_import_dialog = None;
+        }
+    }
+
+    /// Show the layout presets with a preview, adding their containers to the data mask once
+    /// confirmed
+    fn show_layout_preset_dialog(&mut self, ctx: &egui::Context) {
+        let (Some(dialog), Some(project)) = (&mut self.layout_preset_dialog, &self.project) else {
+            self.layout_preset_dialog = None;
+            return;
+        };
+
+        let mut open = true;
+        let mut close = false;
+        egui::Window::new("Layout preset")
+            .open(&mut open)
+            .collapsible(false)
+            .resizable(false)
+            .show(ctx, |ui| {
+                ui.label(format!(
+                    "Empty containers are added to data mask {}, ready to fill",
+                    dialog.mask.value()
+                ));
+                for preset in LayoutPreset::ALL {
+                    ui.radio_value(&mut dialog.preset, preset, preset.name());
+                }
+                egui::Grid::new("layout_preset_spacing")
+                    .num_columns(2)
+                    .show(ui, |ui| {
+                        ui.label("Safe area margin (px)");
+                        ui.add(
+                            egui::DragValue::new(&mut dialog.margin)
+                                .range(0..=project.mask_size / 4),
+                        );
+                        ui.end_row();
+                        ui.label("Gap (px)");
+                        ui.add(
+                            egui::DragValue::new(&mut dialog.gap).range(0..=project.mask_size / 4),
+                        );
+                        ui.end_row();
+                    });
+
+                // Preview of the regions in the mask
+                let regions = dialog
+                    .preset
+                    .regions(project.mask_size, dialog.margin, dialog.gap);
+                let (rect, _) = ui.allocate_exact_size(
+                    egui::vec2(LAYOUT_PREVIEW_SIZE, LAYOUT_PREVIEW_SIZE),
+                    egui::Sense::hover(),
+                );
+                let scale = LAYOUT_PREVIEW_SIZE / project.mask_size.max(1) as f32;
+                let painter = ui.painter_at(rect);
+                let visuals = ui.visuals();
+                painter.rect_filled(rect, 0.0, visuals.extreme_bg_color);
+                for region in &regions {
+                    let region_rect = egui::Rect::from_min_size(
+                        rect.min + egui::vec2(region.x as f32, region.y as f32) * scale,
+                        egui::vec2(region.width as f32, region.height as f32) * scale,
+                    );
+                    painter.rect_filled(region_rect, 0.0, visuals.selection.bg_fill);
+                    painter.text(
+                        region_rect.center(),
+                        egui::Align2::CENTER_CENTER,
+                        region.name,
+                        egui::FontId::proportional(10.0),
+                        visuals.strong_text_color(),
+                    );
+                }
+
+                ui.separator();
+                ui.horizontal(|ui| {
+                    if ui
+                        .button(format!("Add {} containers", regions.len()))
+                        .clicked()
+                    {
+                        match project.apply_layout_preset(
+                            dialog.mask,
+                            dialog.preset,
+                            dialog.margin,
+                            dialog.gap,
+                        ) {
+                            Ok(created) => {
+                                log::info!("Added {} containers of the layout", created.len())
+                            }
+                            Err(e) => log::error!("Failed to apply the layout preset: {}", e),
+                        }
+                        close = true;
+                    }
+                    close |= ui.button("Cancel").clicked();
+                });
+            });
+        if !open || close {
+            self.layout_preset_dialog = None;
+        }
+    }
+
+    /// Show the changes of an imported object list, which are only applied when confirmed
+    fn show_object_list_import(&mut self, ctx: &egui::Context) {
+        let (Some(import), Some(project)) = (&mut self.object_list_import, &self.project) else {
+            self.object_list_import = None;
+            return;
+        };
+        // Compared again whenever the project changes
+        if import.generation != project.get_pool_generation() {
+            import.generation = project.get_pool_generation();
+            import.changes = preview_object_list_import(project, &import.csv);
+        }
+
+        let mut open = true;
+        let mut close = false;
+        egui::Window::new(format!("Import {}", import.file_name))
+            .id(egui::Id::new("object_list_import"))
+            .open(&mut open)
+            .default_width(420.0)
+            .show(ctx, |ui| {
+                let changes = match &import.changes {
+                    Ok(changes) => changes,
+                    Err(e) => {
+                        ui.colored_label(egui::Color32::RED, e);
+                        return;
+                    }
+                };
+                if changes.is_empty() {
+                    ui.label("The object list doesn't change any object.");
+                    return;
+                }
+                ui.label(format!("{} changes, nothing is applied yet", changes.len()));
+                egui::ScrollArea::vertical()
+                    .max_height(300.0)
+                    .show(ui, |ui| {
+                        egui::Grid::new("object_list_changes")
+                            .striped(true)
+                            .num_columns(4)
+                            .show(ui, |ui| {
+                                for change in changes {
+                                    let name = project
+                                        .get_object(change.object)
+                                        .map_or(String::new(), |object| {
+                                            project.get_object_info(object).get_name(object)
+                                        });
+                                    if ui
+                                        .link(format!("{}: {}", change.object.value(), name))
+                                        .clicked()
+                                    {
+                                        project
+                                            .get_mut_selected()
+                                            .replace(NullableObjectId(Some(change.object)));
+                                    }
+                                    ui.weak(change.column.name());
+                                    ui.label(&change.old);
+                                    ui.label(format!("\u{2192} {}", change.new));
+                                    ui.end_row();
+                                }
+                            });
+                    });
+                ui.separator();
+                if ui
+                    .button(format!("Apply {} changes", changes.len()))
+                    .clicked()
+                {
+                    match project.import_object_list(changes) {
+                        Ok(count) => log::info!("Imported changes of {} objects", count),
+                        Err(e) => log::error!("Failed to import the object list: {}", e),
+                    }
+                    close = true;
+                }
+            });
+        if !open || close {
+            self.object_list_import = None;
+        }
+    }
+
+    /// Show the objects that can't be reached from the working set, so they can be removed
+    fn show_orphan_cleanup(&mut self, ctx: &egui::Context) {
+        let (Some(cleanup), Some(project)) = (&mut self.orphan_cleanup, &self.project) else {
+            self.orphan_cleanup = None;
+            return;
+        };
+
+        let orphans = project.find_orphans();
+        cleanup.selected.retain(|id| orphans.contains(id));
+        let mut open = true;
+        egui::Window::new("Orphan objects")
+            .open(&mut open)
+            .default_width(360.0)
+            .show(ctx, |ui| {
+                if orphans.is_empty() {
+                    ui.label("Every object is reachable from the working set.");
+                    return;
+                }
+                ui.label(format!(
+                    "{} objects can't be reached from the working set or the auxiliary objects",
+                    orphans.len()
+                ));
+                ui.horizontal(|ui| {
+                    if ui.button("Select all").clicked() {
+                        cleanup.selected = orphans.iter().copied().collect();
+                    }
+                    if ui.button("Select none").clicked() {
+                        cleanup.selected.clear();
+                    }
+                });
+                egui::ScrollArea::vertical()
+                    .max_height(300.0)
+                    .show(ui, |ui| {
+                        egui::Grid::new("orphan_objects")
+                            .striped(true)
+                            .num_columns(3)
+                            .show(ui, |ui| {
+                                for id in &orphans {
+                                    let Some(object) = project.get_object(*id) else {
+                                        continue;
+                                    };
+                                    let mut selected = cleanup.selected.contains(id);
+                                    if ui.checkbox(&mut selected, "").changed() {
+                                        if selected {
+                                            cleanup.selected.insert(*id);
+                                        } else {
+                                            cleanup.selected.remove(id);
+                                        }
+                                    }
+                                    let name = format!(
+                                        "{}: {}",
+                                        id.value(),
+                                        project.get_object_info(object).get_name(object)
+                                    );
+                                    if ui.link(name).clicked() {
+                                        project
+                                            .get_mut_selected()
+                                            .replace(NullableObjectId(Some(*id)));
+                                    }
+                                    ui.weak(format!("{:?}", object.object_type()));
+                                    ui.end_row();
+                                }
+                            });
+                    });
+                ui.separator();
+                if ui
+                    .add_enabled(
+                        !cleanup.selected.is_empty(),
+                        egui::Button::new(format!("Remove {} selected", cleanup.selected.len())),
+                    )
+                    .clicked()
+                {
+                    let ids: Vec<ObjectId> = orphans
+                        .iter()
+                        .filter(|id| cleanup.selected.contains(id))
+                        .copied()
+                        .collect();
+                    if let Err(e) = project.remove_orphans(&ids) {
+                        log::error!("Failed to remove orphan objects: {}", e);
+                    }
+                    cleanup.selected.clear();
+                }
+            });
+        if !open {
+            self.orphan_cleanup = None;
+        }
+    }
+
+    /// Show the macros with the events and macros running them, flagging the macros that never
+    /// run so they can be removed
+    fn show_macro_report(&mut self, ctx: &egui::Context) {
+        let (Some(report), Some(project)) = (&mut self.macro_report, &self.project) else {
+            self.macro_report = None;
+            return;
+        };
+
+        let usages = analyse_macro_usage(project.get_pool());
+        let dead: Vec<ObjectId> = usages
+            .iter()
+            .filter(|usage| usage.is_dead())
+            .map(|usage| usage.macro_id)
+            .collect();
+        report.selected.retain(|id| dead.contains(id));
+        let object_name = |id: ObjectId| match project.get_object(id) {
+            Some(object) => format!(
+                "{}: {}",
+                id.value(),
+                project.get_object_info(object).get_name(object)
+            ),
+            None => id.value().to_string(),
+        };
+        let mut navigate_to = None;
+        let mut open = true;
+        egui::Window::new("Macro usage")
+            .open(&mut open)
+            .default_width(420.0)
+            .show(ctx, |ui| {
+                if usages.is_empty() {
+                    ui.label("The pool has no macros.");
+                    return;
+                }
+                ui.label(format!(
+                    "{} macros, {} of which never run",
+                    usages.len(),
+                    dead.len()
+                ));
+                if !dead.is_empty() {
+                    ui.horizontal(|ui| {
+                        if ui.button("Select all unused").clicked() {
+                            report.selected = dead.iter().copied().collect();
+                        }
+                        if ui.button("Select none").clicked() {
+                            report.selected.clear();
+                        }
+                    });
+                }
+                egui::ScrollArea::vertical()
+                    .max_height(360.0)
+                    .show(ui, |ui| {
+                        for usage in &usages {
+                            ui.horizontal(|ui| {
+                                if usage.is_dead() {
+                                    let mut selected = report.selected.contains(&usage.macro_id);
+                                    if ui.checkbox(&mut selected, "").changed() {
+                                        if selected {
+                                            report.selected.insert(usage.macro_id);
+                                        } else {
+                                            report.selected.remove(&usage.macro_id);
+                                        }
+                                    }
+                                }
+                                if ui.link(object_name(usage.macro_id)).clicked() {
+                                    navigate_to = Some(usage.macro_id);
+                                }
+                                if usage.is_dead() {
+                                    ui.colored_label(egui::Color32::YELLOW, "never runs");
+                                }
+                            });
+                            ui.indent(("macro_triggers", usage.macro_id.value()), |ui| {
+                                for trigger in &usage.triggers {
+                                    let (id, text) = match trigger {
+                                        MacroTrigger::Event(id, event) => {
+                                            (*id, format!("{:?} of", event))
+                                        }
+                                        MacroTrigger::Macro(id) => {
+                                            (*id, "Run by macro".to_string())
+                                        }
+                                    };
+                                    ui.horizontal(|ui| {
+                                        ui.weak(text);
+                                        if ui.link(object_name(id)).clicked() {
+                                            navigate_to = Some(id);
+                                        }
+                                    });
+                                }
+                            });
+                        }
+                    });
+                ui.separator();
+                if ui
+                    .add_enabled(
+                        !report.selected.is_empty(),
+                        egui::Button::new(format!("Remove {} selected", report.selected.len())),
+                    )
+                    .on_hover_text("Remove the macros in a single undo step")
+                    .clicked()
+                {
+                    let ids: Vec<ObjectId> = dead
+                        .iter()
+                        .filter(|id| report.selected.contains(id))
+                        .copied()
+                        .collect();
+                    if let Err(e) = project.remove_dead_macros(&ids) {
+                        log::error!("Failed to remove unused macros: {}", e);
+                    }
+                    report.selected.clear();
+                }
+            });
+        if let (Some(id), Some(project)) = (navigate_to, &self.project) {
+            project
+                .get_mut_selected()
+                .replace(NullableObjectId(Some(id)));
+        }
+        if !open {
+            self.macro_report = None;
+        }
+    }
+
+    /// Show the references to missing objects, each can be remapped to an existing object,
+    /// replaced by a placeholder or removed
+    fn show_repair_wizard(&mut self, ctx: &egui::Context) {
+        let (Some(wizard), Some(project)) = (&mut self.repair_wizard, &self.project) else {
+            self.repair_wizard = None;
+            return;
+        };
+
+        let broken = project.find_broken_references();
+        let mut open = true;
+        egui::Window::new("Repair broken references")
+            .open(&mut open)
+            .default_width(480.0)
+            .show(ctx, |ui| {
+                if let Some(message) = &wizard.message {
+                    ui.label(message);
+                }
+                if broken.is_empty() {
+                    ui.label("All referenced objects are in the pool.");
+                    return;
+                }
+                egui::ScrollArea::vertical()
+                    .max_height(400.0)
+                    .show(ui, |ui| {
+                        for (missing, parents) in &broken {
+                            ui.push_id(missing.value(), |ui| {
+                                let expected = project.get_expected_types(*missing);
+                                ui.strong(format!("Missing object {}", missing.value()));
+                                ui.horizontal_wrapped(|ui| {
+                                    ui.label("Referenced by:");
+                                    for parent in parents {
+                                        let Some(object) = project.get_object(*parent) else {
+                                            continue;
+                                        };
+                                        let name = project.get_object_info(object).get_name(object);
+                                        if ui
+                                            .link(format!("{}: {}", parent.value(), name))
+                                            .clicked()
+                                        {
+                                            project
+                                                .get_mut_selected()
+                                                .replace(NullableObjectId(Some(*parent)));
+                                        }
+                                    }
+                                });
+
+                                ui.horizontal(|ui| {
+                                    let candidates = if expected.is_empty() {
+                                        project.get_pool().objects().iter().collect()
+                                    } else {
+                                        project.get_objects_by_types(&expected)
+                                    };
+                                    let target = wizard.remap_targets.get(missing).copied();
+                                    let selected_text = target
+                                        .and_then(|id| project.get_object(id))
+                                        .map(|object| {
+                                            format!(
+                                                "{}: {}",
+                                                object.id().value(),
+                                                project.get_object_info(object).get_name(object)
+                                            )
+                                        })
+                                        .unwrap_or_else(|| "Choose object".to_string());
+                                    egui::ComboBox::from_id_salt("remap_target")
+                                        .selected_text(selected_text)
+                                        .show_ui(ui, |ui| {
+                                            for candidate in candidates {
+                                                let name = format!(
+                                                    "{}: {}",
+                                                    candidate.id().value(),
+                                                    project
+                                                        .get_object_info(candidate)
+                                                        .get_name(candidate)
+                                                );
+                                                if ui
+                                                    .selectable_label(
+                                                        target == Some(candidate.id()),
+                                                        name,
+                                                    )
+                                                    .clicked()
+                                                {
+                                                    wizard
+                                                        .remap_targets
+                                                        .insert(*missing, candidate.id());
+                                                }
+                                            }
+                                        });
+                                    if ui
+                                        .add_enabled(target.is_some(), egui::Button::new("Remap"))
+                                        .clicked()
+                                    {
+                                        if let Some(to) = target {
+                                            let result =
+                                                project.execute(PoolCommand::RedirectReferences {
+                                                    from: *missing,
+                                                    to,
+                                                });
+                                            wizard.message = Some(match result {
+                                                Ok(()) => format!(
+                                                    "References to {} now point to {}",
+                                                    missing.value(),
+                                                    to.value()
+                                                ),
+                                                Err(e) => e.to_string(),
+                                            });
+                                        }
+                                    }
+                                });
+
+                                ui.horizontal(|ui| {
+                                    let placeholder_type = wizard
+                                        .placeholder_types
+                                        .get(missing)
+                                        .copied()
+                                        .or_else(|| expected.first().copied())
+                                        .unwrap_or(ObjectType::Container);
+                                    egui::ComboBox::from_id_salt("placeholder_type")
+                                        .selected_text(format!("{:?}", placeholder_type))
+                                        .show_ui(ui, |ui| {
+                                            let types = if expected.is_empty() {
+                                                ObjectType::values()
+                                            } else {
+                                                expected.clone()
+                                            };
+                                            for object_type in types {
+                                                if ui
+                                                    .selectable_label(
+                                                        object_type == placeholder_type,
+                                                        format!("{:?}", object_type),
+                                                    )
+                                                    .clicked()
+                                                {
+                                                    wizard
+                                                        .placeholder_types
+                                                        .insert(*missing, object_type);
+                                                }
+                                            }
+                                        });
+                                    if ui.button("Create placeholder").clicked() {
+                                        wizard.message = Some(
+                                            match project
+                                                .create_placeholder(*missing, placeholder_type)
+                                            {
+                                                Ok(()) => format!(
+                                                    "Created {:?} {} as placeholder",
+                                                    placeholder_type,
+                                                    missing.value()
+                                                ),
+                                                Err(e) => e.to_string(),
+                                            },
+                                        );
+                                    }
+                                    if ui
+                                    .button("Remove references")
+                                    .on_hover_text(
+                                        "Remove the references from lists of children and clear \
+                                        optional references",
+                                    )
+                                    .clicked()
+                                {
+                                    wizard.message =
+                                        Some(match project.remove_broken_references(*missing) {
+                                            Ok(0) => format!(
+                                                "Removed the references to {}",
+                                                missing.value()
+                                            ),
+                                            Ok(left) => format!(
+                                                "{} required references to {} are left, remap \
+                                                them or create a placeholder",
+                                                left,
+                                                missing.value()
+                                            ),
+                                            Err(e) => e.to_string(),
+                                        });
+                                }
+                                });
+                                ui.separator();
+                            });
+                        }
+                    });
+            });
+        if !open {
+            self.repair_wizard = None;
+        }
+    }
+
+    /// Show the dialog to renumber the objects of all or some types into a range of IDs per type
+    fn show_renumber_dialog(&mut self, ctx: &egui::Context) {
+        let (Some(dialog), Some(project)) = (&mut self.renumber_dialog, &self.project) else {
+            self.renumber_dialog = None;
+            return;
+        };
+
+        let present_types: Vec<ObjectType> = ObjectType::values()
+            .into_iter()
+            .filter(|object_type| !project.get_objects_by_type(*object_type).is_empty())
+            .collect();
+        let mut open = true;
+        let mut close = false;
+        egui::Window::new("Renumber objects")
+            .open(&mut open)
+            .default_width(420.0)
+            .show(ctx, |ui| {
+                ui.label(
+                    "Objects are numbered in a range of IDs per type, in the order of their \
+                    current IDs. References are updated and names are kept.",
+                );
+                ui.checkbox(&mut dialog.all_types, "All object types");
+                if !dialog.all_types {
+                    egui::ScrollArea::vertical()
+                        .id_salt("renumber_types")
+                        .max_height(160.0)
+                        .show(ui, |ui| {
+                            for object_type in &present_types {
+                                let mut checked = dialog.object_types.contains(object_type);
+                                let range = project.object_id_range(*object_type);
+                                if ui
+                                    .checkbox(
+                                        &mut checked,
+                                        format!(
+                                            "{:?} ({} to {})",
+                                            object_type,
+                                            range.start(),
+                                            range.end()
+                                        ),
+                                    )
+                                    .changed()
+                                {
+                                    if checked {
+                                        dialog.object_types.insert(*object_type);
+                                    } else {
+                                        dialog.object_types.remove(object_type);
+                                    }
+                                }
+                            }
+                        });
+                }
+                let object_types: Vec<ObjectType> = present_types
+                    .iter()
+                    .filter(|object_type| {
+                        dialog.all_types || dialog.object_types.contains(object_type)
+                    })
+                    .copied()
+                    .collect();
+
+                ui.separator();
+                let renumbered = match project.plan_renumbering(&object_types) {
+                    Ok(renumbered) => renumbered,
+                    Err(e) => {
+                        ui.colored_label(egui::Color32::RED, e);
+                        return;
+                    }
+                };
+                ui.label(format!("{} objects get a new ID", renumbered.len()));
+                egui::ScrollArea::vertical()
+                    .id_salt("renumber_preview")
+                    .max_height(300.0)
+                    .show(ui, |ui| {
+                        egui::Grid::new("renumber_preview_grid")
+                            .striped(true)
+                            .num_columns(2)
+                            .show(ui, |ui| {
+                                for (from, to) in &renumbered {
+                                    let Some(object) = project.get_object(*from) else {
+                                        continue;
+                                    };
+                                    ui.label(project.get_object_info(object).get_name(object));
+                                    ui.label(format!("{} \u{2192} {}", from.value(), to.value()));
+                                    ui.end_row();
+                                }
+                            });
+                    });
+                if ui
+                    .add_enabled(!renumbered.is_empty(), egui::Button::new("Renumber"))
+                    .clicked()
+                {
+                    if let Err(e) = project.remap_object_references(renumbered) {
+                        log::error!("Failed to renumber objects: {}", e);
+                    }
+                    close = true;
+                }
+            });
+        if !open || close {
+            self.renumber_dialog = None;
+        }
+    }
+
+    /// Show the dialog to find and replace text in the custom names of the objects
+    /// Objects matching a name, an identifier of the generated C header or an ID (decimal or
+    /// hexadecimal), e.g. from ECU logs. Exact matches come first, followed by objects of which
+    /// the name or identifier contains the query.
+    fn go_to_candidates<'a>(project: &'a EditorProject, query: &str) -> Vec<&'a Object> {
+        let query = query.trim();
+        if query.is_empty() {
+            return Vec::new();
+        }
+        let id = query.parse::<u16>().ok().or_else(|| {
+            query
+                .strip_prefix("0x")
+                .or_else(|| query.strip_prefix("0X"))
+                .and_then(|hex| u16::from_str_radix(hex, 16).ok())
+        });
+        let identifier = Self::to_c_identifier(query);
+        let lowercase = query.to_lowercase();
+
+        let mut exact = Vec::new();
+        let mut partial = Vec::new();
+        for object in project.get_pool().objects() {
+            let info = project.get_object_info(object);
+            let name = info.get_name(object);
+            let c_name = Self::to_c_identifier(&info.get_export_name(object));
+            if id == Some(object.id().value())
+                || name.eq_ignore_ascii_case(query)
+                || c_name == identifier
+            {
+                exact.push(object);
+            } else if name.to_lowercase().contains(&lowercase) || c_name.contains(&identifier) {
+                partial.push(object);
+            }
+        }
+        exact.extend(partial);
+        exact.truncate(MAX_GO_TO_CANDIDATES);
+        exact
+    }
+
+    /// Select an object by its name, header identifier or ID, Enter picks the first match
+    fn show_go_to_dialog(&mut self, ctx: &egui::Context) {
+        let (Some(query), Some(project)) = (&mut self.go_to, &self.project) else {
+            self.go_to = None;
+            return;
+        };
+
+        let mut open = true;
+        let mut close = false;
+        egui::Window::new("Go to object")
+            .open(&mut open)
+            .default_width(360.0)
+            .show(ctx, |ui| {
+                let response = ui.add(
+                    egui::TextEdit::singleline(query)
+                        .hint_text("Name, header identifier or ID")
+                        .desired_width(f32::INFINITY),
+                );
+                if query.is_empty() && !response.has_focus() {
+                    response.request_focus();
+                }
+                let candidates = Self::go_to_candidates(project, query);
+                let mut chosen = None;
+                if response.lost_focus() && ui.input(|i| i.key_pressed(egui::Key::Enter)) {
+                    chosen = candidates.first().map(|object| object.id());
+                }
+                if ui.input(|i| i.key_pressed(egui::Key::Escape)) {
+                    close = true;
+                }
+                if !query.trim().is_empty() && candidates.is_empty() {
+                    ui.weak("No matching object");
+                }
+                egui::ScrollArea::vertical()
+                    .max_height(240.0)
+                    .show(ui, |ui| {
+                        for object in &candidates {
+                            let info = project.get_object_info(object);
+                            let label = format!(
+                                "{}: {} ({})",
+                                object.id().value(),
+                                info.get_name(object),
+                                Self::to_c_identifier(&info.get_export_name(object))
+                            );
+                            if ui.selectable_label(false, label).clicked() {
+                                chosen = Some(object.id());
+                            }
+                        }
+                    });
+                if let Some(id) = chosen {
+                    project
+                        .get_mut_selected()
+                        .replace(NullableObjectId(Some(id)));
+                    close = true;
+                }
+            });
+        if !open || close {
+            self.go_to = None;
+        }
+    }
+
+    fn show_find_replace_dialog(&mut self, ctx: &egui::Context) {
+        let (Some(dialog), Some(project)) = (&mut self.find_replace_dialog, &self.project) else {
+            self.find_replace_dialog = None;
+            return;
+        };
+
+        let mut open = true;
+        egui::Window::new("Find & replace names")
+            .open(&mut open)
+            .default_width(480.0)
+            .show(ctx, |ui| {
+                egui::Grid::new("find_replace_fields")
+                    .num_columns(2)
+                    .show(ui, |ui| {
+                        ui.label("Find:");
+                        ui.text_edit_singleline(&mut dialog.find);
+                        ui.end_row();
+                        ui.label("Replace with:");
+                        ui.text_edit_singleline(&mut dialog.replace);
+                        ui.end_row();
+                    });
+                ui.horizontal(|ui| {
+                    ui.checkbox(&mut dialog.use_regex, "Regular expression")
+                        .on_hover_text(
+                            "Use $1, $2, ... in the replacement to insert capture groups",
+                        );
+                    ui.checkbox(&mut dialog.case_sensitive, "Match case");
+                });
+
+                let replace = match NameReplace::new(
+                    &dialog.find,
+                    &dialog.replace,
+                    dialog.use_regex,
+                    dialog.case_sensitive,
+                ) {
+                    Ok(replace) => replace,
+                    Err(e) => {
+                        ui.colored_label(egui::Color32::RED, e);
+                        return;
+                    }
+                };
+                let matches = project.preview_name_replace(&replace);
+                ui.separator();
+                if dialog.find.is_empty() {
+                    ui.weak("Only custom names are searched, default names are left as they are");
+                    return;
+                }
+                ui.label(format!("{} names match", matches.len()));
+                egui::ScrollArea::vertical()
+                    .max_height(300.0)
+                    .show(ui, |ui| {
+                        egui::Grid::new("find_replace_preview")
+                            .striped(true)
+                            .num_columns(3)
+                            .show(ui, |ui| {
+                                for (id, name, new_name) in &matches {
+                                    if ui.link(id.value().to_string()).clicked() {
+                                        project
+                                            .get_mut_selected()
+                                            .replace(NullableObjectId(Some(*id)));
+                                    }
+                                    ui.label(name);
+                                    ui.label(format!("\u{2192} {}", new_name));
+                                    ui.end_row();
+                                }
+                            });
+                    });
+                if ui
+                    .add_enabled(!matches.is_empty(), egui::Button::new("Replace all"))
+                    .clicked()
+                {
+                    let names: Vec<(ObjectId, String)> = matches
+                        .into_iter()
+                        .map(|(id, _, new_name)| (id, new_name))
+                        .collect();
+                    project.rename_objects(&names);
+                }
+            });
+        if !open {
+            self.find_replace_dialog = None;
+        }
+    }
+
+    /// Show the dialog to rename the chosen objects at once, with a preview of the new names
+    fn show_batch_rename_dialog(&mut self, ctx: &egui::Context) {
+        let (Some(dialog), Some(project)) = (&mut self.batch_rename_dialog, &self.project) else {
+            self.batch_rename_dialog = None;
+            return;
+        };
+
+        let mut open = true;
+        egui::Window::new("Batch rename")
+            .open(&mut open)
+            .default_width(520.0)
+            .show(ctx, |ui| {
+                ui.horizontal(|ui| {
+                    ui.label("Objects:");
+                    ui.add(
+                        egui::TextEdit::singleline(&mut dialog.filter)
+                            .hint_text("Filter by name..."),
+                    );
+                });
+                let filter = dialog.filter.to_lowercase();
+                let listed: Vec<&Object> = project
+                    .get_pool()
+                    .objects()
+                    .iter()
+                    .filter(|object| {
+                        project
+                            .get_object_info(object)
+                            .get_name(object)
+                            .to_lowercase()
+                            .contains(&filter)
+                    })
+                    .collect();
+                ui.horizontal(|ui| {
+                    if ui.button("Select listed").clicked() {
+                        dialog
+                            .selected
+                            .extend(listed.iter().map(|object| object.id()));
+                    }
+                    if ui.button("Select none").clicked() {
+                        dialog.selected.clear();
+                    }
+                    ui.label(format!("{} selected", dialog.selected.len()));
+                });
+                egui::ScrollArea::vertical()
+                    .id_salt("batch_rename_objects")
+                    .max_height(200.0)
+                    .show(ui, |ui| {
+                        for object in &listed {
+                            let mut selected = dialog.selected.contains(&object.id());
+                            let name = format!(
+                                "{}: {}",
+                                object.id().value(),
+                                project.get_object_info(object).get_name(object)
+                            );
+                            if ui.checkbox(&mut selected, name).changed() {
+                                if selected {
+                                    dialog.selected.insert(object.id());
+                                } else {
+                                    dialog.selected.remove(&object.id());
+                                }
+                            }
+                        }
+                    });
+                ui.separator();
+
+                let rename = &mut dialog.rename;
+                egui::Grid::new("batch_rename_fields")
+                    .num_columns(2)
+                    .show(ui, |ui| {
+                        ui.label("Find:");
+                        ui.text_edit_singleline(&mut rename.find);
+                        ui.end_row();
+                        ui.label("Replace with:");
+                        ui.text_edit_singleline(&mut rename.replace);
+                        ui.end_row();
+                        ui.label("Pattern:");
+                        ui.add(
+                            egui::TextEdit::singleline(&mut rename.pattern)
+                                .hint_text("e.g. BTN_{n:02} or {name}_{n}"),
+                        )
+                        .on_hover_text(
+                            "{name} is the current name, {n} the number of the object and \
+                            {n:02} the number padded to 2 digits. Leave empty to keep the name.",
+                        );
+                        ui.end_row();
+                        ui.label("First number:");
+                        ui.add(egui::DragValue::new(&mut rename.start));
+                        ui.end_row();
+                        ui.label("Prefix:");
+                        ui.text_edit_singleline(&mut rename.prefix);
+                        ui.end_row();
+                        ui.label("Suffix:");
+                        ui.text_edit_singleline(&mut rename.suffix);
+                        ui.end_row();
+                    });
+
+                let ids: Vec<ObjectId> = project
+                    .get_pool()
+                    .objects()
+                    .iter()
+                    .map(|object| object.id())
+                    .filter(|id| dialog.selected.contains(id))
+                    .collect();
+                let renamed = match project.preview_batch_rename(&ids, &dialog.rename) {
+                    Ok(renamed) => renamed,
+                    Err(e) => {
+                        ui.colored_label(egui::Color32::RED, e);
+                        return;
+                    }
+                };
+                ui.separator();
+                ui.label(format!("{} names change", renamed.len()));
+                egui::ScrollArea::vertical()
+                    .id_salt("batch_rename_preview")
+                    .max_height(200.0)
+                    .show(ui, |ui| {
+                        egui::Grid::new("batch_rename_preview")
+                            .striped(true)
+                            .num_columns(2)
+                            .show(ui, |ui| {
+                                for (_, name, new_name) in &renamed {
+                                    ui.label(name);
+                                    ui.label(format!("\u{2192} {}", new_name));
+                                    ui.end_row();
+                                }
+                            });
+                    });
+                if ui
+                    .add_enabled(!renamed.is_empty(), egui::Button::new("Rename"))
+                    .on_hover_text("Rename the objects in a single undo step")
+                    .clicked()
+                {
+                    let names: Vec<(ObjectId, String)> = renamed
+                        .into_iter()
+                        .map(|(id, _, new_name)| (id, new_name))
+                        .collect();
+                    project.rename_objects(&names);
+                }
+            });
+        if !open {
+            self.batch_rename_dialog = None;
+        }
+    }
+
+    /// Show the input objects to enable or disable at once, e.g. for a read-only demo variant of
+    /// the pool, with the objects that changed
+    fn show_input_enable_dialog(&mut self, ctx: &egui::Context) {
+        let (Some(dialog), Some(project)) = (&mut self.input_enable_dialog, &self.project) else {
+            self.input_enable_dialog = None;
+            return;
+        };
+
+        let mut open = true;
+        egui::Window::new("Enable or disable inputs")
+            .open(&mut open)
+            .default_width(420.0)
+            .show(ctx, |ui| {
+                let inputs = project.get_objects_by_types(&INPUT_TYPES);
+                let name = |id: ObjectId| {
+                    project
+                        .get_object(id)
+                        .map_or(id.value().to_string(), |object| {
+                            format!(
+                                "{}: {}",
+                                id.value(),
+                                project.get_object_info(object).get_name(object)
+                            )
+                        })
+                };
+                ui.horizontal(|ui| {
+                    if ui.button("Select all").clicked() {
+                        dialog
+                            .selected
+                            .extend(inputs.iter().map(|object| object.id()));
+                    }
+                    if ui.button("Select none").clicked() {
+                        dialog.selected.clear();
+                    }
+                    ui.label(format!("{} selected", dialog.selected.len()));
+                });
+                egui::ScrollArea::vertical()
+                    .id_salt("input_enable_objects")
+                    .max_height(240.0)
+                    .show(ui, |ui| {
+                        for object in &inputs {
+                            let mut selected = dialog.selected.contains(&object.id());
+                            if ui.checkbox(&mut selected, name(object.id())).changed() {
+                                if selected {
+                                    dialog.selected.insert(object.id());
+                                } else {
+                                    dialog.selected.remove(&object.id());
+                                }
+                            }
+                        }
+                    });
+                ui.separator();
+
+                let ids: Vec<ObjectId> = inputs
+                    .iter()
+                    .map(|object| object.id())
+                    .filter(|id| dialog.selected.contains(id))
+                    .collect();
+                let mut set_enabled = None;
+                ui.horizontal(|ui| {
+                    if ui
+                        .add_enabled(!ids.is_empty(), egui::Button::new("Enable"))
+                        .clicked()
+                    {
+                        set_enabled = Some(true);
+                    }
+                    if ui
+                        .add_enabled(!ids.is_empty(), egui::Button::new("Disable"))
+                        .on_hover_text("E.g. for a read-only demo variant of the pool")
+                        .clicked()
+                    {
+                        set_enabled = Some(false);
+                    }
+                });
+                if let Some(enabled) = set_enabled {
+                    match project.set_inputs_enabled(&ids, enabled) {
+                        Ok(changed) => dialog.changed = Some((changed, enabled)),
+                        Err(e) => log::error!("Failed to change the inputs: {}", e),
+                    }
+                }
+
+                if let Some((changed, enabled)) = &dialog.changed {
+                    let action = if *enabled { "Enabled" } else { "Disabled" };
+                    if changed.is_empty() {
+                        ui.weak(format!("{} no inputs, they already were", action));
+                    } else {
+                        egui::CollapsingHeader::new(format!("{} {} inputs", action, changed.len()))
+                            .default_open(true)
+                            .show(ui, |ui| {
+                                for id in changed {
+                                    if ui.link(name(*id)).clicked() {
+                                        project
+                                            .get_mut_selected()
+                                            .replace(NullableObjectId(Some(*id)));
+                                    }
+                                }
+                            });
+                    }
+                }
+            });
+        if !open {
+            self.input_enable_dialog = None;
+        }
+    }
+
+    /// Show the smart names proposed for all objects next to their current names, each of which
+    /// can be kept
+    fn show_smart_naming_dialog(&mut self, ctx: &egui::Context) {
+        let (Some(dialog), Some(project)) = (&mut self.smart_naming_dialog, &self.project) else {
+            self.smart_naming_dialog = None;
+            return;
+        };
+
+        let mut open = true;
+        let mut close = false;
+        egui::Window::new("Smart naming")
+            .open(&mut open)
+            .default_width(520.0)
+            .show(ctx, |ui| {
+                ui.label(
+                    "Names that look chosen by someone are kept unless checked. Uncheck the \
+                    objects that should keep their name.",
+                );
+                let mut parent_naming = project.is_parent_naming();
+                if ui
+                    .checkbox(
+                        &mut parent_naming,
+                        "Start with the name of the mask or container",
+                    )
+                    .on_hover_text(
+                        "Precede the names by the name of the mask or container showing the \
+                        object, e.g. \"Main Button 2\", so they stay meaningful in the object \
+                        list and exported headers. Saved with the project.",
+                    )
+                    .changed()
+                {
+                    project.set_parent_naming(parent_naming);
+                    *dialog = SmartNamingDialog::new(project.preview_smart_naming());
+                }
+                ui.horizontal(|ui| {
+                    if ui.button("Select all").clicked() {
+                        dialog.keep.clear();
+                    }
+                    if ui.button("Select none").clicked() {
+                        dialog
+                            .keep
+                            .extend(dialog.renames.iter().map(|rename| rename.object));
+                    }
+                    ui.label(format!(
+                        "{} of {} renamed",
+                        dialog.renames.len() - dialog.keep.len(),
+                        dialog.renames.len()
+                    ));
+                });
+                ui.separator();
+                egui::ScrollArea::vertical()
+                    .id_salt("smart_naming_preview")
+                    .max_height(400.0)
+                    .show(ui, |ui| {
+                        egui::Grid::new("smart_naming_preview")
+                            .striped(true)
+                            .num_columns(3)
+                            .show(ui, |ui| {
+                                for rename in &dialog.renames {
+                                    let mut apply = !dialog.keep.contains(&rename.object);
+                                    let label =
+                                        format!("{}: {}", rename.object.value(), rename.name);
+                                    if ui.checkbox(&mut apply, label).changed() {
+                                        if apply {
+                                            dialog.keep.remove(&rename.object);
+                                        } else {
+                                            dialog.keep.insert(rename.object);
+                                        }
+                                    }
+                                    ui.label(format!("\u{2192} {}", rename.new_name));
+                                    if rename.intentional {
+                                        ui.weak("looks intentional");
+                                    } else {
+                                        ui.label("");
+                                    }
+                                    ui.end_row();
+                                }
+                            });
+                    });
+                ui.separator();
+                ui.horizontal(|ui| {
+                    let count = dialog.renames.len() - dialog.keep.len();
+                    if ui
+                        .add_enabled(
+                            count > 0,
+                            egui::Button::new(format!("Rename {} objects", count)),
+                        )
+                        .on_hover_text("Rename the objects in a single undo step")
+                        .clicked()
+                    {
+                        let names: Vec<(ObjectId, String)> = dialog
+                            .renames
+                            .iter()
+                            .filter(|rename| !dialog.keep.contains(&rename.object))
+                            .map(|rename| (rename.object, rename.new_name.clone()))
+                            .collect();
+                        project.rename_objects(&names);
+                        close = true;
+                    }
+                    if ui.button("Cancel").clicked() {
+                        close = true;
+                    }
+                });
+            });
+        if !open || close {
+            self.smart_naming_dialog = None;
+        }
+    }
+
+    /// Show the tool replacing every use of a font by another font, with the text objects that
+    /// change and whether their text still fits
+    fn show_font_substitution(&mut self, ctx: &egui::Context) {
+        let (Some(tool), Some(project)) = (&mut self.font_substitution, &self.project) else {
+            self.font_substitution = None;
+            return;
+        };
+
+        let object_name = |object: &Object| {
+            format!(
+                "{}: {}",
+                object.id().value(),
+                project.get_object_info(object).get_name(object)
+            )
+        };
+        let object_combo = |ui: &mut egui::Ui,
+                            label: &str,
+                            selected: &mut Option<ObjectId>,
+                            candidates: &[&Object],
+                            none: Option<&str>| {
+            let selected_text = selected
+                .and_then(|id| project.get_object(id))
+                .map(|object| object_name(object))
+                .or(none.map(str::to_string))
+                .unwrap_or_else(|| "Choose object".to_string());
+            egui::ComboBox::from_label(label)
+                .selected_text(selected_text)
+                .show_ui(ui, |ui| {
+                    if let Some(none) = none {
+                        ui.selectable_value(selected, None, none);
+                    }
+                    for candidate in candidates {
+                        ui.selectable_value(selected, Some(candidate.id()), object_name(candidate));
+                    }
+                });
+        };
+
+        let fonts = project.get_objects_by_type(ObjectType::FontAttributes);
+        let masks = project.get_objects_by_types(&[
+            ObjectType::DataMask,
+            ObjectType::AlarmMask,
+            ObjectType::WindowMask,
+        ]);
+        let mut open = true;
+        egui::Window::new("Replace font")
+            .open(&mut open)
+            .default_width(460.0)
+            .show(ctx, |ui| {
+                object_combo(ui, "Replace", &mut tool.from, &fonts, None);
+                object_combo(ui, "With", &mut tool.to, &fonts, None);
+                object_combo(ui, "On", &mut tool.scope, &masks, Some("All masks"));
+                if let Some(message) = &tool.message {
+                    ui.label(message);
+                }
+
+                let (Some(from), Some(to)) = (tool.from, tool.to) else {
+                    return;
+                };
+                let Some(Object::FontAttributes(font)) = project.get_object(to) else {
+                    return;
+                };
+                if from == to {
+                    ui.weak("Choose two different fonts");
+                    return;
+                }
+                let uses = preview_font_substitution(project.get_pool(), from, font, tool.scope);
+                ui.separator();
+                ui.label(format!("{} objects use the font", uses.len()));
+                egui::ScrollArea::vertical()
+                    .max_height(300.0)
+                    .show(ui, |ui| {
+                        egui::Grid::new("font_substitution_preview")
+                            .striped(true)
+                            .num_columns(3)
+                            .show(ui, |ui| {
+                                for font_use in &uses {
+                                    let Some(object) = project.get_object(font_use.object) else {
+                                        continue;
+                                    };
+                                    ui.weak(format!("{:?}", object.object_type()));
+                                    if ui.link(object_name(object)).clicked() {
+                                        project
+                                            .get_mut_selected()
+                                            .replace(NullableObjectId(Some(object.id())));
+                                    }
+                                    match &font_use.warning {
+                                        Some(warning) => {
+                                            ui.colored_label(
+                                                ui.visuals().warn_fg_color,
+                                                format!("\u{26A0} {}", warning),
+                                            );
+                                        }
+                                        None => {
+                                            ui.label("Fits");
+                                        }
+                                    }
+                                    ui.end_row();
+                                }
+                            });
+                    });
+                if ui
+                    .add_enabled(!uses.is_empty(), egui::Button::new("Replace font"))
+                    .clicked()
+                {
+                    tool.message = Some(match project.substitute_font(from, to, tool.scope) {
+                        Ok(count) => format!("Replaced the font of {} objects", count),
+                        Err(e) => format!("Failed to replace the font: {}", e),
+                    });
+                }
+            });
+        if !open {
+            self.font_substitution = None;
+        }
+    }
+
+    /// Show where the project was backed up before the last bulk operation, for a few seconds
+    fn show_backup_toast(&mut self, ctx: &egui::Context) {
+        if let Some(notice) = self.project.as_ref().and_then(|p| p.take_backup_notice()) {
+            self.backup_toast = Some((notice, Instant::now()));
+        }
+        let Some((notice, shown_at)) = &self.backup_toast else {
+            return;
+        };
+        let remaining = BACKUP_TOAST_DURATION.saturating_sub(shown_at.elapsed());
+        if remaining.is_zero() {
+            self.backup_toast = None;
+            return;
+        }
+        ctx.request_repaint_after(remaining);
+
+        let mut close = false;
+        egui::Area::new(egui::Id::new("backup_toast"))
+            .anchor(egui::Align2::RIGHT_BOTTOM, [-10.0, -10.0])
+            .show(ctx, |ui| {
+                egui::Frame::popup(ui.style()).show(ui, |ui| {
+                    ui.set_max_width(400.0);
+                    ui.horizontal(|ui| {
+                        ui.strong(format!("{} done", notice.operation));
+                        close = ui.small_button("\u{2715}").clicked();
+                    });
+                    match &notice.location {
+                        #[cfg(not(target_arch = "wasm32"))]
+                        Ok(path) => {
+                            ui.label("The project before the change was backed up to");
+                            ui.hyperlink_to(path, format!("file://{}", path));
+                        }
+                        #[cfg(target_arch = "wasm32")]
+                        Ok(key) => {
+                            ui.label(format!(
+                                "The project before the change was backed up to the browser \
+                                storage as {}",
+                                key
+                            ));
+                        }
+                        Err(e) => {
+                            ui.colored_label(
+                                egui::Color32::RED,
+                                format!("The backup before the change failed: {}", e),
+                            );
+                        }
+                    }
+                });
+            });
+        if close {
+            self.backup_toast = None;
+        }
+    }
+
+    /// Take over the path the project was saved to, its autosaves are no longer needed
+    fn handle_project_saved(&mut self) {
+        while let Ok(path) = self.saved_path_channel.1.try_recv() {
+            self.autosaver.discard(self.project_path.as_deref());
+            self.autosaver.discard(Some(&path));
+            self.settings.add_recent_file(&path);
+            self.settings.save();
+            self.project_path = Some(path);
+        }
+    }
+
+    /// Show the offer to restore the autosave of the project that was just opened, or on startup
+    /// the unsaved work of an earlier session
+    fn show_autosave_offer(&mut self, ctx: &egui::Context) {
+        let Some(autosave) = self.autosave_offer.take() else {
+            return;
+        };
+        let mut restore = false;
+        let mut discard = false;
+        let startup = self.project.is_none();
+        egui::Modal::new(egui::Id::new("autosave_offer")).show(ctx, |ui| {
+            ui.set_width(400.0);
+            if startup {
+                ui.heading("Restore unsaved work?");
+                ui.label(format!(
+                    "The designer was closed with unsaved changes, they were autosaved {}.",
+                    autosave.metadata.age()
+                ));
+            } else {
+                ui.heading("Restore autosave?");
+                ui.label(format!(
+                    "This project has unsaved changes that were autosaved {}.",
+                    autosave.metadata.age()
+                ));
+            }
+            if let Some(path) = &autosave.metadata.source_path {
+                ui.weak(path);
+            }
+            ui.add_space(10.0);
+            ui.horizontal(|ui| {
+                restore = ui.button("Restore").clicked();
+                discard = ui
+                    .button("Discard")
+                    .on_hover_text("Delete the autosave and keep the project as it was opened")
+                    .clicked();
+            });
+        });
+
+        if restore {
+            match EditorProject::load_project(autosave.project.as_bytes()) {
+                Ok(project) => {
+                    // The restored changes are not in the project file yet
+                    project.set_modified();
+                    self.project = Some(project);
+                    self.simulation = None;
+                    self.analyzer = PoolAnalyzer::default();
+                    if startup {
+                        self.autosaver.reset();
+                        self.project_path = autosave.metadata.source_path.clone();
+                        // Untitled work is autosaved under the name of this session from now on
+                        if self.project_path.is_none() {
+                            self.autosaver.discard_autosave(&autosave);
+                        }
+                    }
+                }
+                Err(e) => log::error!("Failed to restore autosave: {}", e),
+            }
+        } else if discard {
+            self.autosaver.discard_autosave(&autosave);
+        } else {
+            self.autosave_offer = Some(autosave);
+        }
+    }
+
+    /// Open a file dialog to save a pool file
+    fn save_pool(&mut self) {
+        if let Some(pool) = &self.project {
+            let task = rfd::AsyncFileDialog::new()
+                .set_file_name("object_pool.iop")
+                .save_file();
+            // Serialize the pool as it was when saving was requested, editing may continue
+            let snapshot = pool.snapshot();
+            execute(async move {
+                let file = task.await;
+                if let Some(file) = file {
+                    _ = file.write(&snapshot.as_iop()).await;
+                }
+            });
+        }
+    }
+
+    /// Open a file dialog to save an object with all objects it references as a pool of its own
+    fn save_subtree_pool(&mut self, id: ObjectId, renumber: bool) {
+        if let Some(project) = &self.project {
+            let file_name = project
+                .get_object(id)
+                .map_or("selection".to_string(), |object| {
+                    project.get_object_info(object).get_export_name(object)
+                });
+            let task = rfd::AsyncFileDialog::new()
+                .set_file_name(format!("{}.iop", file_name))
+                .save_file();
+            let data = project.subtree_pool(id, renumber).as_iop();
+            execute(async move {
+                let file = task.await;
+                if let Some(file) = file {
+                    _ = file.write(&data).await;
+                }
+            });
+        }
+    }
+
+    /// Open a file dialog to save the selected object with its children as PNG image, on a
+    /// transparent background, e.g. for documentation and icon reviews
+    fn save_object_image(&mut self, id: ObjectId, scale: f32) {
+        let Some(project) = &self.project else {
+            return;
+        };
+        let snapshot = project.snapshot();
+        let pool = &*snapshot;
+        let Some(object) = pool.object_by_id(id) else {
+            return;
+        };
+        let image = match object {
+            Object::SoftKeyMask(mask) => {
+                ag_iso_terminal_designer::render_soft_key_mask_to_image(mask, pool, scale)
+            }
+            _ => ag_iso_terminal_designer::render_object_to_image(object, pool, scale),
+        };
+        let png = match ag_iso_terminal_designer::encode_png(&image) {
+            Ok(png) => png,
+            Err(e) => {
+                log::error!("Failed to encode image of object {}: {}", id.value(), e);
+                return;
+            }
+        };
+        let name = project.get_object_info(object).get_export_name(object);
+        let task = rfd::AsyncFileDialog::new()
+            .set_file_name(format!(
+                "{}_{}.png",
+                id.value(),
+                Self::to_c_identifier(&name).to_lowercase()
+            ))
+            .add_filter("PNG", &["png"])
+            .save_file();
+        execute(async move {
+            let file = task.await;
+            if let Some(file) = file {
+                _ = file.write(&png).await;
+            }
+        });
+    }
+
+    /// Save the pool once per language of the working set, with the translated texts
+    fn save_localized_pools(&mut self) {
+        if let Some(project) = &self.project {
+            let files = project
+                .get_language_codes()
+                .iter()
+                .map(|language| {
+                    (
+                        format!("object_pool_{}.iop", language.to_lowercase()),
+                        project.localized_pool(language).as_iop(),
+                    )
+                })
+                .collect();
+            save_files(files);
+        }
+    }
+
+    /// Open a file dialog to save the log of all changes of this session, as JSON or CSV
+    fn save_audit_log(&mut self, csv: bool) {
+        if let Some(project) = &self.project {
+            let audit_log = project.get_audit_log();
+            let (file_name, data) = if csv {
+                ("audit_log.csv", audit_log.to_csv())
+            } else {
+                match audit_log.to_json() {
+                    Ok(data) => ("audit_log.json", data),
+                    Err(e) => {
+                        log::error!("Failed to serialize audit log: {}", e);
+                        return;
+                    }
+                }
+            };
+            let task = rfd::AsyncFileDialog::new()
+                .set_file_name(file_name)
+                .save_file();
+            execute(async move {
+                if let Some(file) = task.await {
+                    if let Err(e) = file.write(&data).await {
+                        log::error!("Failed to save audit log: {}", e);
+                    }
+                }
+            });
+        }
+    }
+
+    /// Open a file dialog to save a project file
+    fn save_project(&mut self, ctx: &egui::Context) {
+        if let Some(project) = &self.project {
+            match project.save_project() {
+                Ok(contents) => {
+                    let task = rfd::AsyncFileDialog::new()
+                        .set_file_name("project.aitp")
+                        .add_filter("AgIsoTerminal Project", &["aitp"])
+                        .save_file();
+                    let sender = self.saved_path_channel.0.clone();
+                    let ctx = ctx.clone();
+                    execute(async move {
+                        let file = task.await;
+                        if let Some(file) = file {
+                            match file.write(&contents).await {
+                                Ok(()) => {
+                                    if let Some(path) = file_path(&file) {
+                                        let _ = sender.send(path);
+                                        ctx.request_repaint();
+                                    }
+                                }
+                                Err(e) => log::error!("Failed to write project: {}", e),
+                            }
+                        }
+                    });
+                }
+                Err(e) => {
+                    log::error!("Failed to save project: {}", e);
+                    // TODO: Show error dialog
+                }
+            }
+        }
+    }
+
+    /// Convert a string to a valid C identifier
+    fn to_c_identifier(name: &str) -> String {
+        name.chars()
+            .map(|c| match c {
+                'a'..='z' | 'A'..='Z' | '0'..='9' => c.to_ascii_uppercase(),
+                _ => '_',
+            })
+            .collect()
+    }
+
+    /// Open a file dialog to save a C header file with object IDs
+    fn save_header(&mut self) {
+        if let Some(project) = &self.project {
+            let header = Self::header_contents(project, &self.settings);
+            let contents = header.into_bytes();
+            let task = rfd::AsyncFileDialog::new()
+                .set_file_name("object_pool.h")
+                .add_filter("C Header", &["h"])
+                .save_file();
+            execute(async move {
+                let file = task.await;
+                if let Some(file) = file {
+                    _ = file.write(&contents).await;
+                }
+            });
+        }
+    }
+
+    /// The C header with the IDs of all objects, in the style chosen in the settings
+    fn header_contents(project: &EditorProject, settings: &DesignerSettings) -> String {
+        let pool = project.get_pool();
+        let style = settings.general.header_style;
+        let constant = |name: &str, value: u16| match style {
+            HeaderStyle::Defines => format!("#define {} {}\n", name, value),
+            HeaderStyle::EnumClass | HeaderStyle::Constexpr => {
+                format!("constexpr std::uint16_t {} = {};\n", name, value)
+            }
+        };
+
+        // Start with the header
+        let mut header = String::from("// Object IDs for the objects in the object pool.\n\n");
+        header.push_str("#pragma once\n");
+        if style != HeaderStyle::Defines {
+            header.push_str("\n#include <cstdint>\n\n");
+        }
+        header.push_str(&constant("UNDEFINED", 65535));
+
+        if settings.general.header_constants {
+            header.push_str("\n// Size of the data mask area the pool is designed for\n");
+            header.push_str(&constant("DATA_MASK_WIDTH", project.mask_size));
+            header.push_str(&constant("DATA_MASK_HEIGHT", project.mask_size));
+            header.push_str("// VT version the pool is made for\n");
+            let vt_version =
+                vt_version_number(project.get_vt_version()).max(required_vt_version(pool));
+            header.push_str(&constant("VT_VERSION", vt_version as u16));
+        }
+
+        // All objects at once, or per type under a banner
+        let groups: Vec<(Option<ObjectType>, Vec<&Object>)> =
+            if settings.general.header_group_by_type {
+                ObjectType::values()
+                    .into_iter()
+                    .map(|object_type| (Some(object_type), pool.objects_by_type(object_type)))
+                    .filter(|(_, objects)| !objects.is_empty())
+                    .collect()
+            } else {
+                vec![(None, pool.objects().iter().collect())]
+            };
+
+        let mut used = HashSet::new();
+        for (object_type, mut objects) in groups {
+            // Sort by ID for consistent output
+            objects.sort_by_key(|obj| u16::from(obj.id()));
+            if let Some(object_type) = object_type {
+                let banner = format!("// {}\n", "-".repeat(74));
+                header.push('\n');
+                header.push_str(&banner);
+                header.push_str(&format!("// {:?}\n", object_type));
+                header.push_str(&banner);
+            }
+            let indent = if style == HeaderStyle::EnumClass {
+                // Names only have to be unique within their enum
+                used.clear();
+                let name = object_type.map_or("ObjectId".to_string(), |object_type| {
+                    format!("{:?}Id", object_type)
+                });
+                header.push_str(&format!("\nenum class {} : std::uint16_t {{\n", name));
+                "    "
+            } else {
+                ""
+            };
+
+            for obj in objects {
+                let info = project.get_object_info(obj);
+                let id = u16::from(obj.id());
+                let mut identifier = Self::to_c_identifier(&info.get_export_name(obj));
+                if identifier.starts_with(|c: char| c.is_ascii_digit()) {
+                    identifier.insert(0, '_');
+                }
+                let identifier = Self::unique_identifier(identifier, id, &mut used);
+                if settings.general.header_notes {
+                    for line in info.notes.lines() {
+                        header.push_str(&format!("{}// {}\n", indent, line));
+                    }
+                }
+                match style {
+                    HeaderStyle::Defines | HeaderStyle::Constexpr => {
+                        header.push_str(&constant(&identifier, id))
+                    }
+                    HeaderStyle::EnumClass => {
+                        header.push_str(&format!("    {} = {},\n", identifier, id))
+                    }
+                }
+            }
+            if style == HeaderStyle::EnumClass {
+                header.push_str("};\n");
+            }
+        }
+        header
+    }
+
+    /// Open a file dialog to save a CSV list of all objects, e.g. for review in a spreadsheet
+    fn save_object_list(&mut self) {
+        if let Some(project) = &self.project {
+            let contents = object_list_csv(project).into_bytes();
+            let task = rfd::AsyncFileDialog::new()
+                .set_file_name("object_list.csv")
+                .add_filter("CSV", &["csv"])
+                .save_file();
+            execute(async move {
+                let file = task.await;
+                if let Some(file) = file {
+                    _ = file.write(&contents).await;
+                }
+            });
+        }
+    }
+
+    /// Open a file dialog to save the structure of the pool as JSON, for external tooling like
+    /// test scripts and documentation generators
+    fn save_manifest(&mut self) {
+        if let Some(project) = &self.project {
+            let contents = match PoolManifest::new(project).to_json() {
+                Ok(contents) => contents,
+                Err(e) => {
+                    log::error!("Failed to serialize the pool manifest: {}", e);
+                    return;
+                }
+            };
+            let task = rfd::AsyncFileDialog::new()
+                .set_file_name("pool_manifest.json")
+                .add_filter("JSON", &["json"])
+                .save_file();
+            execute(async move {
+                let file = task.await;
+                if let Some(file) = file {
+                    _ = file.write(&contents).await;
+                }
+            });
+        }
+    }
+
+    /// Convert a name to a CamelCase Rust type or variant identifier
+    fn to_rust_type_identifier(name: &str) -> String {
+        let identifier: String = name
+            .split(|c: char| !c.is_ascii_alphanumeric())
+            .filter(|word| !word.is_empty())
+            .map(|word| {
+                let mut chars = word.chars();
+                match chars.next() {
+                    Some(first) => first.to_ascii_uppercase().to_string() + chars.as_str(),
+                    None => String::new(),
+                }
+            })
+            .collect();
+        if identifier.is_empty() || identifier.starts_with(|c: char| c.is_ascii_digit()) {
+            format!("Object{}", identifier)
+        } else {
+            identifier
+        }
+    }
+
+    /// Convert an object type to a snake_case Rust module name
+    fn to_rust_module_name(object_type: ObjectType) -> String {
+        let mut module = String::new();
+        for (idx, c) in format!("{:?}", object_type).chars().enumerate() {
+            if c.is_ascii_uppercase() && idx > 0 {
+                module.push('_');
+            }
+            module.push(c.to_ascii_lowercase());
+        }
+        module
+    }
+
+    /// Make an identifier that is a Rust keyword usable, as raw identifier or with a trailing
+    /// underscore for the keywords that can't be raw identifiers
+    fn escape_rust_keyword(identifier: String) -> String {
+        const KEYWORDS: &[&str] = &[
+            "abstract", "as", "async", "await", "become", "box", "break", "const", "continue",
+            "do", "dyn", "else", "enum", "extern", "false", "final", "fn", "for", "gen", "if",
+            "impl", "in", "let", "loop", "macro", "match", "mod", "move", "mut", "override",
+            "priv", "pub", "ref", "return", "static", "struct", "trait", "true", "try", "type",
+            "typeof", "unsafe", "unsized", "use", "virtual", "where", "while", "yield",
+        ];
+        match identifier.as_str() {
+            "self" | "Self" | "super" | "crate" | "_" => identifier + "_",
+            keyword if KEYWORDS.contains(&keyword) => format!("r#{}", identifier),
+            _ => identifier,
+        }
+    }
+
+    /// Make an identifier unique by appending the object ID when it is already in use
+    fn unique_identifier(identifier: String, id: u16, used: &mut HashSet<String>) -> String {
+        let identifier = if used.contains(&identifier) {
+            format!("{}_{}", identifier, id)
+        } else {
+            identifier
+        };
+        used.insert(identifier.clone());
+        identifier
+    }
+
+    /// Collect the IDs of all objects that are (indirectly) referenced by an object
+    fn collect_descendant_ids(pool: &ObjectPool, object: &Object, ids: &mut Vec<u16>) {
+        for id in object.referenced_objects() {
+            if ids.contains(&id.value()) {
+                continue;
+            }
+            ids.push(id.value());
+            if let Some(child) = pool.object_by_id(id) {
+                Self::collect_descendant_ids(pool, child, ids);
+            }
+        }
+    }
+
+    /// Open a file dialog to save a Rust module with object IDs
+    fn save_rust_module(&mut self) {
+        if let Some(project) = &self.project {
+            let snapshot = project.snapshot();
+            let pool = &*snapshot;
+
+            let mut module = String::from("//! Object IDs for the objects in the object pool.\n\n");
+            module.push_str("#![allow(dead_code)]\n\n");
+            module.push_str("/// ID of an object in the object pool\n");
+            module
+                .push_str("#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]\n");
+            module.push_str("pub struct ObjectId(pub u16);\n\n");
+            module.push_str("impl ObjectId {\n");
+            module.push_str("    pub const fn value(self) -> u16 {\n");
+            module.push_str("        self.0\n");
+            module.push_str("    }\n");
+            module.push_str("}\n\n");
+            module.push_str("impl From<ObjectId> for u16 {\n");
+            module.push_str("    fn from(id: ObjectId) -> u16 {\n");
+            module.push_str("        id.0\n");
+            module.push_str("    }\n");
+            module.push_str("}\n\n");
+            module.push_str("/// Object ID used to reference no object\n");
+            module.push_str("pub const UNDEFINED: ObjectId = ObjectId(65535);\n");
+
+            // Group the objects per type, sorted by ID for consistent output
+            for object_type in ObjectType::values() {
+                let mut objects = pool.objects_by_type(object_type);
+                if objects.is_empty() {
+                    continue;
+                }
+                objects.sort_by_key(|obj| u16::from(obj.id()));
+
+                module.push_str(&format!(
+                    "\npub mod {} {{\n",
+                    Self::escape_rust_keyword(Self::to_rust_module_name(object_type))
+                ));
+                module.push_str("    use super::ObjectId;\n\n");
+                let mut used = HashSet::new();
+                for obj in objects {
+                    let name = project.get_object_info(obj).get_export_name(obj);
+                    let id = u16::from(obj.id());
+                    let mut identifier = Self::to_c_identifier(&name);
+                    if identifier.starts_with(|c: char| c.is_ascii_digit()) {
+                        identifier.insert(0, '_');
+                    }
+                    let identifier = Self::unique_identifier(
+                        Self::escape_rust_keyword(identifier),
+                        id,
+                        &mut used,
+                    );
+                    module.push_str(&format!(
+                        "    pub const {}: ObjectId = ObjectId({});\n",
+                        identifier, id
+                    ));
+                }
+                module.push_str("}\n");
+            }
+
+            // Optionally add an enum per mask with all objects shown on it
+            if self.settings.general.rust_export_mask_enums {
+                let mut masks =
+                    pool.objects_by_types(&[ObjectType::DataMask, ObjectType::AlarmMask]);
+                masks.sort_by_key(|obj| u16::from(obj.id()));
+
+                let mut used_enums = HashSet::new();
+                for mask in masks {
+                    let mut ids = Vec::new();
+                    Self::collect_descendant_ids(pool, mask, &mut ids);
+                    if ids.is_empty() {
+                        continue;
+                    }
+
+                    let mask_name = project.get_object_info(mask).get_export_name(mask);
+                    let enum_name = Self::unique_identifier(
+                        format!("{}Objects", Self::to_rust_type_identifier(&mask_name)),
+                        mask.id().value(),
+                        &mut used_enums,
+                    );
+                    module.push_str(&format!(
+                        "\n/// Objects shown on mask {}\n",
+                        mask.id().value()
+                    ));
+                    module.push_str("#[repr(u16)]\n");
+                    module.push_str("#[derive(Debug, Clone, Copy, PartialEq, Eq)]\n");
+                    module.push_str(&format!("pub enum {} {{\n", enum_name));
+                    let mut used_variants = HashSet::new();
+                    for id in ids {
+                        let Some(obj) = ObjectId::new(id).ok().and_then(|id| pool.object_by_id(id))
+                        else {
+                            continue;
+                        };
+                        let name = project.get_object_info(obj).get_export_name(obj);
+                        let variant = Self::unique_identifier(
+                            Self::escape_rust_keyword(Self::to_rust_type_identifier(&name)),
+                            id,
+                            &mut used_variants,
+                        );
+                        module.push_str(&format!("    {} = {},\n", variant, id));
+                    }
+                    module.push_str("}\n");
+                }
+            }
+
+            let contents = module.into_bytes();
+            let task = rfd::AsyncFileDialog::new()
+                .set_file_name("object_pool.rs")
+                .add_filter("Rust Module", &["rs"])
+                .save_file();
+            execute(async move {
+                let file = task.await;
+                if let Some(file) = file {
+                    _ = file.write(&contents).await;
+                }
+            });
+        }
+    }
+
+    /// Render all masks off-screen and save them as PNG images
+    fn export_mask_images(&mut self, dark_mode: bool) {
+        if let Some(project) = &self.project {
+            let snapshot = project.snapshot();
+            let pool = &*snapshot;
+            let theme = self.settings.canvas.export_theme(dark_mode);
+            let margin = self.settings.canvas.export_margin as usize;
+
+            let mut files = Vec::new();
+            for obj in pool.objects_by_types(&[
+                ObjectType::DataMask,
+                ObjectType::AlarmMask,
+                ObjectType::SoftKeyMask,
+            ]) {
+                let image = match obj {
+                    Object::SoftKeyMask(mask) => {
+                        ag_iso_terminal_designer::render_soft_key_mask_to_image(mask, pool, 1.0)
+                    }
+                    _ => ag_iso_terminal_designer::render_object_to_image(obj, pool, 1.0),
+                };
+                let image = ag_iso_terminal_designer::frame_image(
+                    &image,
+                    theme.surround_colour(),
+                    theme.guide_colour(),
+                    margin,
+                );
+                match ag_iso_terminal_designer::encode_png(&image) {
+                    Ok(png) => {
+                        let name = project.get_object_info(obj).get_export_name(obj);
+                        files.push((
+                            format!(
+                                "{}_{}.png",
+                                obj.id().value(),
+                                Self::to_c_identifier(&name).to_lowercase()
+                            ),
+                            png,
+                        ));
+                    }
+                    Err(e) => {
+                        log::error!("Failed to encode image of mask {}: {}", obj.id().value(), e);
+                    }
+                }
+            }
+
+            save_files(files);
+        }
+    }
+
+    /// Open a file dialog to save every key rendered at the soft key size to its own PNG file,
+    /// named after the key, for use in operator manuals
+    fn export_key_images(&mut self) {
+        if let Some(project) = &self.project {
+            let snapshot = project.snapshot();
+            let pool = &*snapshot;
+
+            let mut used = HashSet::new();
+            let mut files = Vec::new();
+            for obj in pool.objects_by_type(ObjectType::Key) {
+                // Keys are shown at the exact size of a soft key, without a frame around them
+                let image = ag_iso_terminal_designer::render_object_to_image(obj, pool, 1.0);
+                match ag_iso_terminal_designer::encode_png(&image) {
+                    Ok(png) => {
+                        let name = project.get_object_info(obj).get_export_name(obj);
+                        let name = Self::unique_identifier(
+                            Self::to_c_identifier(&name).to_lowercase(),
+                            obj.id().value(),
+                            &mut used,
+                        );
+                        files.push((format!("{}.png", name), png));
+                    }
+                    Err(e) => {
+                        log::error!("Failed to encode image of key {}: {}", obj.id().value(), e);
+                    }
+                }
+            }
+
+            save_files(files);
+        }
+    }
+}
+
+/// Render the text field of an in-progress rename.
+/// Pressing enter or clicking elsewhere stores the new name, escape cancels the rename.
+fn render_rename_text_edit(
+    ui: &mut egui::Ui,
+    project: &EditorProject,
+    this_ui_id: egui::Id,
+    object_id: ObjectId,
+    mut name: String,
+) {
+    let response = ui.add(egui::TextEdit::singleline(&mut name).id(this_ui_id.with("rename")));
+    project.set_renaming_object(this_ui_id, object_id, name); // Update the name in the project
+    let cancelled = ui.input(|i| i.key_pressed(egui::Key::Escape));
+    if response.lost_focus() {
+        project.finish_renaming_object(!cancelled);
+    } else if !response.has_focus() {
+        // We need to focus the text edit when we start renaming
+        response.request_focus();
+    }
+}
+
+/// Render an object that can be selected, `parent` is the object it is shown as a child of
+fn render_selectable_object(
+    ui: &mut egui::Ui,
+    object: &Object,
+    project: &EditorProject,
+    parent: Option<ObjectId>,
+) {
+    let this_ui_id = ui.id();
+    let object_info = project.get_object_info(object);
+
+    let renaming_object = project.get_renaming_object();
+    if let Some((_, _, name)) =
+        renaming_object.filter(|(ui_id, id, _)| *id == object.id() && *ui_id == this_ui_id)
+    {
+        render_rename_text_edit(ui, project, this_ui_id, object.id(), name);
+    } else {
+        let is_selected = project.get_selected() == object.id().into();
+        let label_text = format!(
+            "{}: {}",
+            u16::from(object.id()),
+            object_info.get_name(object)
+        );
+        let response = ui
+            .horizontal(|ui| {
+                render_object_thumbnail(ui, project, object, LIST_THUMBNAIL_SIZE);
+                ui.selectable_label(is_selected, label_text)
+            })
+            .inner;
+
+        if response.clicked() {
+            project
+                .get_mut_selected()
+                .replace(NullableObjectId(Some(object.id())));
+        }
+        if response.double_clicked() || (is_selected && project.take_rename_selected_request()) {
+            project.set_renaming_object(this_ui_id, object.id(), object_info.get_name(object));
+        }
+
+        response.context_menu(|ui| {
+            if ui
+                .button("Rename")
+                .on_hover_text(format!(
+                    "Rename object ({})",
+                    ui.ctx().format_shortcut(&RENAME_SHORTCUT)
+                ))
+                .clicked()
+            {
+                project.set_renaming_object(this_ui_id, object.id(), object_info.get_name(object));
+                ui.close();
+            }
+            if ui
+                .button("Duplicate with children")
+                .on_hover_text(
+                    "Duplicate the object with the objects it shows, using new IDs, and place the \
+                    duplicate below the original",
+                )
+                .clicked()
+            {
+                match project.duplicate_with_children(object.id(), parent) {
+                    Ok(id) => {
+                        project
+                            .get_mut_selected()
+                            .replace(NullableObjectId(Some(id)));
+                    }
+                    Err(e) => log::error!("Failed to duplicate object: {}", e),
+                }
+                ui.close();
+            }
+            if ui.button("Delete").on_hover_text("Delete object").clicked() {
+                if let Err(e) = project.execute(PoolCommand::RemoveObject(object.id())) {
+                    log::error!("Failed to delete object: {}", e);
+                }
+                ui.close();
+            }
+        });
+    }
+}
+
+/// A child reference dragged in the hierarchy, at `index` in the references of `parent`
+#[derive(Clone, Copy)]
+struct HierarchyDrag {
+    parent: ObjectId,
+    index: usize,
+}
+
+/// Render the row of an object in the hierarchy. A child can be dragged onto another object to
+/// move it there, or onto the upper or lower edge of a row to place it before or after that
+/// object, e.g. to change the z-order within its parent.
+fn render_hierarchy_row(
+    ui: &mut egui::Ui,
+    row_id: egui::Id,
+    object: &Object,
+    project: &EditorProject,
+    parent: Option<ObjectId>,
+    index: usize,
+) {
+    let response = match parent {
+        Some(parent_id) => {
+            ui.dnd_drag_source(
+                row_id.with("hierarchy_drag"),
+                HierarchyDrag {
+                    parent: parent_id,
+                    index,
+                },
+                |ui| render_selectable_object(ui, object, project, parent),
+            )
+            .response
+        }
+        None => {
+            ui.scope(|ui| render_selectable_object(ui, object, project, parent))
+                .response
+        }
+    };
+
+    if response.dnd_hover_payload::<HierarchyDrag>().is_none() {
+        return;
+    }
+    let rect = response.rect;
+    let edge = rect.height() / 4.0;
+    let pointer_y = ui
+        .ctx()
+        .pointer_interact_pos()
+        .map_or(rect.center().y, |pos| pos.y);
+    // Place before or after the object when on an edge, otherwise in the object
+    let sibling = match parent {
+        Some(parent_id) if pointer_y < rect.top() + edge => Some((parent_id, false)),
+        Some(parent_id) if pointer_y > rect.bottom() - edge => Some((parent_id, true)),
+        _ => None,
+    };
+    let stroke = ui.visuals().selection.stroke;
+    match sibling {
+        Some((_, false)) => ui.painter().hline(rect.x_range(), rect.top(), stroke),
+        Some((_, true)) => ui.painter().hline(rect.x_range(), rect.bottom(), stroke),
+        None => ui
+            .painter()
+            .rect_stroke(rect, 2.0, stroke, egui::epaint::StrokeKind::Inside),
+    }
+
+    let Some(drag) = response.dnd_release_payload::<HierarchyDrag>() else {
+        return;
+    };
+    if parent == Some(drag.parent) && index == drag.index {
+        return;
+    }
+    let result = match sibling {
+        Some((parent_id, after)) => {
+            project.move_child(drag.parent, drag.index, parent_id, Some((index, after)))
+        }
+        None => project.move_child(drag.parent, drag.index, object.id(), None),
+    };
+    if let Err(e) = result {
+        log::error!("Failed to move object: {}", e);
+    }
+}
+
+fn render_object_hierarchy(
+    ui: &mut egui::Ui,
+    parent_id: egui::Id,
+    object: &Object,
+    project: &EditorProject,
+    parent: Option<ObjectId>,
+    index: usize,
+    path: &[u16],
+) {
+    let refs = object.referenced_objects();
+    if refs.is_empty() {
+        ui.horizontal(|ui| {
+            ui.add_space(ui.spacing().indent);
+            render_hierarchy_row(ui, parent_id, object, project, parent, index);
+        });
+    } else {
+        let id = parent_id.with(project.get_object_info(object).get_unique_id());
+        let path = [path, &[object.id().value()]].concat();
+        let state = egui::collapsing_header::CollapsingState::load_with_default_open(
+            ui.ctx(),
+            id,
+            project.is_node_expanded(&path),
+        );
+        project.set_node_expanded(&path, state.is_open());
+        state
+            .show_header(ui, |ui| {
+                render_hierarchy_row(ui, parent_id, object, project, parent, index);
+            })
+            .body(|ui| {
+                for (idx, obj_id) in refs.iter().enumerate() {
+                    match project.get_pool().object_by_id(*obj_id) {
+                        Some(obj) => {
+                            render_object_hierarchy(
+                                ui,
+                                id.with(idx),
+                                obj,
+                                project,
+                                Some(object.id()),
+                                idx,
+                                &path,
+                            );
+                        }
+                        None => {
+                            ui.colored_label(
+                                egui::Color32::RED,
+                                format!("Missing object: {}", obj_id.value()),
+                            );
+                        }
+                    }
+                }
+            });
+    }
+}
 
-            // Sort by ID for consistent output
-            objects.sort_by_key(|&(_, id)| id);
+/// Render the active mask of the simulation, clicking on input objects opens their entry dialog.
+/// The input that has focus is indicated in the given style and colour.
+fn render_simulation(
+    ui: &mut egui::Ui,
+    simulation: &mut Simulation,
+    mask_size: u16,
+    theme: CanvasTheme,
+    (focus_style, focus_colour): (FocusStyle, egui::Color32),
+) {
+    let mut clicked = None;
+    let pool = simulation.get_pool();
+    if let Some((transition, from, to, progress)) = simulation.mask_transition_progress() {
+        egui::ScrollArea::both().show(ui, |ui| {
+            render_mask_transition(ui, pool, (from, to), transition, progress, mask_size, theme);
+        });
+        ui.ctx().request_repaint();
+        return;
+    }
+    match pool
+        .working_set_object()
+        .and_then(|working_set| pool.object_by_id(working_set.active_mask))
+    {
+        Some(mask) => {
+            egui::ScrollArea::both().show(ui, |ui| {
+                ui.add_sized(
+                    [mask_size as f32, mask_size as f32],
+                    InteractiveMaskRenderer {
+                        object: mask,
+                        pool,
+                        selected: None,
+                        focused: simulation
+                            .get_focused()
+                            .map(|id| (id, focus_style, focus_colour)),
+                        theme,
+                        selected_callback: Box::new(|object_id| clicked = Some(object_id)),
+                    },
+                );
+            });
+        }
+        None => {
+            ui.colored_label(egui::Color32::RED, "No active mask to simulate");
+        }
+    }
+
+    if let Some(object_id) = clicked {
+        simulation.activate_object(object_id);
+    }
+}
+
+/// Draw the child picked for a macro command moving it where it is moved to, dragging it changes
+/// the position
+fn render_child_move_target(
+    ui: &mut egui::Ui,
+    project: &EditorProject,
+    pool: &ObjectPool,
+    mask_rect: egui::Rect,
+    mut pick: ChildMovePick,
+    theme: CanvasTheme,
+) {
+    let Some(target) = &mut pick.target else {
+        return;
+    };
+    let Some(child) = pool.object_by_id(target.child) else {
+        return;
+    };
+    let (width, height) = pool.content_size(child);
+    let size = egui::vec2(width as f32, height as f32);
+    let parent_min = mask_rect.min
+        + egui::vec2(
+            target.parent_position.0 as f32,
+            target.parent_position.1 as f32,
+        );
+    let from_rect = egui::Rect::from_min_size(
+        parent_min + egui::vec2(target.from.0 as f32, target.from.1 as f32),
+        size,
+    );
+    let to_rect = egui::Rect::from_min_size(
+        parent_min + egui::vec2(target.to.0 as f32, target.to.1 as f32),
+        size,
+    );
+
+    let response = ui.interact(
+        to_rect,
+        ui.id().with(("child_move_target", target.child.value())),
+        egui::Sense::drag(),
+    );
+    if response.dragged() {
+        let delta = response.drag_delta();
+        if delta != egui::Vec2::ZERO {
+            // Whole VT pixels, the remainder of the drag is kept in egui's memory
+            let remainder_id = response.id.with("remainder");
+            let total = delta + ui.data(|data| data.get_temp(remainder_id).unwrap_or_default());
+            let moved = total.round();
+            ui.data_mut(|data| data.insert_temp(remainder_id, total - moved));
+            target.to.0 = target.to.0.saturating_add(moved.x as i16);
+            target.to.1 = target.to.1.saturating_add(moved.y as i16);
+            project.set_child_move_pick(Some(pick.clone()));
+        }
+    }
+
+    let painter = ui.painter();
+    painter.rect_stroke(
+        from_rect,
+        0.0,
+        egui::Stroke::new(1.0, theme.guide_colour()),
+        egui::epaint::StrokeKind::Outside,
+    );
+    painter.line_segment(
+        [from_rect.center(), to_rect.center()],
+        egui::Stroke::new(1.0, theme.selection_colour()),
+    );
+    painter.rect_filled(to_rect, 0.0, theme.selection_colour().gamma_multiply(0.3));
+    painter.rect_stroke(
+        to_rect,
+        0.0,
+        egui::Stroke::new(2.0, theme.selection_colour()),
+        egui::epaint::StrokeKind::Outside,
+    );
+    if response.hovered() || response.dragged() {
+        ui.ctx().set_cursor_icon(egui::CursorIcon::Grab);
+    }
+}
+
+/// Render a frame of the animated change from one mask to the other, `progress` going from 0 to 1.
+/// The masks can't be operated during the transition.
+fn render_mask_transition(
+    ui: &mut egui::Ui,
+    pool: &ObjectPool,
+    (from, to): (ObjectId, ObjectId),
+    transition: MaskTransition,
+    progress: f32,
+    mask_size: u16,
+    theme: CanvasTheme,
+) {
+    let size = mask_size as f32;
+    let (rect, _) = ui.allocate_exact_size(egui::vec2(size, size), egui::Sense::hover());
+    for (id, offset, opacity) in [
+        (from, -progress, 1.0 - progress),
+        (to, 1.0 - progress, progress),
+    ] {
+        let Some(mask) = pool.object_by_id(id) else {
+            continue;
+        };
+        let mask_rect = match transition {
+            MaskTransition::Slide => rect.translate(egui::vec2(offset * size, 0.0)),
+            _ => rect,
+        };
+        let mut child = ui.new_child(egui::UiBuilder::new().max_rect(mask_rect));
+        child.set_clip_rect(rect.intersect(ui.clip_rect()));
+        if transition == MaskTransition::Crossfade {
+            child.multiply_opacity(opacity);
+        }
+        child.put(
+            mask_rect,
+            InteractiveMaskRenderer {
+                object: mask,
+                pool,
+                selected: None,
+                focused: None,
+                theme,
+                selected_callback: Box::new(|_| {}),
+            },
+        );
+    }
+}
+
+/// Preview a window mask at its place in the cell layout of a user-layout data mask
+fn render_window_mask_preview(
+    ui: &mut egui::Ui,
+    window_mask: &WindowMask,
+    pool: &ObjectPool,
+    mask_size: u16,
+    theme: CanvasTheme,
+) {
+    // Window title and icon, as the VT may show them next to the window
+    ui.horizontal(|ui| {
+        for id in [window_mask.window_icon.0, window_mask.window_title.0]
+            .into_iter()
+            .flatten()
+        {
+            if let Some(obj) = pool.object_by_id(id) {
+                let (width, height) = pool.content_size(obj);
+                let (rect, _) = ui.allocate_exact_size(
+                    egui::vec2(width as f32, height as f32),
+                    egui::Sense::hover(),
+                );
+                let mut child_ui = ui.new_child(egui::UiBuilder::new().max_rect(rect));
+                obj.render(&mut child_ui, pool, Point::default());
+            }
+        }
+    });
+
+    egui::ScrollArea::both().show(ui, |ui| {
+        let (rect, _) = ui.allocate_exact_size(
+            egui::vec2(mask_size as f32, mask_size as f32),
+            egui::Sense::hover(),
+        );
+        let cell_size = ag_iso_terminal_designer::window_mask_cell_size(mask_size);
+        let stroke = egui::Stroke::new(1.0, theme.guide_colour());
 
-            // Add defines for each object
-            for (name, id) in objects {
-                header.push_str(&format!("#define {} {}\n", name, id));
+        // Cell layout of the user-layout data mask
+        for row in 0..6 {
+            for column in 0..2 {
+                let cell = egui::Rect::from_min_size(
+                    rect.min + egui::vec2(column as f32 * cell_size.x, row as f32 * cell_size.y),
+                    cell_size,
+                );
+                ui.painter()
+                    .rect_stroke(cell, 0.0, stroke, egui::epaint::StrokeKind::Inside);
             }
+        }
 
-            let contents = header.into_bytes();
-            let task = rfd::AsyncFileDialog::new()
-                .set_file_name("object_pool.h")
-                .add_filter("C Header", &["h"])
-                .save_file();
-            execute(async move {
-                let file = task.await;
-                if let Some(file) = file {
-                    _ = file.write(&contents).await;
+        let (columns, rows) = ag_iso_terminal_designer::window_mask_cells(window_mask.cell_format);
+        let window_rect = egui::Rect::from_min_size(
+            rect.min,
+            egui::vec2(cell_size.x * columns as f32, cell_size.y * rows as f32),
+        );
+        let mut child_ui = ui.new_child(egui::UiBuilder::new().max_rect(window_rect));
+        window_mask.render(&mut child_ui, pool, Point::default());
+        ui.painter().rect_stroke(
+            window_rect,
+            0.0,
+            egui::Stroke::new(2.0, theme.selection_colour()),
+            egui::epaint::StrokeKind::Outside,
+        );
+    });
+}
+
+/// Show the results of the pool analysis, clicking an object selects it
+/// Show the differences between the project and an IOP or project file, each changed object
+/// with the attributes that changed. Returns false once the window is closed.
+fn render_drift_check(
+    ctx: &egui::Context,
+    project: &EditorProject,
+    drift_check: &mut DriftCheck,
+) -> bool {
+    // Compared again whenever the project changes
+    if drift_check.generation != project.get_pool_generation() {
+        drift_check.generation = project.get_pool_generation();
+        drift_check.differences = project.compare_with_pool(&drift_check.pool);
+        drift_check
+            .differences
+            .retain(|difference| !matches!(difference, ProjectEvent::SelectionChanged(_)));
+        drift_check.identical = drift_check.pool.as_iop() == project.get_pool().as_iop();
+        drift_check.current = None;
+    }
+
+    let mut open = true;
+    egui::Window::new(format!("Compare with {}", drift_check.file_name))
+        .id(egui::Id::new("drift_check"))
+        .open(&mut open)
+        .default_width(420.0)
+        .show(ctx, |ui| {
+            if drift_check.identical {
+                ui.colored_label(
+                    egui::Color32::GREEN,
+                    "\u{2714} The file is identical to the export of the project",
+                );
+                return;
+            }
+            if drift_check.differences.is_empty() {
+                ui.label(
+                    "The objects are equal, the file only differs in the order of the objects",
+                );
+                return;
+            }
+            let count = |f: fn(&ProjectEvent) -> bool| {
+                drift_check.differences.iter().filter(|d| f(d)).count()
+            };
+            ui.colored_label(
+                egui::Color32::ORANGE,
+                format!(
+                    "{} objects only in the project, {} only in {}, {} changed",
+                    count(|d| matches!(d, ProjectEvent::ObjectAdded(_))),
+                    count(|d| matches!(d, ProjectEvent::ObjectRemoved(_))),
+                    drift_check.file_name,
+                    count(|d| matches!(d, ProjectEvent::AttributeChanged(_))),
+                ),
+            );
+
+            let total = drift_check.differences.len();
+            let mut navigated = false;
+            ui.horizontal(|ui| {
+                if ui.button("\u{2B06} Previous").clicked() {
+                    drift_check.current = Some(match drift_check.current {
+                        Some(idx) if idx > 0 => idx - 1,
+                        _ => total - 1,
+                    });
+                    navigated = true;
+                }
+                if ui.button("\u{2B07} Next").clicked() {
+                    drift_check.current = Some(match drift_check.current {
+                        Some(idx) if idx + 1 < total => idx + 1,
+                        _ => 0,
+                    });
+                    navigated = true;
+                }
+                if let Some(idx) = drift_check.current {
+                    ui.label(format!("Difference {} of {}", idx + 1, total));
+                }
+            });
+            if navigated {
+                if let Some(id) = drift_check
+                    .current
+                    .and_then(|idx| drift_check.differences.get(idx))
+                    .and_then(difference_object)
+                    .filter(|id| project.get_object(*id).is_some())
+                {
+                    *project.get_mut_selected().borrow_mut() = id.into();
+                }
+            }
+            ui.separator();
+
+            egui::ScrollArea::vertical().show(ui, |ui| {
+                for (idx, difference) in drift_check.differences.iter().enumerate() {
+                    let Some(id) = difference_object(difference) else {
+                        continue;
+                    };
+                    let text = match difference {
+                        ProjectEvent::ObjectAdded(_) => "Only in the project",
+                        ProjectEvent::ObjectRemoved(_) => "Only in the file",
+                        _ => "Changed",
+                    };
+                    let is_current = drift_check.current == Some(idx);
+                    let response = ui.horizontal(|ui| {
+                        if ui.selectable_label(is_current, text).clicked() {
+                            drift_check.current = Some(idx);
+                            if project.get_object(id).is_some() {
+                                *project.get_mut_selected().borrow_mut() = id.into();
+                            }
+                        }
+                        match project.get_object(id) {
+                            Some(object) => {
+                                let label = format!(
+                                    "{}: {}",
+                                    id.value(),
+                                    project.get_object_info(object).get_name(object)
+                                );
+                                if ui.link(label).clicked() {
+                                    drift_check.current = Some(idx);
+                                    *project.get_mut_selected().borrow_mut() = id.into();
+                                }
+                            }
+                            None => {
+                                let object = drift_check.pool.object_by_id(id);
+                                let name = drift_check
+                                    .names
+                                    .get(&id)
+                                    .map(|name| format!(": {}", name))
+                                    .unwrap_or_default();
+                                let object_type = object
+                                    .map(|object| format!(" ({:?})", object.object_type()))
+                                    .unwrap_or_default();
+                                ui.label(format!("object {}{}{}", id.value(), name, object_type));
+                            }
+                        }
+                    });
+                    if is_current && navigated {
+                        response.response.scroll_to_me(Some(egui::Align::Center));
+                    }
+
+                    let (Some(old), Some(new)) =
+                        (drift_check.pool.object_by_id(id), project.get_object(id))
+                    else {
+                        continue;
+                    };
+                    egui::CollapsingHeader::new("Attributes")
+                        .id_salt(("drift_check_attributes", id.value()))
+                        .open(navigated.then_some(is_current))
+                        .show(ui, |ui| {
+                            for change in attribute_changes(old, new) {
+                                ui.horizontal_wrapped(|ui| {
+                                    ui.strong(&change.attribute);
+                                    ui.label(format!("{} \u{2192} {}", change.old, change.new));
+                                });
+                            }
+                        });
+                }
+            });
+        });
+    open
+}
+
+/// Read an IOP or project file, with the names of its objects if it is a project file
+fn read_pool_file(content: Vec<u8>) -> (ObjectPool, HashMap<ObjectId, String>) {
+    // Project files are JSON, anything else is read as an IOP file
+    match EditorProject::load_project(&content) {
+        Ok(project) => {
+            let names = project
+                .get_pool()
+                .objects()
+                .iter()
+                .map(|object| {
+                    (
+                        object.id(),
+                        project.get_object_info(object).get_name(object),
+                    )
+                })
+                .collect();
+            (project.get_pool().clone(), names)
+        }
+        Err(_) => (ObjectPool::from_iop(content), HashMap::new()),
+    }
+}
+
+/// The name of the file at the path, for titles and messages
+fn file_name(path: &Option<String>) -> String {
+    path.as_deref()
+        .and_then(|path| std::path::Path::new(path).file_name())
+        .map_or("the file".to_string(), |name| {
+            name.to_string_lossy().into_owned()
+        })
+}
+
+/// The object a difference is about
+fn difference_object(difference: &ProjectEvent) -> Option<ObjectId> {
+    match difference {
+        ProjectEvent::ObjectAdded(id)
+        | ProjectEvent::ObjectRemoved(id)
+        | ProjectEvent::AttributeChanged(id) => Some(*id),
+        ProjectEvent::SelectionChanged(_) => None,
+    }
+}
+
+/// Create, restore and compare named snapshots of the pool.
+/// Returns false once the window is closed.
+fn render_snapshot_manager(
+    ctx: &egui::Context,
+    project: &EditorProject,
+    name: &mut String,
+    compared: &mut Option<usize>,
+) -> bool {
+    let mut open = true;
+    egui::Window::new("Snapshots")
+        .open(&mut open)
+        .default_width(360.0)
+        .show(ctx, |ui| {
+            ui.horizontal(|ui| {
+                ui.add(
+                    egui::TextEdit::singleline(name)
+                        .hint_text("e.g. before redesigning alarm masks"),
+                );
+                if ui
+                    .add_enabled(!name.trim().is_empty(), egui::Button::new("Create"))
+                    .clicked()
+                {
+                    project.create_snapshot(name.trim().to_string());
+                    name.clear();
+                }
+            });
+            ui.separator();
+
+            let snapshots = project.get_snapshots();
+            if snapshots.is_empty() {
+                ui.weak("No snapshots yet, snapshots are stored in the project file");
+            }
+            for (idx, (snapshot_name, created_at)) in snapshots.iter().enumerate().rev() {
+                ui.horizontal(|ui| {
+                    ui.label(snapshot_name)
+                        .on_hover_text(ag_iso_terminal_designer::describe_age(*created_at));
+                    if ui.button("Restore").clicked() {
+                        if let Err(e) = project.restore_snapshot(idx) {
+                            log::error!("Failed to restore snapshot: {}", e);
+                        }
+                    }
+                    if ui
+                        .selectable_label(*compared == Some(idx), "Compare")
+                        .clicked()
+                    {
+                        *compared = if *compared == Some(idx) {
+                            None
+                        } else {
+                            Some(idx)
+                        };
+                    }
+                    if ui.button("Delete").clicked() {
+                        project.delete_snapshot(idx);
+                        *compared = None;
+                    }
+                });
+            }
+
+            if let Some(idx) = *compared {
+                ui.separator();
+                let changes = project.diff_snapshot(idx);
+                if changes.is_empty() {
+                    ui.label("The pool is equal to the snapshot");
+                }
+                egui::ScrollArea::vertical()
+                    .max_height(300.0)
+                    .show(ui, |ui| {
+                        for change in changes {
+                            let (text, id) = match change {
+                                ProjectEvent::ObjectAdded(id) => ("Added", id),
+                                ProjectEvent::ObjectRemoved(id) => ("Removed", id),
+                                ProjectEvent::AttributeChanged(id) => ("Changed", id),
+                                ProjectEvent::SelectionChanged(_) => continue,
+                            };
+                            ui.horizontal(|ui| {
+                                ui.label(text);
+                                match project.get_object(id) {
+                                    Some(object) => {
+                                        let label = format!(
+                                            "{}: {}",
+                                            id.value(),
+                                            project.get_object_info(object).get_name(object)
+                                        );
+                                        if ui.link(label).clicked() {
+                                            *project.get_mut_selected().borrow_mut() = id.into();
+                                        }
+                                    }
+                                    None => {
+                                        ui.label(format!("object {}", id.value()));
+                                    }
+                                }
+                            });
+                        }
+                    });
+            }
+        });
+    open
+}
+
+/// List the undo and redo steps, clicking a step undoes or redoes up to the state after it
+fn render_history_window(ctx: &egui::Context, open: &mut bool, project: &mut EditorProject) {
+    let undo_labels = project.get_undo_labels();
+    let redo_labels = project.get_redo_labels();
+    let mut jump = None;
+    egui::Window::new("History")
+        .open(open)
+        .default_width(260.0)
+        .show(ctx, |ui| {
+            egui::ScrollArea::vertical().show(ui, |ui| {
+                let current = undo_labels.len();
+                if ui
+                    .selectable_label(current == 0, "Start of the session")
+                    .clicked()
+                {
+                    jump = Some(current as isize);
+                }
+                for (idx, label) in undo_labels.iter().enumerate() {
+                    if ui.selectable_label(idx + 1 == current, label).clicked() {
+                        jump = Some((current - idx - 1) as isize);
+                    }
+                }
+                for (idx, label) in redo_labels.iter().enumerate() {
+                    let text = egui::RichText::new(label).weak();
+                    if ui
+                        .selectable_label(false, text)
+                        .on_hover_text("Undone, click to redo up to this step")
+                        .clicked()
+                    {
+                        jump = Some(-(idx as isize + 1));
+                    }
+                }
+            });
+        });
+    if let Some(steps) = jump {
+        project.jump_in_history(steps);
+    }
+}
+
+fn render_analysis_window(
+    ctx: &egui::Context,
+    open: &mut bool,
+    analyzer: &PoolAnalyzer,
+    project: &EditorProject,
+) {
+    egui::Window::new("Pool analysis")
+        .open(open)
+        .default_width(360.0)
+        .show(ctx, |ui| {
+            if analyzer.is_running() {
+                ui.horizontal(|ui| {
+                    ui.spinner();
+                    ui.label("Analysing...");
+                });
+            }
+            let Some(report) = analyzer.get_report() else {
+                return;
+            };
+            let pool = project.get_pool();
+            let object_link = |ui: &mut egui::Ui, id: ObjectId| {
+                let label = match pool.object_by_id(id) {
+                    Some(obj) => format!(
+                        "{}: {}",
+                        id.value(),
+                        project.get_object_info(obj).get_name(obj)
+                    ),
+                    None => id.value().to_string(),
+                };
+                if ui.link(label).clicked() {
+                    project
+                        .get_mut_selected()
+                        .replace(NullableObjectId(Some(id)));
+                }
+            };
+
+            let mask_size_issues = check_mask_size(pool, project.mask_size);
+            egui::ScrollArea::vertical().show(ui, |ui| {
+                let issue_count = report.issues.len() + mask_size_issues.len();
+                egui::CollapsingHeader::new(format!("Issues ({})", issue_count))
+                    .default_open(true)
+                    .show(ui, |ui| {
+                        if issue_count == 0 {
+                            ui.weak("No issues found");
+                        }
+                        for issue in report.issues.iter().chain(&mask_size_issues) {
+                            ui.horizontal(|ui| {
+                                if let Some(id) = issue.object {
+                                    object_link(ui, id);
+                                }
+                                ui.colored_label(egui::Color32::YELLOW, &issue.message);
+                            });
+                        }
+                    });
+
+                render_guidelines(ui, project, &object_link);
+
+                egui::CollapsingHeader::new(format!(
+                    "Size statistics ({} bytes)",
+                    report.total_bytes
+                ))
+                .show(ui, |ui| {
+                    egui::Grid::new("analysis_statistics")
+                        .striped(true)
+                        .num_columns(3)
+                        .show(ui, |ui| {
+                            ui.strong("Type");
+                            ui.strong("Objects");
+                            ui.strong("Bytes");
+                            ui.end_row();
+                            for statistics in &report.statistics {
+                                ui.label(format!("{:?}", statistics.object_type));
+                                ui.label(statistics.count.to_string());
+                                ui.label(statistics.bytes.to_string());
+                                ui.end_row();
+                            }
+                        });
+                });
+
+                egui::CollapsingHeader::new(format!(
+                    "Unreferenced objects ({})",
+                    report.unreferenced.len()
+                ))
+                .show(ui, |ui| {
+                    for id in &report.unreferenced {
+                        object_link(ui, *id);
+                    }
+                });
+
+                let duplicate_bytes: usize = report
+                    .duplicate_pictures
+                    .iter()
+                    .map(|group| group.duplicate_bytes)
+                    .sum();
+                egui::CollapsingHeader::new(format!(
+                    "Duplicate pictures ({} bytes)",
+                    duplicate_bytes
+                ))
+                .show(ui, |ui| {
+                    if report.duplicate_pictures.is_empty() {
+                        ui.weak("No duplicate pictures found");
+                    }
+                    for (idx, group) in report.duplicate_pictures.iter().enumerate() {
+                        ui.push_id(idx, |ui| {
+                            render_duplicate_pictures(ui, project, group, &object_link);
+                        });
+                        ui.separator();
+                    }
+                });
+
+                egui::CollapsingHeader::new("Most referenced objects").show(ui, |ui| {
+                    let mut counts: Vec<_> = report.reference_counts.iter().collect();
+                    counts.sort_by(|a, b| b.1.cmp(a.1).then(a.0.value().cmp(&b.0.value())));
+                    for (id, count) in counts.into_iter().take(20) {
+                        ui.horizontal(|ui| {
+                            object_link(ui, *id);
+                            ui.weak(format!("{} references", count));
+                        });
+                    }
+                });
+            });
+        });
+}
+
+/// Show the tags of the object, with a field to add a tag
+fn render_tags(ui: &mut egui::Ui, project: &EditorProject, id: ObjectId) {
+    let mut tags = project.get_tags(id);
+    let mut changed = false;
+    ui.horizontal_wrapped(|ui| {
+        ui.label("Tags:");
+        tags.retain(|tag| {
+            let keep = !ui
+                .small_button(format!("{} \u{2715}", tag))
+                .on_hover_text("Remove tag")
+                .clicked();
+            changed |= !keep;
+            keep
+        });
+
+        let new_tag_id = ui.id().with(("new_tag", id));
+        let mut new_tag = ui
+            .data(|data| data.get_temp::<String>(new_tag_id))
+            .unwrap_or_default();
+        let response = ui.add(
+            egui::TextEdit::singleline(&mut new_tag)
+                .hint_text("Add tag...")
+                .desired_width(80.0),
+        );
+        let all_tags = project.get_all_tags();
+        let response = if all_tags.is_empty() {
+            response
+        } else {
+            response.on_hover_text(format!("Tags in the project: {}", all_tags.join(", ")))
+        };
+        let tag = new_tag.trim().replace(char::is_whitespace, "_");
+        if response.lost_focus() && ui.input(|i| i.key_pressed(egui::Key::Enter)) {
+            if !tag.is_empty() && !tags.contains(&tag) {
+                tags.push(tag);
+                changed = true;
+            }
+            new_tag.clear();
+        }
+        ui.data_mut(|data| data.insert_temp(new_tag_id, new_tag));
+    });
+    if changed {
+        project.set_tags(id, tags);
+    }
+}
+
+/// List the objects using the selected object, clicking one of them selects it
+fn render_usages(ui: &mut egui::Ui, project: &EditorProject, id: ObjectId) {
+    let usages = project.find_usages(id);
+    egui::CollapsingHeader::new(format!("Used by ({})", usages.len()))
+        .id_salt("object_usages")
+        .default_open(true)
+        .show(ui, |ui| {
+            if usages.is_empty() {
+                ui.weak("Not used by any object");
+                return;
+            }
+            egui::Grid::new("object_usages_grid")
+                .striped(true)
+                .num_columns(3)
+                .show(ui, |ui| {
+                    for usage in &usages {
+                        let Some(obj) = project.get_object(usage.id) else {
+                            continue;
+                        };
+                        ui.label(format!("{:?}", obj.object_type()));
+                        let name = format!(
+                            "{}: {}",
+                            usage.id.value(),
+                            project.get_object_info(obj).get_name(obj)
+                        );
+                        if ui.link(name).clicked() {
+                            project
+                                .get_mut_selected()
+                                .replace(NullableObjectId(Some(usage.id)));
+                        }
+                        match usage.via {
+                            Some(pointer) => {
+                                if ui
+                                    .link(format!("via pointer {}", pointer.value()))
+                                    .clicked()
+                                {
+                                    project
+                                        .get_mut_selected()
+                                        .replace(NullableObjectId(Some(pointer)));
+                                }
+                            }
+                            None => {
+                                ui.weak("direct");
+                            }
+                        }
+                        ui.end_row();
+                    }
+                });
+        });
+}
+
+/// Colour of a usage category in the mask usage heat map
+fn usage_category_colour(category: UsageCategory) -> egui::Color32 {
+    match category {
+        UsageCategory::Text => egui::Color32::from_rgb(80, 160, 255),
+        UsageCategory::Graphics => egui::Color32::from_rgb(80, 220, 120),
+        UsageCategory::Input => egui::Color32::from_rgb(255, 140, 60),
+    }
+}
+
+/// Size in points of the heat map in the mask usage window
+const USAGE_MAP_SIZE: f32 = 240.0;
+
+/// Show the area of a mask covered by each object type, with a heat map of the covered area
+/// per category. Overlapping objects show brighter.
+fn render_mask_usage_window(
+    ctx: &egui::Context,
+    open: &mut bool,
+    project: &EditorProject,
+    view: &mut MaskUsageView,
+) {
+    egui::Window::new("Mask usage")
+        .open(open)
+        .default_width(300.0)
+        .show(ctx, |ui| {
+            let pool = project.get_pool();
+            let masks =
+                project.get_objects_by_types(&[ObjectType::DataMask, ObjectType::AlarmMask]);
+            let mask_id = view
+                .mask
+                .filter(|id| masks.iter().any(|mask| mask.id() == *id))
+                .or_else(|| pool.working_set_object().map(|ws| ws.active_mask))
+                .or_else(|| masks.first().map(|mask| mask.id()));
+            let mask_name = |mask: &Object| {
+                format!(
+                    "{}: {}",
+                    mask.id().value(),
+                    project.get_object_info(mask).get_name(mask)
+                )
+            };
+            let Some(mask) = mask_id.and_then(|id| project.get_object(id)) else {
+                ui.weak("The pool has no data or alarm masks");
+                return;
+            };
+            egui::ComboBox::from_label("Mask")
+                .selected_text(mask_name(mask))
+                .show_ui(ui, |ui| {
+                    for candidate in &masks {
+                        if ui
+                            .selectable_label(candidate.id() == mask.id(), mask_name(candidate))
+                            .clicked()
+                        {
+                            view.mask = Some(candidate.id());
+                        }
+                    }
+                });
+
+            let usage = analyse_mask_usage(pool, mask, project.mask_size);
+            let (response, painter) = ui.allocate_painter(
+                egui::vec2(USAGE_MAP_SIZE, USAGE_MAP_SIZE),
+                egui::Sense::hover(),
+            );
+            let rect = response.rect;
+            let scale = USAGE_MAP_SIZE / project.mask_size.max(1) as f32;
+            painter.rect_filled(rect, 0.0, egui::Color32::from_gray(20));
+            for region in &usage.regions {
+                let region_rect = egui::Rect::from_min_size(
+                    rect.min + egui::vec2(region.x as f32, region.y as f32) * scale,
+                    egui::vec2(region.width as f32, region.height as f32) * scale,
+                )
+                .intersect(rect);
+                painter.rect_filled(
+                    region_rect,
+                    0.0,
+                    usage_category_colour(region.category).gamma_multiply(0.35),
+                );
+            }
+
+            ui.horizontal(|ui| {
+                for category in UsageCategory::ALL {
+                    ui.colored_label(
+                        usage_category_colour(category),
+                        format!("{:?} {:.0}%", category, usage.coverage(category) * 100.0),
+                    );
                 }
             });
-        }
-    }
+            ui.separator();
+            egui::Grid::new("mask_usage_types")
+                .striped(true)
+                .num_columns(3)
+                .show(ui, |ui| {
+                    ui.strong("Type");
+                    ui.strong("Objects");
+                    ui.strong("Area");
+                    ui.end_row();
+                    for type_usage in &usage.types {
+                        ui.label(format!("{:?}", type_usage.object_type));
+                        ui.label(type_usage.count.to_string());
+                        ui.label(format!(
+                            "{:.1}%",
+                            type_usage.area as f32 / usage.mask_area.max(1) as f32 * 100.0
+                        ));
+                        ui.end_row();
+                    }
+                });
+        });
 }
 
-fn render_selectable_object(ui: &mut egui::Ui, object: &Object, project: &EditorProject) {
-    let this_ui_id = ui.id();
-    let object_info = project.get_object_info(object);
-
-    let renaming_object = project.get_renaming_object();
-    if renaming_object
-        .clone()
-        .is_some_and(|(ui_id, id, _)| id == object.id() && ui_id == this_ui_id)
-    {
-        let mut name = renaming_object.unwrap().2;
-        let response = ui.text_edit_singleline(&mut name);
-        project.set_renaming_object(this_ui_id, object.id(), name); // Update the name in the project
-        let cancelled = ui.input(|i| i.key_pressed(egui::Key::Escape));
-        if response.lost_focus() {
-            project.finish_renaming_object(!cancelled);
-        } else if !response.has_focus() {
-            // We need to focus the text edit when we start renaming
-            response.request_focus();
-        }
-    } else {
-        let is_selected = project.get_selected() == object.id().into();
-        let label_text = format!(
-            "{}: {}",
-            u16::from(object.id()),
-            object_info.get_name(object)
-        );
-        let response = ui.selectable_label(is_selected, label_text);
-
-        if response.clicked() {
-            project
-                .get_mut_selected()
-                .replace(NullableObjectId(Some(object.id())));
+/// Render a rule with an optional limit: a checkbox turning the rule on and the limit
+fn render_guideline_rule<T: egui::emath::Numeric>(
+    ui: &mut egui::Ui,
+    label: &str,
+    limit: &mut Option<T>,
+    default: T,
+    suffix: &str,
+) {
+    ui.horizontal(|ui| {
+        let mut enabled = limit.is_some();
+        if ui.checkbox(&mut enabled, label).changed() {
+            *limit = enabled.then_some(default);
         }
-        if response.double_clicked() {
-            project.set_renaming_object(this_ui_id, object.id(), object_info.get_name(object));
+        if let Some(value) = limit {
+            ui.add(egui::DragValue::new(value).suffix(suffix));
         }
+    });
+}
 
-        response.context_menu(|ui| {
-            if ui.button("Rename").on_hover_text("Rename object").clicked() {
-                project.set_renaming_object(this_ui_id, object.id(), object_info.get_name(object));
-                ui.close();
-            }
-            if ui.button("Delete").on_hover_text("Delete object").clicked() {
-                project.get_mut_pool().borrow_mut().remove(object.id());
-                ui.close();
+/// Render the HMI guideline rules of the project and the objects not following them
+fn render_guidelines(
+    ui: &mut egui::Ui,
+    project: &EditorProject,
+    object_link: &dyn Fn(&mut egui::Ui, ObjectId),
+) {
+    let mut settings = project.get_guideline_settings();
+    let issues = check_guidelines(project.get_pool(), &settings, project.mask_size);
+    egui::CollapsingHeader::new(format!("Guidelines ({})", issues.len())).show(ui, |ui| {
+        let recommended = GuidelineSettings::recommended(settings.screen_width_mm);
+        ui.horizontal(|ui| {
+            ui.label("Screen width:");
+            ui.add(
+                egui::DragValue::new(&mut settings.screen_width_mm)
+                    .range(10.0..=1000.0)
+                    .suffix(" mm"),
+            )
+            .on_hover_text("Physical width of the data mask on the terminal");
+            if ui
+                .button("Use ISO 15077 / AEF rules")
+                .on_hover_text("Turn on all rules with their recommended limits")
+                .clicked()
+            {
+                settings = recommended.clone();
             }
         });
-    }
+        render_guideline_rule(
+            ui,
+            "Minimum touch target",
+            &mut settings.min_touch_target_mm,
+            recommended.min_touch_target_mm.unwrap_or_default(),
+            " mm",
+        );
+        render_guideline_rule(
+            ui,
+            "Viewing distance",
+            &mut settings.viewing_distance_mm,
+            recommended.viewing_distance_mm.unwrap_or_default(),
+            " mm",
+        );
+        render_guideline_rule(
+            ui,
+            "Maximum high priority alarms",
+            &mut settings.max_high_priority_alarms,
+            recommended.max_high_priority_alarms.unwrap_or_default(),
+            "",
+        );
+        ui.separator();
+        if !settings.any_enabled() {
+            ui.weak("No guideline rules enabled");
+        } else if issues.is_empty() {
+            ui.weak("The pool follows the enabled guidelines");
+        }
+        for issue in &issues {
+            ui.horizontal(|ui| {
+                if let Some(id) = issue.object {
+                    object_link(ui, id);
+                }
+                ui.colored_label(egui::Color32::YELLOW, &issue.message);
+            });
+        }
+    });
+    project.set_guideline_settings(settings);
 }
 
-fn render_object_hierarchy(
+/// Size of an object pointer in the pool, replacing a duplicate picture by one saves less
+const OBJECT_POINTER_BYTES: usize = 5;
+
+fn render_duplicate_pictures(
     ui: &mut egui::Ui,
-    parent_id: egui::Id,
-    object: &Object,
     project: &EditorProject,
+    group: &DuplicatePictures,
+    object_link: &dyn Fn(&mut egui::Ui, ObjectId),
 ) {
-    let refs = object.referenced_objects();
-    if refs.is_empty() {
+    ui.horizontal(|ui| {
+        ui.label("Keep");
+        object_link(ui, group.original);
+    });
+    for id in &group.duplicates {
         ui.horizontal(|ui| {
-            ui.add_space(ui.spacing().indent);
-            render_selectable_object(ui, object, project);
+            ui.label(" - duplicate");
+            object_link(ui, *id);
         });
-    } else {
-        let id = parent_id.with(project.get_object_info(object).get_unique_id());
-        egui::collapsing_header::CollapsingState::load_with_default_open(ui.ctx(), id, false)
-            .show_header(ui, |ui| {
-                render_selectable_object(ui, object, project);
-            })
-            .body(|ui| {
-                for (idx, obj_id) in refs.iter().enumerate() {
-                    match project.get_pool().object_by_id(*obj_id) {
-                        Some(obj) => {
-                            render_object_hierarchy(ui, id.with(idx), obj, project);
-                        }
-                        None => {
-                            ui.colored_label(
-                                egui::Color32::RED,
-                                format!("Missing object: {:?}", id),
-                            );
-                        }
-                    }
-                }
-            });
     }
+
+    // Identical pixels may still be shown at a different size
+    let width = |id: ObjectId| match project.get_object(id) {
+        Some(Object::PictureGraphic(o)) => Some(o.width),
+        _ => None,
+    };
+    if group
+        .duplicates
+        .iter()
+        .any(|id| width(*id) != width(group.original))
+    {
+        ui.colored_label(
+            egui::Color32::YELLOW,
+            "Some duplicates are shown at a different width than the kept picture",
+        );
+    }
+
+    let pointer_bytes = group
+        .duplicate_bytes
+        .saturating_sub(group.duplicates.len() * OBJECT_POINTER_BYTES);
+    ui.horizontal(|ui| {
+        if ui
+            .button("Redirect references")
+            .on_hover_text(format!(
+                "Reference the kept picture instead of the duplicates and remove them, saving {} bytes",
+                group.duplicate_bytes
+            ))
+            .clicked()
+        {
+            if let Err(e) = project.merge_duplicates(group.original, &group.duplicates) {
+                log::error!("Failed to merge duplicate pictures: {}", e);
+            }
+        }
+        if ui
+            .button("Convert to object pointers")
+            .on_hover_text(format!(
+                "Replace the duplicates by object pointers to the kept picture, so their IDs \
+                stay valid, saving {} bytes",
+                pointer_bytes
+            ))
+            .clicked()
+        {
+            if let Err(e) =
+                project.replace_duplicates_with_pointers(group.original, &group.duplicates)
+            {
+                log::error!("Failed to convert duplicate pictures: {}", e);
+            }
+        }
+    });
 }
 
 fn update_object_hierarchy_headers(
@@ -504,12 +4957,45 @@ fn update_object_hierarchy_headers(
 
 impl eframe::App for DesignerApp {
     fn update(&mut self, ctx: &egui::Context, _: &mut eframe::Frame) {
+        self.profiler.begin_frame();
+        // Low-power mode skips the animations of the UI and the anti-aliasing of shapes
+        let low_power = self.settings.canvas.low_power;
         ctx.style_mut(|style| {
             style.interaction.selectable_labels = false;
+            style.animation_time = if low_power {
+                0.0
+            } else {
+                egui::Style::default().animation_time
+            };
         });
+        ctx.tessellation_options_mut(|options| options.feathering = !low_power);
 
         // Handle file dialog
-        self.handle_file_loaded();
+        self.handle_file_loaded(ctx);
+        self.handle_project_saved();
+        self.show_pool_loading(ctx);
+        self.show_recovery_report(ctx);
+        self.show_autosave_offer(ctx);
+        self.show_unsupported_objects(ctx);
+        self.show_template_dialog(ctx);
+        self.show_find_replace_dialog(ctx);
+        self.show_go_to_dialog(ctx);
+        self.show_batch_rename_dialog(ctx);
+        self.show_input_enable_dialog(ctx);
+        self.show_smart_naming_dialog(ctx);
+        self.show_font_substitution(ctx);
+        self.show_orphan_cleanup(ctx);
+        self.show_macro_report(ctx);
+        self.show_object_list_import(ctx);
+        self.show_repair_wizard(ctx);
+        self.show_renumber_dialog(ctx);
+        self.show_image_reimport(ctx);
+        self.show_object_info_check(ctx);
+        self.show_merge_dialog(ctx);
+        self.show_layout_preset_dialog(ctx);
+        self.show_image_import_dialog(ctx);
+        self.show_share_link(ctx);
+        self.show_backup_toast(ctx);
 
         // Check for image load requests
         if let Some(pool) = &self.project {
@@ -518,6 +5004,19 @@ impl eframe::App for DesignerApp {
             }
         }
 
+        // Check for picture format changes, the linked images are relative to the project
+        if let Some(pool) = &self.project {
+            if let Some((object_id, format)) = pool.take_picture_format_request() {
+                let project_dir = self
+                    .project_path
+                    .as_deref()
+                    .and_then(|path| std::path::Path::new(path).parent());
+                if let Err(e) = pool.change_picture_format(object_id, format, project_dir) {
+                    log::error!("Failed to change the picture format: {}", e);
+                }
+            }
+        }
+
         if self.show_development_popup {
             egui::Window::new("🚧 Under Active Development")
                 .collapsible(false)
@@ -591,11 +5090,13 @@ impl eframe::App for DesignerApp {
                     let mut new_obj = ag_iso_terminal_designer::default_object(object_type);
 
                     // Allocate a new ID efficiently
-                    let id = pool.allocate_object_id();
+                    let id = pool.allocate_object_id_for_type(object_type);
                     new_obj.mut_id().set_value(id.value()).ok();
 
                     // Add object to pool
-                    pool.get_mut_pool().borrow_mut().add(new_obj.clone());
+                    if let Err(e) = pool.execute(PoolCommand::AddObject(new_obj.clone())) {
+                        log::error!("Failed to add object: {}", e);
+                    }
 
                     // Set the custom name
                     let mut object_info = pool.object_info.borrow_mut();
@@ -618,6 +5119,18 @@ impl eframe::App for DesignerApp {
             }
         }
 
+        // Show settings dialog
+        if let Some(dialog) = &mut self.settings_dialog {
+            if !dialog.show(ctx, &mut self.settings) {
+                self.settings_dialog = None;
+            }
+        }
+
+        if ctx.input_mut(|i| i.consume_shortcut(&PERFORMANCE_HUD_SHORTCUT)) {
+            self.settings.general.show_performance_hud ^= true;
+            self.settings.save();
+        }
+
         egui::TopBottomPanel::top("topbar").show(ctx, |ui| {
             ui.horizontal_wrapped(|ui| {
                 egui::widgets::global_theme_preference_buttons(ui);
@@ -625,72 +5138,464 @@ impl eframe::App for DesignerApp {
 
                 // Undo/redo buttons
                 if let Some(pool) = &mut self.project {
-                    let undo_shortcut =
-                        egui::KeyboardShortcut::new(egui::Modifiers::CTRL, egui::Key::Z);
-                    let redo_shortcut =
-                        egui::KeyboardShortcut::new(egui::Modifiers::CTRL, egui::Key::Y);
-
                     if ui
                         .add_enabled(
                             pool.undo_available(),
                             egui::widgets::Button::new("\u{2BAA}"),
                         )
-                        .on_hover_text(format!("Undo ({})", ctx.format_shortcut(&undo_shortcut)))
+                        .on_hover_text(format!("Undo ({})", ctx.format_shortcut(&UNDO_SHORTCUT)))
+                        .clicked()
+                        || ctx.input_mut(|i| i.consume_shortcut(&UNDO_SHORTCUT))
+                    {
+                        pool.undo();
+                    }
+                    if ui
+                        .add_enabled(
+                            pool.redo_available(),
+                            egui::widgets::Button::new("\u{2BAB}"),
+                        )
+                        .on_hover_text(format!("Redo ({})", ctx.format_shortcut(&REDO_SHORTCUT)))
+                        .clicked()
+                        || ctx.input_mut(|i| i.consume_shortcut(&REDO_SHORTCUT))
+                    {
+                        pool.redo();
+                    }
+                    ui.separator();
+                }
+
+                ui.menu_button("File", |ui| {
+                    ui.label("Project Files");
+                    ui.menu_button("New from template", |ui| {
+                        for template in &STARTER_TEMPLATES {
+                            if ui
+                                .button(template.name)
+                                .on_hover_text(template.description)
+                                .clicked()
+                            {
+                                self.open_project(template.create_project(), None);
+                                ui.close();
+                            }
+                        }
+                    });
+                    if ui.button("Open Project (.aitp)").clicked() {
+                        self.open_file_dialog(FileDialogReason::LoadProject, ctx);
+                        ui.close();
+                    }
+                    #[cfg(not(target_arch = "wasm32"))]
+                    ui.add_enabled_ui(!self.settings.recent_files.is_empty(), |ui| {
+                        ui.menu_button("Open Recent", |ui| {
+                            for path in self.settings.recent_files.clone() {
+                                if ui.button(&path).clicked() {
+                                    self.open_recent_file(&path, ctx);
+                                    ui.close();
+                                }
+                            }
+                            ui.separator();
+                            if ui.button("Clear recent files").clicked() {
+                                self.settings.recent_files.clear();
+                                self.settings.save();
+                                ui.close();
+                            }
+                        });
+                    });
+                    if self.project.is_some() && ui.button("Save Project (.aitp)").clicked() {
+                        self.save_project(ctx);
+                        ui.close();
+                    }
+                    #[cfg(not(target_arch = "wasm32"))]
+                    if let Some(project) = &self.project {
+                        if ui
+                            .button("Re-import changed images")
+                            .on_hover_text(
+                                "Import the images linked to picture graphics again if they \
+                                changed on disk since they were imported",
+                            )
+                            .clicked()
+                        {
+                            let project_dir = self
+                                .project_path
+                                .as_deref()
+                                .and_then(|path| std::path::Path::new(path).parent());
+                            self.image_reimport = Some(project.reimport_changed_images(project_dir));
+                            ui.close();
+                        }
+                    }
+                    #[cfg(target_arch = "wasm32")]
+                    if let Some(project) = &self.project {
+                        if ui
+                            .button("Copy share link")
+                            .on_hover_text(
+                                "Copy a link holding the project, opening the project as it is \
+                                now in the browser of a colleague",
+                            )
+                            .clicked()
+                        {
+                            let share_link = Self::make_share_link(project);
+                            if let Ok(link) = &share_link {
+                                ctx.copy_text(link.clone());
+                            }
+                            self.share_link = Some(share_link);
+                            ui.close();
+                        }
+                    }
+                    if let Some(project) = &self.project {
+                        if ui
+                            .button("Check object info")
+                            .on_hover_text(
+                                "Remove the names and other info of deleted objects, add it for \
+                                new objects and fix objects sharing a unique identifier",
+                            )
+                            .clicked()
+                        {
+                            self.object_info_check = Some(project.check_object_info());
+                            ui.close();
+                        }
+                    }
+                    if self.project.is_some() {
+                        ui.menu_button("Export audit log", |ui| {
+                            ui.weak("Every change made to the project in this session");
+                            if ui.button("JSON (.json)").clicked() {
+                                self.save_audit_log(false);
+                                ui.close();
+                            }
+                            if ui.button("CSV (.csv)").clicked() {
+                                self.save_audit_log(true);
+                                ui.close();
+                            }
+                        });
+                    }
+
+                    ui.separator();
+                    ui.label("ISOBUS Files");
+
+                    if ui.button("Import IOP (.iop)").clicked() {
+                        self.open_file_dialog(FileDialogReason::LoadPool, ctx);
+                        ui.close();
+                    }
+
+                    if self.project.is_some() && ui.button("Export IOP (.iop)").clicked() {
+                        self.save_pool();
+                        ui.close();
+                    }
+                    if let Some(selected) = self.project.as_ref().and_then(|p| p.get_selected().0) {
+                        ui.menu_button("Export selection as IOP (.iop)", |ui| {
+                            ui.weak("The selected object with all objects it references");
+                            if ui.button("Keep object IDs").clicked() {
+                                self.save_subtree_pool(selected, false);
+                                ui.close();
+                            }
+                            if ui
+                                .button("Renumber from 0")
+                                .on_hover_text(
+                                    "Number the objects from 0 in the order they are referenced",
+                                )
+                                .clicked()
+                            {
+                                self.save_subtree_pool(selected, true);
+                                ui.close();
+                            }
+                        });
+                        ui.menu_button("Export selection as PNG (.png)", |ui| {
+                            ui.weak("The selected object with its children");
+                            for scale in PNG_EXPORT_SCALES {
+                                if ui
+                                    .button(format!("{}:1", scale))
+                                    .on_hover_text(format!(
+                                        "{} image pixels per VT pixel, on a transparent background",
+                                        scale
+                                    ))
+                                    .clicked()
+                                {
+                                    self.save_object_image(selected, scale as f32);
+                                    ui.close();
+                                }
+                            }
+                        });
+                    }
+                    if self.project.is_some()
+                        && ui
+                            .button("Compare with IOP/AITP\u{2026}")
+                            .on_hover_text(
+                                "List the differences with an IOP or project file, e.g. the one \
+                                released to machines or an update of a vendor",
+                            )
+                            .clicked()
+                    {
+                        self.open_file_dialog(FileDialogReason::CompareWithPool, ctx);
+                        ui.close();
+                    }
+                    if self.project.is_some()
+                        && ui
+                            .button("Merge project\u{2026}")
+                            .on_hover_text(
+                                "Bring the changes of another copy of the project, edited from \
+                                the same original, into this one",
+                            )
+                            .clicked()
+                    {
+                        self.merge_dialog = Some(MergeDialog::default());
+                        ui.close();
+                    }
+                    if self
+                        .project
+                        .as_ref()
+                        .is_some_and(|project| !project.get_language_codes().is_empty())
+                        && ui
+                            .button("Export IOP per language (.iop)")
+                            .on_hover_text(
+                                "Export one pool per language of the working set, with the translated texts",
+                            )
+                            .clicked()
+                    {
+                        self.save_localized_pools();
+                        ui.close();
+                    }
+                    if self.project.is_some() && ui.button("Export Header (.h)").clicked() {
+                        self.save_header();
+                        ui.close();
+                    }
+                    if self.project.is_some() && ui.button("Export Rust module (.rs)").clicked() {
+                        self.save_rust_module();
+                        ui.close();
+                    }
+
+                    if self.project.is_some() {
+                        ui.separator();
+                        ui.label("Documentation");
+                        if ui
+                            .button("Export mask images (.png)")
+                            .on_hover_text(
+                                "Render every data, alarm and soft key mask to a PNG file",
+                            )
+                            .clicked()
+                        {
+                            self.export_mask_images(ctx.style().visuals.dark_mode);
+                            ui.close();
+                        }
+                        if ui
+                            .button("Export key images (.png)")
+                            .on_hover_text(
+                                "Render every key at the soft key size to its own PNG file, \
+                                named after the key",
+                            )
+                            .clicked()
+                        {
+                            self.export_key_images();
+                            ui.close();
+                        }
+                        if ui
+                            .button("Export object list (.csv)")
+                            .on_hover_text(
+                                "List every object with its ID, name, type, parents, size, colour \
+                                and value, e.g. for traceability matrices and reviews",
+                            )
+                            .clicked()
+                        {
+                            self.save_object_list();
+                            ui.close();
+                        }
+                        if ui
+                            .button("Export manifest (.json)")
+                            .on_hover_text(
+                                "Write the ID, type, name, size, parents and children of every \
+                                object as JSON, for test scripts and documentation generators",
+                            )
+                            .clicked()
+                        {
+                            self.save_manifest();
+                            ui.close();
+                        }
+                        if ui
+                            .button("Import object list (.csv)")
+                            .on_hover_text(
+                                "Apply the names, sizes, colours and values of an edited object \
+                                list, after reviewing the changes",
+                            )
+                            .clicked()
+                        {
+                            self.open_file_dialog(FileDialogReason::ImportObjectList, ctx);
+                            ui.close();
+                        }
+                    }
+                });
+
+                if ui.button("Settings").clicked() {
+                    self.settings_dialog = Some(SettingsDialog::new(&self.settings));
+                }
+
+                if let Some(project) = &self.project {
+                    let simulating = self.simulation.is_some();
+                    if ui
+                        .selectable_label(simulating, "Simulate")
+                        .on_hover_text(
+                            "Operate the pool like on a VT, changes to the design are picked up \
+                            when the simulation is restarted",
+                        )
                         .clicked()
-                        || ctx.input_mut(|i| i.consume_shortcut(&undo_shortcut))
                     {
-                        pool.undo();
+                        self.simulation = if simulating {
+                            None
+                        } else {
+                            Some(Simulation::new(project.snapshot()))
+                        };
+                    }
+                }
+
+                if self.project.is_some()
+                    && ui
+                        .toggle_value(&mut self.settings.canvas.animations, "Animations")
+                        .on_hover_text(
+                            "Animate flashing text, blinking objects and mask transitions, turn \
+                            off for screenshots",
+                        )
+                        .changed()
+                {
+                    self.settings.save();
+                }
+
+                if self.project.as_ref().is_some_and(|p| p.is_modified()) {
+                    ui.weak("Unsaved changes");
+                }
+
+                if self.project.is_some() {
+                    ui.toggle_value(&mut self.show_analysis, "Analysis")
+                        .on_hover_text("Validate the pool and show size and reference statistics");
+                    ui.toggle_value(&mut self.show_history, "History")
+                        .on_hover_text("Show the undo history and jump to an earlier state");
+                    let mut show_find_replace = self.find_replace_dialog.is_some();
+                    if ui
+                        .toggle_value(&mut show_find_replace, "Find & replace")
+                        .on_hover_text("Find and replace text in the names of the objects")
+                        .changed()
+                    {
+                        self.find_replace_dialog =
+                            show_find_replace.then(FindReplaceDialog::default);
+                    }
+                    let mut show_batch_rename = self.batch_rename_dialog.is_some();
+                    if ui
+                        .toggle_value(&mut show_batch_rename, "Batch rename")
+                        .on_hover_text(
+                            "Rename several objects at once with a prefix, suffix, replacement \
+                            or numbering pattern",
+                        )
+                        .changed()
+                    {
+                        self.batch_rename_dialog =
+                            show_batch_rename.then(BatchRenameDialog::default);
+                    }
+                    let mut show_input_enable = self.input_enable_dialog.is_some();
+                    if ui
+                        .toggle_value(&mut show_input_enable, "Enable inputs")
+                        .on_hover_text(
+                            "Enable or disable several input objects at once, e.g. for a \
+                            read-only demo variant of the pool",
+                        )
+                        .changed()
+                    {
+                        self.input_enable_dialog =
+                            show_input_enable.then(InputEnableDialog::default);
+                    }
+                    let mut show_smart_naming = self.smart_naming_dialog.is_some();
+                    if ui
+                        .toggle_value(&mut show_smart_naming, "Smart naming")
+                        .on_hover_text(
+                            "Propose names following the naming rules for all objects, to review \
+                            before they are applied",
+                        )
+                        .changed()
+                    {
+                        self.smart_naming_dialog = self
+                            .project
+                            .as_ref()
+                            .filter(|_| show_smart_naming)
+                            .map(|project| {
+                                project.set_naming_rules(&self.settings.naming.rules);
+                                SmartNamingDialog::new(project.preview_smart_naming())
+                            });
+                    }
+                    let mut show_font_substitution = self.font_substitution.is_some();
+                    if ui
+                        .toggle_value(&mut show_font_substitution, "Replace font")
+                        .on_hover_text(
+                            "Make every text object use another font attributes object, \
+                            e.g. for a global typography change",
+                        )
+                        .changed()
+                    {
+                        self.font_substitution =
+                            show_font_substitution.then(FontSubstitution::default);
+                    }
+                    let mut show_mask_usage = self.mask_usage.is_some();
+                    if ui
+                        .toggle_value(&mut show_mask_usage, "Mask usage")
+                        .on_hover_text(
+                            "Show how much of a mask is covered by text, graphics and inputs",
+                        )
+                        .changed()
+                    {
+                        self.mask_usage = show_mask_usage.then(MaskUsageView::default);
+                    }
+                    let broken_references = self
+                        .project
+                        .as_ref()
+                        .map_or(0, |project| project.find_broken_references().len());
+                    if broken_references > 0 || self.repair_wizard.is_some() {
+                        let mut show_repair = self.repair_wizard.is_some();
+                        if ui
+                            .toggle_value(
+                                &mut show_repair,
+                                format!("Repair references ({})", broken_references),
+                            )
+                            .on_hover_text(
+                                "Remap, replace or remove the references to objects that are \
+                                missing from the pool",
+                            )
+                            .changed()
+                        {
+                            self.repair_wizard = show_repair.then(RepairWizard::default);
+                        }
+                    }
+                    let mut show_renumber = self.renumber_dialog.is_some();
+                    if ui
+                        .toggle_value(&mut show_renumber, "Renumber")
+                        .on_hover_text("Renumber the objects into a range of IDs per type")
+                        .changed()
+                    {
+                        self.renumber_dialog = show_renumber.then(RenumberDialog::default);
+                    }
+                    let mut show_orphans = self.orphan_cleanup.is_some();
+                    if ui
+                        .toggle_value(&mut show_orphans, "Orphans")
+                        .on_hover_text(
+                            "List the objects that can't be reached from the working set, to \
+                            remove them before exporting",
+                        )
+                        .changed()
+                    {
+                        self.orphan_cleanup = show_orphans.then(OrphanCleanup::default);
+                    }
+                    let mut show_macros = self.macro_report.is_some();
+                    if ui
+                        .toggle_value(&mut show_macros, "Macros")
+                        .on_hover_text(
+                            "List the macros with the events and macros running them, to remove \
+                            the macros that never run",
+                        )
+                        .changed()
+                    {
+                        self.macro_report = show_macros.then(MacroReport::default);
                     }
+                    let mut show_snapshots = self.snapshot_manager.is_some();
                     if ui
-                        .add_enabled(
-                            pool.redo_available(),
-                            egui::widgets::Button::new("\u{2BAB}"),
-                        )
-                        .on_hover_text(format!("Redo ({})", ctx.format_shortcut(&redo_shortcut)))
-                        .clicked()
-                        || ctx.input_mut(|i| i.consume_shortcut(&redo_shortcut))
+                        .toggle_value(&mut show_snapshots, "Snapshots")
+                        .on_hover_text("Store named copies of the pool in the project")
+                        .changed()
                     {
-                        pool.redo();
+                        self.snapshot_manager =
+                            show_snapshots.then(|| (String::new(), None));
                     }
-                    ui.separator();
                 }
 
-                ui.menu_button("File", |ui| {
-                    ui.label("Project Files");
-                    if ui.button("Open Project (.aitp)").clicked() {
-                        self.open_file_dialog(FileDialogReason::LoadProject, ctx);
-                        ui.close();
-                    }
-                    if self.project.is_some() && ui.button("Save Project (.aitp)").clicked() {
-                        self.save_project();
-                        ui.close();
-                    }
-
-                    ui.separator();
-                    ui.label("ISOBUS Files");
-
-                    if ui.button("Import IOP (.iop)").clicked() {
-                        self.open_file_dialog(FileDialogReason::LoadPool, ctx);
-                        ui.close();
-                    }
-
-                    ui.checkbox(
-                        &mut self.apply_smart_naming_on_import,
-                        "Apply smart naming on import",
-                    )
-                    .on_hover_text(
-                        "Automatically apply smart naming to objects when importing IOP files",
-                    );
-                    if self.project.is_some() && ui.button("Export IOP (.iop)").clicked() {
-                        self.save_pool();
-                        ui.close();
-                    }
-                    if self.project.is_some() && ui.button("Export Header (.h)").clicked() {
-                        self.save_header();
-                        ui.close();
-                    }
-                });
-
                 if self.project.is_some() {
                     // Add a new object
                     ui.menu_button("Add object", |ui| {
@@ -707,20 +5612,151 @@ impl eframe::App for DesignerApp {
                             }
                         });
                     });
+
+                    ui.menu_button("Templates", |ui| {
+                        let selected = self.project.as_ref().and_then(|p| p.get_selected().0);
+                        if ui
+                            .add_enabled(
+                                selected.is_some(),
+                                egui::Button::new("Save selected as template..."),
+                            )
+                            .clicked()
+                        {
+                            if let (Some(project), Some(root)) = (&self.project, selected) {
+                                let name = project
+                                    .get_object(root)
+                                    .map(|obj| project.get_object_info(obj).get_name(obj))
+                                    .unwrap_or_default();
+                                self.template_dialog = Some(TemplateDialog::Save { root, name });
+                            }
+                            ui.close();
+                        }
+                        let data_mask = selected.filter(|id| {
+                            self.project.as_ref().and_then(|p| p.get_object(*id)).is_some_and(
+                                |object| object.object_type() == ObjectType::DataMask,
+                            )
+                        });
+                        if ui
+                            .add_enabled(
+                                data_mask.is_some(),
+                                egui::Button::new("Layout preset for selected data mask..."),
+                            )
+                            .clicked()
+                        {
+                            self.layout_preset_dialog = data_mask.map(|mask| LayoutPresetDialog {
+                                mask,
+                                preset: LayoutPreset::HeaderContentFooter,
+                                margin: DEFAULT_LAYOUT_SPACING,
+                                gap: DEFAULT_LAYOUT_SPACING,
+                            });
+                            ui.close();
+                        }
+                        if ui.button("Insert template...").clicked() {
+                            self.template_dialog = Some(TemplateDialog::Insert {
+                                templates: ag_iso_terminal_designer::list_templates(),
+                            });
+                            ui.close();
+                        }
+                    });
                 }
 
                 if let Some(pool) = &mut self.project {
                     ui.with_layout(egui::Layout::right_to_left(egui::Align::Center), |ui| {
-                        ui.add(
-                            egui::Slider::new(&mut pool.mask_size, 100..=2000)
-                                .text("Virtual Mask size"),
-                        );
+                        // Right to left, so the label goes last
+                        mask_size_selector(ui, "mask_size", &mut pool.mask_size);
+                        ui.label("Virtual mask size:");
+                        let mut vt_version = vt_version_number(pool.get_vt_version());
+                        egui::ComboBox::from_id_salt("vt_version")
+                            .selected_text(format!("VT version {}", vt_version))
+                            .show_ui(ui, |ui| {
+                                for number in BASE_VT_VERSION..=LATEST_VT_VERSION {
+                                    ui.selectable_value(
+                                        &mut vt_version,
+                                        number,
+                                        format!("VT version {}", number),
+                                    );
+                                }
+                            })
+                            .response
+                            .on_hover_text(
+                                "VT version the pool is made for, objects and attributes of later \
+                                versions are disabled in the editors",
+                            );
+                        pool.set_vt_version(vt_version);
+                        let languages = pool.get_language_codes();
+                        if !languages.is_empty() {
+                            let mut preview_language = pool.get_preview_language();
+                            egui::ComboBox::from_id_salt("preview_language")
+                                .selected_text(
+                                    preview_language.as_deref().unwrap_or("Pool text"),
+                                )
+                                .show_ui(ui, |ui| {
+                                    ui.selectable_value(&mut preview_language, None, "Pool text");
+                                    for language in languages {
+                                        ui.selectable_value(
+                                            &mut preview_language,
+                                            Some(language.clone()),
+                                            language,
+                                        );
+                                    }
+                                })
+                                .response
+                                .on_hover_text(
+                                    "Preview the masks with the translated texts of a language",
+                                );
+                            if preview_language != pool.get_preview_language() {
+                                pool.set_preview_language(preview_language);
+                            }
+                        }
+                        if ui
+                            .checkbox(
+                                &mut self.settings.canvas.localized_numbers,
+                                "Localized numbers",
+                            )
+                            .on_hover_text(
+                                "Preview numbers with the decimal separator of the working set language",
+                            )
+                            .changed()
+                        {
+                            self.settings.save();
+                        }
                     });
                 }
             });
         });
 
+        let mut panel_widths = (None, None);
         if let Some(pool) = &mut self.project {
+            // Copy the selected object to the system clipboard or paste objects from it, unless a
+            // text field handles the clipboard
+            if !ctx.wants_keyboard_input() {
+                let clipboard_events: Vec<egui::Event> = ctx.input(|i| {
+                    i.events
+                        .iter()
+                        .filter(|event| matches!(event, egui::Event::Copy | egui::Event::Paste(_)))
+                        .cloned()
+                        .collect()
+                });
+                for event in clipboard_events {
+                    match event {
+                        egui::Event::Copy => {
+                            if let Some(id) = pool.get_selected().into() {
+                                ctx.copy_text(pool.copy_objects(&[id]));
+                            }
+                        }
+                        egui::Event::Paste(text) => match pool.paste_objects(&text) {
+                            Ok(ids) => {
+                                if let Some(id) = ids.first() {
+                                    pool.get_mut_selected().replace(NullableObjectId(Some(*id)));
+                                }
+                            }
+                            Err(e) => log::error!("Failed to paste objects: {}", e),
+                        },
+                        _ => {}
+                    }
+                }
+            }
+
             // Set forward and backward navigation shortcuts to mouse buttons
             if ctx.input(|i| i.pointer.button_released(egui::PointerButton::Extra1)) {
                 pool.set_previous_selected();
@@ -728,185 +5764,536 @@ impl eframe::App for DesignerApp {
                 pool.set_next_selected();
             }
 
-            // Object selector panel
-            egui::SidePanel::left("left_panel").show(ctx, |ui| {
-                egui::ScrollArea::vertical().show(ui, |ui| {
-                    ui.style_mut().wrap_mode = Some(egui::TextWrapMode::Truncate);
-                    if let Some(working_set) = pool.get_pool().working_set_object() {
-                        render_object_hierarchy(
-                            ui,
-                            egui::Id::new(OBJECT_HIERARCHY_ID),
-                            &Object::WorkingSet(working_set.clone()),
-                            pool,
-                        );
-                    } else {
-                        ui.colored_label(
-                            egui::Color32::RED,
-                            "No working set, please add a new working set...",
-                        );
-                    }
-                    let auxiliary_objects = pool.get_pool().objects_by_types(&[
-                        ObjectType::AuxiliaryFunctionType1,
-                        ObjectType::AuxiliaryInputType1,
-                        ObjectType::AuxiliaryFunctionType2,
-                        ObjectType::AuxiliaryInputType2,
-                    ]);
-                    if !auxiliary_objects.is_empty() {
-                        ui.separator();
-                        for object in auxiliary_objects {
-                            render_selectable_object(ui, object, pool);
-                        }
-                    }
-                    ui.separator();
-
-                    // Filter objects in the pool by name
-                    let filter_id = ui.id().with("filter_text");
-                    let mut filter_text = ui
-                        .data(|data| data.get_temp::<String>(filter_id))
-                        .unwrap_or_default();
+            // Rename the selected object, picked up by the first widget that shows it
+            if pool.get_renaming_object().is_none()
+                && ctx.input_mut(|i| i.consume_shortcut(&RENAME_SHORTCUT))
+            {
+                pool.request_rename_selected();
+            }
+            if ctx.input_mut(|i| i.consume_shortcut(&GO_TO_SHORTCUT)) {
+                self.go_to = Some(String::new());
+            }
 
-                    ui.horizontal(|ui| {
-                        ui.with_layout(egui::Layout::right_to_left(egui::Align::Center), |ui| {
-                            ui.add_space(ui.spacing().scroll.bar_width);
-                            ui.menu_button("\u{2195}", |ui| {
-                                if ui.button("Sort by name").clicked() {
-                                    let pool_copy = pool.clone();
-                                    pool.sort_objects_by(|a, b| {
-                                        pool_copy
-                                            .get_object_info(a)
-                                            .get_name(a)
-                                            .cmp(&pool_copy.get_object_info(b).get_name(b))
-                                    });
-                                    ui.close();
-                                }
-                                if ui.button("Sort by id").clicked() {
-                                    pool.sort_objects_by(|a, b| {
-                                        u16::from(a.id()).cmp(&u16::from(b.id()))
-                                    });
-                                    ui.close();
+            // Object selector panel
+            let section_start = Instant::now();
+            let mut left_panel = egui::SidePanel::left("left_panel");
+            if let Some(width) = self.settings.session.left_panel_width {
+                left_panel = left_panel.default_width(width);
+            }
+            let left_panel_response = left_panel.show(ctx, |ui| {
+                ui.style_mut().wrap_mode = Some(egui::TextWrapMode::Truncate);
+                let hierarchy_height = ui.available_height() / 2.0;
+                egui::TopBottomPanel::top("hierarchy_panel")
+                    .resizable(true)
+                    .default_height(hierarchy_height)
+                    .show_inside(ui, |ui| {
+                        egui::ScrollArea::vertical().show(ui, |ui| {
+                            if let Some(working_set) = pool.get_pool().working_set_object() {
+                                render_object_hierarchy(
+                                    ui,
+                                    egui::Id::new(OBJECT_HIERARCHY_ID),
+                                    &Object::WorkingSet(working_set.clone()),
+                                    pool,
+                                    None,
+                                    0,
+                                    &[],
+                                );
+                            } else {
+                                ui.colored_label(
+                                    egui::Color32::RED,
+                                    "No working set, please add a new working set...",
+                                );
+                            }
+                            let auxiliary_objects = pool.get_pool().objects_by_types(&[
+                                ObjectType::AuxiliaryFunctionType1,
+                                ObjectType::AuxiliaryInputType1,
+                                ObjectType::AuxiliaryFunctionType2,
+                                ObjectType::AuxiliaryInputType2,
+                            ]);
+                            if !auxiliary_objects.is_empty() {
+                                ui.separator();
+                                for object in auxiliary_objects {
+                                    render_selectable_object(ui, object, pool, None);
                                 }
-                            })
-                            .response
-                            .on_hover_text("Sort objects");
+                            }
+                            ui.allocate_space(ui.available_size());
+                        });
+                    });
 
-                            let filter_shortcut =
-                                egui::KeyboardShortcut::new(egui::Modifiers::CTRL, egui::Key::F);
+                // Filter objects in the pool by name
+                let filter_id = ui.id().with("filter_text");
+                let mut filter_text = ui
+                    .data(|data| data.get_temp::<String>(filter_id))
+                    .unwrap_or_default();
 
-                            let response = ui
-                                .add(
-                                    egui::TextEdit::singleline(&mut filter_text)
-                                        .hint_text("Filter object by name...")
-                                        .desired_width(ui.available_width()),
-                                )
-                                .on_hover_text(format!(
-                                    "Search shortcut ({})",
-                                    ctx.format_shortcut(&filter_shortcut)
-                                ));
-                            if response.changed() {
-                                ui.data_mut(|data| {
-                                    data.insert_temp(filter_id, filter_text.clone())
+                ui.horizontal(|ui| {
+                    ui.with_layout(egui::Layout::right_to_left(egui::Align::Center), |ui| {
+                        ui.add_space(ui.spacing().scroll.bar_width);
+                        ui.menu_button("\u{2195}", |ui| {
+                            if ui.button("Sort by name").clicked() {
+                                let pool_copy = pool.clone();
+                                pool.sort_objects_by(|a, b| {
+                                    pool_copy
+                                        .get_object_info(a)
+                                        .get_name(a)
+                                        .cmp(&pool_copy.get_object_info(b).get_name(b))
                                 });
-                            } else if ctx.input_mut(|i| i.consume_shortcut(&filter_shortcut)) {
-                                response.request_focus();
+                                ui.close();
                             }
-                        });
+                            if ui.button("Sort by id").clicked() {
+                                pool.sort_objects_by(|a, b| {
+                                    u16::from(a.id()).cmp(&u16::from(b.id()))
+                                });
+                                ui.close();
+                            }
+                        })
+                        .response
+                        .on_hover_text("Sort objects");
+
+                        let response = ui
+                            .add(
+                                egui::TextEdit::singleline(&mut filter_text)
+                                    .hint_text("Filter object by name...")
+                                    .desired_width(ui.available_width()),
+                            )
+                            .on_hover_text(format!(
+                                "Search shortcut ({}). Use tag:name to only list objects \
+                                    with a tag, -tag:name to leave them out.",
+                                ctx.format_shortcut(&FILTER_SHORTCUT)
+                            ));
+                        if response.changed() {
+                            ui.data_mut(|data| data.insert_temp(filter_id, filter_text.clone()));
+                        } else if ctx.input_mut(|i| i.consume_shortcut(&FILTER_SHORTCUT)) {
+                            response.request_focus();
+                        }
                     });
+                });
 
-                    let filter_text = filter_text.to_lowercase();
-                    for object in pool.get_pool().objects() {
-                        if filter_text.is_empty()
-                            || pool
-                                .get_object_info(object)
-                                .get_name(object)
-                                .to_lowercase()
-                                .contains(&filter_text)
-                        {
-                            render_selectable_object(ui, object, pool);
+                // Filter objects by category, combined with the name filter
+                let categories_id = ui.id().with("filter_categories");
+                let mut categories = ui
+                    .data(|data| data.get_temp::<HashSet<ObjectCategory>>(categories_id))
+                    .unwrap_or_default();
+                let categories_before = categories.clone();
+                ui.horizontal_wrapped(|ui| {
+                    for category in ObjectCategory::ALL {
+                        let mut active = categories.contains(&category);
+                        if ui.toggle_value(&mut active, category.name()).changed() {
+                            if active {
+                                categories.insert(category);
+                            } else {
+                                categories.remove(&category);
+                            }
                         }
                     }
-
-                    ui.allocate_space(ui.available_size());
+                    if !categories.is_empty()
+                        && ui
+                            .small_button("\u{2716}")
+                            .on_hover_text("Show all types")
+                            .clicked()
+                    {
+                        categories.clear();
+                    }
                 });
-            });
+                if categories != categories_before {
+                    ui.data_mut(|data| data.insert_temp(categories_id, categories.clone()));
+                }
 
-            // Main panel
-            egui::CentralPanel::default().show(ctx, |ui| {
-                if pool
-                    .get_pool()
-                    .objects_by_type(ObjectType::DataMask)
-                    .is_empty()
-                {
-                    ui.colored_label(
-                        egui::Color32::RED,
-                        "Missing data masks, please load a pool file or add a new mask...",
+                let objects: Vec<&Object> = pool
+                    .find_objects_by_name(&filter_text)
+                    .into_iter()
+                    .filter(|object| {
+                        categories.is_empty()
+                            || categories
+                                .iter()
+                                .any(|category| category.contains(object.object_type()))
+                    })
+                    .collect();
+                // Only the visible rows are rendered, large pools have thousands of objects
+                egui::ScrollArea::vertical()
+                    .id_salt("object_list")
+                    .auto_shrink(false)
+                    .show_rows(
+                        ui,
+                        ui.spacing().interact_size.y,
+                        objects.len(),
+                        |ui, rows| {
+                            for object in &objects[rows] {
+                                render_selectable_object(ui, object, pool, None);
+                            }
+                        },
                     );
+            });
+            self.profiler
+                .record(ProfileSection::Hierarchy, section_start);
+            panel_widths.0 = Some(left_panel_response.response.rect.width());
+
+            // Preview numbers with the decimal separator of the previewed or working set language
+            let decimal_separator = pool
+                .get_preview_language()
+                .or_else(|| pool.get_language_codes().into_iter().next())
+                .filter(|_| self.settings.canvas.localized_numbers)
+                .map_or('.', |code| {
+                    ag_iso_terminal_designer::decimal_separator_for_language(&code)
+                });
+            ag_iso_terminal_designer::set_decimal_separator_preview(ctx, decimal_separator);
+            let animations = self.settings.canvas.animations && !low_power;
+            ag_iso_terminal_designer::set_animations_enabled(ctx, animations);
+            pool.set_id_settings(&self.settings.ids);
+            pool.set_naming_rules(&self.settings.naming.rules);
+            ag_iso_terminal_designer::set_blinking_objects_preview(
+                ctx,
+                pool.get_blinking_objects(),
+            );
+            ag_iso_terminal_designer::set_graphics_commands_preview(
+                ctx,
+                pool.get_graphics_previews(),
+            );
+
+            // Simulation log and auxiliary control
+            if let Some(simulation) = &mut self.simulation {
+                let transition = if animations {
+                    self.settings.simulation.mask_transition
                 } else {
-                    match pool.get_pool().working_set_object() {
-                        Some(mask) => match pool.get_pool().object_by_id(mask.active_mask) {
-                            Some(obj) => {
-                                let selected_ref = pool.get_mut_selected();
-
-                                egui::ScrollArea::both().show(ui, |ui| {
-                                    ui.add_sized(
-                                        [pool.mask_size as f32, pool.mask_size as f32],
-                                        InteractiveMaskRenderer {
-                                            object: obj,
-                                            pool: pool.get_pool(),
-                                            selected_callback: Box::new(move |object_id| {
-                                                *selected_ref.borrow_mut() =
-                                                    NullableObjectId(Some(object_id));
-                                            }),
-                                        },
+                    MaskTransition::None
+                };
+                simulation.set_mask_transition(
+                    transition,
+                    std::time::Duration::from_millis(
+                        self.settings.simulation.mask_transition_duration_ms as u64,
+                    ),
+                );
+                if simulation.update(ctx.input(|i| i.stable_dt)) {
+                    if low_power {
+                        ctx.request_repaint_after(LOW_POWER_REPAINT_INTERVAL);
+                    } else {
+                        ctx.request_repaint();
+                    }
+                }
+                egui::TopBottomPanel::bottom("simulation_panel")
+                    .resizable(true)
+                    .show(ctx, |ui| {
+                        ui.columns(3, |columns| {
+                            columns[0].horizontal(|ui| {
+                                ui.strong("Simulation log");
+                                if let Some(timing) = simulation.mask_timing() {
+                                    ui.weak(timing).on_hover_text(
+                                        "Last change of the active mask and how long the \
+                                        previous mask was shown",
                                     );
+                                }
+                                ui.with_layout(
+                                    egui::Layout::right_to_left(egui::Align::Center),
+                                    |ui| {
+                                        if ui
+                                            .small_button("Next input")
+                                            .on_hover_text("Move the focus to the next input")
+                                            .clicked()
+                                        {
+                                            simulation.move_focus(true);
+                                        }
+                                        if ui
+                                            .small_button("Previous input")
+                                            .on_hover_text("Move the focus to the previous input")
+                                            .clicked()
+                                        {
+                                            simulation.move_focus(false);
+                                        }
+                                    },
+                                );
+                            });
+                            egui::ScrollArea::vertical()
+                                .id_salt("simulation_log")
+                                .stick_to_bottom(true)
+                                .show(&mut columns[0], |ui| {
+                                    for line in simulation.get_log() {
+                                        ui.label(line);
+                                    }
+                                    ui.allocate_space(egui::vec2(ui.available_width(), 0.0));
                                 });
-                            }
+
+                            columns[1].strong("Auxiliary control");
+                            egui::ScrollArea::vertical().id_salt("simulation_aux").show(
+                                &mut columns[1],
+                                |ui| {
+                                    simulation.render_aux_control(ui, pool);
+                                },
+                            );
+
+                            columns[2].strong("Demo script").on_hover_text(
+                                "Timed steps replayed in the simulation, e.g. for a demo loop",
+                            );
+                            egui::ScrollArea::vertical()
+                                .id_salt("simulation_demo")
+                                .show(&mut columns[2], |ui| {
+                                    simulation.render_demo_control(ui, pool);
+                                });
+                        });
+                    });
+            }
+
+            // Main panel
+            let section_start = Instant::now();
+            let preview_pool = pool.get_preview_pool();
+            let canvas_theme = self
+                .settings
+                .canvas
+                .canvas_theme(ctx.style().visuals.dark_mode);
+            egui::CentralPanel::default()
+                .frame(
+                    egui::Frame::central_panel(&ctx.style()).fill(canvas_theme.surround_colour()),
+                )
+                .show(ctx, |ui| {
+                    let selected_window_mask = pool
+                        .get_selected()
+                        .0
+                        .and_then(|id| pool.get_pool().object_by_id(id))
+                        .and_then(|obj| match obj {
+                            Object::WindowMask(mask) => Some(mask),
+                            _ => None,
+                        });
+
+                    if let Some(simulation) = &mut self.simulation {
+                        render_simulation(
+                            ui,
+                            simulation,
+                            pool.mask_size,
+                            canvas_theme,
+                            (
+                                self.settings.terminal.focus_style,
+                                self.settings.terminal.focus_colour(),
+                            ),
+                        );
+                    } else if let Some(window_mask) = selected_window_mask {
+                        render_window_mask_preview(
+                            ui,
+                            window_mask,
+                            &preview_pool,
+                            pool.mask_size,
+                            canvas_theme,
+                        );
+                    } else if pool
+                        .get_pool()
+                        .objects_by_type(ObjectType::DataMask)
+                        .is_empty()
+                    {
+                        ui.colored_label(
+                            egui::Color32::RED,
+                            "Missing data masks, please load a pool file or add a new mask...",
+                        );
+                    } else {
+                        match pool.get_pool().working_set_object() {
+                            Some(mask) => match preview_pool.object_by_id(mask.active_mask) {
+                                Some(obj) => {
+                                    // A macro command moving a child is built while the macro
+                                    // is selected
+                                    let child_move_pick =
+                                        pool.get_child_move_pick().filter(|pick| {
+                                            pool.get_selected().0 == Some(pick.macro_id)
+                                        });
+                                    if child_move_pick.is_none() {
+                                        pool.set_child_move_pick(None);
+                                    }
+
+                                    egui::ScrollArea::both().show(ui, |ui| {
+                                        let mut clicked = None;
+                                        let response = ui.add_sized(
+                                            [pool.mask_size as f32, pool.mask_size as f32],
+                                            InteractiveMaskRenderer {
+                                                object: obj,
+                                                pool: &preview_pool,
+                                                selected: pool.get_selected().0,
+                                                focused: None,
+                                                theme: canvas_theme,
+                                                selected_callback: Box::new(|object_id| {
+                                                    clicked = Some(object_id)
+                                                }),
+                                            },
+                                        );
+                                        match (clicked, child_move_pick) {
+                                            (Some(object_id), Some(mut pick)) => {
+                                                pick.target = find_child_move_target(
+                                                    &preview_pool,
+                                                    obj,
+                                                    object_id,
+                                                );
+                                                pool.set_child_move_pick(Some(pick));
+                                            }
+                                            (Some(object_id), None) => {
+                                                pool.get_mut_selected()
+                                                    .replace(NullableObjectId(Some(object_id)));
+                                            }
+                                            (None, Some(pick)) => render_child_move_target(
+                                                ui,
+                                                pool,
+                                                &preview_pool,
+                                                response.rect,
+                                                pick,
+                                                canvas_theme,
+                                            ),
+                                            (None, None) => {}
+                                        }
+                                    });
+                                }
+                                None => {
+                                    ui.colored_label(
+                                        egui::Color32::RED,
+                                        format!("Missing data mask: {:?}", mask),
+                                    );
+                                }
+                            },
                             None => {
                                 ui.colored_label(
                                     egui::Color32::RED,
-                                    format!("Missing data mask: {:?}", mask),
+                                    "No working sets, please add a new working set...",
                                 );
                             }
-                        },
-                        None => {
-                            ui.colored_label(
-                                egui::Color32::RED,
-                                "No working sets, please add a new working set...",
-                            );
                         }
                     }
-                }
-            });
+                });
+            self.profiler.record(ProfileSection::Canvas, section_start);
 
             // Parameters panel
-            egui::SidePanel::right("right_panel").show(ctx, |ui: &mut egui::Ui| {
+            let section_start = Instant::now();
+            let mut right_panel = egui::SidePanel::right("right_panel");
+            if let Some(width) = self.settings.session.right_panel_width {
+                right_panel = right_panel.default_width(width);
+            }
+            let right_panel_response = right_panel.show(ctx, |ui: &mut egui::Ui| {
                 if let Some(id) = pool.get_selected().into() {
-                    if let Some(obj) = pool.get_mut_pool().borrow_mut().object_mut_by_id(id) {
+                    if let Some(mut edited) = pool.get_editable_object(id) {
+                        let obj = &mut edited;
                         egui::ScrollArea::vertical().show(ui, |ui| {
                             // Display editable object name as header
                             ui.horizontal(|ui| {
                                 ui.label("Name:");
 
-                                let object_info = pool.get_object_info(obj);
-                                let mut name = object_info.get_name(obj);
-                                let response = ui.text_edit_singleline(&mut name);
+                                let header_id = ui.id().with("object_name_header");
+                                let renaming_object = pool.get_renaming_object();
+                                if let Some((_, _, name)) = renaming_object
+                                    .filter(|(ui_id, id, _)| *id == obj.id() && *ui_id == header_id)
+                                {
+                                    render_rename_text_edit(ui, pool, header_id, obj.id(), name);
+                                } else {
+                                    let object_info = pool.get_object_info(obj);
+                                    let mut name = object_info.get_name(obj);
+                                    let response = ui.add(
+                                        egui::TextEdit::singleline(&mut name)
+                                            .id(header_id.with("rename")),
+                                    );
 
-                                if response.changed() {
-                                    let mut object_info_map = pool.object_info.borrow_mut();
-                                    if let Some(info) = object_info_map.get_mut(&obj.id()) {
-                                        info.set_name(name);
+                                    // Start renaming as soon as the field is focused, either by
+                                    // the user or by a rename request no other widget picked up
+                                    if response.gained_focus()
+                                        || response.changed()
+                                        || pool.take_rename_selected_request()
+                                    {
+                                        pool.set_renaming_object(header_id, obj.id(), name);
                                     }
                                 }
                             });
+                            ui.horizontal(|ui| {
+                                ui.label("Export alias:");
+                                let mut alias = pool.get_export_alias(id).unwrap_or_default();
+                                let name = pool.get_object_info(obj).get_name(obj);
+                                if ui
+                                    .add(
+                                        egui::TextEdit::singleline(&mut alias)
+                                            .hint_text(Self::to_c_identifier(&name)),
+                                    )
+                                    .on_hover_text(
+                                        "Identifier used instead of the name in the exported \
+                                        header and Rust module",
+                                    )
+                                    .changed()
+                                {
+                                    pool.set_export_alias(id, Some(alias));
+                                }
+                            });
+                            egui::CollapsingHeader::new("Notes")
+                                .id_salt("object_notes")
+                                .default_open(!pool.get_notes(id).is_empty())
+                                .show(ui, |ui| {
+                                    let mut notes = pool.get_notes(id);
+                                    if ui
+                                        .add(
+                                            egui::TextEdit::multiline(&mut notes)
+                                                .desired_rows(3)
+                                                .desired_width(f32::INFINITY)
+                                                .hint_text(
+                                                    "Description, purpose, open questions...",
+                                                ),
+                                        )
+                                        .changed()
+                                    {
+                                        pool.set_notes(id, notes);
+                                    }
+                                });
+                            render_tags(ui, pool, id);
+                            let mut restricted = pool.is_restricted(id);
+                            if ui
+                                .checkbox(&mut restricted, "Restricted")
+                                .on_hover_text(
+                                    "Require unlocking before this object and the objects it \
+                                    shows can be edited, e.g. for certified screens",
+                                )
+                                .changed()
+                            {
+                                pool.set_restricted(id, restricted);
+                            }
+                            let mut blinking = pool.is_blinking(id);
+                            if ui
+                                .checkbox(&mut blinking, "Blink in preview")
+                                .on_hover_text(
+                                    "Show the object blinking, like the ECU hiding and showing it \
+                                    periodically to grab attention",
+                                )
+                                .changed()
+                            {
+                                pool.set_blinking(id, blinking);
+                            }
+                            let restricting = pool.get_restricting_object(id);
+                            if let Some(restricting) = restricting {
+                                ui.colored_label(
+                                    egui::Color32::ORANGE,
+                                    format!(
+                                        "\u{26A0} Restricted by object {}, changes may affect \
+                                        certified screens",
+                                        restricting.value()
+                                    ),
+                                );
+                            }
+                            if let Some(unlock_id) = restricting
+                                .or_else(|| (restricted && pool.is_unlocked(id)).then_some(id))
+                            {
+                                let mut unlocked = pool.is_unlocked(unlock_id);
+                                if ui.toggle_value(&mut unlocked, "Unlock editing").changed() {
+                                    pool.set_unlocked(unlock_id, unlocked);
+                                }
+                            }
                             ui.separator();
 
-                            obj.render_parameters(ui, pool);
+                            ui.add_enabled_ui(restricting.is_none(), |ui| {
+                                obj.render_parameters(ui, pool);
+                            });
                             let (width, height) = pool.get_pool().content_size(obj);
                             ui.separator();
                             let desired_size = egui::Vec2::new(width as f32, height as f32);
                             ui.allocate_ui(desired_size, |ui| {
                                 obj.render(ui, pool.get_pool(), Point::default());
                             });
+                            ui.separator();
+                            render_usages(ui, pool, id);
                         });
+
+                        // Store the edits made in the parameters
+                        if pool.get_editable_object(id).as_ref() != Some(&edited) {
+                            if let Err(e) =
+                                pool.execute(PoolCommand::SetObject { id, object: edited })
+                            {
+                                log::error!("Failed to edit object: {}", e);
+                            }
+                        }
                     } else {
                         ui.colored_label(
                             egui::Color32::RED,
@@ -916,6 +6303,53 @@ impl eframe::App for DesignerApp {
                 }
                 ui.allocate_space(ui.available_size());
             });
+            self.profiler
+                .record(ProfileSection::Properties, section_start);
+            panel_widths.1 = Some(right_panel_response.response.rect.width());
+
+            // Pool analysis, runs in the background and is picked up when finished
+            if self.show_analysis {
+                let section_start = Instant::now();
+                self.analyzer
+                    .analyse(pool.snapshot(), pool.get_pool_generation(), ctx);
+                if self.analyzer.poll() {
+                    ctx.request_repaint();
+                }
+                render_analysis_window(ctx, &mut self.show_analysis, &self.analyzer, pool);
+                self.profiler
+                    .record(ProfileSection::Validation, section_start);
+            }
+
+            if self.show_history {
+                render_history_window(ctx, &mut self.show_history, pool);
+            }
+
+            if let Some(view) = &mut self.mask_usage {
+                let mut open = true;
+                render_mask_usage_window(ctx, &mut open, pool, view);
+                if !open {
+                    self.mask_usage = None;
+                }
+            }
+
+            if let Some(drift_check) = &mut self.drift_check {
+                if !render_drift_check(ctx, pool, drift_check) {
+                    self.drift_check = None;
+                }
+            }
+
+            if let Some((name, compared)) = &mut self.snapshot_manager {
+                if !render_snapshot_manager(ctx, pool, name, compared) {
+                    self.snapshot_manager = None;
+                }
+            }
+
+            if let Some(simulation) = &mut self.simulation {
+                simulation.show_input_dialog(ctx);
+            }
+
+            // Drop rename requests when no object is selected
+            pool.take_rename_selected_request();
 
             if pool.update_pool() {
                 ctx.request_repaint();
@@ -934,9 +6368,45 @@ impl eframe::App for DesignerApp {
                 ctx.request_repaint();
             }
         } else {
+            let mut starter = None;
             egui::CentralPanel::default().show(ctx, |ui| {
                 ui.label("No object pool loaded, please load a pool file...");
+                ui.add_space(10.0);
+                ui.strong("Or start a new project from a template:");
+                egui::Grid::new("starter_templates")
+                    .num_columns(2)
+                    .show(ui, |ui| {
+                        for template in &STARTER_TEMPLATES {
+                            if ui.button(template.name).clicked() {
+                                starter = Some(template);
+                            }
+                            ui.weak(template.description);
+                            ui.end_row();
+                        }
+                    });
             });
+            if let Some(template) = starter {
+                self.open_project(template.create_project(), None);
+            }
+        }
+
+        if let Some(project) = &self.project {
+            if self.settings.autosave.enabled {
+                // Settings may have changed since the last frame
+                self.autosaver.configure(&self.settings.autosave);
+                if let Some(remaining) =
+                    self.autosaver.update(project, self.project_path.as_deref())
+                {
+                    ctx.request_repaint_after(remaining);
+                }
+            }
+        }
+
+        self.record_session(ctx, panel_widths);
+
+        self.profiler.end_frame();
+        if self.settings.general.show_performance_hud {
+            self.profiler.show_overlay(ctx);
         }
     }
 }
@@ -1012,18 +6482,49 @@ fn main() {
     });
 }
 
-/// Find the closest color index in the palette for a given RGB value
-fn find_closest_color_index(r: u8, g: u8, b: u8) -> u8 {
-    fn quantize_channel(c: u8) -> u8 {
-        // ((c + 25) / 51) in integer math, capped to 0..5
-        let v = (c as u16 + 25) / 51;
-        v.min(5) as u8
+/// Path of a picked file, only known on native targets
+#[cfg(not(target_arch = "wasm32"))]
+fn file_path(file: &rfd::FileHandle) -> Option<String> {
+    Some(file.path().display().to_string())
+}
+
+#[cfg(target_arch = "wasm32")]
+fn file_path(_file: &rfd::FileHandle) -> Option<String> {
+    None
+}
+
+/// Save multiple files at once, the user picks the target folder
+#[cfg(not(target_arch = "wasm32"))]
+fn save_files(files: Vec<(String, Vec<u8>)>) {
+    if files.is_empty() {
+        return;
     }
-    let rq = quantize_channel(r);
-    let gq = quantize_channel(g);
-    let bq = quantize_channel(b);
+    let task = rfd::AsyncFileDialog::new().pick_folder();
+    execute(async move {
+        if let Some(folder) = task.await {
+            for (name, contents) in files {
+                if let Err(e) = std::fs::write(folder.path().join(&name), contents) {
+                    log::error!("Failed to write {}: {}", name, e);
+                }
+            }
+        }
+    });
+}
 
-    16 + 36 * rq + 6 * gq + bq
+/// Save multiple files at once, the browser can't pick a folder so every file is downloaded separately
+#[cfg(target_arch = "wasm32")]
+fn save_files(files: Vec<(String, Vec<u8>)>) {
+    execute(async move {
+        for (name, contents) in files {
+            let file = rfd::AsyncFileDialog::new()
+                .set_file_name(&name)
+                .save_file()
+                .await;
+            if let Some(file) = file {
+                _ = file.write(&contents).await;
+            }
+        }
+    });
 }
 
 #[cfg(not(target_arch = "wasm32"))]
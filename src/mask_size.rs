@@ -0,0 +1,96 @@
+//! Copyright 2024 - The Open-Agriculture Developers
+//! SPDX-License-Identifier: GPL-3.0-or-later
+//! Authors: Daan Steenbergen
+
+use crate::AnalysisIssue;
+use ag_iso_stack::object_pool::object::Object;
+use ag_iso_stack::object_pool::{ObjectPool, ObjectType};
+use eframe::egui;
+
+/// Data mask sizes of common terminals in pixels, from the small 200 px masks to 800 px
+pub const MASK_SIZE_PRESETS: [u16; 5] = [200, 240, 320, 480, 800];
+
+/// Smallest and largest custom mask size
+pub const MIN_MASK_SIZE: u16 = 100;
+pub const MAX_MASK_SIZE: u16 = 2000;
+
+/// Name of a mask size, a preset or a custom size
+pub fn mask_size_name(mask_size: u16) -> String {
+    if MASK_SIZE_PRESETS.contains(&mask_size) {
+        format!("{} px", mask_size)
+    } else {
+        format!("Custom ({} px)", mask_size)
+    }
+}
+
+/// Find the objects placed outside of the data and alarm masks at the given mask size, and
+/// check the mask is large enough for the pool
+pub fn check_mask_size(pool: &ObjectPool, mask_size: u16) -> Vec<AnalysisIssue> {
+    let mut issues = Vec::new();
+
+    let (minimum, _) = pool.get_minimum_mask_sizes();
+    if minimum > mask_size {
+        issues.push(AnalysisIssue {
+            object: None,
+            message: format!(
+                "The pool needs a data mask of at least {} px, larger than {} px",
+                minimum, mask_size
+            ),
+        });
+    }
+
+    for mask in pool.objects_by_types(&[ObjectType::DataMask, ObjectType::AlarmMask]) {
+        let refs = match mask {
+            Object::DataMask(o) => &o.object_refs,
+            Object::AlarmMask(o) => &o.object_refs,
+            _ => continue,
+        };
+        for object_ref in refs {
+            let Some(child) = pool.object_by_id(object_ref.id) else {
+                continue;
+            };
+            let (width, height) = pool.content_size(child);
+            let right = object_ref.offset.x as i32 + width as i32;
+            let bottom = object_ref.offset.y as i32 + height as i32;
+            if object_ref.offset.x < 0
+                || object_ref.offset.y < 0
+                || right > mask_size as i32
+                || bottom > mask_size as i32
+            {
+                issues.push(AnalysisIssue {
+                    object: Some(child.id()),
+                    message: format!(
+                        "Placed outside of the {} px mask {}, at ({}, {}) to ({}, {})",
+                        mask_size,
+                        mask.id().value(),
+                        object_ref.offset.x,
+                        object_ref.offset.y,
+                        right,
+                        bottom
+                    ),
+                });
+            }
+        }
+    }
+    issues
+}
+
+/// Select a mask size from the presets, or enter a custom size.
+/// Returns true if the size changed.
+pub fn mask_size_selector(ui: &mut egui::Ui, id_salt: &str, mask_size: &mut u16) -> bool {
+    let before = *mask_size;
+    egui::ComboBox::from_id_salt(id_salt)
+        .selected_text(mask_size_name(*mask_size))
+        .show_ui(ui, |ui| {
+            for preset in MASK_SIZE_PRESETS {
+                ui.selectable_value(mask_size, preset, mask_size_name(preset));
+            }
+        });
+    ui.add(
+        egui::DragValue::new(mask_size)
+            .range(MIN_MASK_SIZE..=MAX_MASK_SIZE)
+            .suffix(" px"),
+    )
+    .on_hover_text("Custom mask size");
+    *mask_size != before
+}
@@ -0,0 +1,215 @@
+//! Copyright 2024 - The Open-Agriculture Developers
+//! SPDX-License-Identifier: GPL-3.0-or-later
+//! Authors: Daan Steenbergen
+
+use ag_iso_stack::object_pool::object::Object;
+use ag_iso_stack::object_pool::{ObjectId, ObjectPool, ObjectType};
+
+/// What an object shows to the operator, to judge the information density of a mask
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum UsageCategory {
+    Text,
+    Graphics,
+    Input,
+}
+
+impl UsageCategory {
+    pub const ALL: [UsageCategory; 3] = [
+        UsageCategory::Text,
+        UsageCategory::Graphics,
+        UsageCategory::Input,
+    ];
+
+    /// The category of the objects of a type, `None` for objects that only group or configure
+    /// other objects
+    pub fn of(object_type: ObjectType) -> Option<Self> {
+        match object_type {
+            ObjectType::OutputString | ObjectType::OutputNumber | ObjectType::OutputList => {
+                Some(UsageCategory::Text)
+            }
+            ObjectType::OutputLine
+            | ObjectType::OutputRectangle
+            | ObjectType::OutputEllipse
+            | ObjectType::OutputPolygon
+            | ObjectType::OutputMeter
+            | ObjectType::OutputLinearBarGraph
+            | ObjectType::OutputArchedBarGraph
+            | ObjectType::PictureGraphic
+            | ObjectType::GraphicsContext
+            | ObjectType::ScaledGraphic
+            | ObjectType::Animation => Some(UsageCategory::Graphics),
+            ObjectType::InputBoolean
+            | ObjectType::InputString
+            | ObjectType::InputNumber
+            | ObjectType::InputList
+            | ObjectType::Button => Some(UsageCategory::Input),
+            _ => None,
+        }
+    }
+
+    fn bit(self) -> u8 {
+        match self {
+            UsageCategory::Text => 0x01,
+            UsageCategory::Graphics => 0x02,
+            UsageCategory::Input => 0x04,
+        }
+    }
+}
+
+/// Objects of a single type shown on a mask
+#[derive(Clone)]
+pub struct TypeUsage {
+    pub object_type: ObjectType,
+    pub count: usize,
+    /// Pixels covered by the objects, overlapping objects are counted for each of them
+    pub area: usize,
+}
+
+/// Area of an object on the mask, in VT pixels
+#[derive(Clone)]
+pub struct UsageRegion {
+    pub id: ObjectId,
+    pub category: UsageCategory,
+    pub x: i32,
+    pub y: i32,
+    pub width: u16,
+    pub height: u16,
+}
+
+/// How much of a mask is covered by text, graphics and inputs
+#[derive(Clone)]
+pub struct MaskUsage {
+    /// Pixels of the mask
+    pub mask_area: usize,
+    pub types: Vec<TypeUsage>,
+    /// Pixels covered by each category, overlapping objects of a category are counted once
+    pub categories: Vec<(UsageCategory, usize)>,
+    pub regions: Vec<UsageRegion>,
+}
+
+impl MaskUsage {
+    /// Part of the mask covered by the category, from 0 to 1
+    pub fn coverage(&self, category: UsageCategory) -> f32 {
+        let area = self
+            .categories
+            .iter()
+            .find(|(c, _)| *c == category)
+            .map_or(0, |(_, area)| *area);
+        area as f32 / self.mask_area.max(1) as f32
+    }
+}
+
+/// Collect the objects shown on the mask with their position, following containers and object
+/// pointers. Objects with a category aren't followed, their children are part of them.
+fn collect_regions(
+    pool: &ObjectPool,
+    object: &Object,
+    x: i32,
+    y: i32,
+    depth: usize,
+    regions: &mut Vec<(UsageRegion, ObjectType)>,
+) {
+    // Guard against circular references through object pointers
+    if depth > 32 {
+        return;
+    }
+    if let Some(category) = UsageCategory::of(object.object_type()) {
+        let (width, height) = pool.content_size(object);
+        regions.push((
+            UsageRegion {
+                id: object.id(),
+                category,
+                x,
+                y,
+                width,
+                height,
+            },
+            object.object_type(),
+        ));
+        return;
+    }
+    let refs = match object {
+        Object::DataMask(o) => &o.object_refs,
+        Object::AlarmMask(o) => &o.object_refs,
+        Object::Container(o) => &o.object_refs,
+        Object::ObjectPointer(o) => {
+            if let Some(child) = o.value.0.and_then(|id| pool.object_by_id(id)) {
+                collect_regions(pool, child, x, y, depth + 1, regions);
+            }
+            return;
+        }
+        _ => return,
+    };
+    for object_ref in refs {
+        if let Some(child) = pool.object_by_id(object_ref.id) {
+            collect_regions(
+                pool,
+                child,
+                x + object_ref.offset.x as i32,
+                y + object_ref.offset.y as i32,
+                depth + 1,
+                regions,
+            );
+        }
+    }
+}
+
+/// Measure the counts and covered area of the object types shown on a data or alarm mask of
+/// the given size
+pub fn analyse_mask_usage(pool: &ObjectPool, mask: &Object, mask_size: u16) -> MaskUsage {
+    let mut found = Vec::new();
+    collect_regions(pool, mask, 0, 0, 0, &mut found);
+
+    let size = mask_size as i32;
+    let clip = |region: &UsageRegion| {
+        let x0 = region.x.clamp(0, size);
+        let y0 = region.y.clamp(0, size);
+        let x1 = (region.x + region.width as i32).clamp(0, size);
+        let y1 = (region.y + region.height as i32).clamp(0, size);
+        (x0, y0, x1, y1)
+    };
+
+    // Mark the pixels covered by each category, so overlaps within a category count once
+    let mut coverage = vec![0u8; mask_size as usize * mask_size as usize];
+    let mut types: Vec<TypeUsage> = Vec::new();
+    for (region, object_type) in &found {
+        let (x0, y0, x1, y1) = clip(region);
+        for y in y0..y1 {
+            let row = y as usize * mask_size as usize;
+            for cell in &mut coverage[row + x0 as usize..row + x1 as usize] {
+                *cell |= region.category.bit();
+            }
+        }
+        let area = ((x1 - x0) * (y1 - y0)) as usize;
+        match types.iter_mut().find(|t| t.object_type == *object_type) {
+            Some(usage) => {
+                usage.count += 1;
+                usage.area += area;
+            }
+            None => types.push(TypeUsage {
+                object_type: *object_type,
+                count: 1,
+                area,
+            }),
+        }
+    }
+    types.sort_by(|a, b| b.area.cmp(&a.area));
+
+    let categories = UsageCategory::ALL
+        .iter()
+        .map(|category| {
+            let area = coverage
+                .iter()
+                .filter(|cell| **cell & category.bit() != 0)
+                .count();
+            (*category, area)
+        })
+        .collect();
+
+    MaskUsage {
+        mask_area: coverage.len(),
+        types,
+        categories,
+        regions: found.into_iter().map(|(region, _)| region).collect(),
+    }
+}
@@ -0,0 +1,51 @@
+//! Copyright 2024 - The Open-Agriculture Developers
+//! SPDX-License-Identifier: GPL-3.0-or-later
+//! Authors: Daan Steenbergen
+
+use regex::{NoExpand, Regex, RegexBuilder};
+
+/// Replacement of text in object names, by substring or regular expression
+pub struct NameReplace {
+    pattern: Regex,
+    replacement: String,
+    /// The replacement is inserted as is, instead of expanding `$1` style capture groups
+    literal: bool,
+}
+
+impl NameReplace {
+    /// Returns an error if the regular expression is invalid
+    pub fn new(
+        find: &str,
+        replacement: &str,
+        use_regex: bool,
+        case_sensitive: bool,
+    ) -> Result<Self, String> {
+        let pattern = if use_regex {
+            find.to_string()
+        } else {
+            regex::escape(find)
+        };
+        let pattern = RegexBuilder::new(&pattern)
+            .case_insensitive(!case_sensitive)
+            .build()
+            .map_err(|e| e.to_string())?;
+        Ok(NameReplace {
+            pattern,
+            replacement: replacement.to_string(),
+            literal: !use_regex,
+        })
+    }
+
+    /// The name with all matches replaced, `None` if nothing matches
+    pub fn apply(&self, name: &str) -> Option<String> {
+        if self.pattern.as_str().is_empty() || !self.pattern.is_match(name) {
+            return None;
+        }
+        let replaced = if self.literal {
+            self.pattern.replace_all(name, NoExpand(&self.replacement))
+        } else {
+            self.pattern.replace_all(name, self.replacement.as_str())
+        };
+        Some(replaced.into_owned())
+    }
+}
@@ -4,9 +4,20 @@
 
 use crate::allowed_object_relationships::get_allowed_child_refs;
 use crate::allowed_object_relationships::AllowedChildRefs;
+use crate::child_move::{ChildMoveCommand, ChildMovePick};
+use crate::colour_contrast::{
+    background_behind, contrast_ratio, suggest_contrasting_colours, MIN_CONTRAST_RATIO,
+};
+use crate::commands::PoolCommand;
+use crate::editor_project::get_active_colour_palette;
+use crate::graphics_commands;
+use crate::object_rendering::{paint_graphics_commands, window_mask_cell_size, window_mask_cells};
+use crate::picture_scaling::{self, ScaledVariantKind};
 use crate::possible_events::PossibleEvents;
+use crate::vt_compatibility::vt_version_number;
 use crate::EditorProject;
 
+use ag_iso_stack::network_management::name::NAME;
 use ag_iso_stack::object_pool::object::*;
 use ag_iso_stack::object_pool::object_attributes::*;
 use ag_iso_stack::object_pool::vt_version::VtVersion;
@@ -21,7 +32,7 @@ use std::collections::HashSet;
 
 /// Check if adding a reference from `parent_id` to `child_id` would create a circular reference
 /// Returns true if it would create a cycle (and should be blocked)
-fn would_create_circular_reference(
+pub(crate) fn would_create_circular_reference(
     pool: &ObjectPool,
     parent_id: ObjectId,
     child_id: ObjectId,
@@ -58,6 +69,280 @@ fn would_create_circular_reference(
     false
 }
 
+/// Object types for which a thumbnail is shown in the object pickers and the hierarchy
+const THUMBNAIL_OBJECT_TYPES: &[ObjectType] = &[
+    ObjectType::DataMask,
+    ObjectType::AlarmMask,
+    ObjectType::Container,
+    ObjectType::Key,
+    ObjectType::Button,
+    ObjectType::InputBoolean,
+    ObjectType::InputNumber,
+    ObjectType::OutputString,
+    ObjectType::OutputNumber,
+    ObjectType::OutputLine,
+    ObjectType::OutputRectangle,
+    ObjectType::PictureGraphic,
+    ObjectType::ObjectPointer,
+];
+
+/// Size of the thumbnails next to the candidates in the object pickers
+const PICKER_THUMBNAIL_SIZE: f32 = 24.0;
+
+/// Render a small thumbnail of a visual object in a square of `max_size` points, so it can be
+/// recognized without knowing its ID
+pub fn render_object_thumbnail(
+    ui: &mut egui::Ui,
+    design: &EditorProject,
+    object: &Object,
+    max_size: f32,
+) {
+    if !THUMBNAIL_OBJECT_TYPES.contains(&object.object_type()) {
+        return;
+    }
+
+    let (_, rect) = ui.allocate_space(egui::vec2(max_size, max_size));
+    if let Some(texture) = design.get_object_thumbnail(ui.ctx(), object) {
+        // Fit the thumbnail in the allocated square while keeping the aspect ratio
+        let size = texture.size_vec2();
+        let scale = (max_size / size.x).min(max_size / size.y);
+        let image_rect = egui::Rect::from_center_size(rect.center(), size * scale);
+        ui.painter().image(
+            texture.id(),
+            image_rect,
+            egui::Rect::from_min_max(egui::pos2(0.0, 0.0), egui::pos2(1.0, 1.0)),
+            egui::Color32::WHITE,
+        );
+    }
+}
+
+/// Render the candidates of an object picker, listing recently used and most referenced
+/// candidates at the top so the common choices don't require scrolling.
+/// The closure renders a single candidate and returns its response, clicks are remembered as picks.
+fn render_picker_candidates<'a>(
+    ui: &mut egui::Ui,
+    design: &'a EditorProject,
+    allowed_types: &[ObjectType],
+    mut add_candidate: impl FnMut(&mut egui::Ui, &'a Object) -> egui::Response,
+) {
+    let candidates = design.get_objects_by_types(allowed_types);
+
+    let featured = design.get_featured_candidates(&candidates);
+    if !featured.is_empty() {
+        ui.weak("Recent & most used");
+        for candidate in featured {
+            if add_candidate(ui, candidate).clicked() {
+                design.note_object_picked(candidate.id());
+            }
+        }
+        ui.separator();
+    }
+
+    for candidate in candidates {
+        if add_candidate(ui, candidate).clicked() {
+            design.note_object_picked(candidate.id());
+        }
+    }
+}
+
+/// Size in points of a single swatch in the colour selector
+const COLOUR_SWATCH_SIZE: f32 = 16.0;
+
+/// Paint a clickable swatch of the colour at `index` in the pool palette
+fn render_colour_swatch(
+    ui: &mut egui::Ui,
+    design: &EditorProject,
+    index: u8,
+    selected: bool,
+) -> egui::Response {
+    let (rect, response) = ui.allocate_exact_size(
+        egui::vec2(COLOUR_SWATCH_SIZE, COLOUR_SWATCH_SIZE),
+        egui::Sense::click(),
+    );
+    let colour = design.get_pool().color_by_index(index);
+    ui.painter().rect_filled(
+        rect,
+        2.0,
+        egui::Color32::from_rgb(colour.r, colour.g, colour.b),
+    );
+    let stroke = if selected {
+        egui::Stroke::new(2.0, ui.visuals().selection.stroke.color)
+    } else if response.hovered() {
+        ui.visuals().widgets.hovered.fg_stroke
+    } else {
+        ui.visuals().widgets.noninteractive.bg_stroke
+    };
+    ui.painter()
+        .rect_stroke(rect, 2.0, stroke, egui::StrokeKind::Inside);
+    response
+}
+
+/// Amount of colours suggested when text doesn't contrast enough with its background
+const CONTRAST_SUGGESTIONS: usize = 6;
+
+/// Warn when the font colour doesn't contrast enough with the background behind the text, with
+/// the closest colours that do. The background is the background colour of the object, or what
+/// is behind it when it is transparent. Clicking a suggestion changes the font colour of the font
+/// attributes.
+fn render_contrast_check(
+    ui: &mut egui::Ui,
+    design: &EditorProject,
+    id: ObjectId,
+    font_attributes: ObjectId,
+    transparent: bool,
+    background_colour: u8,
+) {
+    let background = if transparent {
+        background_behind(design, id)
+    } else {
+        Some(background_colour)
+    };
+    let (Some(Object::FontAttributes(font)), Some(background)) =
+        (design.get_object(font_attributes), background)
+    else {
+        return;
+    };
+    let pool = design.get_pool();
+    let ratio = contrast_ratio(
+        &pool.color_by_index(font.font_colour),
+        &pool.color_by_index(background),
+    );
+    if ratio >= MIN_CONTRAST_RATIO {
+        return;
+    }
+
+    ui.colored_label(
+        egui::Color32::YELLOW,
+        format!(
+            "Low contrast between font colour {} and background {}: {:.1}:1, at least {}:1 \
+            is readable",
+            font.font_colour, background, ratio, MIN_CONTRAST_RATIO
+        ),
+    );
+    let suggestions =
+        suggest_contrasting_colours(pool, font.font_colour, background, CONTRAST_SUGGESTIONS);
+    let mut picked = None;
+    ui.horizontal(|ui| {
+        ui.label("Use font colour:");
+        for colour in suggestions {
+            let ratio = contrast_ratio(
+                &pool.color_by_index(colour),
+                &pool.color_by_index(background),
+            );
+            if render_colour_swatch(ui, design, colour, false)
+                .on_hover_text(format!(
+                    "{} ({:.1}:1), changes the font attributes {} of every object using them",
+                    colour,
+                    ratio,
+                    font_attributes.value()
+                ))
+                .clicked()
+            {
+                picked = Some(colour);
+            }
+        }
+    });
+
+    if let Some(colour) = picked {
+        let mut font = font.clone();
+        font.font_colour = colour;
+        if let Err(e) = design.execute(PoolCommand::SetObject {
+            id: font_attributes,
+            object: Object::FontAttributes(font),
+        }) {
+            log::error!("Failed to change the font colour: {}", e);
+        }
+        design.note_colour_used(colour);
+    }
+}
+
+/// Colour selector showing the recently used colours and the project palette
+/// above the full grid of 256 colours
+fn color_swatch_selector(
+    ui: &mut egui::Ui,
+    value: &mut u8,
+    design: &EditorProject,
+    label: &str,
+) -> egui::Response {
+    ui.horizontal(|ui| {
+        render_colour_swatch(ui, design, *value, false);
+        egui::ComboBox::from_id_salt(label)
+            .selected_text(format!("{}", value))
+            .close_behavior(egui::PopupCloseBehavior::CloseOnClickOutside)
+            .show_ui(ui, |ui| {
+                ui.spacing_mut().item_spacing = egui::vec2(2.0, 2.0);
+                let mut picked = None;
+
+                let recent = design.get_recent_colours();
+                if !recent.is_empty() {
+                    ui.weak("Recently used");
+                    ui.horizontal(|ui| {
+                        for colour in recent {
+                            if render_colour_swatch(ui, design, colour, colour == *value)
+                                .on_hover_text(format!("{}", colour))
+                                .clicked()
+                            {
+                                picked = Some(colour);
+                            }
+                        }
+                    });
+                }
+
+                ui.horizontal(|ui| {
+                    ui.weak("Project palette");
+                    if ui
+                        .small_button("+")
+                        .on_hover_text("Add the current colour to the project palette")
+                        .clicked()
+                    {
+                        design.add_palette_colour(*value);
+                    }
+                });
+                ui.horizontal(|ui| {
+                    let palette = design.get_palette_colours();
+                    if palette.is_empty() {
+                        ui.weak("No colours added yet");
+                    }
+                    for colour in palette {
+                        let response = render_colour_swatch(ui, design, colour, colour == *value)
+                            .on_hover_text(format!("{} (right-click to remove)", colour));
+                        if response.clicked() {
+                            picked = Some(colour);
+                        } else if response.secondary_clicked() {
+                            design.remove_palette_colour(colour);
+                        }
+                    }
+                });
+                ui.separator();
+
+                if let Some(palette) = get_active_colour_palette(design.get_pool()) {
+                    ui.weak(format!("Custom colour palette {}", palette.id.value()));
+                }
+                for row in 0..16u8 {
+                    ui.horizontal(|ui| {
+                        for column in 0..16u8 {
+                            let colour = row * 16 + column;
+                            if render_colour_swatch(ui, design, colour, colour == *value)
+                                .on_hover_text(format!("{}", colour))
+                                .clicked()
+                            {
+                                picked = Some(colour);
+                            }
+                        }
+                    });
+                }
+
+                if let Some(colour) = picked {
+                    *value = colour;
+                    design.note_colour_used(colour);
+                    ui.close();
+                }
+            });
+        ui.label(label);
+    })
+    .response
+}
+
 pub trait ConfigurableObject {
     fn render_parameters(&mut self, ui: &mut egui::Ui, design: &EditorProject);
 }
@@ -101,22 +386,22 @@ impl ConfigurableObject for Object {
             Object::InputAttributes(o) => o.render_parameters(ui, design),
             Object::ObjectPointer(o) => o.render_parameters(ui, design),
             Object::Macro(o) => o.render_parameters(ui, design),
-            Object::AuxiliaryFunctionType1(o) => (),
-            Object::AuxiliaryInputType1(o) => (),
+            Object::AuxiliaryFunctionType1(o) => o.render_parameters(ui, design),
+            Object::AuxiliaryInputType1(o) => o.render_parameters(ui, design),
             Object::AuxiliaryFunctionType2(o) => o.render_parameters(ui, design),
             Object::AuxiliaryInputType2(o) => o.render_parameters(ui, design),
             Object::AuxiliaryControlDesignatorType2(o) => o.render_parameters(ui, design),
-            Object::WindowMask(o) => (),
+            Object::WindowMask(o) => o.render_parameters(ui, design),
             Object::KeyGroup(o) => (),
-            Object::GraphicsContext(o) => (),
-            Object::ExtendedInputAttributes(o) => (),
+            Object::GraphicsContext(o) => o.render_parameters(ui, design),
+            Object::ExtendedInputAttributes(o) => o.render_parameters(ui, design),
             Object::ColourMap(o) => (),
             Object::ObjectLabelReferenceList(o) => (),
-            Object::ExternalObjectDefinition(o) => (),
-            Object::ExternalReferenceName(o) => (),
-            Object::ExternalObjectPointer(o) => (),
+            Object::ExternalObjectDefinition(o) => o.render_parameters(ui, design),
+            Object::ExternalReferenceName(o) => o.render_parameters(ui, design),
+            Object::ExternalObjectPointer(o) => o.render_parameters(ui, design),
             Object::Animation(o) => (),
-            Object::ColourPalette(o) => (),
+            Object::ColourPalette(o) => o.render_parameters(ui, design),
             Object::GraphicData(o) => (),
             Object::WorkingSetSpecialControls(o) => (),
             Object::ScaledGraphic(o) => (),
@@ -124,6 +409,26 @@ impl ConfigurableObject for Object {
     }
 }
 
+/// Render attributes that were introduced in a later VT version. They are disabled if the project
+/// is made for an older VT version, so values of an imported pool are still shown.
+fn render_from_vt_version<R>(
+    ui: &mut egui::Ui,
+    design: &EditorProject,
+    required: VtVersion,
+    add_contents: impl FnOnce(&mut egui::Ui) -> R,
+) -> R {
+    let supported = design.supports_vt_version(required);
+    let response = ui.add_enabled_ui(supported, add_contents);
+    if !supported {
+        response.response.on_hover_text(format!(
+            "Requires VT version {} or later, the project is made for VT version {}",
+            vt_version_number(required),
+            vt_version_number(design.get_vt_version()),
+        ));
+    }
+    response.inner
+}
+
 fn render_object_id(ui: &mut egui::Ui, id: &mut ObjectId, design: &EditorProject) {
     let mut current_id = u16::from(*id);
 
@@ -138,7 +443,7 @@ fn render_object_id(ui: &mut egui::Ui, id: &mut ObjectId, design: &EditorProject
         let new_id = ObjectId::new(current_id).unwrap();
 
         // Check if the new ID is already used by another object (excluding the current object)
-        let conflict = design.get_pool().object_by_id(new_id).is_some() && new_id != *id;
+        let conflict = design.get_object(new_id).is_some() && new_id != *id;
 
         let conflict_storage = ui.id().with("conflict");
         let was_conflict = ui.data(|data| data.get_temp::<u16>(conflict_storage));
@@ -165,7 +470,7 @@ fn render_object_id(ui: &mut egui::Ui, id: &mut ObjectId, design: &EditorProject
         }
 
         // Add the object type display
-        if let Some(obj) = design.get_pool().object_by_id(*id) {
+        if let Some(obj) = design.get_object(*id) {
             ui.separator();
             ui.label("Type:");
             ui.label(format!("{:?}", obj.object_type()));
@@ -185,7 +490,7 @@ fn render_object_id_selector(
     egui::ComboBox::from_id_salt(format!("object_id_selector_{}", idx))
         .selected_text(format!("{:?}", object_id.value()))
         .show_ui(ui, |ui| {
-            for potential_child in pool.objects_by_types(allowed_child_objects) {
+            render_picker_candidates(ui, design, allowed_child_objects, |ui, potential_child| {
                 let child_id = potential_child.id();
 
                 // Check if this would create a circular reference
@@ -209,11 +514,17 @@ fn render_object_id_selector(
                     }
                 );
 
-                // Disable selection if it would create a circular reference
-                ui.add_enabled_ui(!would_be_circular, |ui| {
-                    ui.selectable_value(object_id, child_id, label);
-                });
-            }
+                ui.horizontal(|ui| {
+                    render_object_thumbnail(ui, design, potential_child, PICKER_THUMBNAIL_SIZE);
+
+                    // Disable selection if it would create a circular reference
+                    ui.add_enabled_ui(!would_be_circular, |ui| {
+                        ui.selectable_value(object_id, child_id, label)
+                    })
+                    .inner
+                })
+                .inner
+            });
         });
 }
 
@@ -234,7 +545,7 @@ fn render_nullable_object_id_selector(
         )
         .show_ui(ui, |ui| {
             ui.selectable_value(object_id, NullableObjectId::NULL, "None");
-            for potential_child in pool.objects_by_types(allowed_child_objects) {
+            render_picker_candidates(ui, design, allowed_child_objects, |ui, potential_child| {
                 let child_id = potential_child.id();
 
                 // Check if this would create a circular reference
@@ -258,11 +569,17 @@ fn render_nullable_object_id_selector(
                     }
                 );
 
-                // Disable selection if it would create a circular reference
-                ui.add_enabled_ui(!would_be_circular, |ui| {
-                    ui.selectable_value(object_id, child_id.into(), label);
-                });
-            }
+                ui.horizontal(|ui| {
+                    render_object_thumbnail(ui, design, potential_child, PICKER_THUMBNAIL_SIZE);
+
+                    // Disable selection if it would create a circular reference
+                    ui.add_enabled_ui(!would_be_circular, |ui| {
+                        ui.selectable_value(object_id, child_id.into(), label)
+                    })
+                    .inner
+                })
+                .inner
+            });
         });
 }
 
@@ -302,7 +619,7 @@ fn render_object_references_list(
             let mut idx = 0;
             while idx < object_refs.len() {
                 let obj_ref = &mut object_refs[idx];
-                let obj = design.get_pool().object_by_id(obj_ref.id);
+                let obj = design.get_object(obj_ref.id);
 
                 ui.label(" - ");
                 render_object_id_selector(
@@ -377,7 +694,7 @@ fn render_object_id_list(
         .show(ui, |ui| {
             let mut idx = 0;
             while idx < object_ids.len() {
-                let obj: Option<&Object> = design.get_pool().object_by_id(object_ids[idx]);
+                let obj: Option<&Object> = design.get_object(object_ids[idx]);
 
                 ui.label(" - ");
                 render_object_id_selector(
@@ -442,7 +759,7 @@ fn render_nullable_object_id_list(
                     Some(current_object_id),
                 );
                 if let Some(object_id) = &mut nullable_object_ids[idx].0 {
-                    let obj: Option<&Object> = design.get_pool().object_by_id(*object_id);
+                    let obj: Option<&Object> = design.get_object(*object_id);
 
                     if let Some(obj) = obj {
                         if ui.link(format!("{:?}", obj.object_type())).clicked() {
@@ -497,42 +814,197 @@ fn render_add_object_id(
                         result = (None, true);
                     }
                 }
-                for potential_child in pool.objects_by_types(allowed_child_objects) {
-                    let child_id = potential_child.id();
+                render_picker_candidates(
+                    ui,
+                    design,
+                    allowed_child_objects,
+                    |ui, potential_child| {
+                        let child_id = potential_child.id();
 
-                    // Check if this would create a circular reference
-                    let would_be_circular = if let Some(parent_id) = current_object_id {
-                        would_create_circular_reference(pool, parent_id, child_id)
-                    } else {
-                        false
-                    };
-
-                    let object_info = design.get_object_info(potential_child);
-                    let name = object_info.get_name(potential_child);
-                    let label = format!(
-                        "{:?}: {:?} - {}{}",
-                        u16::from(child_id),
-                        potential_child.object_type(),
-                        name,
-                        if would_be_circular {
-                            " ⚠ (circular)"
+                        // Check if this would create a circular reference
+                        let would_be_circular = if let Some(parent_id) = current_object_id {
+                            would_create_circular_reference(pool, parent_id, child_id)
                         } else {
-                            ""
-                        }
-                    );
+                            false
+                        };
+
+                        let object_info = design.get_object_info(potential_child);
+                        let name = object_info.get_name(potential_child);
+                        let label = format!(
+                            "{:?}: {:?} - {}{}",
+                            u16::from(child_id),
+                            potential_child.object_type(),
+                            name,
+                            if would_be_circular {
+                                " ⚠ (circular)"
+                            } else {
+                                ""
+                            }
+                        );
 
-                    // Only allow clicking if it wouldn't create a circular reference
-                    ui.add_enabled_ui(!would_be_circular, |ui| {
-                        if ui.selectable_label(false, label).clicked() {
-                            result = (Some(child_id), true);
-                        }
-                    });
-                }
+                        ui.horizontal(|ui| {
+                            render_object_thumbnail(
+                                ui,
+                                design,
+                                potential_child,
+                                PICKER_THUMBNAIL_SIZE,
+                            );
+
+                            // Only allow clicking if it wouldn't create a circular reference
+                            let response = ui
+                                .add_enabled_ui(!would_be_circular, |ui| {
+                                    ui.selectable_label(false, label)
+                                })
+                                .inner;
+                            if response.clicked() {
+                                result = (Some(child_id), true);
+                            }
+                            response
+                        })
+                        .inner
+                    },
+                );
             });
     });
     result
 }
 
+/// Create variants of a picture for a list of widths, as new pictures or as scaled graphics
+fn render_scaled_variants(ui: &mut egui::Ui, design: &EditorProject, picture: &PictureGraphic) {
+    egui::CollapsingHeader::new("Scaled variants")
+        .id_salt("scaled_variants")
+        .show(ui, |ui| {
+            let widths_id = ui.id().with("widths");
+            let kind_id = ui.id().with("kind");
+            let mut text = ui.data(|data| data.get_temp::<String>(widths_id).unwrap_or_default());
+            let mut kind = ui.data(|data| {
+                data.get_temp::<ScaledVariantKind>(kind_id)
+                    .unwrap_or(ScaledVariantKind::Resampled)
+            });
+
+            ui.horizontal(|ui| {
+                ui.label("Widths:");
+                ui.add(egui::TextEdit::singleline(&mut text).hint_text("e.g. 32, 64, 128"));
+            });
+            ui.radio_value(
+                &mut kind,
+                ScaledVariantKind::Resampled,
+                "Resampled pictures",
+            )
+            .on_hover_text("A new picture graphic per width, for every VT version");
+            ui.radio_value(
+                &mut kind,
+                ScaledVariantKind::ScaledGraphic,
+                "Scaled graphics (VT version 6 or later)",
+            )
+            .on_hover_text("A scaled graphic per width showing this picture");
+
+            let widths = picture_scaling::parse_widths(&text);
+            for width in &widths {
+                ui.weak(format!(
+                    "{}x{}",
+                    width,
+                    picture_scaling::scaled_height(picture, *width)
+                ));
+            }
+            if ui
+                .add_enabled(!widths.is_empty(), egui::Button::new("Create variants"))
+                .clicked()
+            {
+                match design.create_scaled_variants(picture.id, &widths, kind) {
+                    Ok(created) => {
+                        log::info!("Created {} scaled variants", created.len());
+                        text.clear();
+                    }
+                    Err(e) => log::error!("Failed to create scaled variants: {}", e),
+                }
+            }
+
+            ui.data_mut(|data| {
+                data.insert_temp(widths_id, text);
+                data.insert_temp(kind_id, kind);
+            });
+        });
+}
+
+/// Edit the text of an object per language of the working set.
+/// Languages without a translation use the text of the object itself.
+fn render_translations(ui: &mut egui::Ui, design: &EditorProject, id: ObjectId, text: &str) {
+    let languages = design.get_language_codes();
+    if languages.is_empty() {
+        return;
+    }
+    egui::CollapsingHeader::new("Translations")
+        .id_salt("translations")
+        .show(ui, |ui| {
+            egui::Grid::new("translations_grid")
+                .num_columns(2)
+                .show(ui, |ui| {
+                    for language in &languages {
+                        ui.label(language);
+                        let mut translation =
+                            design.get_translation(id, language).unwrap_or_default();
+                        let response =
+                            ui.add(egui::TextEdit::singleline(&mut translation).hint_text(text));
+                        if response.changed() {
+                            let translation = Some(translation).filter(|t| !t.is_empty());
+                            design.set_translation(id, language, translation);
+                        }
+                        ui.end_row();
+                    }
+                });
+        });
+}
+
+/// Edit the drawing commands previewed on a graphics context and show the resulting canvas
+fn render_graphics_commands(ui: &mut egui::Ui, design: &EditorProject, context: &GraphicsContext) {
+    egui::CollapsingHeader::new("Drawing command preview")
+        .id_salt("graphics_commands")
+        .show(ui, |ui| {
+            ui.label("Commands the ECU draws on the canvas at runtime, one per line:")
+                .on_hover_text(egui::RichText::new(graphics_commands::COMMAND_SYNTAX).monospace());
+            let mut commands = design.get_graphics_commands(context.id);
+            let response = ui.add(
+                egui::TextEdit::multiline(&mut commands)
+                    .code_editor()
+                    .desired_rows(6)
+                    .desired_width(f32::INFINITY)
+                    .hint_text("cursor 10 10\nrectangle 40 20 fill\ntext Hello"),
+            );
+            if response.changed() {
+                design.set_graphics_commands(context.id, commands.clone());
+            }
+
+            let (parsed, errors) = graphics_commands::parse_commands(&commands);
+            for (line, error) in errors {
+                ui.colored_label(egui::Color32::RED, format!("Line {}: {}", line, error));
+            }
+
+            if context.canvas_width == 0 || context.canvas_height == 0 {
+                ui.label("The canvas is empty, give it a width and height to preview it");
+                return;
+            }
+            let canvas_size = egui::vec2(context.canvas_width as f32, context.canvas_height as f32);
+            let zoom = (ui.available_width() / canvas_size.x).min(2.0);
+            let (rect, _) = ui.allocate_exact_size(canvas_size * zoom, egui::Sense::hover());
+            let painter = ui.painter_at(rect);
+            let pool = design.get_pool();
+            let background = pool.color_by_index(context.background_colour);
+            painter.rect_filled(
+                rect,
+                0.0,
+                egui::Color32::from_rgb(background.r, background.g, background.b),
+            );
+            paint_graphics_commands(ui, &painter, rect, zoom, pool, context, &parsed);
+            painter.rect_stroke(
+                rect,
+                0.0,
+                ui.visuals().widgets.noninteractive.bg_stroke,
+                egui::StrokeKind::Outside,
+            );
+        });
+}
+
 fn render_macro_references(
     ui: &mut egui::Ui,
     design: &EditorProject,
@@ -548,8 +1020,7 @@ fn render_macro_references(
                 let macro_ref = &mut macro_refs[idx];
 
                 if let Some(macro_obj) = design
-                    .get_pool()
-                    .objects_by_type(ObjectType::Macro)
+                    .get_objects_by_type(ObjectType::Macro)
                     .iter()
                     .find(|o| u16::from(o.id()) == macro_ref.macro_id as u16)
                 {
@@ -574,8 +1045,7 @@ fn render_macro_references(
                         egui::ComboBox::from_id_salt("macro_id")
                             .selected_text(format!("{:?}", macro_ref.macro_id))
                             .show_ui(ui, |ui| {
-                                for potential_macro in
-                                    design.get_pool().objects_by_type(ObjectType::Macro)
+                                for potential_macro in design.get_objects_by_type(ObjectType::Macro)
                                 {
                                     ui.selectable_value(
                                         &mut macro_ref.macro_id,
@@ -660,16 +1130,10 @@ fn render_add_macro_reference(
 impl ConfigurableObject for WorkingSet {
     fn render_parameters(&mut self, ui: &mut egui::Ui, design: &EditorProject) {
         render_object_id(ui, &mut self.id, design);
-        ui.add(
-            egui::Slider::new(&mut self.background_colour, 0..=255)
-                .text("Background Colour")
-                .drag_value_speed(1.0),
-        );
+        color_swatch_selector(ui, &mut self.background_colour, design, "Background Colour");
         ui.checkbox(&mut self.selectable, "Selectable");
         ui.horizontal(|ui| {
-            let masks = design
-                .get_pool()
-                .objects_by_types(&[ObjectType::DataMask, ObjectType::AlarmMask]);
+            let masks = design.get_objects_by_types(&[ObjectType::DataMask, ObjectType::AlarmMask]);
             egui::ComboBox::from_label("Active Mask")
                 .selected_text(format!("{:?}", u16::from(self.active_mask)))
                 .show_ui(ui, |ui| {
@@ -693,7 +1157,7 @@ impl ConfigurableObject for WorkingSet {
             design.mask_size,
             design.mask_size,
             &mut self.object_refs,
-            &Self::get_allowed_child_refs(VtVersion::Version3),
+            &Self::get_allowed_child_refs(design.get_vt_version()),
             self.id,
         );
 
@@ -711,11 +1175,7 @@ impl ConfigurableObject for WorkingSet {
 impl ConfigurableObject for DataMask {
     fn render_parameters(&mut self, ui: &mut egui::Ui, design: &EditorProject) {
         render_object_id(ui, &mut self.id, design);
-        ui.add(
-            egui::Slider::new(&mut self.background_colour, 0..=255)
-                .text("Background Colour")
-                .drag_value_speed(1.0),
-        );
+        color_swatch_selector(ui, &mut self.background_colour, design, "Background Colour");
         ui.horizontal(|ui| {
             egui::ComboBox::from_label("Soft Key Mask")
                 .selected_text(
@@ -729,7 +1189,7 @@ impl ConfigurableObject for DataMask {
                         NullableObjectId(None),
                         "None".to_string(),
                     );
-                    for object in design.get_pool().objects_by_type(ObjectType::SoftKeyMask) {
+                    for object in design.get_objects_by_type(ObjectType::SoftKeyMask) {
                         ui.selectable_value(
                             &mut self.soft_key_mask,
                             NullableObjectId(Some(object.id())),
@@ -751,7 +1211,7 @@ impl ConfigurableObject for DataMask {
             design.mask_size,
             design.mask_size,
             &mut self.object_refs,
-            &Self::get_allowed_child_refs(VtVersion::Version3),
+            &Self::get_allowed_child_refs(design.get_vt_version()),
             self.id,
         );
 
@@ -769,11 +1229,7 @@ impl ConfigurableObject for DataMask {
 impl ConfigurableObject for AlarmMask {
     fn render_parameters(&mut self, ui: &mut egui::Ui, design: &EditorProject) {
         render_object_id(ui, &mut self.id, design);
-        ui.add(
-            egui::Slider::new(&mut self.background_colour, 0..=255)
-                .text("Background Colour")
-                .drag_value_speed(1.0),
-        );
+        color_swatch_selector(ui, &mut self.background_colour, design, "Background Colour");
         ui.horizontal(|ui| {
             egui::ComboBox::from_label("Soft Key Mask")
                 .selected_text(
@@ -787,7 +1243,7 @@ impl ConfigurableObject for AlarmMask {
                         NullableObjectId(None),
                         "None".to_string(),
                     );
-                    for object in design.get_pool().objects_by_type(ObjectType::SoftKeyMask) {
+                    for object in design.get_objects_by_type(ObjectType::SoftKeyMask) {
                         ui.selectable_value(
                             &mut self.soft_key_mask,
                             NullableObjectId(Some(object.id())),
@@ -822,7 +1278,7 @@ impl ConfigurableObject for AlarmMask {
             design.mask_size,
             design.mask_size,
             &mut self.object_refs,
-            &Self::get_allowed_child_refs(VtVersion::Version3),
+            &Self::get_allowed_child_refs(design.get_vt_version()),
             self.id,
         );
 
@@ -859,7 +1315,7 @@ impl ConfigurableObject for Container {
             self.width,
             self.height,
             &mut self.object_refs,
-            &Self::get_allowed_child_refs(VtVersion::Version3),
+            &Self::get_allowed_child_refs(design.get_vt_version()),
             self.id,
         );
 
@@ -877,18 +1333,14 @@ impl ConfigurableObject for Container {
 impl ConfigurableObject for SoftKeyMask {
     fn render_parameters(&mut self, ui: &mut egui::Ui, design: &EditorProject) {
         render_object_id(ui, &mut self.id, design);
-        ui.add(
-            egui::Slider::new(&mut self.background_colour, 0..=255)
-                .text("Background Colour")
-                .drag_value_speed(1.0),
-        );
+        color_swatch_selector(ui, &mut self.background_colour, design, "Background Colour");
         ui.separator();
         ui.label("Objects:");
         render_object_id_list(
             ui,
             design,
             &mut self.objects,
-            &Self::get_allowed_child_refs(VtVersion::Version3),
+            &Self::get_allowed_child_refs(design.get_vt_version()),
             self.id,
         );
 
@@ -906,11 +1358,7 @@ impl ConfigurableObject for SoftKeyMask {
 impl ConfigurableObject for Key {
     fn render_parameters(&mut self, ui: &mut egui::Ui, design: &EditorProject) {
         render_object_id(ui, &mut self.id, design);
-        ui.add(
-            egui::Slider::new(&mut self.background_colour, 0..=255)
-                .text("Background Colour")
-                .drag_value_speed(1.0),
-        );
+        color_swatch_selector(ui, &mut self.background_colour, design, "Background Colour");
         ui.horizontal(|ui| {
             ui.label("Key code:");
             ui.radio_value(&mut self.key_code, 0, "ACK");
@@ -924,7 +1372,7 @@ impl ConfigurableObject for Key {
             design.mask_size,
             design.mask_size,
             &mut self.object_refs,
-            &Self::get_allowed_child_refs(VtVersion::Version3),
+            &Self::get_allowed_child_refs(design.get_vt_version()),
             self.id,
         );
 
@@ -954,16 +1402,8 @@ impl ConfigurableObject for Button {
                 .drag_value_speed(1.0),
         );
 
-        ui.add(
-            egui::Slider::new(&mut self.background_colour, 0..=255)
-                .text("Background Colour")
-                .drag_value_speed(1.0),
-        );
-        ui.add(
-            egui::Slider::new(&mut self.border_colour, 0..=255)
-                .text("Border Colour")
-                .drag_value_speed(1.0),
-        );
+        color_swatch_selector(ui, &mut self.background_colour, design, "Background Colour");
+        color_swatch_selector(ui, &mut self.border_colour, design, "Border Colour");
 
         ui.horizontal(|ui| {
             ui.label("Key code:");
@@ -980,14 +1420,15 @@ impl ConfigurableObject for Button {
             });
         }
 
-        // TODO: check if we have VT version 4 or later
-        // ui.checkbox(&mut self.options.suppress_border, "Suppress Border");
-        // ui.checkbox(
-        //     &mut self.options.transparent_background,
-        //     "Transparent Background",
-        // );
-        // ui.checkbox(&mut self.options.disabled, "Disabled");
-        // ui.checkbox(&mut self.options.no_border, "No Border");
+        render_from_vt_version(ui, design, VtVersion::Version4, |ui| {
+            ui.checkbox(&mut self.options.suppress_border, "Suppress Border");
+            ui.checkbox(
+                &mut self.options.transparent_background,
+                "Transparent Background",
+            );
+            ui.checkbox(&mut self.options.disabled, "Disabled");
+            ui.checkbox(&mut self.options.no_border, "No Border");
+        });
 
         ui.separator();
         ui.label("Objects:");
@@ -997,7 +1438,7 @@ impl ConfigurableObject for Button {
             self.width,
             self.height,
             &mut self.object_refs,
-            &Self::get_allowed_child_refs(VtVersion::Version3),
+            &Self::get_allowed_child_refs(design.get_vt_version()),
             self.id,
         );
 
@@ -1015,11 +1456,7 @@ impl ConfigurableObject for Button {
 impl ConfigurableObject for InputBoolean {
     fn render_parameters(&mut self, ui: &mut egui::Ui, design: &EditorProject) {
         render_object_id(ui, &mut self.id, design);
-        ui.add(
-            egui::Slider::new(&mut self.background_colour, 0..=255)
-                .text("Background Colour")
-                .drag_value_speed(1.0),
-        );
+        color_swatch_selector(ui, &mut self.background_colour, design, "Background Colour");
         ui.add(
             egui::Slider::new(&mut self.width, 0..=design.mask_size)
                 .text("Width")
@@ -1028,20 +1465,22 @@ impl ConfigurableObject for InputBoolean {
         egui::ComboBox::from_id_salt("foreground_colour")
             .selected_text(format!("{:?}", u16::from(self.foreground_colour)))
             .show_ui(ui, |ui| {
-                for potential_child in design
-                    .get_pool()
-                    .objects_by_type(ObjectType::FontAttributes)
-                {
-                    ui.selectable_value(
-                        &mut self.foreground_colour,
-                        potential_child.id(),
-                        format!(
-                            "{:?}: {:?}",
-                            u16::from(potential_child.id()),
-                            potential_child.object_type()
-                        ),
-                    );
-                }
+                render_picker_candidates(
+                    ui,
+                    design,
+                    &[ObjectType::FontAttributes],
+                    |ui, potential_child| {
+                        ui.selectable_value(
+                            &mut self.foreground_colour,
+                            potential_child.id(),
+                            format!(
+                                "{:?}: {:?}",
+                                u16::from(potential_child.id()),
+                                potential_child.object_type()
+                            ),
+                        )
+                    },
+                );
             });
         ui.horizontal(|ui| {
             ui.label("Variable reference:");
@@ -1053,20 +1492,22 @@ impl ConfigurableObject for InputBoolean {
                         NullableObjectId::NULL,
                         "None",
                     );
-                    for potential_child in design
-                        .get_pool()
-                        .objects_by_type(ObjectType::NumberVariable)
-                    {
-                        ui.selectable_value(
-                            &mut self.variable_reference,
-                            potential_child.id().into(),
-                            format!(
-                                "{:?}: {:?}",
-                                u16::from(potential_child.id()),
-                                potential_child.object_type()
-                            ),
-                        );
-                    }
+                    render_picker_candidates(
+                        ui,
+                        design,
+                        &[ObjectType::NumberVariable],
+                        |ui, potential_child| {
+                            ui.selectable_value(
+                                &mut self.variable_reference,
+                                potential_child.id().into(),
+                                format!(
+                                    "{:?}: {:?}",
+                                    u16::from(potential_child.id()),
+                                    potential_child.object_type()
+                                ),
+                            )
+                        },
+                    );
                 });
         });
         if self.variable_reference.0.is_none() {
@@ -1103,26 +1544,24 @@ impl ConfigurableObject for InputString {
                 .text("Height")
                 .drag_value_speed(1.0),
         );
-        ui.add(
-            egui::Slider::new(&mut self.background_colour, 0..=255)
-                .text("Background Colour")
-                .drag_value_speed(1.0),
-        );
+        color_swatch_selector(ui, &mut self.background_colour, design, "Background Colour");
         ui.horizontal(|ui| {
             ui.label("Font attributes:");
             egui::ComboBox::from_id_salt("font_attributes")
                 .selected_text(format!("{:?}", u16::from(self.font_attributes)))
                 .show_ui(ui, |ui| {
-                    for potential_child in design
-                        .get_pool()
-                        .objects_by_type(ObjectType::FontAttributes)
-                    {
-                        ui.selectable_value(
-                            &mut self.font_attributes,
-                            potential_child.id(),
-                            format!("{:?}", u16::from(potential_child.id())),
-                        );
-                    }
+                    render_picker_candidates(
+                        ui,
+                        design,
+                        &[ObjectType::FontAttributes],
+                        |ui, potential_child| {
+                            ui.selectable_value(
+                                &mut self.font_attributes,
+                                potential_child.id(),
+                                format!("{:?}", u16::from(potential_child.id())),
+                            )
+                        },
+                    );
                 });
         });
         ui.horizontal(|ui| {
@@ -1131,24 +1570,50 @@ impl ConfigurableObject for InputString {
                 .selected_text(format!("{:?}", u16::from(self.input_attributes)))
                 .show_ui(ui, |ui| {
                     ui.selectable_value(&mut self.input_attributes, NullableObjectId::NULL, "None");
-                    for potential_child in design
-                        .get_pool()
-                        .objects_by_type(ObjectType::InputAttributes)
-                    {
-                        ui.selectable_value(
-                            &mut self.input_attributes,
-                            potential_child.id().into(),
-                            format!("{:?}", u16::from(potential_child.id())),
-                        );
-                    }
+                    render_picker_candidates(
+                        ui,
+                        design,
+                        &[
+                            ObjectType::InputAttributes,
+                            ObjectType::ExtendedInputAttributes,
+                        ],
+                        |ui, potential_child| {
+                            ui.selectable_value(
+                                &mut self.input_attributes,
+                                potential_child.id().into(),
+                                format!(
+                                    "{:?}: {:?}",
+                                    u16::from(potential_child.id()),
+                                    potential_child.object_type()
+                                ),
+                            )
+                        },
+                    );
                 });
+            let extended = self
+                .input_attributes
+                .0
+                .and_then(|id| design.get_object(id))
+                .is_some_and(|obj| obj.object_type() == ObjectType::ExtendedInputAttributes);
+            if extended {
+                ui.weak("(VT version 4 or later)");
+            }
         });
         ui.checkbox(&mut self.options.transparent, "Transparent Background");
+        render_contrast_check(
+            ui,
+            design,
+            self.id,
+            self.font_attributes,
+            self.options.transparent,
+            self.background_colour,
+        );
         ui.checkbox(&mut self.options.auto_wrap, "Auto Wrap");
-        // TODO: check if we have VT version 4 or later
-        // if self.options.auto_wrap {
-        //     ui.checkbox(&mut self.options.wrap_on_hyphen, "Wrap on Hyphen");
-        // }
+        render_from_vt_version(ui, design, VtVersion::Version4, |ui| {
+            if self.options.auto_wrap {
+                ui.checkbox(&mut self.options.wrap_on_hyphen, "Wrap on Hyphen");
+            }
+        });
         ui.horizontal(|ui| {
             ui.label("Variable reference:");
             egui::ComboBox::from_id_salt("variable_reference")
@@ -1159,20 +1624,22 @@ impl ConfigurableObject for InputString {
                         NullableObjectId::NULL,
                         "None",
                     );
-                    for potential_child in design
-                        .get_pool()
-                        .objects_by_type(ObjectType::StringVariable)
-                    {
-                        ui.selectable_value(
-                            &mut self.variable_reference,
-                            potential_child.id().into(),
-                            format!(
-                                "{:?}: {:?}",
-                                u16::from(potential_child.id()),
-                                potential_child.object_type()
-                            ),
-                        );
-                    }
+                    render_picker_candidates(
+                        ui,
+                        design,
+                        &[ObjectType::StringVariable],
+                        |ui, potential_child| {
+                            ui.selectable_value(
+                                &mut self.variable_reference,
+                                potential_child.id().into(),
+                                format!(
+                                    "{:?}: {:?}",
+                                    u16::from(potential_child.id()),
+                                    potential_child.object_type()
+                                ),
+                            )
+                        },
+                    );
                 });
         });
         ui.horizontal(|ui| {
@@ -1193,28 +1660,30 @@ impl ConfigurableObject for InputString {
                 "Right",
             );
         });
-        // TODO: check if we have VT version 4 or later
-        // ui.horizontal(|ui| {
-        //     ui.label("Vertical Justification:");
-        //     ui.radio_value(
-        //         &mut self.justification.vertical,
-        //         VerticalAlignment::Top,
-        //         "Top",
-        //     );
-        //     ui.radio_value(
-        //         &mut self.justification.vertical,
-        //         VerticalAlignment::Middle,
-        //         "Middle",
-        //     );
-        //     ui.radio_value(
-        //         &mut self.justification.vertical,
-        //         VerticalAlignment::Bottom,
-        //         "Bottom",
-        //     );
-        // });
+        render_from_vt_version(ui, design, VtVersion::Version4, |ui| {
+            ui.horizontal(|ui| {
+                ui.label("Vertical Justification:");
+                ui.radio_value(
+                    &mut self.justification.vertical,
+                    VerticalAlignment::Top,
+                    "Top",
+                );
+                ui.radio_value(
+                    &mut self.justification.vertical,
+                    VerticalAlignment::Middle,
+                    "Middle",
+                );
+                ui.radio_value(
+                    &mut self.justification.vertical,
+                    VerticalAlignment::Bottom,
+                    "Bottom",
+                );
+            });
+        });
         if self.variable_reference.0.is_none() {
             ui.label("Initial value:");
             ui.text_edit_singleline(&mut self.value);
+            render_translations(ui, design, self.id, &self.value);
         }
         ui.checkbox(&mut self.enabled, "Enabled");
         ui.separator();
@@ -1241,29 +1710,35 @@ impl ConfigurableObject for InputNumber {
                 .text("Height")
                 .drag_value_speed(1.0),
         );
-        ui.add(
-            egui::Slider::new(&mut self.background_colour, 0..=255)
-                .text("Background Colour")
-                .drag_value_speed(1.0),
-        );
+        color_swatch_selector(ui, &mut self.background_colour, design, "Background Colour");
         ui.horizontal(|ui| {
             ui.label("Font attributes:");
             egui::ComboBox::from_id_salt("font_attributes")
                 .selected_text(format!("{:?}", u16::from(self.font_attributes)))
                 .show_ui(ui, |ui| {
-                    for potential_child in design
-                        .get_pool()
-                        .objects_by_type(ObjectType::FontAttributes)
-                    {
-                        ui.selectable_value(
-                            &mut self.font_attributes,
-                            potential_child.id(),
-                            format!("{:?}", u16::from(potential_child.id())),
-                        );
-                    }
+                    render_picker_candidates(
+                        ui,
+                        design,
+                        &[ObjectType::FontAttributes],
+                        |ui, potential_child| {
+                            ui.selectable_value(
+                                &mut self.font_attributes,
+                                potential_child.id(),
+                                format!("{:?}", u16::from(potential_child.id())),
+                            )
+                        },
+                    );
                 });
         });
         ui.checkbox(&mut self.options.transparent, "Transparent Background");
+        render_contrast_check(
+            ui,
+            design,
+            self.id,
+            self.font_attributes,
+            self.options.transparent,
+            self.background_colour,
+        );
         ui.checkbox(
             &mut self.options.display_leading_zeros,
             "Display Leading Zeros",
@@ -1272,8 +1747,9 @@ impl ConfigurableObject for InputNumber {
             &mut self.options.display_zero_as_blank,
             "Display Zero as Blank",
         );
-        // TODO: check if we have VT version 4 or later
-        // ui.checkbox(&mut self.options.truncate, "Truncate");
+        render_from_vt_version(ui, design, VtVersion::Version4, |ui| {
+            ui.checkbox(&mut self.options.truncate, "Truncate");
+        });
         ui.horizontal(|ui| {
             ui.label("Variable reference:");
             egui::ComboBox::from_id_salt("variable_reference")
@@ -1284,20 +1760,22 @@ impl ConfigurableObject for InputNumber {
                         NullableObjectId::NULL,
                         "None",
                     );
-                    for potential_child in design
-                        .get_pool()
-                        .objects_by_type(ObjectType::NumberVariable)
-                    {
-                        ui.selectable_value(
-                            &mut self.variable_reference,
-                            potential_child.id().into(),
-                            format!(
-                                "{:?}: {:?}",
-                                u16::from(potential_child.id()),
-                                potential_child.object_type()
-                            ),
-                        );
-                    }
+                    render_picker_candidates(
+                        ui,
+                        design,
+                        &[ObjectType::NumberVariable],
+                        |ui, potential_child| {
+                            ui.selectable_value(
+                                &mut self.variable_reference,
+                                potential_child.id().into(),
+                                format!(
+                                    "{:?}: {:?}",
+                                    u16::from(potential_child.id()),
+                                    potential_child.object_type()
+                                ),
+                            )
+                        },
+                    );
                 });
         });
         if self.variable_reference.0.is_none() {
@@ -1349,29 +1827,31 @@ impl ConfigurableObject for InputNumber {
                 "Right",
             );
         });
-        // TODO: check if we have VT version 4 or later
-        // ui.horizontal(|ui| {
-        //     ui.label("Vertical Justification:");
-        //     ui.radio_value(
-        //         &mut self.justification.vertical,
-        //         VerticalAlignment::Top,
-        //         "Top",
-        //     );
-        //     ui.radio_value(
-        //         &mut self.justification.vertical,
-        //         VerticalAlignment::Middle,
-        //         "Middle",
-        //     );
-        //     ui.radio_value(
-        //         &mut self.justification.vertical,
-        //         VerticalAlignment::Bottom,
-        //         "Bottom",
-        //     );
-        // });
+        render_from_vt_version(ui, design, VtVersion::Version4, |ui| {
+            ui.horizontal(|ui| {
+                ui.label("Vertical Justification:");
+                ui.radio_value(
+                    &mut self.justification.vertical,
+                    VerticalAlignment::Top,
+                    "Top",
+                );
+                ui.radio_value(
+                    &mut self.justification.vertical,
+                    VerticalAlignment::Middle,
+                    "Middle",
+                );
+                ui.radio_value(
+                    &mut self.justification.vertical,
+                    VerticalAlignment::Bottom,
+                    "Bottom",
+                );
+            });
+        });
 
         ui.checkbox(&mut self.options2.enabled, "Enabled");
-        // TODO: check if we have VT version 4 or later
-        // ui.checkbox(&mut self.options2.real_time_editing, "Real Time Editing");
+        render_from_vt_version(ui, design, VtVersion::Version4, |ui| {
+            ui.checkbox(&mut self.options2.real_time_editing, "Real Time Editing");
+        });
 
         ui.separator();
         ui.label("Macros:");
@@ -1407,20 +1887,22 @@ impl ConfigurableObject for InputList {
                         NullableObjectId::NULL,
                         "None",
                     );
-                    for potential_child in design
-                        .get_pool()
-                        .objects_by_type(ObjectType::NumberVariable)
-                    {
-                        ui.selectable_value(
-                            &mut self.variable_reference,
-                            potential_child.id().into(),
-                            format!(
-                                "{:?}: {:?}",
-                                u16::from(potential_child.id()),
-                                potential_child.object_type()
-                            ),
-                        );
-                    }
+                    render_picker_candidates(
+                        ui,
+                        design,
+                        &[ObjectType::NumberVariable],
+                        |ui, potential_child| {
+                            ui.selectable_value(
+                                &mut self.variable_reference,
+                                potential_child.id().into(),
+                                format!(
+                                    "{:?}: {:?}",
+                                    u16::from(potential_child.id()),
+                                    potential_child.object_type()
+                                ),
+                            )
+                        },
+                    );
                 });
         });
         if self.variable_reference.0.is_none() {
@@ -1429,8 +1911,9 @@ impl ConfigurableObject for InputList {
         }
 
         ui.checkbox(&mut self.options.enabled, "Enabled");
-        // TODO: check if we have VT version 4 or later
-        // ui.checkbox(&mut self.options.real_time_editing, "Real Time Editing");
+        render_from_vt_version(ui, design, VtVersion::Version4, |ui| {
+            ui.checkbox(&mut self.options.real_time_editing, "Real Time Editing");
+        });
 
         ui.separator();
         ui.label("List items:");
@@ -1438,7 +1921,7 @@ impl ConfigurableObject for InputList {
             ui,
             design,
             &mut self.list_items,
-            &Self::get_allowed_child_refs(VtVersion::Version3),
+            &Self::get_allowed_child_refs(design.get_vt_version()),
             self.id,
         );
 
@@ -1466,34 +1949,41 @@ impl ConfigurableObject for OutputString {
                 .text("Height")
                 .drag_value_speed(1.0),
         );
-        ui.add(
-            egui::Slider::new(&mut self.background_colour, 0..=255)
-                .text("Background Colour")
-                .drag_value_speed(1.0),
-        );
+        color_swatch_selector(ui, &mut self.background_colour, design, "Background Colour");
         ui.horizontal(|ui| {
             ui.label("Font attributes:");
             egui::ComboBox::from_id_salt("font_attributes")
                 .selected_text(format!("{:?}", u16::from(self.font_attributes)))
                 .show_ui(ui, |ui| {
-                    for potential_child in design
-                        .get_pool()
-                        .objects_by_type(ObjectType::FontAttributes)
-                    {
-                        ui.selectable_value(
-                            &mut self.font_attributes,
-                            potential_child.id(),
-                            format!("{:?}", u16::from(potential_child.id())),
-                        );
-                    }
+                    render_picker_candidates(
+                        ui,
+                        design,
+                        &[ObjectType::FontAttributes],
+                        |ui, potential_child| {
+                            ui.selectable_value(
+                                &mut self.font_attributes,
+                                potential_child.id(),
+                                format!("{:?}", u16::from(potential_child.id())),
+                            )
+                        },
+                    );
                 });
         });
         ui.checkbox(&mut self.options.transparent, "Transparent Background");
+        render_contrast_check(
+            ui,
+            design,
+            self.id,
+            self.font_attributes,
+            self.options.transparent,
+            self.background_colour,
+        );
         ui.checkbox(&mut self.options.auto_wrap, "Auto Wrap");
-        // TODO: check if we have VT version 4 or later
-        // if self.options.auto_wrap {
-        //     ui.checkbox(&mut self.options.wrap_on_hyphen, "Wrap on Hyphen");
-        // }
+        render_from_vt_version(ui, design, VtVersion::Version4, |ui| {
+            if self.options.auto_wrap {
+                ui.checkbox(&mut self.options.wrap_on_hyphen, "Wrap on Hyphen");
+            }
+        });
         ui.horizontal(|ui| {
             ui.label("Variable reference:");
             egui::ComboBox::from_id_salt("variable_reference")
@@ -1504,20 +1994,22 @@ impl ConfigurableObject for OutputString {
                         NullableObjectId::NULL,
                         "None",
                     );
-                    for potential_child in design
-                        .get_pool()
-                        .objects_by_type(ObjectType::StringVariable)
-                    {
-                        ui.selectable_value(
-                            &mut self.variable_reference,
-                            potential_child.id().into(),
-                            format!(
-                                "{:?}: {:?}",
-                                u16::from(potential_child.id()),
-                                potential_child.object_type()
-                            ),
-                        );
-                    }
+                    render_picker_candidates(
+                        ui,
+                        design,
+                        &[ObjectType::StringVariable],
+                        |ui, potential_child| {
+                            ui.selectable_value(
+                                &mut self.variable_reference,
+                                potential_child.id().into(),
+                                format!(
+                                    "{:?}: {:?}",
+                                    u16::from(potential_child.id()),
+                                    potential_child.object_type()
+                                ),
+                            )
+                        },
+                    );
                 });
         });
         ui.horizontal(|ui| {
@@ -1538,28 +2030,30 @@ impl ConfigurableObject for OutputString {
                 "Right",
             );
         });
-        // TODO: check if we have VT version 4 or later
-        // ui.horizontal(|ui| {
-        //     ui.label("Vertical Justification:");
-        //     ui.radio_value(
-        //         &mut self.justification.vertical,
-        //         VerticalAlignment::Top,
-        //         "Top",
-        //     );
-        //     ui.radio_value(
-        //         &mut self.justification.vertical,
-        //         VerticalAlignment::Middle,
-        //         "Middle",
-        //     );
-        //     ui.radio_value(
-        //         &mut self.justification.vertical,
-        //         VerticalAlignment::Bottom,
-        //         "Bottom",
-        //     );
-        // });
+        render_from_vt_version(ui, design, VtVersion::Version4, |ui| {
+            ui.horizontal(|ui| {
+                ui.label("Vertical Justification:");
+                ui.radio_value(
+                    &mut self.justification.vertical,
+                    VerticalAlignment::Top,
+                    "Top",
+                );
+                ui.radio_value(
+                    &mut self.justification.vertical,
+                    VerticalAlignment::Middle,
+                    "Middle",
+                );
+                ui.radio_value(
+                    &mut self.justification.vertical,
+                    VerticalAlignment::Bottom,
+                    "Bottom",
+                );
+            });
+        });
         if self.variable_reference.0.is_none() {
             ui.label("Initial value:");
             ui.text_edit_singleline(&mut self.value);
+            render_translations(ui, design, self.id, &self.value);
         }
         ui.separator();
         ui.label("Macros:");
@@ -1585,30 +2079,36 @@ impl ConfigurableObject for OutputNumber {
                 .text("Height")
                 .drag_value_speed(1.0),
         );
-        ui.add(
-            egui::Slider::new(&mut self.background_colour, 0..=255)
-                .text("Background Colour")
-                .drag_value_speed(1.0),
-        );
+        color_swatch_selector(ui, &mut self.background_colour, design, "Background Colour");
         ui.horizontal(|ui| {
             ui.label("Font attributes:");
             egui::ComboBox::from_id_salt("font_attributes")
                 .selected_text(format!("{:?}", u16::from(self.font_attributes)))
                 .show_ui(ui, |ui| {
-                    for potential_child in design
-                        .get_pool()
-                        .objects_by_type(ObjectType::FontAttributes)
-                    {
-                        ui.selectable_value(
-                            &mut self.font_attributes,
-                            potential_child.id(),
-                            format!("{:?}", u16::from(potential_child.id())),
-                        );
-                    }
+                    render_picker_candidates(
+                        ui,
+                        design,
+                        &[ObjectType::FontAttributes],
+                        |ui, potential_child| {
+                            ui.selectable_value(
+                                &mut self.font_attributes,
+                                potential_child.id(),
+                                format!("{:?}", u16::from(potential_child.id())),
+                            )
+                        },
+                    );
                 });
         });
 
         ui.checkbox(&mut self.options.transparent, "Transparent Background");
+        render_contrast_check(
+            ui,
+            design,
+            self.id,
+            self.font_attributes,
+            self.options.transparent,
+            self.background_colour,
+        );
         ui.checkbox(
             &mut self.options.display_leading_zeros,
             "Display Leading Zeros",
@@ -1617,8 +2117,9 @@ impl ConfigurableObject for OutputNumber {
             &mut self.options.display_zero_as_blank,
             "Display Zero as Blank",
         );
-        // TODO: check if we have VT version 4 or later
-        // ui.checkbox(&mut self.options.truncate, "Truncate");
+        render_from_vt_version(ui, design, VtVersion::Version4, |ui| {
+            ui.checkbox(&mut self.options.truncate, "Truncate");
+        });
         ui.horizontal(|ui| {
             ui.label("Variable reference:");
             egui::ComboBox::from_id_salt("variable_reference")
@@ -1629,20 +2130,22 @@ impl ConfigurableObject for OutputNumber {
                         NullableObjectId::NULL,
                         "None",
                     );
-                    for potential_child in design
-                        .get_pool()
-                        .objects_by_type(ObjectType::NumberVariable)
-                    {
-                        ui.selectable_value(
-                            &mut self.variable_reference,
-                            potential_child.id().into(),
-                            format!(
-                                "{:?}: {:?}",
-                                u16::from(potential_child.id()),
-                                potential_child.object_type()
-                            ),
-                        );
-                    }
+                    render_picker_candidates(
+                        ui,
+                        design,
+                        &[ObjectType::NumberVariable],
+                        |ui, potential_child| {
+                            ui.selectable_value(
+                                &mut self.variable_reference,
+                                potential_child.id().into(),
+                                format!(
+                                    "{:?}: {:?}",
+                                    u16::from(potential_child.id()),
+                                    potential_child.object_type()
+                                ),
+                            )
+                        },
+                    );
                 });
         });
         if self.variable_reference.0.is_none() {
@@ -1685,25 +2188,26 @@ impl ConfigurableObject for OutputNumber {
                 "Right",
             );
         });
-        // TODO: check if we have VT version 4 or later
-        // ui.horizontal(|ui| {
-        //     ui.label("Vertical Justification:");
-        //     ui.radio_value(
-        //         &mut self.justification.vertical,
-        //         VerticalAlignment::Top,
-        //         "Top",
-        //     );
-        //     ui.radio_value(
-        //         &mut self.justification.vertical,
-        //         VerticalAlignment::Middle,
-        //         "Middle",
-        //     );
-        //     ui.radio_value(
-        //         &mut self.justification.vertical,
-        //         VerticalAlignment::Bottom,
-        //         "Bottom",
-        //     );
-        // });
+        render_from_vt_version(ui, design, VtVersion::Version4, |ui| {
+            ui.horizontal(|ui| {
+                ui.label("Vertical Justification:");
+                ui.radio_value(
+                    &mut self.justification.vertical,
+                    VerticalAlignment::Top,
+                    "Top",
+                );
+                ui.radio_value(
+                    &mut self.justification.vertical,
+                    VerticalAlignment::Middle,
+                    "Middle",
+                );
+                ui.radio_value(
+                    &mut self.justification.vertical,
+                    VerticalAlignment::Bottom,
+                    "Bottom",
+                );
+            });
+        });
 
         ui.separator();
         ui.label("Macros:");
@@ -1741,20 +2245,22 @@ impl ConfigurableObject for OutputList {
                         NullableObjectId::NULL,
                         "None",
                     );
-                    for potential_child in design
-                        .get_pool()
-                        .objects_by_type(ObjectType::NumberVariable)
-                    {
-                        ui.selectable_value(
-                            &mut self.variable_reference,
-                            potential_child.id().into(),
-                            format!(
-                                "{:?}: {:?}",
-                                u16::from(potential_child.id()),
-                                potential_child.object_type()
-                            ),
-                        );
-                    }
+                    render_picker_candidates(
+                        ui,
+                        design,
+                        &[ObjectType::NumberVariable],
+                        |ui, potential_child| {
+                            ui.selectable_value(
+                                &mut self.variable_reference,
+                                potential_child.id().into(),
+                                format!(
+                                    "{:?}: {:?}",
+                                    u16::from(potential_child.id()),
+                                    potential_child.object_type()
+                                ),
+                            )
+                        },
+                    );
                 });
         });
 
@@ -1769,7 +2275,7 @@ impl ConfigurableObject for OutputList {
             ui,
             design,
             &mut self.list_items,
-            &Self::get_allowed_child_refs(VtVersion::Version3),
+            &Self::get_allowed_child_refs(design.get_vt_version()),
             self.id,
         );
 
@@ -1793,24 +2299,26 @@ impl ConfigurableObject for OutputLine {
             egui::ComboBox::from_id_salt("line_attributes")
                 .selected_text(format!("{:?}", u16::from(self.line_attributes)))
                 .show_ui(ui, |ui| {
-                    for potential_child in design
-                        .get_pool()
-                        .objects_by_type(ObjectType::LineAttributes)
-                    {
-                        ui.selectable_value(
-                            &mut self.line_attributes,
-                            potential_child.id(),
-                            format!(
-                                "{:?}: {:?}",
-                                u16::from(potential_child.id()),
-                                potential_child.object_type()
-                            ),
-                        );
-                    }
+                    render_picker_candidates(
+                        ui,
+                        design,
+                        &[ObjectType::LineAttributes],
+                        |ui, potential_child| {
+                            ui.selectable_value(
+                                &mut self.line_attributes,
+                                potential_child.id(),
+                                format!(
+                                    "{:?}: {:?}",
+                                    u16::from(potential_child.id()),
+                                    potential_child.object_type()
+                                ),
+                            )
+                        },
+                    );
                 });
 
             // If a valid line_attributes object is selected, provide a link to navigate there
-            if let Some(obj) = design.get_pool().object_by_id(self.line_attributes) {
+            if let Some(obj) = design.get_object(self.line_attributes) {
                 if ui.link("(view)").clicked() {
                     *design.get_mut_selected().borrow_mut() = self.line_attributes.into();
                 }
@@ -1864,24 +2372,26 @@ impl ConfigurableObject for OutputRectangle {
             egui::ComboBox::from_id_salt("line_attributes_selector")
                 .selected_text(format!("{:?}", u16::from(self.line_attributes)))
                 .show_ui(ui, |ui| {
-                    for potential_child in design
-                        .get_pool()
-                        .objects_by_type(ObjectType::LineAttributes)
-                    {
-                        ui.selectable_value(
-                            &mut self.line_attributes,
-                            potential_child.id(),
-                            format!(
-                                "{:?}: {:?}",
-                                u16::from(potential_child.id()),
-                                potential_child.object_type()
-                            ),
-                        );
-                    }
+                    render_picker_candidates(
+                        ui,
+                        design,
+                        &[ObjectType::LineAttributes],
+                        |ui, potential_child| {
+                            ui.selectable_value(
+                                &mut self.line_attributes,
+                                potential_child.id(),
+                                format!(
+                                    "{:?}: {:?}",
+                                    u16::from(potential_child.id()),
+                                    potential_child.object_type()
+                                ),
+                            )
+                        },
+                    );
                 });
 
             // Link to view the selected line attributes object
-            if let Some(obj) = design.get_pool().object_by_id(self.line_attributes) {
+            if let Some(obj) = design.get_object(self.line_attributes) {
                 if ui.link("(view)").clicked() {
                     *design.get_mut_selected().borrow_mut() = self.line_attributes.into();
                 }
@@ -1917,25 +2427,27 @@ impl ConfigurableObject for OutputRectangle {
                 )
                 .show_ui(ui, |ui| {
                     ui.selectable_value(&mut self.fill_attributes, NullableObjectId::NULL, "None");
-                    for potential_child in design
-                        .get_pool()
-                        .objects_by_type(ObjectType::FillAttributes)
-                    {
-                        ui.selectable_value(
-                            &mut self.fill_attributes,
-                            potential_child.id().into(),
-                            format!(
-                                "{:?}: {:?}",
-                                u16::from(potential_child.id()),
-                                potential_child.object_type()
-                            ),
-                        );
-                    }
+                    render_picker_candidates(
+                        ui,
+                        design,
+                        &[ObjectType::FillAttributes],
+                        |ui, potential_child| {
+                            ui.selectable_value(
+                                &mut self.fill_attributes,
+                                potential_child.id().into(),
+                                format!(
+                                    "{:?}: {:?}",
+                                    u16::from(potential_child.id()),
+                                    potential_child.object_type()
+                                ),
+                            )
+                        },
+                    );
                 });
 
             // Link to view the selected fill attributes object if present
             if let Some(id) = self.fill_attributes.into() {
-                if let Some(obj) = design.get_pool().object_by_id(id) {
+                if let Some(obj) = design.get_object(id) {
                     if ui.link("(view)").clicked() {
                         *design.get_mut_selected().borrow_mut() = id.into();
                     }
@@ -1965,24 +2477,26 @@ impl ConfigurableObject for OutputEllipse {
             egui::ComboBox::from_id_salt("line_attributes_selector")
                 .selected_text(format!("{:?}", u16::from(self.line_attributes)))
                 .show_ui(ui, |ui| {
-                    for potential_child in design
-                        .get_pool()
-                        .objects_by_type(ObjectType::LineAttributes)
-                    {
-                        ui.selectable_value(
-                            &mut self.line_attributes,
-                            potential_child.id(),
-                            format!(
-                                "{:?}: {:?}",
-                                u16::from(potential_child.id()),
-                                potential_child.object_type()
-                            ),
-                        );
-                    }
+                    render_picker_candidates(
+                        ui,
+                        design,
+                        &[ObjectType::LineAttributes],
+                        |ui, potential_child| {
+                            ui.selectable_value(
+                                &mut self.line_attributes,
+                                potential_child.id(),
+                                format!(
+                                    "{:?}: {:?}",
+                                    u16::from(potential_child.id()),
+                                    potential_child.object_type()
+                                ),
+                            )
+                        },
+                    );
                 });
 
             // Link to navigate to the chosen line attributes object
-            if let Some(obj) = design.get_pool().object_by_id(self.line_attributes) {
+            if let Some(obj) = design.get_object(self.line_attributes) {
                 if ui.link("(view)").clicked() {
                     *design.get_mut_selected().borrow_mut() = self.line_attributes.into();
                 }
@@ -2033,25 +2547,27 @@ impl ConfigurableObject for OutputEllipse {
                 )
                 .show_ui(ui, |ui| {
                     ui.selectable_value(&mut self.fill_attributes, NullableObjectId::NULL, "None");
-                    for potential_child in design
-                        .get_pool()
-                        .objects_by_type(ObjectType::FillAttributes)
-                    {
-                        ui.selectable_value(
-                            &mut self.fill_attributes,
-                            potential_child.id().into(),
-                            format!(
-                                "{:?}: {:?}",
-                                u16::from(potential_child.id()),
-                                potential_child.object_type()
-                            ),
-                        );
-                    }
+                    render_picker_candidates(
+                        ui,
+                        design,
+                        &[ObjectType::FillAttributes],
+                        |ui, potential_child| {
+                            ui.selectable_value(
+                                &mut self.fill_attributes,
+                                potential_child.id().into(),
+                                format!(
+                                    "{:?}: {:?}",
+                                    u16::from(potential_child.id()),
+                                    potential_child.object_type()
+                                ),
+                            )
+                        },
+                    );
                 });
 
             // Link to view the chosen fill attributes object, if any
             if let Some(id) = self.fill_attributes.into() {
-                if let Some(obj) = design.get_pool().object_by_id(id) {
+                if let Some(obj) = design.get_object(id) {
                     if ui.link("(view)").clicked() {
                         *design.get_mut_selected().borrow_mut() = id.into();
                     }
@@ -2092,24 +2608,26 @@ impl ConfigurableObject for OutputPolygon {
             egui::ComboBox::from_id_salt("line_attributes_selector")
                 .selected_text(format!("{:?}", u16::from(self.line_attributes)))
                 .show_ui(ui, |ui| {
-                    for potential_child in design
-                        .get_pool()
-                        .objects_by_type(ObjectType::LineAttributes)
-                    {
-                        ui.selectable_value(
-                            &mut self.line_attributes,
-                            potential_child.id(),
-                            format!(
-                                "{:?}: {:?}",
-                                u16::from(potential_child.id()),
-                                potential_child.object_type()
-                            ),
-                        );
-                    }
+                    render_picker_candidates(
+                        ui,
+                        design,
+                        &[ObjectType::LineAttributes],
+                        |ui, potential_child| {
+                            ui.selectable_value(
+                                &mut self.line_attributes,
+                                potential_child.id(),
+                                format!(
+                                    "{:?}: {:?}",
+                                    u16::from(potential_child.id()),
+                                    potential_child.object_type()
+                                ),
+                            )
+                        },
+                    );
                 });
 
             // Link to navigate to the chosen line attributes object
-            if let Some(obj) = design.get_pool().object_by_id(self.line_attributes) {
+            if let Some(obj) = design.get_object(self.line_attributes) {
                 if ui.link("(view)").clicked() {
                     *design.get_mut_selected().borrow_mut() = self.line_attributes.into();
                 }
@@ -2128,25 +2646,27 @@ impl ConfigurableObject for OutputPolygon {
                 )
                 .show_ui(ui, |ui| {
                     ui.selectable_value(&mut self.fill_attributes, NullableObjectId::NULL, "None");
-                    for potential_child in design
-                        .get_pool()
-                        .objects_by_type(ObjectType::FillAttributes)
-                    {
-                        ui.selectable_value(
-                            &mut self.fill_attributes,
-                            potential_child.id().into(),
-                            format!(
-                                "{:?}: {:?}",
-                                u16::from(potential_child.id()),
-                                potential_child.object_type()
-                            ),
-                        );
-                    }
+                    render_picker_candidates(
+                        ui,
+                        design,
+                        &[ObjectType::FillAttributes],
+                        |ui, potential_child| {
+                            ui.selectable_value(
+                                &mut self.fill_attributes,
+                                potential_child.id().into(),
+                                format!(
+                                    "{:?}: {:?}",
+                                    u16::from(potential_child.id()),
+                                    potential_child.object_type()
+                                ),
+                            )
+                        },
+                    );
                 });
 
             // Link to view the chosen fill attributes object
             if let Some(id) = self.fill_attributes.into() {
-                if let Some(obj) = design.get_pool().object_by_id(id) {
+                if let Some(obj) = design.get_object(id) {
                     if ui.link("(view)").clicked() {
                         *design.get_mut_selected().borrow_mut() = id.into();
                     }
@@ -2230,22 +2750,15 @@ impl ConfigurableObject for OutputMeter {
                 .drag_value_speed(1.0),
         );
 
-        ui.add(
-            egui::Slider::new(&mut self.needle_colour, 0..=255)
-                .text("Needle Colour")
-                .drag_value_speed(1.0),
-        );
+        color_swatch_selector(ui, &mut self.needle_colour, design, "Needle Colour");
 
-        ui.add(
-            egui::Slider::new(&mut self.border_colour, 0..=255)
-                .text("Border Colour")
-                .drag_value_speed(1.0),
-        );
+        color_swatch_selector(ui, &mut self.border_colour, design, "Border Colour");
 
-        ui.add(
-            egui::Slider::new(&mut self.arc_and_tick_colour, 0..=255)
-                .text("Arc & Tick Colour")
-                .drag_value_speed(1.0),
+        color_swatch_selector(
+            ui,
+            &mut self.arc_and_tick_colour,
+            design,
+            "Arc & Tick Colour",
         );
 
         ui.checkbox(&mut self.options.draw_arc, "Draw Arc");
@@ -2308,20 +2821,22 @@ impl ConfigurableObject for OutputMeter {
                         NullableObjectId::NULL,
                         "None",
                     );
-                    for potential_child in design
-                        .get_pool()
-                        .objects_by_type(ObjectType::NumberVariable)
-                    {
-                        ui.selectable_value(
-                            &mut self.variable_reference,
-                            potential_child.id().into(),
-                            format!(
-                                "{:?}: {:?}",
-                                u16::from(potential_child.id()),
-                                potential_child.object_type()
-                            ),
-                        );
-                    }
+                    render_picker_candidates(
+                        ui,
+                        design,
+                        &[ObjectType::NumberVariable],
+                        |ui, potential_child| {
+                            ui.selectable_value(
+                                &mut self.variable_reference,
+                                potential_child.id().into(),
+                                format!(
+                                    "{:?}: {:?}",
+                                    u16::from(potential_child.id()),
+                                    potential_child.object_type()
+                                ),
+                            )
+                        },
+                    );
                 });
         });
 
@@ -2357,16 +2872,13 @@ impl ConfigurableObject for OutputLinearBarGraph {
                 .drag_value_speed(1.0),
         );
 
-        ui.add(
-            egui::Slider::new(&mut self.colour, 0..=255)
-                .text("Bar Colour")
-                .drag_value_speed(1.0),
-        );
+        color_swatch_selector(ui, &mut self.colour, design, "Bar Colour");
         if self.options.draw_target_line {
-            ui.add(
-                egui::Slider::new(&mut self.target_line_colour, 0..=255)
-                    .text("Target Line Colour")
-                    .drag_value_speed(1.0),
+            color_swatch_selector(
+                ui,
+                &mut self.target_line_colour,
+                design,
+                "Target Line Colour",
             );
         }
 
@@ -2447,20 +2959,22 @@ impl ConfigurableObject for OutputLinearBarGraph {
                         NullableObjectId::NULL,
                         "None",
                     );
-                    for potential_child in design
-                        .get_pool()
-                        .objects_by_type(ObjectType::NumberVariable)
-                    {
-                        ui.selectable_value(
-                            &mut self.variable_reference,
-                            potential_child.id().into(),
-                            format!(
-                                "{:?}: {:?}",
-                                u16::from(potential_child.id()),
-                                potential_child.object_type()
-                            ),
-                        );
-                    }
+                    render_picker_candidates(
+                        ui,
+                        design,
+                        &[ObjectType::NumberVariable],
+                        |ui, potential_child| {
+                            ui.selectable_value(
+                                &mut self.variable_reference,
+                                potential_child.id().into(),
+                                format!(
+                                    "{:?}: {:?}",
+                                    u16::from(potential_child.id()),
+                                    potential_child.object_type()
+                                ),
+                            )
+                        },
+                    );
                 });
         });
 
@@ -2484,20 +2998,22 @@ impl ConfigurableObject for OutputLinearBarGraph {
                         NullableObjectId::NULL,
                         "None",
                     );
-                    for potential_child in design
-                        .get_pool()
-                        .objects_by_type(ObjectType::NumberVariable)
-                    {
-                        ui.selectable_value(
-                            &mut self.target_value_variable_reference,
-                            potential_child.id().into(),
-                            format!(
-                                "{:?}: {:?}",
-                                u16::from(potential_child.id()),
-                                potential_child.object_type()
-                            ),
-                        );
-                    }
+                    render_picker_candidates(
+                        ui,
+                        design,
+                        &[ObjectType::NumberVariable],
+                        |ui, potential_child| {
+                            ui.selectable_value(
+                                &mut self.target_value_variable_reference,
+                                potential_child.id().into(),
+                                format!(
+                                    "{:?}: {:?}",
+                                    u16::from(potential_child.id()),
+                                    potential_child.object_type()
+                                ),
+                            )
+                        },
+                    );
                 });
         });
 
@@ -2533,16 +3049,13 @@ impl ConfigurableObject for OutputArchedBarGraph {
                 .drag_value_speed(1.0),
         );
 
-        ui.add(
-            egui::Slider::new(&mut self.colour, 0..=255)
-                .text("Bar Colour")
-                .drag_value_speed(1.0),
-        );
+        color_swatch_selector(ui, &mut self.colour, design, "Bar Colour");
         if self.options.draw_target_line {
-            ui.add(
-                egui::Slider::new(&mut self.target_line_colour, 0..=255)
-                    .text("Target Line Colour")
-                    .drag_value_speed(1.0),
+            color_swatch_selector(
+                ui,
+                &mut self.target_line_colour,
+                design,
+                "Target Line Colour",
             );
         }
 
@@ -2645,20 +3158,22 @@ impl ConfigurableObject for OutputArchedBarGraph {
                         NullableObjectId::NULL,
                         "None",
                     );
-                    for potential_child in design
-                        .get_pool()
-                        .objects_by_type(ObjectType::NumberVariable)
-                    {
-                        ui.selectable_value(
-                            &mut self.variable_reference,
-                            potential_child.id().into(),
-                            format!(
-                                "{:?}: {:?}",
-                                u16::from(potential_child.id()),
-                                potential_child.object_type()
-                            ),
-                        );
-                    }
+                    render_picker_candidates(
+                        ui,
+                        design,
+                        &[ObjectType::NumberVariable],
+                        |ui, potential_child| {
+                            ui.selectable_value(
+                                &mut self.variable_reference,
+                                potential_child.id().into(),
+                                format!(
+                                    "{:?}: {:?}",
+                                    u16::from(potential_child.id()),
+                                    potential_child.object_type()
+                                ),
+                            )
+                        },
+                    );
                 });
         });
 
@@ -2682,20 +3197,22 @@ impl ConfigurableObject for OutputArchedBarGraph {
                         NullableObjectId::NULL,
                         "None",
                     );
-                    for potential_child in design
-                        .get_pool()
-                        .objects_by_type(ObjectType::NumberVariable)
-                    {
-                        ui.selectable_value(
-                            &mut self.target_value_variable_reference,
-                            potential_child.id().into(),
-                            format!(
-                                "{:?}: {:?}",
-                                u16::from(potential_child.id()),
-                                potential_child.object_type()
-                            ),
-                        );
-                    }
+                    render_picker_candidates(
+                        ui,
+                        design,
+                        &[ObjectType::NumberVariable],
+                        |ui, potential_child| {
+                            ui.selectable_value(
+                                &mut self.target_value_variable_reference,
+                                potential_child.id().into(),
+                                format!(
+                                    "{:?}: {:?}",
+                                    u16::from(potential_child.id()),
+                                    potential_child.object_type()
+                                ),
+                            )
+                        },
+                    );
                 });
         });
 
@@ -2729,138 +3246,28 @@ impl ConfigurableObject for PictureGraphic {
         ui.label(format!("Data Size (bytes): {}", self.data.len()));
         ui.horizontal(|ui| {
             ui.label("Format:");
-            if ui
-                .radio(
-                    self.format == PictureGraphicFormat::Monochrome,
-                    "Monochrome",
-                )
-                .clicked()
-            {
-                match self.format {
-                    PictureGraphicFormat::FourBit => {
-                        self.data = self
-                            .data_as_raw_encoded()
-                            .windows(4)
-                            .step_by(4)
-                            .flat_map(|chunk| {
-                                let mut byte = 0;
-                                for (i, bit) in chunk.iter().enumerate() {
-                                    for j in 0..2 {
-                                        if *bit & (1 << j) != 0 {
-                                            byte |= 1 << (i * 2 + j);
-                                        }
-                                    }
-                                }
-                                vec![byte]
-                            })
-                            .collect();
-                    }
-                    PictureGraphicFormat::EightBit => {
-                        self.data = self
-                            .data_as_raw_encoded()
-                            .windows(8)
-                            .step_by(8)
-                            .flat_map(|chunk| {
-                                let mut byte = 0;
-                                for (i, bit) in chunk.iter().enumerate() {
-                                    if *bit != 0 {
-                                        byte |= 1 << i;
-                                    }
-                                }
-                                vec![byte]
-                            })
-                            .collect();
-                    }
-                    _ => {}
-                }
-                self.format = PictureGraphicFormat::Monochrome;
-                self.options.data_code_type = DataCodeType::Raw;
-            }
-            if ui
-                .radio(self.format == PictureGraphicFormat::FourBit, "4-bit colour")
-                .clicked()
-            {
-                match self.format {
-                    PictureGraphicFormat::Monochrome => {
-                        self.data = self
-                            .data_as_raw_encoded()
-                            .iter()
-                            .flat_map(|value| {
-                                let mut result = vec![];
-                                for idx in 0..8 {
-                                    let bit_color = value << idx & 0x01;
-                                    if idx % 2 == 0 {
-                                        result.push(bit_color);
-                                    } else if let Some(last) = result.last_mut() {
-                                        *last |= bit_color >> 4;
-                                    }
-                                }
-                                result
-                            })
-                            .collect();
-                    }
-                    PictureGraphicFormat::EightBit => {
-                        self.data = self
-                            .data_as_raw_encoded()
-                            .windows(2)
-                            .step_by(2)
-                            .flat_map(|values| {
-                                let high = (values[0] & 0x0F) << 4;
-                                let low = values[1] & 0x0F;
-                                vec![high | low]
-                            })
-                            .collect();
-                    }
-                    _ => {}
-                }
-                self.format = PictureGraphicFormat::FourBit;
-                self.options.data_code_type = DataCodeType::Raw;
-            }
-            if ui
-                .radio(
-                    self.format == PictureGraphicFormat::EightBit,
-                    "8-bit colour",
-                )
-                .clicked()
-            {
-                match self.format {
-                    PictureGraphicFormat::Monochrome => {
-                        self.data = self
-                            .data_as_raw_encoded()
-                            .iter()
-                            .flat_map(|value| {
-                                let mut result = vec![];
-                                for bit in 0..8 {
-                                    result.push(value >> bit & 0x01);
-                                }
-                                result
-                            })
-                            .collect();
-                    }
-                    PictureGraphicFormat::FourBit => {
-                        self.data = self
-                            .data_as_raw_encoded()
-                            .iter()
-                            .flat_map(|value| {
-                                let high = (value >> 4) & 0x0F;
-                                let low = value & 0x0F;
-                                vec![high, low]
-                            })
-                            .collect();
-                    }
-                    _ => {}
+            for (format, label) in [
+                (PictureGraphicFormat::Monochrome, "Monochrome"),
+                (PictureGraphicFormat::FourBit, "4-bit colour"),
+                (PictureGraphicFormat::EightBit, "8-bit colour"),
+            ] {
+                // The pixels are converted by the project, from the linked image if possible
+                if ui.radio(self.format == format, label).clicked() && self.format != format {
+                    design.request_picture_format(self.id, format);
                 }
-                self.format = PictureGraphicFormat::EightBit;
-                self.options.data_code_type = DataCodeType::Raw;
             }
         });
+        if design.get_image_source(self.id).is_none() {
+            ui.weak("Not linked to an image, colours may be lost when changing the format");
+        }
         ui.horizontal(|ui| {
             ui.checkbox(&mut self.options.transparent, "Transparent Pixels");
             if self.options.transparent {
-                ui.add(
-                    egui::Slider::new(&mut self.transparency_colour, 0..=255)
-                        .text("Transparent Colour")
-                        .drag_value_speed(1.0),
+                color_swatch_selector(
+                    ui,
+                    &mut self.transparency_colour,
+                    design,
+                    "Transparent Colour",
                 );
             }
         });
@@ -2875,6 +3282,7 @@ impl ConfigurableObject for PictureGraphic {
         {
             design.request_image_load(self.id);
         }
+        render_scaled_variants(ui, design, self);
 
         ui.separator();
         ui.label("Macros:");
@@ -2906,6 +3314,7 @@ impl ConfigurableObject for StringVariable {
             ui.label("Initial Value:");
             ui.text_edit_singleline(&mut self.value);
         });
+        render_translations(ui, design, self.id, &self.value);
     }
 }
 
@@ -2913,14 +3322,10 @@ impl ConfigurableObject for FontAttributes {
     fn render_parameters(&mut self, ui: &mut egui::Ui, design: &EditorProject) {
         render_object_id(ui, &mut self.id, design);
 
-        ui.add(
-            egui::Slider::new(&mut self.font_colour, 0..=255)
-                .text("Font Colour")
-                .drag_value_speed(1.0),
-        );
+        color_swatch_selector(ui, &mut self.font_colour, design, "Font Colour");
 
-        // let is_proportional = self.font_style.proportional; // TODO: check if we have VT version 4 or later
-        let is_proportional = false;
+        let is_proportional =
+            design.supports_vt_version(VtVersion::Version4) && self.font_style.proportional;
 
         // If proportional bit is set, font_size is proportional, otherwise non-proportional.
         if is_proportional {
@@ -2983,7 +3388,11 @@ impl ConfigurableObject for FontAttributes {
             const PROPRIETARY_RANGE_V3_AND_PRIOR: std::ops::RangeInclusive<u8> = 255..=255;
             const PROPRIETARY_RANGE_V4_AND_LATER: std::ops::RangeInclusive<u8> = 240..=255;
 
-            let range = PROPRIETARY_RANGE_V3_AND_PRIOR; // TODO: check if we have VT version 4 or later
+            let range = if design.supports_vt_version(VtVersion::Version4) {
+                PROPRIETARY_RANGE_V4_AND_LATER
+            } else {
+                PROPRIETARY_RANGE_V3_AND_PRIOR
+            };
 
             let mut raw_value = match self.font_type {
                 FontType::Proprietary(v) => v,
@@ -3008,16 +3417,17 @@ impl ConfigurableObject for FontAttributes {
                 egui::ComboBox::from_id_salt("font_type")
                     .selected_text(format!("{:?}", self.font_type))
                     .show_ui(ui, |ui| {
-                        // Known fonts
-                        for value in &[
-                            FontType::Latin1,
-                            FontType::Latin9,
-                            // TODO: check if we have VT version 4 or later
-                            // FontType::Latin2,
-                            // FontType::Latin4,
-                            // FontType::Cyrillic,
-                            // FontType::Greek,
-                        ] {
+                        // Known fonts, the fonts after Latin 9 were added in VT version 4
+                        let mut fonts = vec![FontType::Latin1, FontType::Latin9];
+                        if design.supports_vt_version(VtVersion::Version4) {
+                            fonts.extend([
+                                FontType::Latin2,
+                                FontType::Latin4,
+                                FontType::Cyrillic,
+                                FontType::Greek,
+                            ]);
+                        }
+                        for value in &fonts {
                             if ui
                                 .selectable_label(&self.font_type == value, format!("{:?}", value))
                                 .clicked()
@@ -3038,7 +3448,9 @@ impl ConfigurableObject for FontAttributes {
         ui.checkbox(&mut self.font_style.inverted, "Inverted");
         ui.checkbox(&mut self.font_style.flashing_inverted, "Flashing Inverted");
         ui.checkbox(&mut self.font_style.flashing_hidden, "Flashing Hidden");
-        // ui.checkbox(&mut self.font_style.proportional, "Proportional"); // TODO: check if we have VT version 4 or later
+        render_from_vt_version(ui, design, VtVersion::Version4, |ui| {
+            ui.checkbox(&mut self.font_style.proportional, "Proportional");
+        });
 
         ui.separator();
         ui.label("Macros:");
@@ -3055,11 +3467,7 @@ impl ConfigurableObject for LineAttributes {
     fn render_parameters(&mut self, ui: &mut egui::Ui, design: &EditorProject) {
         render_object_id(ui, &mut self.id, design);
 
-        ui.add(
-            egui::Slider::new(&mut self.line_colour, 0..=255)
-                .text("Line Colour")
-                .drag_value_speed(1.0),
-        );
+        color_swatch_selector(ui, &mut self.line_colour, design, "Line Colour");
 
         ui.add(
             egui::Slider::new(&mut self.line_width, 0..=255)
@@ -3134,11 +3542,7 @@ impl ConfigurableObject for FillAttributes {
         if self.fill_type == 2 {
             ui.label("Fill Colour:")
                 .on_hover_text("Select the colour index (0-255) to use for filling the area.");
-            ui.add(
-                egui::Slider::new(&mut self.fill_colour, 0..=255)
-                    .text("Fill Colour")
-                    .drag_value_speed(1.0),
-            );
+            color_swatch_selector(ui, &mut self.fill_colour, design, "Fill Colour");
         } else if self.fill_type == 3 {
             ui.label("Fill Pattern (PictureGraphic Object):")
                 .on_hover_text("Select a PictureGraphic object to use as a pattern.\n\
@@ -3155,7 +3559,7 @@ impl ConfigurableObject for FillAttributes {
                 );
 
                 if let Some(pattern_id) = self.fill_pattern.0 {
-                    if let Some(obj) = design.get_pool().object_by_id(pattern_id) {
+                    if let Some(obj) = design.get_object(pattern_id) {
                         if ui.link("(view)").clicked() {
                             *design.get_mut_selected().borrow_mut() = pattern_id.into();
                         }
@@ -3213,6 +3617,112 @@ impl ConfigurableObject for InputAttributes {
     }
 }
 
+/// Describe the characters of a code plane range, e.g. "'A' - 'Z'"
+fn character_range_preview(plane: u8, range: &CharacterRange) -> String {
+    let to_char = |character: u16| {
+        char::from_u32(((plane as u32) << 16) | character as u32)
+            .filter(|c| !c.is_control())
+            .map_or(format!("U+{:04X}", character), |c| format!("'{}'", c))
+    };
+    format!(
+        "{} - {}",
+        to_char(range.first_character),
+        to_char(range.last_character)
+    )
+}
+
+impl ConfigurableObject for ExtendedInputAttributes {
+    fn render_parameters(&mut self, ui: &mut egui::Ui, design: &EditorProject) {
+        render_object_id(ui, &mut self.id, design);
+
+        ui.horizontal(|ui| {
+            ui.label("Validation Type:");
+            ui.radio_value(
+                &mut self.validation_type,
+                ValidationType::ValidCharacters,
+                "Valid Characters",
+            );
+            ui.radio_value(
+                &mut self.validation_type,
+                ValidationType::InvalidCharacters,
+                "Invalid Characters",
+            );
+        });
+
+        ui.separator();
+        ui.label("Code planes:");
+        let mut plane_idx = 0;
+        while plane_idx < self.code_planes.len() {
+            let duplicate = self.code_planes[..plane_idx]
+                .iter()
+                .any(|other| other.number == self.code_planes[plane_idx].number);
+
+            ui.push_id(plane_idx, |ui| {
+                ui.horizontal(|ui| {
+                    ui.label("Plane:");
+                    ui.add(
+                        egui::DragValue::new(&mut self.code_planes[plane_idx].number).range(0..=16),
+                    );
+                    if duplicate {
+                        ui.colored_label(egui::Color32::RED, "Plane is listed twice");
+                    }
+                    render_index_modifiers(ui, plane_idx, &mut self.code_planes);
+                });
+            });
+            let Some(code_plane) = self.code_planes.get_mut(plane_idx) else {
+                break;
+            };
+
+            egui::Grid::new(("character_ranges", plane_idx))
+                .striped(true)
+                .min_col_width(0.0)
+                .show(ui, |ui| {
+                    let mut range_idx = 0;
+                    while range_idx < code_plane.character_ranges.len() {
+                        let range = &mut code_plane.character_ranges[range_idx];
+                        ui.label(" - ");
+                        ui.add(
+                            egui::DragValue::new(&mut range.first_character)
+                                .hexadecimal(4, false, true)
+                                .prefix("0x"),
+                        );
+                        ui.add(
+                            egui::DragValue::new(&mut range.last_character)
+                                .hexadecimal(4, false, true)
+                                .prefix("0x"),
+                        );
+                        if range.first_character > range.last_character {
+                            ui.colored_label(egui::Color32::RED, "First is after last");
+                        } else {
+                            ui.weak(character_range_preview(code_plane.number, range));
+                        }
+                        render_index_modifiers(ui, range_idx, &mut code_plane.character_ranges);
+                        range_idx += 1;
+                        ui.end_row();
+                    }
+                });
+            if ui.button("Add character range").clicked() {
+                code_plane.character_ranges.push(CharacterRange {
+                    first_character: 0x20,
+                    last_character: 0x7E,
+                });
+            }
+            ui.add_space(4.0);
+            plane_idx += 1;
+        }
+
+        if ui.button("Add code plane").clicked() {
+            let number = (0..=16u8)
+                .find(|number| !self.code_planes.iter().any(|p| p.number == *number))
+                .unwrap_or(0);
+            self.code_planes.push(CodePlane {
+                number,
+                character_ranges: Vec::new(),
+            });
+        }
+    }
+}
+
 impl ConfigurableObject for ObjectPointer {
     fn render_parameters(&mut self, ui: &mut egui::Ui, design: &EditorProject) {
         render_object_id(ui, &mut self.id, design);
@@ -3227,11 +3737,14 @@ impl ConfigurableObject for ObjectPointer {
                         .parent_objects(self.id)
                         .iter()
                         .flat_map(|parent_obj| {
-                            get_allowed_child_refs(parent_obj.object_type(), VtVersion::Version3)
-                                .into_iter()
+                            get_allowed_child_refs(
+                                parent_obj.object_type(),
+                                design.get_vt_version(),
+                            )
+                            .into_iter()
                         })
                         .collect();
-                    for potential_child in design.get_pool().objects_by_types(&object_types) {
+                    for potential_child in design.get_objects_by_types(&object_types) {
                         ui.selectable_value(
                             &mut self.value,
                             potential_child.id().into(),
@@ -3244,7 +3757,7 @@ impl ConfigurableObject for ObjectPointer {
                     }
                 });
             if let Some(id) = self.value.into() {
-                if let Some(object) = design.get_pool().object_by_id(id) {
+                if let Some(object) = design.get_object(id) {
                     if ui.link(format!("{:?}", object.object_type())).clicked() {
                         *design.get_mut_selected().borrow_mut() = id.into();
                     }
@@ -3334,8 +3847,8 @@ impl ConfigurableObject for Macro {
                 .selected_text("Select command")
                 .show_ui(ui, |ui| {
                     for &(code, name, version) in ALLOWED_MACRO_COMMANDS {
-                        if version > VtVersion::Version3 {
-                            continue; // TODO: check which version pool we have
+                        if !design.supports_vt_version(version) {
+                            continue;
                         }
 
                         if ui
@@ -3347,18 +3860,170 @@ impl ConfigurableObject for Macro {
                     }
                 });
         });
+        render_child_move_helper(ui, self, design);
     }
 }
 
-impl ConfigurableObject for AuxiliaryFunctionType2 {
+/// Build a command moving a child at runtime by picking the child on the canvas and dragging it
+/// to where the macro moves it, instead of entering the IDs and positions as bytes
+fn render_child_move_helper(ui: &mut egui::Ui, macro_object: &mut Macro, design: &EditorProject) {
+    ui.separator();
+    let Some(pick) = design
+        .get_child_move_pick()
+        .filter(|pick| pick.macro_id == macro_object.id)
+    else {
+        ui.horizontal(|ui| {
+            ui.label("Move a child:");
+            for command in ChildMoveCommand::ALL {
+                if ui
+                    .button(command.name())
+                    .on_hover_text(
+                        "Pick the child on the canvas and drag it to where the macro moves it",
+                    )
+                    .clicked()
+                {
+                    design.set_child_move_pick(Some(ChildMovePick {
+                        macro_id: macro_object.id,
+                        command,
+                        target: None,
+                    }));
+                }
+            }
+        });
+        return;
+    };
+
+    ui.strong(pick.command.name());
+    match &pick.target {
+        None => {
+            ui.label("Click the child to move on the canvas.");
+        }
+        Some(target) => {
+            let name = |id: ObjectId| match design.get_pool().object_by_id(id) {
+                Some(object) => design.get_object_info(object).get_name(object),
+                None => id.value().to_string(),
+            };
+            ui.label(format!("{} in {}", name(target.child), name(target.parent)));
+            ui.label(format!(
+                "({}, {}) \u{2192} ({}, {}), drag the child on the canvas",
+                target.from.0, target.from.1, target.to.0, target.to.1
+            ));
+            match target.command_bytes(pick.command) {
+                Ok(bytes) => {
+                    ui.monospace(
+                        bytes
+                            .iter()
+                            .map(|byte| format!("{:02X}", byte))
+                            .collect::<Vec<_>>()
+                            .join(" "),
+                    );
+                    if ui.button("Add command").clicked() {
+                        macro_object.commands.extend(bytes);
+                        design.set_child_move_pick(None);
+                    }
+                }
+                Err(e) => {
+                    ui.colored_label(egui::Color32::RED, e);
+                }
+            }
+        }
+    }
+    if ui.button("Cancel").clicked() {
+        design.set_child_move_pick(None);
+    }
+}
+
+/// Function types of auxiliary type 1 objects
+const AUX_TYPE1_FUNCTION_TYPES: &[(u8, &str)] = &[
+    (0, "Latching (on/off)"),
+    (1, "Analogue"),
+    (2, "Non-latching (momentary)"),
+];
+
+fn render_aux_type1_function_type(ui: &mut egui::Ui, function_type: &mut u8) {
+    ui.horizontal(|ui| {
+        ui.label("Function Type:");
+        let selected = AUX_TYPE1_FUNCTION_TYPES
+            .iter()
+            .find(|(value, _)| value == function_type)
+            .map_or(format!("Reserved ({})", function_type), |(_, name)| {
+                name.to_string()
+            });
+        egui::ComboBox::from_id_salt("aux_type1_function_type")
+            .selected_text(selected)
+            .show_ui(ui, |ui| {
+                for (value, name) in AUX_TYPE1_FUNCTION_TYPES {
+                    ui.selectable_value(function_type, *value, *name);
+                }
+            });
+    });
+}
+
+impl ConfigurableObject for AuxiliaryFunctionType1 {
     fn render_parameters(&mut self, ui: &mut egui::Ui, design: &EditorProject) {
         render_object_id(ui, &mut self.id, design);
 
-        ui.add(
-            egui::Slider::new(&mut self.background_colour, 0..=255)
-                .text("Background Colour")
-                .drag_value_speed(1.0),
+        color_swatch_selector(ui, &mut self.background_colour, design, "Background Colour");
+        render_aux_type1_function_type(ui, &mut self.function_type);
+
+        ui.separator();
+        ui.label("Objects:");
+        render_object_references_list(
+            ui,
+            design,
+            design.mask_size,
+            design.mask_size,
+            &mut self.object_refs,
+            &Self::get_allowed_child_refs(design.get_vt_version()),
+            self.id,
+        );
+    }
+}
+
+impl ConfigurableObject for AuxiliaryInputType1 {
+    fn render_parameters(&mut self, ui: &mut egui::Ui, design: &EditorProject) {
+        render_object_id(ui, &mut self.id, design);
+
+        color_swatch_selector(ui, &mut self.background_colour, design, "Background Colour");
+        render_aux_type1_function_type(ui, &mut self.function_type);
+
+        ui.horizontal(|ui| {
+            ui.label("Input ID:");
+            ui.add(egui::DragValue::new(&mut self.input_id).range(0..=250));
+        });
+        let duplicate = design
+            .get_objects_by_type(ObjectType::AuxiliaryInputType1)
+            .into_iter()
+            .any(|obj| match obj {
+                Object::AuxiliaryInputType1(o) => o.id != self.id && o.input_id == self.input_id,
+                _ => false,
+            });
+        if duplicate {
+            ui.colored_label(
+                egui::Color32::RED,
+                "Another auxiliary input uses this input ID",
+            );
+        }
+
+        ui.separator();
+        ui.label("Objects:");
+        render_object_references_list(
+            ui,
+            design,
+            design.mask_size,
+            design.mask_size,
+            &mut self.object_refs,
+            &Self::get_allowed_child_refs(design.get_vt_version()),
+            self.id,
         );
+    }
+}
+
+impl ConfigurableObject for AuxiliaryFunctionType2 {
+    fn render_parameters(&mut self, ui: &mut egui::Ui, design: &EditorProject) {
+        render_object_id(ui, &mut self.id, design);
+
+        color_swatch_selector(ui, &mut self.background_colour, design, "Background Colour");
 
         ui.horizontal(|ui| {
             ui.label("Function Type:");
@@ -3408,7 +4073,7 @@ impl ConfigurableObject for AuxiliaryFunctionType2 {
             design.mask_size,
             design.mask_size,
             &mut self.object_refs,
-            &Self::get_allowed_child_refs(VtVersion::Version3),
+            &Self::get_allowed_child_refs(design.get_vt_version()),
             self.id,
         );
     }
@@ -3418,11 +4083,7 @@ impl ConfigurableObject for AuxiliaryInputType2 {
     fn render_parameters(&mut self, ui: &mut egui::Ui, design: &EditorProject) {
         render_object_id(ui, &mut self.id, design);
 
-        ui.add(
-            egui::Slider::new(&mut self.background_colour, 0..=255)
-                .text("Background Colour")
-                .drag_value_speed(1.0),
-        );
+        color_swatch_selector(ui, &mut self.background_colour, design, "Background Colour");
 
         ui.horizontal(|ui| {
             ui.label("Function Type:");
@@ -3471,7 +4132,7 @@ impl ConfigurableObject for AuxiliaryInputType2 {
             design.mask_size,
             design.mask_size,
             &mut self.object_refs,
-            &Self::get_allowed_child_refs(VtVersion::Version3),
+            &Self::get_allowed_child_refs(design.get_vt_version()),
             self.id,
         );
     }
@@ -3526,7 +4187,7 @@ impl ConfigurableObject for AuxiliaryControlDesignatorType2 {
                             ObjectType::AuxiliaryInputType2,
                         ];
 
-                        for potential_child in design.get_pool().objects_by_types(allowed_types) {
+                        for potential_child in design.get_objects_by_types(allowed_types) {
                             if ui
                                 .selectable_label(
                                     NullableObjectId::from(potential_child.id())
@@ -3546,7 +4207,7 @@ impl ConfigurableObject for AuxiliaryControlDesignatorType2 {
 
                 // Provide a link to navigate to the selected object
                 if let Some(ref_id) = self.auxiliary_object_id.into() {
-                    if let Some(obj) = design.get_pool().object_by_id(ref_id) {
+                    if let Some(obj) = design.get_object(ref_id) {
                         if ui.link(format!("{:?}", obj.object_type())).clicked() {
                             *design.get_mut_selected().borrow_mut() = ref_id.into();
                         }
@@ -3558,3 +4219,574 @@ impl ConfigurableObject for AuxiliaryControlDesignatorType2 {
         }
     }
 }
+
+/// Edit a NAME as its raw 64 bit value, showing the decoded fields that identify the ECU
+fn render_name(ui: &mut egui::Ui, label: &str, name: &mut NAME) {
+    let raw = u64::from(*name);
+    ui.horizontal(|ui| {
+        ui.label(label);
+        let mut text = format!("{:016X}", raw);
+        let response = ui.add(
+            egui::TextEdit::singleline(&mut text)
+                .font(egui::TextStyle::Monospace)
+                .desired_width(140.0),
+        );
+        if response.changed() {
+            if let Ok(new_raw) = u64::from_str_radix(text.trim(), 16) {
+                *name = NAME::new(new_raw);
+            }
+        }
+    });
+    egui::Grid::new(format!("name_fields_{}", label))
+        .num_columns(2)
+        .show(ui, |ui| {
+            let fields = [
+                ("Identity number", raw & 0x1F_FFFF),
+                ("Manufacturer code", (raw >> 21) & 0x7FF),
+                ("ECU instance", (raw >> 32) & 0x7),
+                ("Function instance", (raw >> 35) & 0x1F),
+                ("Function", (raw >> 40) & 0xFF),
+                ("Device class", (raw >> 49) & 0x7F),
+                ("Device class instance", (raw >> 56) & 0xF),
+                ("Industry group", (raw >> 60) & 0x7),
+                ("Self-configurable address", raw >> 63),
+            ];
+            for (field, value) in fields {
+                ui.weak(field);
+                ui.monospace(value.to_string());
+                ui.end_row();
+            }
+        });
+}
+
+impl ConfigurableObject for ExternalObjectDefinition {
+    fn render_parameters(&mut self, ui: &mut egui::Ui, design: &EditorProject) {
+        render_object_id(ui, &mut self.id, design);
+        ui.checkbox(&mut self.options.enabled, "Enabled");
+        ui.separator();
+        render_name(
+            ui,
+            "Working set master allowed to reference:",
+            &mut self.name,
+        );
+
+        ui.separator();
+        ui.label("Objects available to the other working set:");
+        render_nullable_object_id_list(
+            ui,
+            design,
+            &mut self.objects,
+            &Self::get_allowed_child_refs(VtVersion::Version5),
+            self.id,
+        );
+    }
+}
+
+impl ConfigurableObject for ExternalReferenceName {
+    fn render_parameters(&mut self, ui: &mut egui::Ui, design: &EditorProject) {
+        render_object_id(ui, &mut self.id, design);
+        ui.checkbox(&mut self.options.enabled, "Enabled");
+        ui.separator();
+        render_name(ui, "Working set master referenced:", &mut self.name);
+
+        let pointers = design
+            .get_objects_by_type(ObjectType::ExternalObjectPointer)
+            .into_iter()
+            .filter(|obj| match obj {
+                Object::ExternalObjectPointer(o) => {
+                    o.external_reference_name_id == NullableObjectId::from(self.id)
+                }
+                _ => false,
+            })
+            .count();
+        ui.separator();
+        ui.label(format!("Used by {} external object pointer(s)", pointers));
+    }
+}
+
+impl ConfigurableObject for ExternalObjectPointer {
+    fn render_parameters(&mut self, ui: &mut egui::Ui, design: &EditorProject) {
+        render_object_id(ui, &mut self.id, design);
+
+        ui.horizontal(|ui| {
+            ui.label("External reference name:");
+            render_nullable_object_id_selector(
+                ui,
+                0,
+                design,
+                &mut self.external_reference_name_id,
+                &[ObjectType::ExternalReferenceName],
+                None,
+            );
+            if let Some(id) = self.external_reference_name_id.into() {
+                if design.get_object(id).is_none() {
+                    ui.colored_label(egui::Color32::RED, "Missing object in pool");
+                } else if ui.link("(view)").clicked() {
+                    *design.get_mut_selected().borrow_mut() = id.into();
+                }
+            }
+        });
+
+        // The referenced object lives in the pool of another working set, so it can't be picked
+        ui.horizontal(|ui| {
+            ui.label("External object ID:");
+            let mut has_external_object = self.external_object_id.0.is_some();
+            if ui.checkbox(&mut has_external_object, "").changed() {
+                self.external_object_id = if has_external_object {
+                    NullableObjectId(Some(ObjectId::new(0).unwrap()))
+                } else {
+                    NullableObjectId::NULL
+                };
+            }
+            if let Some(id) = &mut self.external_object_id.0 {
+                let mut value = id.value();
+                if ui
+                    .add(egui::DragValue::new(&mut value).speed(1.0).range(0..=65534))
+                    .changed()
+                {
+                    *id = ObjectId::new(value).unwrap();
+                }
+            } else {
+                ui.weak("None");
+            }
+        });
+
+        // The default object is shown when the external object isn't available
+        ui.horizontal(|ui| {
+            ui.label("Default object:");
+            let object_types: Vec<ObjectType> = design
+                .get_parents(self.id)
+                .iter()
+                .flat_map(|parent_obj| {
+                    get_allowed_child_refs(parent_obj.object_type(), VtVersion::Version5)
+                })
+                .filter(|object_type| *object_type != ObjectType::ExternalObjectPointer)
+                .collect();
+            render_nullable_object_id_selector(
+                ui,
+                1,
+                design,
+                &mut self.default_object_id,
+                &object_types,
+                Some(self.id),
+            );
+            if let Some(id) = self.default_object_id.into() {
+                if let Some(object) = design.get_object(id) {
+                    if ui.link(format!("{:?}", object.object_type())).clicked() {
+                        *design.get_mut_selected().borrow_mut() = id.into();
+                    }
+                } else {
+                    ui.colored_label(egui::Color32::RED, "Missing object in pool");
+                }
+            }
+        });
+    }
+}
+
+impl ConfigurableObject for ColourPalette {
+    fn render_parameters(&mut self, ui: &mut egui::Ui, design: &EditorProject) {
+        render_object_id(ui, &mut self.id, design);
+
+        let active = get_active_colour_palette(design.get_pool()).map(|p| p.id) == Some(self.id);
+        if active {
+            ui.label("This palette is used for all colours of the pool");
+        } else {
+            ui.colored_label(
+                egui::Color32::YELLOW,
+                "⚠ Not in use, select it in the Working Set Special Controls",
+            );
+        }
+
+        ui.horizontal(|ui| {
+            ui.label(format!("Colours: {}", self.colours.len()));
+            if ui
+                .button("Start from standard palette")
+                .on_hover_text("Replace all colours by the standard VT colours")
+                .clicked()
+            {
+                self.colours = design.get_standard_colour_palette().to_vec();
+            }
+            if ui.button("Clear").clicked() {
+                self.colours.clear();
+            }
+        });
+        ui.separator();
+
+        // Colours beyond the palette fall back to the standard colours
+        ui.spacing_mut().item_spacing = egui::vec2(2.0, 2.0);
+        for row in 0..16usize {
+            ui.horizontal(|ui| {
+                for column in 0..16usize {
+                    let idx = row * 16 + column;
+                    match self.colours.get_mut(idx) {
+                        Some(colour) => {
+                            let mut rgb = [colour.r, colour.g, colour.b];
+                            if ui
+                                .color_edit_button_srgb(&mut rgb)
+                                .on_hover_text(format!("{}", idx))
+                                .changed()
+                            {
+                                colour.r = rgb[0];
+                                colour.g = rgb[1];
+                                colour.b = rgb[2];
+                            }
+                        }
+                        None => {
+                            if ui
+                                .add_enabled(
+                                    idx == self.colours.len(),
+                                    egui::Button::new("+").min_size(egui::vec2(
+                                        ui.spacing().interact_size.y,
+                                        ui.spacing().interact_size.y,
+                                    )),
+                                )
+                                .on_hover_text("Add a colour to the palette")
+                                .clicked()
+                            {
+                                self.colours.push(design.get_standard_colour_palette()[idx]);
+                            }
+                        }
+                    }
+                }
+            });
+        }
+    }
+}
+
+/// Cell format and objects the VT expects for a predefined window type, None for a free form window
+fn window_type_layout(
+    window_type: WindowType,
+) -> Option<(WindowMaskCellFormat, &'static [ObjectType])> {
+    const NUMBER_WITH_UNITS: &[ObjectType] = &[ObjectType::OutputNumber, ObjectType::OutputString];
+    const NUMBER: &[ObjectType] = &[ObjectType::OutputNumber];
+    const STRING: &[ObjectType] = &[ObjectType::OutputString];
+    const INPUT_NUMBER_WITH_UNITS: &[ObjectType] =
+        &[ObjectType::InputNumber, ObjectType::OutputString];
+    const INPUT_NUMBER: &[ObjectType] = &[ObjectType::InputNumber];
+    const INPUT_STRING: &[ObjectType] = &[ObjectType::InputString];
+    const BAR_GRAPH: &[ObjectType] = &[ObjectType::OutputLinearBarGraph];
+    const SINGLE_BUTTON: &[ObjectType] = &[ObjectType::Button];
+    const DOUBLE_BUTTON: &[ObjectType] = &[ObjectType::Button, ObjectType::Button];
+
+    let layout = match window_type {
+        WindowType::FreeForm => return None,
+        WindowType::NumericOutputValueWithUnits1x1 => {
+            (WindowMaskCellFormat::CF1x1, NUMBER_WITH_UNITS)
+        }
+        WindowType::NumericOutputValueNoUnits1x1 => (WindowMaskCellFormat::CF1x1, NUMBER),
+        WindowType::StringOutputValue1x1 => (WindowMaskCellFormat::CF1x1, STRING),
+        WindowType::NumericInputValueWithUnits1x1 => {
+            (WindowMaskCellFormat::CF1x1, INPUT_NUMBER_WITH_UNITS)
+        }
+        WindowType::NumericInputValueNoUnits1x1 => (WindowMaskCellFormat::CF1x1, INPUT_NUMBER),
+        WindowType::StringInputValue1x1 => (WindowMaskCellFormat::CF1x1, INPUT_STRING),
+        WindowType::HorizontalLinearBarGraph1x1 => (WindowMaskCellFormat::CF1x1, BAR_GRAPH),
+        WindowType::SingleButton1x1 => (WindowMaskCellFormat::CF1x1, SINGLE_BUTTON),
+        WindowType::DoubleButton1x1 => (WindowMaskCellFormat::CF1x1, DOUBLE_BUTTON),
+        WindowType::NumericOutputValueWithUnits2x1 => {
+            (WindowMaskCellFormat::CF2x1, NUMBER_WITH_UNITS)
+        }
+        WindowType::NumericOutputValueNoUnits2x1 => (WindowMaskCellFormat::CF2x1, NUMBER),
+        WindowType::StringOutputValue2x1 => (WindowMaskCellFormat::CF2x1, STRING),
+        WindowType::NumericInputValueWithUnits2x1 => {
+            (WindowMaskCellFormat::CF2x1, INPUT_NUMBER_WITH_UNITS)
+        }
+        WindowType::NumericInputValueNoUnits2x1 => (WindowMaskCellFormat::CF2x1, INPUT_NUMBER),
+        WindowType::StringInputValue2x1 => (WindowMaskCellFormat::CF2x1, INPUT_STRING),
+        WindowType::HorizontalLinearBarGraph2x1 => (WindowMaskCellFormat::CF2x1, BAR_GRAPH),
+        WindowType::SingleButton2x1 => (WindowMaskCellFormat::CF2x1, SINGLE_BUTTON),
+        WindowType::DoubleButton2x1 => (WindowMaskCellFormat::CF2x1, DOUBLE_BUTTON),
+    };
+    Some(layout)
+}
+
+impl ConfigurableObject for WindowMask {
+    fn render_parameters(&mut self, ui: &mut egui::Ui, design: &EditorProject) {
+        render_object_id(ui, &mut self.id, design);
+        color_swatch_selector(ui, &mut self.background_colour, design, "Background Colour");
+        ui.checkbox(&mut self.options.available, "Available");
+        ui.checkbox(&mut self.options.transparent, "Transparent");
+
+        ui.horizontal(|ui| {
+            ui.label("Window type:");
+            egui::ComboBox::from_id_salt("window_type")
+                .selected_text(format!("{:?}", self.window_type))
+                .show_ui(ui, |ui| {
+                    let selectable_types = &[
+                        WindowType::FreeForm,
+                        WindowType::NumericOutputValueWithUnits1x1,
+                        WindowType::NumericOutputValueNoUnits1x1,
+                        WindowType::StringOutputValue1x1,
+                        WindowType::NumericInputValueWithUnits1x1,
+                        WindowType::NumericInputValueNoUnits1x1,
+                        WindowType::StringInputValue1x1,
+                        WindowType::HorizontalLinearBarGraph1x1,
+                        WindowType::SingleButton1x1,
+                        WindowType::DoubleButton1x1,
+                        WindowType::NumericOutputValueWithUnits2x1,
+                        WindowType::NumericOutputValueNoUnits2x1,
+                        WindowType::StringOutputValue2x1,
+                        WindowType::NumericInputValueWithUnits2x1,
+                        WindowType::NumericInputValueNoUnits2x1,
+                        WindowType::StringInputValue2x1,
+                        WindowType::HorizontalLinearBarGraph2x1,
+                        WindowType::SingleButton2x1,
+                        WindowType::DoubleButton2x1,
+                    ];
+
+                    for wt in selectable_types {
+                        ui.selectable_value(&mut self.window_type, *wt, format!("{:?}", wt));
+                    }
+                });
+        });
+
+        // Predefined window types have a fixed size
+        let layout = window_type_layout(self.window_type);
+        if let Some((cell_format, _)) = layout {
+            self.cell_format = cell_format;
+        }
+        ui.add_enabled_ui(layout.is_none(), |ui| {
+            ui.horizontal(|ui| {
+                ui.label("Cell format:");
+                egui::ComboBox::from_id_salt("window_cell_format")
+                    .selected_text(format!("{:?}", self.cell_format))
+                    .show_ui(ui, |ui| {
+                        let selectable_formats = &[
+                            WindowMaskCellFormat::CF1x1,
+                            WindowMaskCellFormat::CF1x2,
+                            WindowMaskCellFormat::CF1x3,
+                            WindowMaskCellFormat::CF1x4,
+                            WindowMaskCellFormat::CF1x5,
+                            WindowMaskCellFormat::CF1x6,
+                            WindowMaskCellFormat::CF2x1,
+                            WindowMaskCellFormat::CF2x2,
+                            WindowMaskCellFormat::CF2x3,
+                            WindowMaskCellFormat::CF2x4,
+                            WindowMaskCellFormat::CF2x5,
+                            WindowMaskCellFormat::CF2x6,
+                        ];
+
+                        for cf in selectable_formats {
+                            let (columns, rows) = window_mask_cells(*cf);
+                            ui.selectable_value(
+                                &mut self.cell_format,
+                                *cf,
+                                format!("{} x {} cells", columns, rows),
+                            );
+                        }
+                    });
+            });
+        })
+        .response
+        .on_disabled_hover_text("The cell format is defined by the window type");
+
+        ui.separator();
+        ui.horizontal(|ui| {
+            ui.label("Name:");
+            render_object_id_selector(
+                ui,
+                0,
+                design,
+                &mut self.name,
+                &[ObjectType::OutputString, ObjectType::ObjectPointer],
+                Some(self.id),
+            );
+        });
+        ui.horizontal(|ui| {
+            ui.label("Window title:");
+            render_nullable_object_id_selector(
+                ui,
+                1,
+                design,
+                &mut self.window_title,
+                &[ObjectType::OutputString, ObjectType::ObjectPointer],
+                Some(self.id),
+            );
+        });
+        ui.horizontal(|ui| {
+            ui.label("Window icon:");
+            render_nullable_object_id_selector(
+                ui,
+                2,
+                design,
+                &mut self.window_icon,
+                &[
+                    ObjectType::Container,
+                    ObjectType::PictureGraphic,
+                    ObjectType::ObjectPointer,
+                ],
+                Some(self.id),
+            );
+        });
+
+        ui.separator();
+        match layout {
+            Some((_, required)) => {
+                let expected = required
+                    .iter()
+                    .map(|t| format!("{:?}", t))
+                    .collect::<Vec<_>>()
+                    .join(", ");
+                ui.label(format!("Required objects: {}", expected));
+                let matches = self.objects.len() == required.len()
+                    && self.objects.iter().zip(required).all(|(id, object_type)| {
+                        design
+                            .get_pool()
+                            .object_by_id(*id)
+                            .is_some_and(|obj| obj.object_type() == *object_type)
+                    });
+                if !matches {
+                    ui.colored_label(
+                        egui::Color32::YELLOW,
+                        "⚠ The referenced objects don't match the window type",
+                    );
+                }
+                render_object_id_list(ui, design, &mut self.objects, required, self.id);
+            }
+            None => {
+                ui.label("Objects:");
+                let (columns, rows) = window_mask_cells(self.cell_format);
+                let cell_size = window_mask_cell_size(design.mask_size);
+                render_object_references_list(
+                    ui,
+                    design,
+                    (cell_size.x * columns as f32) as u16,
+                    (cell_size.y * rows as f32) as u16,
+                    &mut self.object_refs,
+                    &Self::get_allowed_child_refs(VtVersion::Version4),
+                    self.id,
+                );
+            }
+        }
+
+        ui.separator();
+        ui.label("Macros:");
+        render_macro_references(
+            ui,
+            design,
+            &mut self.macro_refs,
+            &Self::get_possible_events(),
+        );
+    }
+}
+
+impl ConfigurableObject for GraphicsContext {
+    fn render_parameters(&mut self, ui: &mut egui::Ui, design: &EditorProject) {
+        render_object_id(ui, &mut self.id, design);
+
+        ui.label("Viewport:");
+        ui.add(
+            egui::Slider::new(&mut self.viewport_width, 0..=design.mask_size)
+                .text("Width")
+                .drag_value_speed(1.0),
+        );
+        ui.add(
+            egui::Slider::new(&mut self.viewport_height, 0..=design.mask_size)
+                .text("Height")
+                .drag_value_speed(1.0),
+        );
+        ui.horizontal(|ui| {
+            ui.label("Position:");
+            ui.add(egui::DragValue::new(&mut self.viewport_x).prefix("x: "));
+            ui.add(egui::DragValue::new(&mut self.viewport_y).prefix("y: "));
+        })
+        .response
+        .on_hover_text("Position of the viewport within the canvas");
+        ui.add(
+            egui::Slider::new(&mut self.viewport_zoom, -32.0..=32.0)
+                .text("Zoom")
+                .drag_value_speed(0.1),
+        )
+        .on_hover_text("Zoom factor of the viewport, 0 shows the canvas without zoom");
+
+        ui.separator();
+        ui.label("Canvas:");
+        ui.add(
+            egui::Slider::new(&mut self.canvas_width, 0..=design.mask_size)
+                .text("Width")
+                .drag_value_speed(1.0),
+        );
+        ui.add(
+            egui::Slider::new(&mut self.canvas_height, 0..=design.mask_size)
+                .text("Height")
+                .drag_value_speed(1.0),
+        );
+        ui.horizontal(|ui| {
+            ui.label("Graphics cursor:");
+            ui.add(egui::DragValue::new(&mut self.graphics_cursor_x).prefix("x: "));
+            ui.add(egui::DragValue::new(&mut self.graphics_cursor_y).prefix("y: "));
+        });
+        ui.horizontal(|ui| {
+            ui.label("Format:");
+            ui.radio_value(&mut self.format, ColorFormat::ColorMonochrome, "Monochrome");
+            ui.radio_value(&mut self.format, ColorFormat::Color4Bit, "4-bit colour");
+            ui.radio_value(&mut self.format, ColorFormat::Color8Bit, "8-bit colour");
+        });
+
+        ui.separator();
+        ui.label("Colours:");
+        color_swatch_selector(ui, &mut self.foreground_colour, design, "Foreground Colour");
+        color_swatch_selector(ui, &mut self.background_colour, design, "Background Colour");
+        ui.horizontal(|ui| {
+            ui.radio_value(
+                &mut self.options.color,
+                ColorOption::ForegroundBackground,
+                "Use foreground and background colours",
+            );
+            ui.radio_value(
+                &mut self.options.color,
+                ColorOption::LineFontFill,
+                "Use line, font and fill attributes",
+            );
+        });
+        ui.horizontal(|ui| {
+            ui.checkbox(&mut self.options.transparent, "Transparent");
+            if self.options.transparent {
+                color_swatch_selector(
+                    ui,
+                    &mut self.transparency_colour,
+                    design,
+                    "Transparency Colour",
+                );
+            }
+        });
+
+        ui.separator();
+        ui.horizontal(|ui| {
+            ui.label("Font Attributes:");
+            render_nullable_object_id_selector(
+                ui,
+                0,
+                design,
+                &mut self.font_attributes_object,
+                &[ObjectType::FontAttributes],
+                Some(self.id),
+            );
+        });
+        ui.horizontal(|ui| {
+            ui.label("Line Attributes:");
+            render_nullable_object_id_selector(
+                ui,
+                1,
+                design,
+                &mut self.line_attributes_object,
+                &[ObjectType::LineAttributes],
+                Some(self.id),
+            );
+        });
+        ui.horizontal(|ui| {
+            ui.label("Fill Attributes:");
+            render_nullable_object_id_selector(
+                ui,
+                2,
+                design,
+                &mut self.fill_attributes_object,
+                &[ObjectType::FillAttributes],
+                Some(self.id),
+            );
+        });
+
+        ui.separator();
+        render_graphics_commands(ui, design, self);
+    }
+}
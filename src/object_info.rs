@@ -2,7 +2,9 @@
 //! SPDX-License-Identifier: GPL-3.0-or-later
 //! Authors: Daan Steenbergen
 
+use crate::image_import::ImageSource;
 use ag_iso_stack::object_pool::object::Object;
+use std::collections::BTreeMap;
 use uuid::Uuid;
 
 #[derive(Debug, Clone)]
@@ -14,6 +16,32 @@ pub struct ObjectInfo {
     /// Optional name for the object.
     /// This is used to give the object a name throughout the editor that is more human-readable
     pub name: Option<String>,
+
+    /// Optional identifier used instead of the name in exported headers and code, so the name
+    /// can stay human-readable
+    pub export_alias: Option<String>,
+
+    /// Free text description of the object, e.g. its purpose or open questions for reviews
+    pub notes: String,
+
+    /// Tags to group objects across the hierarchy, e.g. "page1" or "diagnostics"
+    pub tags: Vec<String>,
+
+    /// Restricted objects, e.g. certified safety-relevant alarm masks, and the objects they show
+    /// can only be edited after explicitly unlocking them
+    pub restricted: bool,
+
+    /// Text of the object per language code, for objects holding text
+    translations: BTreeMap<String, String>,
+
+    /// Drawing commands previewed on a graphics context, as the ECU sends them at runtime
+    pub graphics_commands: String,
+
+    /// Shown blinking in the preview, as the ECU hides and shows the object periodically
+    pub blinking: bool,
+
+    /// Image file a picture graphic was imported from, to import it again when it changes
+    pub image_source: Option<ImageSource>,
 }
 
 impl ObjectInfo {
@@ -21,6 +49,14 @@ impl ObjectInfo {
         ObjectInfo {
             unique_id: Uuid::new_v4(),
             name: None,
+            export_alias: None,
+            notes: String::new(),
+            tags: Vec::new(),
+            restricted: false,
+            translations: BTreeMap::new(),
+            graphics_commands: String::new(),
+            blinking: false,
+            image_source: None,
         }
     }
 
@@ -35,6 +71,15 @@ impl ObjectInfo {
         }
     }
 
+    /// Get the name used for the object in exported headers and code: the export alias if it
+    /// has one, else the name
+    pub fn get_export_name(&self, object: &Object) -> String {
+        match &self.export_alias {
+            Some(alias) => alias.clone(),
+            None => self.get_name(object),
+        }
+    }
+
     /// Set the name of the object.
     pub fn set_name(&mut self, name: String) {
         if !name.is_empty() {
@@ -42,9 +87,31 @@ impl ObjectInfo {
         }
     }
 
+    /// Get the text of the object in the given language, if it is translated
+    pub fn get_translation(&self, language: &str) -> Option<&str> {
+        self.translations.get(language).map(String::as_str)
+    }
+
+    /// Set or, with `None`, remove the text of the object in the given language
+    pub fn set_translation(&mut self, language: &str, text: Option<String>) {
+        match text {
+            Some(text) => self.translations.insert(language.to_string(), text),
+            None => self.translations.remove(language),
+        };
+    }
+
+    pub fn get_translations(&self) -> &BTreeMap<String, String> {
+        &self.translations
+    }
+
     pub fn get_unique_id(&self) -> Uuid {
         self.unique_id
     }
+
+    /// Give the object a new unique identifier, e.g. when it shares one with another object
+    pub fn renew_unique_id(&mut self) {
+        self.unique_id = Uuid::new_v4();
+    }
 }
 
 impl PartialEq for ObjectInfo {
@@ -0,0 +1,430 @@
+//! Copyright 2024 - The Open-Agriculture Developers
+//! SPDX-License-Identifier: GPL-3.0-or-later
+//! Authors: Daan Steenbergen
+
+use crate::EditorProject;
+use ag_iso_stack::object_pool::object::Object;
+use ag_iso_stack::object_pool::ObjectId;
+
+const COLUMNS: [&str; 11] = [
+    "ID",
+    "Name",
+    "Export name",
+    "Type",
+    "Parents",
+    "Width",
+    "Height",
+    "Colour",
+    "References",
+    "Value",
+    "Notes",
+];
+
+/// Quote a CSV field if it contains a separator, quote or line break
+fn csv_field(value: &str) -> String {
+    if value.contains([',', '"', '\n', '\r']) {
+        format!("\"{}\"", value.replace('"', "\"\""))
+    } else {
+        value.to_string()
+    }
+}
+
+/// The value an object shows or holds, e.g. the text of a string or the code of a key
+fn object_value(object: &Object) -> String {
+    match object {
+        Object::OutputString(o) => o.value.clone(),
+        Object::InputString(o) => o.value.clone(),
+        Object::StringVariable(o) => o.value.clone(),
+        Object::OutputNumber(o) => o.value.to_string(),
+        Object::InputNumber(o) => o.value.to_string(),
+        Object::NumberVariable(o) => o.value.to_string(),
+        Object::Key(o) => format!("Key code {}", o.key_code),
+        Object::Button(o) => format!("Key code {}", o.key_code),
+        Object::AlarmMask(o) => format!("Priority {}", o.priority),
+        _ => String::new(),
+    }
+}
+
+/// The colour index of the background, or of the font, line or fill of attributes
+fn object_colour(object: &Object) -> Option<u8> {
+    match object {
+        Object::WorkingSet(o) => Some(o.background_colour),
+        Object::DataMask(o) => Some(o.background_colour),
+        Object::AlarmMask(o) => Some(o.background_colour),
+        Object::SoftKeyMask(o) => Some(o.background_colour),
+        Object::WindowMask(o) => Some(o.background_colour),
+        Object::Key(o) => Some(o.background_colour),
+        Object::Button(o) => Some(o.background_colour),
+        Object::InputBoolean(o) => Some(o.background_colour),
+        Object::InputString(o) => Some(o.background_colour),
+        Object::InputNumber(o) => Some(o.background_colour),
+        Object::OutputString(o) => Some(o.background_colour),
+        Object::OutputNumber(o) => Some(o.background_colour),
+        Object::FontAttributes(o) => Some(o.font_colour),
+        Object::LineAttributes(o) => Some(o.line_colour),
+        Object::FillAttributes(o) => Some(o.fill_colour),
+        _ => None,
+    }
+}
+
+/// A CSV table of all objects in the order of the pool, with their names, parents, size and
+/// main attributes, e.g. for traceability matrices and reviews in a spreadsheet
+pub fn object_list_csv(project: &EditorProject) -> String {
+    let mut csv = COLUMNS.join(",");
+    csv.push_str("\r\n");
+    for object in project.get_pool().objects() {
+        let info = project.get_object_info(object);
+        let ids = |objects: Vec<u16>| {
+            objects
+                .iter()
+                .map(|id| id.to_string())
+                .collect::<Vec<_>>()
+                .join(" ")
+        };
+        let mut parents: Vec<u16> = project
+            .get_parents(object.id())
+            .iter()
+            .map(|parent| parent.id().value())
+            .collect();
+        parents.dedup();
+        let references = object
+            .referenced_objects()
+            .iter()
+            .map(|id| id.value())
+            .collect();
+        let (width, height) = project.get_pool().content_size(object);
+        let fields = [
+            object.id().value().to_string(),
+            info.get_name(object),
+            info.get_export_name(object),
+            format!("{:?}", object.object_type()),
+            ids(parents),
+            width.to_string(),
+            height.to_string(),
+            object_colour(object).map_or(String::new(), |colour| colour.to_string()),
+            ids(references),
+            object_value(object),
+            info.notes.clone(),
+        ];
+        let row: Vec<String> = fields.iter().map(|field| csv_field(field)).collect();
+        csv.push_str(&row.join(","));
+        csv.push_str("\r\n");
+    }
+    csv
+}
+
+/// A column of the object list that can be changed by importing the list again
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum ImportColumn {
+    Name,
+    Width,
+    Height,
+    Colour,
+    Value,
+}
+
+impl ImportColumn {
+    pub const ALL: [ImportColumn; 5] = [
+        ImportColumn::Name,
+        ImportColumn::Width,
+        ImportColumn::Height,
+        ImportColumn::Colour,
+        ImportColumn::Value,
+    ];
+
+    /// Header of the column in the CSV table
+    pub fn name(&self) -> &'static str {
+        match self {
+            ImportColumn::Name => "Name",
+            ImportColumn::Width => "Width",
+            ImportColumn::Height => "Height",
+            ImportColumn::Colour => "Colour",
+            ImportColumn::Value => "Value",
+        }
+    }
+}
+
+/// A change of an object found in an imported object list
+#[derive(Clone, Debug)]
+pub struct ObjectListChange {
+    pub object: ObjectId,
+    pub column: ImportColumn,
+    pub old: String,
+    pub new: String,
+}
+
+/// Split CSV text into rows of fields. Fields may be quoted, and the separator is a semicolon
+/// when the header uses semicolons, as spreadsheets do in some locales.
+fn parse_csv(text: &str) -> Vec<Vec<String>> {
+    let header = text.lines().next().unwrap_or_default();
+    let separator = if header.contains(';') && !header.contains(',') {
+        ';'
+    } else {
+        ','
+    };
+    let mut rows = Vec::new();
+    let mut row = Vec::new();
+    let mut field = String::new();
+    let mut quoted = false;
+    let mut chars = text.trim_start_matches('\u{feff}').chars().peekable();
+    while let Some(c) = chars.next() {
+        match c {
+            '"' if quoted && chars.peek() == Some(&'"') => {
+                field.push('"');
+                chars.next();
+            }
+            '"' => quoted = !quoted,
+            c if quoted => field.push(c),
+            c if c == separator => row.push(std::mem::take(&mut field)),
+            '\r' => {}
+            '\n' => {
+                row.push(std::mem::take(&mut field));
+                rows.push(std::mem::take(&mut row));
+            }
+            c => field.push(c),
+        }
+    }
+    if !field.is_empty() || !row.is_empty() {
+        row.push(field);
+        rows.push(row);
+    }
+    rows
+}
+
+/// The current value of the column of an object, as written to the object list
+fn current_value(project: &EditorProject, object: &Object, column: ImportColumn) -> String {
+    let (width, height) = project.get_pool().content_size(object);
+    match column {
+        ImportColumn::Name => project.get_object_info(object).get_name(object),
+        ImportColumn::Width => width.to_string(),
+        ImportColumn::Height => height.to_string(),
+        ImportColumn::Colour => object_colour(object).map_or(String::new(), |c| c.to_string()),
+        ImportColumn::Value => object_value(object),
+    }
+}
+
+fn parse_number<T: std::str::FromStr>(value: &str, what: &str) -> Result<T, String> {
+    value
+        .trim()
+        .parse()
+        .map_err(|_| format!("Invalid {} '{}'", what, value))
+}
+
+/// Set an attribute of the object to the value of a column. Names aren't part of the object and
+/// aren't set here. Returns an error if the object has no such attribute or the value is invalid.
+pub(crate) fn set_attribute(
+    object: &mut Object,
+    column: ImportColumn,
+    value: &str,
+) -> Result<(), String> {
+    let object_type = object.object_type();
+    let unsupported = || {
+        format!(
+            "{:?} objects have no {} that can be changed",
+            object_type,
+            column.name().to_lowercase()
+        )
+    };
+    match column {
+        ImportColumn::Name => Ok(()),
+        ImportColumn::Width => {
+            let width = parse_number(value, "width")?;
+            match object {
+                Object::Container(o) => o.width = width,
+                Object::Button(o) => o.width = width,
+                Object::InputBoolean(o) => o.width = width,
+                Object::InputString(o) => o.width = width,
+                Object::InputNumber(o) => o.width = width,
+                Object::InputList(o) => o.width = width,
+                Object::OutputString(o) => o.width = width,
+                Object::OutputNumber(o) => o.width = width,
+                Object::OutputList(o) => o.width = width,
+                Object::OutputLine(o) => o.width = width,
+                Object::OutputRectangle(o) => o.width = width,
+                Object::OutputEllipse(o) => o.width = width,
+                Object::OutputPolygon(o) => o.width = width,
+                Object::OutputMeter(o) => o.width = width,
+                Object::OutputLinearBarGraph(o) => o.width = width,
+                Object::OutputArchedBarGraph(o) => o.width = width,
+                _ => return Err(unsupported()),
+            }
+            Ok(())
+        }
+        ImportColumn::Height => {
+            let height = parse_number(value, "height")?;
+            match object {
+                Object::Container(o) => o.height = height,
+                Object::Button(o) => o.height = height,
+                Object::InputString(o) => o.height = height,
+                Object::InputNumber(o) => o.height = height,
+                Object::InputList(o) => o.height = height,
+                Object::OutputString(o) => o.height = height,
+                Object::OutputNumber(o) => o.height = height,
+                Object::OutputList(o) => o.height = height,
+                Object::OutputLine(o) => o.height = height,
+                Object::OutputRectangle(o) => o.height = height,
+                Object::OutputEllipse(o) => o.height = height,
+                Object::OutputPolygon(o) => o.height = height,
+                Object::OutputLinearBarGraph(o) => o.height = height,
+                Object::OutputArchedBarGraph(o) => o.height = height,
+                _ => return Err(unsupported()),
+            }
+            Ok(())
+        }
+        ImportColumn::Colour => {
+            let colour = parse_number(value, "colour index")?;
+            match object {
+                Object::WorkingSet(o) => o.background_colour = colour,
+                Object::DataMask(o) => o.background_colour = colour,
+                Object::AlarmMask(o) => o.background_colour = colour,
+                Object::SoftKeyMask(o) => o.background_colour = colour,
+                Object::WindowMask(o) => o.background_colour = colour,
+                Object::Key(o) => o.background_colour = colour,
+                Object::Button(o) => o.background_colour = colour,
+                Object::InputBoolean(o) => o.background_colour = colour,
+                Object::InputString(o) => o.background_colour = colour,
+                Object::InputNumber(o) => o.background_colour = colour,
+                Object::OutputString(o) => o.background_colour = colour,
+                Object::OutputNumber(o) => o.background_colour = colour,
+                Object::FontAttributes(o) => o.font_colour = colour,
+                Object::LineAttributes(o) => o.line_colour = colour,
+                Object::FillAttributes(o) => o.fill_colour = colour,
+                _ => return Err(unsupported()),
+            }
+            Ok(())
+        }
+        ImportColumn::Value => {
+            match object {
+                Object::OutputString(o) => o.value = value.to_string(),
+                Object::InputString(o) => o.value = value.to_string(),
+                Object::StringVariable(o) => o.value = value.to_string(),
+                Object::OutputNumber(o) => o.value = parse_number(value, "value")?,
+                Object::InputNumber(o) => o.value = parse_number(value, "value")?,
+                Object::NumberVariable(o) => o.value = parse_number(value, "value")?,
+                _ => return Err(unsupported()),
+            }
+            Ok(())
+        }
+    }
+}
+
+/// The changes an edited object list makes to the objects, without applying them. Rows are
+/// matched by the ID column, only the columns of [`ImportColumn`] are imported and columns
+/// missing in the table are left unchanged. Returns an error for the first row that can't be
+/// imported, e.g. an unknown ID or an attribute the object doesn't have.
+pub fn preview_object_list_import(
+    project: &EditorProject,
+    csv: &str,
+) -> Result<Vec<ObjectListChange>, String> {
+    let rows = parse_csv(csv);
+    let Some((header, rows)) = rows.split_first() else {
+        return Err("The file is empty".to_string());
+    };
+    let position = |name: &str| {
+        header
+            .iter()
+            .position(|column| column.trim().eq_ignore_ascii_case(name))
+    };
+    let id_column = position("ID").ok_or("The table has no ID column")?;
+    let columns: Vec<(ImportColumn, usize)> = ImportColumn::ALL
+        .iter()
+        .filter_map(|column| position(column.name()).map(|index| (*column, index)))
+        .collect();
+
+    let mut changes = Vec::new();
+    for (line, row) in rows.iter().enumerate() {
+        // Line numbers as shown by spreadsheets, after the header
+        let line = line + 2;
+        if row.iter().all(|field| field.trim().is_empty()) {
+            continue;
+        }
+        let id = row.get(id_column).map(|id| id.trim()).unwrap_or_default();
+        let object = parse_number::<u16>(id, "ID")
+            .ok()
+            .and_then(|id| ObjectId::new(id).ok())
+            .and_then(|id| project.get_object(id))
+            .ok_or_else(|| format!("Line {}: no object with ID '{}'", line, id))?;
+        for (column, index) in &columns {
+            let Some(new) = row.get(*index) else {
+                continue;
+            };
+            let old = current_value(project, object, *column);
+            if *new == old {
+                continue;
+            }
+            if *column == ImportColumn::Name && new.trim().is_empty() {
+                return Err(format!("Line {}: the name can't be empty", line));
+            }
+            set_attribute(&mut object.clone(), *column, new)
+                .map_err(|e| format!("Line {}: {}", line, e))?;
+            changes.push(ObjectListChange {
+                object: object.id(),
+                column: *column,
+                old,
+                new: new.clone(),
+            });
+        }
+    }
+    Ok(changes)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::parse_csv;
+
+    fn rows(rows: &[&[&str]]) -> Vec<Vec<String>> {
+        rows.iter()
+            .map(|row| row.iter().map(|field| field.to_string()).collect())
+            .collect()
+    }
+
+    #[test]
+    fn quoted_separators() {
+        assert_eq!(
+            parse_csv("ID,Name\n1,\"Speed, km/h\"\n2,\"Line\nbreak\"\n"),
+            rows(&[
+                &["ID", "Name"],
+                &["1", "Speed, km/h"],
+                &["2", "Line\nbreak"]
+            ])
+        );
+    }
+
+    #[test]
+    fn escaped_quotes_and_empty_fields() {
+        assert_eq!(
+            parse_csv("ID,Name,Value\n1,\"Say \"\"hi\"\"\",\"\"\n2,,\n"),
+            rows(&[
+                &["ID", "Name", "Value"],
+                &["1", "Say \"hi\"", ""],
+                &["2", "", ""],
+            ])
+        );
+    }
+
+    #[test]
+    fn byte_order_mark_and_crlf() {
+        assert_eq!(
+            parse_csv("\u{feff}ID,Name\r\n1,Mask\r\n2,Key"),
+            rows(&[&["ID", "Name"], &["1", "Mask"], &["2", "Key"]])
+        );
+    }
+
+    #[test]
+    fn separator_detection() {
+        // Semicolons when the header only uses semicolons, commas are then part of the values
+        assert_eq!(
+            parse_csv("\u{feff}ID;Name;Value\n1;Speed, km/h;1,5\n"),
+            rows(&[&["ID", "Name", "Value"], &["1", "Speed, km/h", "1,5"]])
+        );
+        // Commas otherwise
+        assert_eq!(
+            parse_csv("ID,Name\n1,a;b\n"),
+            rows(&[&["ID", "Name"], &["1", "a;b"]])
+        );
+        assert_eq!(
+            parse_csv("ID,Name;Notes\n1,a;b\n"),
+            rows(&[&["ID", "Name;Notes"], &["1", "a;b"]])
+        );
+    }
+}
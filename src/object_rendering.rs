@@ -3,12 +3,16 @@
 //! Authors: Daan Steenbergen
 
 use std::collections::hash_map::DefaultHasher;
+use std::collections::HashMap;
+use std::collections::HashSet;
 use std::hash::Hash;
 use std::hash::Hasher;
 use std::ops::Sub;
+use std::sync::Arc;
 
 use ag_iso_stack::object_pool::object::*;
 use ag_iso_stack::object_pool::object_attributes::ButtonState;
+use ag_iso_stack::object_pool::object_attributes::ColorOption;
 use ag_iso_stack::object_pool::object_attributes::FontSize;
 use ag_iso_stack::object_pool::object_attributes::FormatType;
 use ag_iso_stack::object_pool::object_attributes::HorizontalAlignment;
@@ -16,8 +20,11 @@ use ag_iso_stack::object_pool::object_attributes::LineDirection;
 use ag_iso_stack::object_pool::object_attributes::PictureGraphicFormat;
 use ag_iso_stack::object_pool::object_attributes::Point;
 use ag_iso_stack::object_pool::object_attributes::VerticalAlignment;
+use ag_iso_stack::object_pool::object_attributes::WindowMaskCellFormat;
+use ag_iso_stack::object_pool::object_attributes::WindowType;
 use ag_iso_stack::object_pool::vt_version::VtVersion;
 use ag_iso_stack::object_pool::Colour;
+use ag_iso_stack::object_pool::ObjectId;
 use ag_iso_stack::object_pool::ObjectPool;
 use ag_iso_stack::object_pool::ObjectRef;
 use eframe::egui;
@@ -29,6 +36,8 @@ use eframe::egui::TextureHandle;
 use eframe::egui::TextureId;
 use eframe::egui::UiBuilder;
 
+use crate::GraphicsCommand;
+
 pub trait RenderableObject {
     fn render(&self, ui: &mut egui::Ui, pool: &ObjectPool, position: Point<i16>);
 }
@@ -38,6 +47,13 @@ impl RenderableObject for Object {
         // Make sure text is truncated if it doesn't fit for all object renderings (useful for error labels)
         ui.style_mut().wrap_mode = Some(TextWrapMode::Truncate);
 
+        let blinking = ui.data(|data| {
+            data.get_temp::<Arc<HashSet<ObjectId>>>(egui::Id::new(BLINKING_OBJECTS_ID))
+        });
+        if blinking.is_some_and(|blinking| blinking.contains(&self.id())) && flash_state(ui) {
+            return;
+        }
+
         match self {
             Object::WorkingSet(o) => o.render(ui, pool, position),
             Object::DataMask(o) => o.render(ui, pool, position),
@@ -74,15 +90,15 @@ impl RenderableObject for Object {
             Object::AuxiliaryFunctionType2(o) => o.render(ui, pool, position),
             Object::AuxiliaryInputType2(o) => o.render(ui, pool, position),
             Object::AuxiliaryControlDesignatorType2(o) => o.render(ui, pool, position),
-            Object::WindowMask(o) => (),
+            Object::WindowMask(o) => o.render(ui, pool, position),
             Object::KeyGroup(o) => (),
-            Object::GraphicsContext(o) => (),
+            Object::GraphicsContext(o) => o.render(ui, pool, position),
             Object::ExtendedInputAttributes(o) => (),
             Object::ColourMap(o) => (),
             Object::ObjectLabelReferenceList(o) => (),
             Object::ExternalObjectDefinition(o) => (),
             Object::ExternalReferenceName(o) => (),
-            Object::ExternalObjectPointer(o) => (),
+            Object::ExternalObjectPointer(o) => o.render(ui, pool, position),
             Object::Animation(o) => (),
             Object::ColourPalette(o) => (),
             Object::GraphicData(o) => (),
@@ -102,6 +118,148 @@ impl Colorable for Colour {
     }
 }
 
+/// Id of the temporary memory holding the decimal separator used to render numbers
+const DECIMAL_SEPARATOR_ID: &str = "decimal_separator_preview";
+
+/// Set the decimal separator used when rendering number objects,
+/// so layouts can be checked with the number format of the operator's language
+pub fn set_decimal_separator_preview(ctx: &egui::Context, separator: char) {
+    ctx.data_mut(|data| data.insert_temp(egui::Id::new(DECIMAL_SEPARATOR_ID), separator));
+}
+
+/// Id of the temporary memory holding the drawing commands previewed on graphics contexts
+const GRAPHICS_COMMANDS_ID: &str = "graphics_commands_preview";
+
+/// Set the drawing commands shown on graphics contexts, per graphics context,
+/// to preview the canvas content the ECU draws at runtime
+pub fn set_graphics_commands_preview(
+    ctx: &egui::Context,
+    previews: Arc<HashMap<ObjectId, Vec<GraphicsCommand>>>,
+) {
+    ctx.data_mut(|data| data.insert_temp(egui::Id::new(GRAPHICS_COMMANDS_ID), previews));
+}
+
+/// Get the decimal separator commonly used with an ISO 639-1 language code
+pub fn decimal_separator_for_language(language_code: &str) -> char {
+    match language_code.to_ascii_lowercase().as_str() {
+        "bg" | "cs" | "da" | "de" | "el" | "es" | "et" | "fi" | "fr" | "hr" | "hu" | "is"
+        | "it" | "lt" | "lv" | "nb" | "nl" | "nn" | "no" | "pl" | "pt" | "ro" | "ru" | "sk"
+        | "sl" | "sr" | "sv" | "tr" | "uk" => ',',
+        _ => '.',
+    }
+}
+
+/// Replace the decimal point of a formatted number with the previewed decimal separator
+fn localize_decimal_separator(ui: &egui::Ui, number: String) -> String {
+    let separator = ui
+        .data(|data| data.get_temp::<char>(egui::Id::new(DECIMAL_SEPARATOR_ID)))
+        .unwrap_or('.');
+    if separator == '.' {
+        number
+    } else {
+        number.replace('.', &separator.to_string())
+    }
+}
+
+/// Id of the temporary memory holding whether flashing and blinking are animated
+const ANIMATIONS_ID: &str = "animations_enabled";
+
+/// Id of the temporary memory holding the objects previewed as blinking
+const BLINKING_OBJECTS_ID: &str = "blinking_objects";
+
+/// Time flashing text and blinking objects stay in each of their two states, VTs commonly
+/// flash at about 1 Hz
+const FLASH_HALF_PERIOD: f64 = 0.5;
+
+/// Animate flashing text and blinking objects, or show them in their normal state, e.g. for
+/// screenshots
+pub fn set_animations_enabled(ctx: &egui::Context, enabled: bool) {
+    ctx.data_mut(|data| data.insert_temp(egui::Id::new(ANIMATIONS_ID), enabled));
+}
+
+/// Set the objects shown blinking, like an ECU hiding and showing them periodically
+pub fn set_blinking_objects_preview(ctx: &egui::Context, objects: Arc<HashSet<ObjectId>>) {
+    ctx.data_mut(|data| data.insert_temp(egui::Id::new(BLINKING_OBJECTS_ID), objects));
+}
+
+/// Whether flashing text and blinking objects are in their alternate state (hidden or
+/// inverted) at this moment. Requests a repaint for the next change of the state while
+/// animations are enabled.
+fn flash_state(ui: &egui::Ui) -> bool {
+    let enabled = ui
+        .data(|data| data.get_temp::<bool>(egui::Id::new(ANIMATIONS_ID)))
+        .unwrap_or(true);
+    if !enabled {
+        return false;
+    }
+    let time = ui.input(|input| input.time);
+    let remaining = FLASH_HALF_PERIOD - time % FLASH_HALF_PERIOD;
+    ui.ctx()
+        .request_repaint_after(std::time::Duration::from_secs_f64(remaining));
+    (time / FLASH_HALF_PERIOD) as u64 % 2 == 1
+}
+
+/// Lay out text with the style of its font attributes: italic, underlined, crossed out and
+/// (flashing) inverted. Inverted text is drawn in the background colour on the font colour.
+fn layout_styled_text(
+    ui: &egui::Ui,
+    text: String,
+    font_id: FontId,
+    font_attributes: &FontAttributes,
+    font_colour: Color32,
+    background_colour: Color32,
+    wrap_width: f32,
+) -> Arc<egui::Galley> {
+    let style = &font_attributes.font_style;
+    let inverted = style.inverted ^ (style.flashing_inverted && flash_state(ui));
+    let (colour, background) = if inverted {
+        (background_colour, font_colour)
+    } else {
+        (font_colour, Color32::TRANSPARENT)
+    };
+    let line_stroke = egui::Stroke::new((font_id.size / 12.0).max(1.0), colour);
+    let format = egui::TextFormat {
+        font_id,
+        color: colour,
+        background,
+        italics: style.italic,
+        underline: if style.underlined {
+            line_stroke
+        } else {
+            egui::Stroke::NONE
+        },
+        strikethrough: if style.crossed_out {
+            line_stroke
+        } else {
+            egui::Stroke::NONE
+        },
+        ..Default::default()
+    };
+    let mut job = egui::text::LayoutJob::single_section(text, format);
+    job.wrap.max_width = wrap_width;
+    ui.fonts(|fonts| fonts.layout_job(job))
+}
+
+/// Paint text laid out with [`layout_styled_text`]. Bold text is painted twice, a pixel apart,
+/// and flashing hidden text is left out while it is hidden.
+fn paint_styled_text(
+    ui: &egui::Ui,
+    position: egui::Pos2,
+    galley: Arc<egui::Galley>,
+    font_attributes: &FontAttributes,
+    font_colour: Color32,
+) {
+    let style = &font_attributes.font_style;
+    if style.flashing_hidden && flash_state(ui) {
+        return;
+    }
+    if style.bold {
+        ui.painter()
+            .galley(position + egui::vec2(1.0, 0.0), galley.clone(), font_colour);
+    }
+    ui.painter().galley(position, galley, font_colour);
+}
+
 // Helper function to lighten a color by a certain amount
 fn lighten_color(color: egui::Color32, amount: f32) -> egui::Color32 {
     let r = (color.r() as f32 + 255.0 * amount).min(255.0) as u8;
@@ -118,6 +276,29 @@ fn darken_color(color: egui::Color32, amount: f32) -> egui::Color32 {
     egui::Color32::from_rgb(r, g, b)
 }
 
+/// Number of user-layout cells (columns, rows) covered by a window mask cell format
+pub fn window_mask_cells(cell_format: WindowMaskCellFormat) -> (u8, u8) {
+    match cell_format {
+        WindowMaskCellFormat::CF1x1 => (1, 1),
+        WindowMaskCellFormat::CF1x2 => (1, 2),
+        WindowMaskCellFormat::CF1x3 => (1, 3),
+        WindowMaskCellFormat::CF1x4 => (1, 4),
+        WindowMaskCellFormat::CF1x5 => (1, 5),
+        WindowMaskCellFormat::CF1x6 => (1, 6),
+        WindowMaskCellFormat::CF2x1 => (2, 1),
+        WindowMaskCellFormat::CF2x2 => (2, 2),
+        WindowMaskCellFormat::CF2x3 => (2, 3),
+        WindowMaskCellFormat::CF2x4 => (2, 4),
+        WindowMaskCellFormat::CF2x5 => (2, 5),
+        WindowMaskCellFormat::CF2x6 => (2, 6),
+    }
+}
+
+/// Size of a single window cell, the user-layout data mask is divided in 2 columns of 6 rows
+pub fn window_mask_cell_size(mask_size: u16) -> egui::Vec2 {
+    egui::vec2(mask_size as f32 / 2.0, mask_size as f32 / 6.0)
+}
+
 fn create_relative_rect(ui: &mut egui::Ui, position: Point<i16>, size: egui::Vec2) -> egui::Rect {
     let width = ui.max_rect().width().sub(position.x as f32).min(size.x);
     let height = ui.max_rect().height().sub(position.y as f32).min(size.y);
@@ -182,6 +363,42 @@ impl RenderableObject for AlarmMask {
     }
 }
 
+impl RenderableObject for WindowMask {
+    fn render(&self, ui: &mut egui::Ui, pool: &ObjectPool, _: Point<i16>) {
+        let rect = ui.available_rect_before_wrap();
+        if !self.options.transparent {
+            ui.painter().rect_filled(
+                rect,
+                0.0,
+                pool.color_by_index(self.background_colour).convert(),
+            );
+        }
+
+        if self.window_type == WindowType::FreeForm {
+            render_object_refs(ui, pool, &self.object_refs);
+            return;
+        }
+
+        // The VT positions the objects of a predefined window type, preview them side by side
+        let slot_width = rect.width() / self.objects.len().max(1) as f32;
+        for (idx, id) in self.objects.iter().enumerate() {
+            match pool.object_by_id(*id) {
+                Some(obj) => {
+                    let (_, height) = pool.content_size(obj);
+                    let position = Point {
+                        x: (idx as f32 * slot_width) as i16,
+                        y: ((rect.height() - height as f32) / 2.0).max(0.0) as i16,
+                    };
+                    obj.render(ui, pool, position);
+                }
+                None => {
+                    ui.colored_label(Color32::RED, format!("Missing object: {:?}", id));
+                }
+            }
+        }
+    }
+}
+
 impl RenderableObject for Container {
     fn render(&self, ui: &mut egui::Ui, pool: &ObjectPool, position: Point<i16>) {
         if self.hidden {
@@ -447,6 +664,7 @@ impl RenderableObject for InputNumber {
             } else {
                 format!("{:.*}", decimals as usize, displayed_value)
             };
+            number_string = localize_decimal_separator(ui, number_string);
 
             // If the "display_leading_zeros" option is set, try to pad the text on the left with zeros
             // so that it fills (or exceeds) the available field width.
@@ -487,11 +705,13 @@ impl RenderableObject for InputNumber {
             let font_id = egui::FontId::new(font_height, font_family);
 
             // Lay out the text.
-            let fonts = ui.fonts(|f| f.clone());
-            let galley = fonts.layout(
+            let galley = layout_styled_text(
+                ui,
                 number_string.clone(),
                 font_id.clone(),
+                font_attributes,
                 font_colour,
+                background_colour,
                 f32::INFINITY,
             );
             let text_size = galley.size();
@@ -536,7 +756,7 @@ impl RenderableObject for InputNumber {
             }
 
             // Draw the number string.
-            ui.painter().galley(paint_pos, galley, font_colour);
+            paint_styled_text(ui, paint_pos, galley, font_attributes, font_colour);
 
             // If the InputNumber object is not enabled (according to its InputNumberOptions),
             // overlay a semi‐transparent gray rectangle.
@@ -593,6 +813,21 @@ impl RenderableObject for ObjectPointer {
     }
 }
 
+impl RenderableObject for ExternalObjectPointer {
+    fn render(&self, ui: &mut egui::Ui, pool: &ObjectPool, position: Point<i16>) {
+        // The external object is in the pool of another working set, so the designer shows the
+        // default object that the VT falls back to
+        if let Some(id) = self.default_object_id.0 {
+            match pool.object_by_id(id) {
+                Some(obj) => obj.render(ui, pool, position),
+                None => {
+                    ui.colored_label(Color32::RED, format!("Missing object: {:?}", self));
+                }
+            }
+        }
+    }
+}
+
 impl RenderableObject for OutputString {
     fn render(&self, ui: &mut egui::Ui, pool: &ObjectPool, position: Point<i16>) {
         let rect = create_relative_rect(
@@ -703,10 +938,13 @@ impl RenderableObject for OutputString {
             f32::INFINITY
         };
 
-        let galley = fonts.layout(
+        let galley = layout_styled_text(
+            ui,
             processed_text,
             FontId::new(font_height, font_family.clone()),
+            font_attributes,
             font_colour,
+            background_colour,
             wrap_width,
         );
 
@@ -757,7 +995,7 @@ impl RenderableObject for OutputString {
             painter.rect_filled(rect, 0.0, background_colour);
         }
 
-        ui.painter().galley(paint_pos, galley, font_colour);
+        paint_styled_text(ui, paint_pos, galley, font_attributes, font_colour);
     }
 }
 
@@ -838,6 +1076,7 @@ impl RenderableObject for OutputNumber {
             } else {
                 format!("{:.*}", decimals as usize, displayed_value)
             };
+            number_string = localize_decimal_separator(ui, number_string);
 
             // 9. The standard states that we must always display at least one digit
             //    before the decimal point (i.e., "0.xxxx" if the absolute value < 1)
@@ -881,7 +1120,6 @@ impl RenderableObject for OutputNumber {
             // 11. We have the final text we want to display in `number_string`.
             //     Next, figure out the font size and alignment. This is similar
             //     to the `OutputString` example.
-            let fonts = ui.fonts(|fonts| fonts.clone());
             let (font_family, font_height) = match font_attributes.font_size {
                 FontSize::NonProportional(npsize) => {
                     // For simplicity, treat it as monospace
@@ -890,10 +1128,13 @@ impl RenderableObject for OutputNumber {
                 FontSize::Proportional(h) => (egui::FontFamily::Proportional, h as f32),
             };
             let font_id = egui::FontId::new(font_height, font_family);
-            let galley = fonts.layout(
+            let galley = layout_styled_text(
+                ui,
                 number_string.clone(),
                 font_id.clone(),
+                font_attributes,
                 font_colour,
+                background_colour,
                 f32::INFINITY, // no wrapping
             );
             let text_size = galley.size();
@@ -938,7 +1179,7 @@ impl RenderableObject for OutputNumber {
             }
 
             // 13. Finally, paint the text
-            ui.painter().galley(paint_pos, galley, font_colour);
+            paint_styled_text(ui, paint_pos, galley, font_attributes, font_colour);
         });
     }
 }
@@ -1076,6 +1317,216 @@ impl RenderableObject for OutputRectangle {
     }
 }
 
+impl RenderableObject for GraphicsContext {
+    fn render(&self, ui: &mut egui::Ui, pool: &ObjectPool, position: Point<i16>) {
+        let rect = create_relative_rect(
+            ui,
+            position,
+            egui::Vec2::new(self.viewport_width as f32, self.viewport_height as f32),
+        );
+
+        // The content of the canvas is drawn at runtime by the application, so we can only show
+        // the empty canvas as it is seen through the viewport
+        let (background_colour, foreground_colour) = match self.options.color {
+            ColorOption::ForegroundBackground => (
+                pool.color_by_index(self.background_colour).convert(),
+                pool.color_by_index(self.foreground_colour).convert(),
+            ),
+            _ => {
+                let fill_colour = match self.fill_attributes_object.into() {
+                    Some(id) => match pool.object_by_id(id) {
+                        Some(Object::FillAttributes(f)) => Some(f.fill_colour),
+                        _ => None,
+                    },
+                    None => None,
+                };
+                let line_colour = match self.line_attributes_object.into() {
+                    Some(id) => match pool.object_by_id(id) {
+                        Some(Object::LineAttributes(l)) => Some(l.line_colour),
+                        _ => None,
+                    },
+                    None => None,
+                };
+                (
+                    pool.color_by_index(fill_colour.unwrap_or(self.background_colour))
+                        .convert(),
+                    pool.color_by_index(line_colour.unwrap_or(self.foreground_colour))
+                        .convert(),
+                )
+            }
+        };
+
+        // A zoom of 0 shows the canvas 1:1, positive values zoom in and negative values zoom out
+        let zoom = if self.viewport_zoom > 0.0 {
+            self.viewport_zoom
+        } else if self.viewport_zoom < 0.0 {
+            1.0 / -self.viewport_zoom
+        } else {
+            1.0
+        };
+        let canvas_rect = egui::Rect::from_min_size(
+            rect.min - egui::vec2(self.viewport_x as f32, self.viewport_y as f32) * zoom,
+            egui::vec2(self.canvas_width as f32, self.canvas_height as f32) * zoom,
+        );
+
+        let painter = ui.painter().with_clip_rect(rect.intersect(ui.clip_rect()));
+        let transparent =
+            self.options.transparent && self.transparency_colour == self.background_colour;
+        if !transparent {
+            painter.rect_filled(canvas_rect, 0.0, background_colour);
+        }
+        painter.rect_stroke(
+            canvas_rect,
+            0.0,
+            egui::Stroke::new(1.0, foreground_colour.gamma_multiply(0.5)),
+            egui::StrokeKind::Inside,
+        );
+
+        let previews = ui.data(|data| {
+            data.get_temp::<Arc<HashMap<ObjectId, Vec<GraphicsCommand>>>>(egui::Id::new(
+                GRAPHICS_COMMANDS_ID,
+            ))
+        });
+        if let Some(commands) = previews
+            .as_ref()
+            .and_then(|previews| previews.get(&self.id))
+        {
+            paint_graphics_commands(ui, &painter, canvas_rect, zoom, pool, self, commands);
+        }
+
+        // Mark the graphics cursor position
+        let cursor = canvas_rect.min
+            + egui::vec2(self.graphics_cursor_x as f32, self.graphics_cursor_y as f32) * zoom;
+        let stroke = egui::Stroke::new(1.0, foreground_colour);
+        painter.line_segment(
+            [cursor - egui::vec2(4.0, 0.0), cursor + egui::vec2(4.0, 0.0)],
+            stroke,
+        );
+        painter.line_segment(
+            [cursor - egui::vec2(0.0, 4.0), cursor + egui::vec2(0.0, 4.0)],
+            stroke,
+        );
+    }
+}
+
+/// Paint drawing commands on the canvas of a graphics context, the canvas being shown in
+/// `canvas_rect` with the given zoom
+pub fn paint_graphics_commands(
+    ui: &egui::Ui,
+    painter: &egui::Painter,
+    canvas_rect: egui::Rect,
+    zoom: f32,
+    pool: &ObjectPool,
+    context: &GraphicsContext,
+    commands: &[GraphicsCommand],
+) {
+    let mut cursor = (context.graphics_cursor_x, context.graphics_cursor_y);
+    let mut foreground = context.foreground_colour;
+    let mut background = context.background_colour;
+    let to_screen = |x: i16, y: i16| canvas_rect.min + egui::vec2(x as f32, y as f32) * zoom;
+    let area = |cursor: (i16, i16), width: u16, height: u16| {
+        egui::Rect::from_min_size(
+            to_screen(cursor.0, cursor.1),
+            egui::vec2(width as f32, height as f32) * zoom,
+        )
+    };
+    let bottom_right = |cursor: (i16, i16), width: u16, height: u16| {
+        (
+            cursor.0.saturating_add(width.saturating_sub(1) as i16),
+            cursor.1.saturating_add(height.saturating_sub(1) as i16),
+        )
+    };
+    let font_attributes = match context.font_attributes_object.into() {
+        Some(id) => match pool.object_by_id(id) {
+            Some(Object::FontAttributes(f)) => Some(f.clone()),
+            _ => None,
+        },
+        None => None,
+    };
+
+    for command in commands {
+        let stroke = egui::Stroke::new(zoom, pool.color_by_index(foreground).convert());
+        match command {
+            GraphicsCommand::SetCursor { x, y } => cursor = (*x, *y),
+            GraphicsCommand::MoveCursor { dx, dy } => {
+                cursor = (cursor.0.saturating_add(*dx), cursor.1.saturating_add(*dy))
+            }
+            GraphicsCommand::SetForeground(colour) => foreground = *colour,
+            GraphicsCommand::SetBackground(colour) => background = *colour,
+            GraphicsCommand::DrawLine { x, y } => {
+                painter.line_segment([to_screen(cursor.0, cursor.1), to_screen(*x, *y)], stroke);
+                cursor = (*x, *y);
+            }
+            GraphicsCommand::DrawRectangle {
+                width,
+                height,
+                filled,
+            } => {
+                let rect = area(cursor, *width, *height);
+                if *filled {
+                    painter.rect_filled(rect, 0.0, stroke.color);
+                } else {
+                    painter.rect_stroke(rect, 0.0, stroke, egui::StrokeKind::Inside);
+                }
+                cursor = bottom_right(cursor, *width, *height);
+            }
+            GraphicsCommand::DrawEllipse {
+                width,
+                height,
+                filled,
+            } => {
+                let rect = area(cursor, *width, *height);
+                if *filled {
+                    painter.add(egui::Shape::ellipse_filled(
+                        rect.center(),
+                        rect.size() / 2.0,
+                        stroke.color,
+                    ));
+                } else {
+                    painter.add(egui::Shape::ellipse_stroke(
+                        rect.center(),
+                        rect.size() / 2.0,
+                        stroke,
+                    ));
+                }
+                cursor = bottom_right(cursor, *width, *height);
+            }
+            GraphicsCommand::EraseRectangle { width, height } => {
+                painter.rect_filled(
+                    area(cursor, *width, *height),
+                    0.0,
+                    pool.color_by_index(background).convert(),
+                );
+                cursor = bottom_right(cursor, *width, *height);
+            }
+            GraphicsCommand::DrawText(text) => {
+                let (font_family, font_height, colour) = match &font_attributes {
+                    Some(font_attributes) => {
+                        let (family, height) = match font_attributes.font_size {
+                            FontSize::NonProportional(size) => {
+                                (egui::FontFamily::Monospace, size.height() as f32)
+                            }
+                            FontSize::Proportional(height) => {
+                                (egui::FontFamily::Proportional, height as f32)
+                            }
+                        };
+                        (family, height, font_attributes.font_colour)
+                    }
+                    None => (egui::FontFamily::Monospace, 8.0, foreground),
+                };
+                let galley = ui.fonts(|fonts| {
+                    fonts.layout_no_wrap(
+                        text.clone(),
+                        egui::FontId::new(font_height * zoom, font_family),
+                        pool.color_by_index(colour).convert(),
+                    )
+                });
+                painter.galley(to_screen(cursor.0, cursor.1), galley, Color32::PLACEHOLDER);
+            }
+        }
+    }
+}
+
 impl RenderableObject for OutputEllipse {
     fn render(&self, ui: &mut egui::Ui, pool: &ObjectPool, position: Point<i16>) {
         let rect = create_relative_rect(
@@ -0,0 +1,331 @@
+//! Copyright 2024 - The Open-Agriculture Developers
+//! SPDX-License-Identifier: GPL-3.0-or-later
+//! Authors: Daan Steenbergen
+
+use std::cell::RefCell;
+use std::collections::HashMap;
+
+use ag_iso_stack::object_pool::object::{Object, SoftKeyMask};
+use ag_iso_stack::object_pool::object_attributes::Point;
+use ag_iso_stack::object_pool::ObjectPool;
+use eframe::egui;
+use eframe::egui::epaint::{Mesh, Primitive};
+use eframe::egui::Color32;
+use eframe::egui::ColorImage;
+
+use crate::RenderableObject;
+
+/// Renders objects into images without a window.
+/// A headless egui context runs the regular `RenderableObject` code, after which the resulting
+/// meshes are rasterized on the CPU. The context is kept alive between renders so fonts are
+/// only loaded once.
+struct OffscreenRenderer {
+    ctx: egui::Context,
+    textures: HashMap<egui::TextureId, ColorImage>,
+}
+
+thread_local! {
+    static RENDERER: RefCell<OffscreenRenderer> = RefCell::new(OffscreenRenderer::new());
+}
+
+impl OffscreenRenderer {
+    fn new() -> Self {
+        OffscreenRenderer {
+            ctx: egui::Context::default(),
+            textures: HashMap::new(),
+        }
+    }
+
+    fn render(&mut self, object: &Object, pool: &ObjectPool, scale: f32) -> ColorImage {
+        let (width, height) = pool.content_size(object);
+        let size = egui::vec2(width.max(1) as f32, height.max(1) as f32);
+
+        let mut raw_input = egui::RawInput {
+            screen_rect: Some(egui::Rect::from_min_size(egui::Pos2::ZERO, size)),
+            ..Default::default()
+        };
+        raw_input
+            .viewports
+            .entry(egui::ViewportId::ROOT)
+            .or_default()
+            .native_pixels_per_point = Some(scale);
+
+        let output = self.ctx.run(raw_input, |ctx| {
+            egui::CentralPanel::default()
+                .frame(egui::Frame::NONE)
+                .show(ctx, |ui| {
+                    object.render(ui, pool, Point::default());
+                });
+        });
+
+        for (id, delta) in &output.textures_delta.set {
+            #[allow(unreachable_patterns)]
+            let delta_image = match &delta.image {
+                egui::ImageData::Color(image) => image,
+                _ => continue,
+            };
+            match (delta.pos, self.textures.get_mut(id)) {
+                (Some([x, y]), Some(existing)) => {
+                    // Partial update of an existing texture
+                    let [w, h] = delta_image.size;
+                    for row in 0..h {
+                        for col in 0..w {
+                            let dst = (y + row) * existing.size[0] + (x + col);
+                            if let Some(pixel) = existing.pixels.get_mut(dst) {
+                                *pixel = delta_image.pixels[row * w + col];
+                            }
+                        }
+                    }
+                }
+                _ => {
+                    self.textures.insert(*id, (**delta_image).clone());
+                }
+            }
+        }
+
+        let pixels_per_point = output.pixels_per_point;
+        let primitives = self.ctx.tessellate(output.shapes, pixels_per_point);
+
+        let mut image = ColorImage::filled(
+            [
+                ((size.x * pixels_per_point).round() as usize).max(1),
+                ((size.y * pixels_per_point).round() as usize).max(1),
+            ],
+            Color32::TRANSPARENT,
+        );
+        for clipped in &primitives {
+            if let Primitive::Mesh(mesh) = &clipped.primitive {
+                self.rasterize_mesh(&mut image, mesh, clipped.clip_rect, pixels_per_point);
+            }
+        }
+
+        for id in &output.textures_delta.free {
+            self.textures.remove(id);
+        }
+
+        image
+    }
+
+    /// Rasterize a single mesh into the image using premultiplied alpha blending
+    fn rasterize_mesh(
+        &self,
+        image: &mut ColorImage,
+        mesh: &Mesh,
+        clip_rect: egui::Rect,
+        pixels_per_point: f32,
+    ) {
+        let texture = self.textures.get(&mesh.texture_id);
+        let [image_width, image_height] = image.size;
+
+        let clip_min_x = (clip_rect.min.x * pixels_per_point).floor().max(0.0) as usize;
+        let clip_min_y = (clip_rect.min.y * pixels_per_point).floor().max(0.0) as usize;
+        let clip_max_x =
+            ((clip_rect.max.x * pixels_per_point).ceil().max(0.0) as usize).min(image_width);
+        let clip_max_y =
+            ((clip_rect.max.y * pixels_per_point).ceil().max(0.0) as usize).min(image_height);
+
+        for triangle in mesh.indices.chunks_exact(3) {
+            let vertices = [
+                &mesh.vertices[triangle[0] as usize],
+                &mesh.vertices[triangle[1] as usize],
+                &mesh.vertices[triangle[2] as usize],
+            ];
+            let points = vertices.map(|v| v.pos.to_vec2() * pixels_per_point);
+
+            let area = edge_function(points[0], points[1], points[2]);
+            if area.abs() < f32::EPSILON {
+                continue;
+            }
+
+            let min_x = points.iter().map(|p| p.x).fold(f32::INFINITY, f32::min);
+            let max_x = points.iter().map(|p| p.x).fold(f32::NEG_INFINITY, f32::max);
+            let min_y = points.iter().map(|p| p.y).fold(f32::INFINITY, f32::min);
+            let max_y = points.iter().map(|p| p.y).fold(f32::NEG_INFINITY, f32::max);
+
+            let x0 = (min_x.floor().max(0.0) as usize).max(clip_min_x);
+            let x1 = (max_x.ceil().max(0.0) as usize).min(clip_max_x);
+            let y0 = (min_y.floor().max(0.0) as usize).max(clip_min_y);
+            let y1 = (max_y.ceil().max(0.0) as usize).min(clip_max_y);
+
+            for y in y0..y1 {
+                for x in x0..x1 {
+                    let sample = egui::vec2(x as f32 + 0.5, y as f32 + 0.5);
+                    let w0 = edge_function(points[1], points[2], sample) / area;
+                    let w1 = edge_function(points[2], points[0], sample) / area;
+                    let w2 = 1.0 - w0 - w1;
+                    if w0 < -1e-4 || w1 < -1e-4 || w2 < -1e-4 {
+                        continue;
+                    }
+                    let weights = [w0, w1, w2];
+
+                    let channel = |f: fn(&Color32) -> u8| -> f32 {
+                        (0..3)
+                            .map(|i| weights[i] * f(&vertices[i].color) as f32)
+                            .sum()
+                    };
+                    let vertex_colour = [
+                        channel(Color32::r),
+                        channel(Color32::g),
+                        channel(Color32::b),
+                        channel(Color32::a),
+                    ];
+
+                    let u: f32 = (0..3).map(|i| weights[i] * vertices[i].uv.x).sum();
+                    let v: f32 = (0..3).map(|i| weights[i] * vertices[i].uv.y).sum();
+                    let texel = texture.map_or(Color32::WHITE, |t| sample_texture(t, u, v));
+
+                    let src = [
+                        vertex_colour[0] * texel.r() as f32 / 255.0,
+                        vertex_colour[1] * texel.g() as f32 / 255.0,
+                        vertex_colour[2] * texel.b() as f32 / 255.0,
+                        vertex_colour[3] * texel.a() as f32 / 255.0,
+                    ];
+
+                    blend_pixel(&mut image.pixels[y * image_width + x], src);
+                }
+            }
+        }
+    }
+}
+
+/// Draw a premultiplied colour, given per channel in the range 0-255, over a pixel
+fn blend_pixel(pixel: &mut Color32, src: [f32; 4]) {
+    let inverse_alpha = 1.0 - src[3] / 255.0;
+    *pixel = Color32::from_rgba_premultiplied(
+        (src[0] + pixel.r() as f32 * inverse_alpha).min(255.0) as u8,
+        (src[1] + pixel.g() as f32 * inverse_alpha).min(255.0) as u8,
+        (src[2] + pixel.b() as f32 * inverse_alpha).min(255.0) as u8,
+        (src[3] + pixel.a() as f32 * inverse_alpha).min(255.0) as u8,
+    );
+}
+
+/// Draw an image over the target image with its top left corner at the given position
+fn blend_image(target: &mut ColorImage, image: &ColorImage, left: usize, top: usize) {
+    let [width, height] = image.size;
+    let target_width = target.size[0];
+    for y in 0..height {
+        for x in 0..width {
+            let src = image.pixels[y * width + x];
+            blend_pixel(
+                &mut target.pixels[(top + y) * target_width + left + x],
+                [src.r(), src.g(), src.b(), src.a()].map(f32::from),
+            );
+        }
+    }
+}
+
+fn edge_function(a: egui::Vec2, b: egui::Vec2, c: egui::Vec2) -> f32 {
+    (b.x - a.x) * (c.y - a.y) - (b.y - a.y) * (c.x - a.x)
+}
+
+/// Nearest-neighbour texture lookup with normalized coordinates
+fn sample_texture(texture: &ColorImage, u: f32, v: f32) -> Color32 {
+    let [width, height] = texture.size;
+    if width == 0 || height == 0 {
+        return Color32::TRANSPARENT;
+    }
+    let x = ((u * width as f32) as usize).min(width - 1);
+    let y = ((v * height as f32) as usize).min(height - 1);
+    texture.pixels[y * width + x]
+}
+
+/// Render an object (including its children) into an image.
+/// The scale determines the amount of pixels per VT pixel.
+pub fn render_object_to_image(object: &Object, pool: &ObjectPool, scale: f32) -> ColorImage {
+    RENDERER.with(|renderer| renderer.borrow_mut().render(object, pool, scale))
+}
+
+/// Render a thumbnail of an object that fits within a square of `max_size` pixels.
+/// Returns None if the object has no visual size.
+pub fn render_object_thumbnail(
+    object: &Object,
+    pool: &ObjectPool,
+    max_size: f32,
+) -> Option<ColorImage> {
+    let (width, height) = pool.content_size(object);
+    if width == 0 || height == 0 {
+        return None;
+    }
+    let scale = (max_size / width as f32)
+        .min(max_size / height as f32)
+        .min(1.0);
+    Some(render_object_to_image(object, pool, scale))
+}
+
+/// Render a soft key mask by stacking its keys vertically on the mask background colour,
+/// similar to how a VT shows the soft key column.
+pub fn render_soft_key_mask_to_image(
+    mask: &SoftKeyMask,
+    pool: &ObjectPool,
+    scale: f32,
+) -> ColorImage {
+    let keys: Vec<ColorImage> = mask
+        .objects
+        .iter()
+        .filter_map(|id| pool.object_by_id(*id))
+        .map(|key| render_object_to_image(key, pool, scale))
+        .collect();
+
+    let width = keys.iter().map(|k| k.size[0]).max().unwrap_or(1);
+    let height = keys.iter().map(|k| k.size[1]).sum::<usize>().max(1);
+
+    let background = pool.color_by_index(mask.background_colour);
+    let mut image = ColorImage::filled(
+        [width, height],
+        Color32::from_rgb(background.r, background.g, background.b),
+    );
+
+    let mut y_offset = 0;
+    for key in keys {
+        blend_image(&mut image, &key, 0, y_offset);
+        y_offset += key.size[1];
+    }
+
+    image
+}
+
+/// Place an image on a background with a margin around it, outlined by a border line
+pub fn frame_image(
+    image: &ColorImage,
+    background: Color32,
+    border: Color32,
+    margin: usize,
+) -> ColorImage {
+    let [width, height] = image.size;
+    let framed_width = width + 2 * margin;
+    let mut framed = ColorImage::filled([framed_width, height + 2 * margin], background);
+
+    blend_image(&mut framed, image, margin, margin);
+
+    // Outline the image just outside of its area
+    if margin > 0 {
+        let (left, top) = (margin - 1, margin - 1);
+        let (right, bottom) = (margin + width, margin + height);
+        for x in left..=right {
+            framed.pixels[top * framed_width + x] = border;
+            framed.pixels[bottom * framed_width + x] = border;
+        }
+        for y in top..=bottom {
+            framed.pixels[y * framed_width + left] = border;
+            framed.pixels[y * framed_width + right] = border;
+        }
+    }
+
+    framed
+}
+
+/// Encode an image as PNG file contents
+pub fn encode_png(image: &ColorImage) -> Result<Vec<u8>, image::ImageError> {
+    let [width, height] = image.size;
+    let bytes: Vec<u8> = image
+        .pixels
+        .iter()
+        .flat_map(|pixel| pixel.to_srgba_unmultiplied())
+        .collect();
+    let buffer = image::RgbaImage::from_raw(width as u32, height as u32, bytes)
+        .expect("Pixel buffer should match the image size");
+
+    let mut png = Vec::new();
+    buffer.write_to(&mut std::io::Cursor::new(&mut png), image::ImageFormat::Png)?;
+    Ok(png)
+}
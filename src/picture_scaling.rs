@@ -0,0 +1,194 @@
+//! Copyright 2024 - The Open-Agriculture Developers
+//! SPDX-License-Identifier: GPL-3.0-or-later
+//! Authors: Daan Steenbergen
+
+use ag_iso_stack::object_pool::object::PictureGraphic;
+use ag_iso_stack::object_pool::object_attributes::{DataCodeType, PictureGraphicFormat};
+use ag_iso_stack::object_pool::Colour;
+
+/// How the scaled variants of a picture are made
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ScaledVariantKind {
+    /// A new picture graphic with resampled pixel data per size, for every VT version
+    Resampled,
+    /// A scaled graphic per size showing the original picture, for VT version 6 or later
+    ScaledGraphic,
+}
+
+/// Amount of colours a pixel of the format can have
+pub(crate) fn colour_count(format: &PictureGraphicFormat) -> usize {
+    match format {
+        PictureGraphicFormat::Monochrome => 2,
+        PictureGraphicFormat::FourBit => 16,
+        PictureGraphicFormat::EightBit => 256,
+    }
+}
+
+/// Index of the colour closest to the given RGB value among the first `count` colours of the
+/// palette
+pub(crate) fn closest_palette_index(palette: &[Colour], count: usize, r: u8, g: u8, b: u8) -> u8 {
+    let distance = |colour: &Colour| {
+        let dr = colour.r as i32 - r as i32;
+        let dg = colour.g as i32 - g as i32;
+        let db = colour.b as i32 - b as i32;
+        dr * dr + dg * dg + db * db
+    };
+    palette
+        .iter()
+        .take(count)
+        .enumerate()
+        .min_by_key(|(_, colour)| distance(colour))
+        .map_or(0, |(index, _)| index as u8)
+}
+
+fn pixels_per_byte(format: &PictureGraphicFormat) -> usize {
+    match format {
+        PictureGraphicFormat::Monochrome => 8,
+        PictureGraphicFormat::FourBit => 2,
+        PictureGraphicFormat::EightBit => 1,
+    }
+}
+
+/// Colour index of every pixel of the picture, row by row
+fn decode_pixels(picture: &PictureGraphic) -> Vec<u8> {
+    let width = picture.actual_width as usize;
+    let height = picture.actual_height as usize;
+    let per_byte = pixels_per_byte(&picture.format);
+    // Every row starts on a new byte
+    let row_bytes = width.div_ceil(per_byte);
+    let data = picture.data_as_raw_encoded();
+
+    let mut pixels = Vec::with_capacity(width * height);
+    for row in data.chunks(row_bytes.max(1)).take(height) {
+        for x in 0..width {
+            let byte = row.get(x / per_byte).copied().unwrap_or(0);
+            let pixel = match picture.format {
+                PictureGraphicFormat::Monochrome => (byte >> (7 - x % 8)) & 0x01,
+                PictureGraphicFormat::FourBit => (byte >> (4 - (x % 2) * 4)) & 0x0F,
+                PictureGraphicFormat::EightBit => byte,
+            };
+            pixels.push(pixel);
+        }
+    }
+    pixels.resize(width * height, 0);
+    pixels
+}
+
+/// Pack the colour indices into raw picture data of the given format
+pub(crate) fn encode_pixels(format: &PictureGraphicFormat, width: usize, pixels: &[u8]) -> Vec<u8> {
+    let per_byte = pixels_per_byte(format);
+    let bits = 8 / per_byte;
+    let mut data = Vec::new();
+    for row in pixels.chunks(width.max(1)) {
+        for chunk in row.chunks(per_byte) {
+            let byte = chunk.iter().enumerate().fold(0u8, |byte, (i, pixel)| {
+                byte | (pixel << (8 - bits * (i + 1)))
+            });
+            data.push(byte);
+        }
+    }
+    data
+}
+
+/// Run-length encode picture data as (count, value) pairs
+pub(crate) fn run_length_encode(data: &[u8]) -> Vec<u8> {
+    let mut rle = Vec::new();
+    for byte in data {
+        match rle.len() {
+            len if len >= 2 && rle[len - 1] == *byte && rle[len - 2] < u8::MAX => {
+                rle[len - 2] += 1;
+            }
+            _ => rle.extend([1, *byte]),
+        }
+    }
+    rle
+}
+
+/// Height of the picture when scaled to the given width, keeping the aspect ratio
+pub fn scaled_height(picture: &PictureGraphic, width: u16) -> u16 {
+    if picture.actual_width == 0 {
+        return 0;
+    }
+    let height = width as u32 * picture.actual_height as u32 / picture.actual_width as u32;
+    height.clamp(1, u16::MAX as u32) as u16
+}
+
+/// Copy of the picture with its pixel data resampled to the given width, keeping the aspect
+/// ratio. Nearest neighbour sampling is used as the pixels are colour indices that can't be
+/// blended.
+pub fn resample_picture(picture: &PictureGraphic, width: u16) -> PictureGraphic {
+    let source = decode_pixels(picture);
+    if source.is_empty() {
+        return picture.clone();
+    }
+    let height = scaled_height(picture, width);
+    let (source_width, source_height) = (
+        picture.actual_width as usize,
+        picture.actual_height as usize,
+    );
+
+    let mut pixels = Vec::with_capacity(width as usize * height as usize);
+    for y in 0..height as usize {
+        let source_y = y * source_height / height as usize;
+        for x in 0..width as usize {
+            let source_x = x * source_width / width as usize;
+            pixels.push(source[source_y * source_width + source_x]);
+        }
+    }
+
+    let mut scaled = picture.clone();
+    scaled.width = width;
+    scaled.actual_width = width;
+    scaled.actual_height = height;
+    store_pixels(&mut scaled, &pixels);
+    scaled
+}
+
+/// Replace the pixel data of the picture by the colour indices, in the format of the picture.
+/// The raw or run-length encoding is chosen, whichever is smaller.
+pub(crate) fn store_pixels(picture: &mut PictureGraphic, pixels: &[u8]) {
+    let raw = encode_pixels(&picture.format, picture.actual_width as usize, pixels);
+    let rle = run_length_encode(&raw);
+    if rle.len() < raw.len() {
+        picture.data = rle;
+        picture.options.data_code_type = DataCodeType::RunLength;
+    } else {
+        picture.data = raw;
+        picture.options.data_code_type = DataCodeType::Raw;
+    }
+}
+
+/// Convert the pixel data of the picture to another format, every pixel gets the closest colour
+/// available in the new format. Used when the image the picture was imported from isn't
+/// available, converting from an 8-bit picture loses the colours outside of the smaller format.
+pub fn convert_format(
+    picture: &mut PictureGraphic,
+    format: PictureGraphicFormat,
+    palette: &[Colour],
+) {
+    if picture.format == format {
+        return;
+    }
+    let count = colour_count(&format);
+    let to_format = |index: u8| match palette.get(index as usize) {
+        Some(_) if (index as usize) < count => index,
+        Some(colour) => closest_palette_index(palette, count, colour.r, colour.g, colour.b),
+        None => 0,
+    };
+    let pixels: Vec<u8> = decode_pixels(picture).into_iter().map(to_format).collect();
+    picture.transparency_colour = to_format(picture.transparency_colour);
+    picture.format = format;
+    store_pixels(picture, &pixels);
+}
+
+/// Parse a list of widths like "32, 64 128", ignoring anything that isn't a valid width
+pub fn parse_widths(text: &str) -> Vec<u16> {
+    let mut widths: Vec<u16> = text
+        .split(|c: char| c == ',' || c.is_whitespace())
+        .filter_map(|part| part.parse().ok())
+        .filter(|width| *width > 0)
+        .collect();
+    widths.sort_unstable();
+    widths.dedup();
+    widths
+}
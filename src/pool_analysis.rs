@@ -0,0 +1,317 @@
+//! Copyright 2024 - The Open-Agriculture Developers
+//! SPDX-License-Identifier: GPL-3.0-or-later
+//! Authors: Daan Steenbergen
+
+use crate::PoolSnapshot;
+use ag_iso_stack::object_pool::object::Object;
+use ag_iso_stack::object_pool::{ObjectId, ObjectPool, ObjectType};
+use eframe::egui;
+use std::collections::hash_map::DefaultHasher;
+use std::collections::HashMap;
+use std::hash::{Hash, Hasher};
+
+#[cfg(not(target_arch = "wasm32"))]
+use std::sync::mpsc::{channel, Receiver};
+
+#[cfg(target_arch = "wasm32")]
+use web_time::{Duration, Instant};
+
+/// Time spent analysing each frame when the analysis can't run in the background
+#[cfg(target_arch = "wasm32")]
+const FRAME_TIME_BUDGET: Duration = Duration::from_millis(4);
+
+/// A problem found in the pool
+#[derive(Clone)]
+pub struct AnalysisIssue {
+    pub object: Option<ObjectId>,
+    pub message: String,
+}
+
+/// Size of all objects of a single type
+#[derive(Clone)]
+pub struct TypeStatistics {
+    pub object_type: ObjectType,
+    pub count: usize,
+    pub bytes: usize,
+}
+
+/// Picture graphics with identical pixel data
+#[derive(Clone)]
+pub struct DuplicatePictures {
+    /// The first of the pictures in the pool, to be kept
+    pub original: ObjectId,
+    pub duplicates: Vec<ObjectId>,
+    /// Size of the duplicates, saved when they are removed
+    pub duplicate_bytes: usize,
+}
+
+/// Result of validating the pool and computing its statistics
+#[derive(Clone, Default)]
+pub struct AnalysisReport {
+    pub issues: Vec<AnalysisIssue>,
+    pub statistics: Vec<TypeStatistics>,
+    pub total_bytes: usize,
+    pub reference_counts: HashMap<ObjectId, usize>,
+    pub unreferenced: Vec<ObjectId>,
+    pub duplicate_pictures: Vec<DuplicatePictures>,
+}
+
+/// Analysis of a single object, merged into the report afterwards
+struct ObjectAnalysis {
+    id: ObjectId,
+    object_type: ObjectType,
+    bytes: usize,
+    references: Vec<ObjectId>,
+    issues: Vec<AnalysisIssue>,
+    /// Hash of the pixel data of a picture graphic
+    picture_hash: Option<u64>,
+}
+
+/// Hash of everything that makes up the image of a picture graphic, the displayed width aside
+fn picture_hash(object: &Object) -> Option<u64> {
+    let Object::PictureGraphic(o) = object else {
+        return None;
+    };
+    let mut hasher = DefaultHasher::new();
+    o.actual_width.hash(&mut hasher);
+    o.actual_height.hash(&mut hasher);
+    o.transparency_colour.hash(&mut hasher);
+    format!("{:?} {:?}", o.format, o.options).hash(&mut hasher);
+    o.data.hash(&mut hasher);
+    Some(hasher.finish())
+}
+
+fn analyse_object(pool: &ObjectPool, object: &Object) -> ObjectAnalysis {
+    let references = object.referenced_objects();
+    let issues = references
+        .iter()
+        .filter(|id| pool.object_by_id(**id).is_none())
+        .map(|id| AnalysisIssue {
+            object: Some(object.id()),
+            message: format!("References missing object {}", id.value()),
+        })
+        .collect();
+
+    ObjectAnalysis {
+        id: object.id(),
+        object_type: object.object_type(),
+        bytes: object.write().len(),
+        references,
+        issues,
+        picture_hash: picture_hash(object),
+    }
+}
+
+/// Combines the object analyses into a report
+#[derive(Default)]
+struct ReportBuilder {
+    report: AnalysisReport,
+    ids: Vec<ObjectId>,
+    /// Pictures by the hash of their pixel data, with their size
+    pictures: HashMap<u64, Vec<(ObjectId, usize)>>,
+}
+
+impl ReportBuilder {
+    fn add(&mut self, analysis: ObjectAnalysis) {
+        let report = &mut self.report;
+        report.issues.extend(analysis.issues);
+        report.total_bytes += analysis.bytes;
+        match report
+            .statistics
+            .iter_mut()
+            .find(|s| s.object_type == analysis.object_type)
+        {
+            Some(statistics) => {
+                statistics.count += 1;
+                statistics.bytes += analysis.bytes;
+            }
+            None => report.statistics.push(TypeStatistics {
+                object_type: analysis.object_type,
+                count: 1,
+                bytes: analysis.bytes,
+            }),
+        }
+        for referenced in analysis.references {
+            *report.reference_counts.entry(referenced).or_insert(0) += 1;
+        }
+        if let Some(hash) = analysis.picture_hash {
+            self.pictures
+                .entry(hash)
+                .or_default()
+                .push((analysis.id, analysis.bytes));
+        }
+        self.ids.push(analysis.id);
+    }
+
+    fn finish(mut self, pool: &ObjectPool) -> AnalysisReport {
+        let report = &mut self.report;
+
+        match pool.working_set_object() {
+            Some(working_set) => {
+                if pool.object_by_id(working_set.active_mask).is_none() {
+                    report.issues.push(AnalysisIssue {
+                        object: Some(working_set.id),
+                        message: "The active mask of the working set doesn't exist".to_string(),
+                    });
+                }
+            }
+            None => report.issues.push(AnalysisIssue {
+                object: None,
+                message: "The pool has no working set".to_string(),
+            }),
+        }
+
+        // The working set is the root of the pool, all other objects should be referenced
+        report.unreferenced = self
+            .ids
+            .into_iter()
+            .filter(|id| {
+                !report.reference_counts.contains_key(id)
+                    && pool
+                        .object_by_id(*id)
+                        .is_some_and(|obj| obj.object_type() != ObjectType::WorkingSet)
+            })
+            .collect();
+        report.unreferenced.sort_by_key(|id| id.value());
+        report.statistics.sort_by(|a, b| b.bytes.cmp(&a.bytes));
+
+        // The objects are added in pool order, so the first picture of a group is kept
+        report.duplicate_pictures = self
+            .pictures
+            .into_values()
+            .filter(|pictures| pictures.len() > 1)
+            .map(|pictures| DuplicatePictures {
+                original: pictures[0].0,
+                duplicates: pictures[1..].iter().map(|(id, _)| *id).collect(),
+                duplicate_bytes: pictures[1..].iter().map(|(_, bytes)| bytes).sum(),
+            })
+            .collect();
+        report
+            .duplicate_pictures
+            .sort_by(|a, b| b.duplicate_bytes.cmp(&a.duplicate_bytes));
+        self.report
+    }
+}
+
+/// State of an analysis that is chunked between frames
+#[cfg(target_arch = "wasm32")]
+struct ChunkedAnalysis {
+    pool: PoolSnapshot,
+    next_index: usize,
+    builder: ReportBuilder,
+}
+
+/// Runs the pool analysis without blocking the UI: on a rayon thread pool on native targets,
+/// and chunked between frames on the web.
+#[derive(Default)]
+pub struct PoolAnalyzer {
+    #[cfg(not(target_arch = "wasm32"))]
+    running: Option<Receiver<AnalysisReport>>,
+    #[cfg(target_arch = "wasm32")]
+    running: Option<ChunkedAnalysis>,
+
+    /// Generation of the pool the current (or running) analysis is made of
+    generation: Option<u64>,
+    report: Option<AnalysisReport>,
+}
+
+impl PoolAnalyzer {
+    /// Start analysing the pool, unless the given generation of it is already analysed.
+    /// A running analysis of an older generation is abandoned.
+    pub fn analyse(&mut self, pool: PoolSnapshot, generation: u64, ctx: &egui::Context) {
+        if self.generation == Some(generation) {
+            return;
+        }
+        self.generation = Some(generation);
+        self.start(pool, ctx);
+    }
+
+    /// Analyse on the thread pool, the UI is repainted when the report is ready
+    #[cfg(not(target_arch = "wasm32"))]
+    fn start(&mut self, pool: PoolSnapshot, ctx: &egui::Context) {
+        use rayon::prelude::*;
+
+        let (sender, receiver) = channel();
+        self.running = Some(receiver);
+        let ctx = ctx.clone();
+        rayon::spawn(move || {
+            let analyses: Vec<ObjectAnalysis> = pool
+                .objects()
+                .par_iter()
+                .map(|object| analyse_object(&pool, object))
+                .collect();
+            let mut builder = ReportBuilder::default();
+            for analysis in analyses {
+                builder.add(analysis);
+            }
+            // The receiver is gone if a newer analysis was started in the meantime
+            _ = sender.send(builder.finish(&pool));
+            ctx.request_repaint();
+        });
+    }
+
+    #[cfg(target_arch = "wasm32")]
+    fn start(&mut self, pool: PoolSnapshot, _ctx: &egui::Context) {
+        self.running = Some(ChunkedAnalysis {
+            pool,
+            next_index: 0,
+            builder: ReportBuilder::default(),
+        });
+    }
+
+    /// Pick up the report of a finished analysis.
+    /// Returns false, the analysis requests a repaint itself when it is finished, so the UI
+    /// doesn't need to repaint while waiting.
+    #[cfg(not(target_arch = "wasm32"))]
+    pub fn poll(&mut self) -> bool {
+        let Some(receiver) = &self.running else {
+            return false;
+        };
+        match receiver.try_recv() {
+            Ok(report) => {
+                self.report = Some(report);
+                self.running = None;
+                false
+            }
+            Err(std::sync::mpsc::TryRecvError::Empty) => false,
+            Err(std::sync::mpsc::TryRecvError::Disconnected) => {
+                log::error!("Pool analysis stopped without a result");
+                self.running = None;
+                false
+            }
+        }
+    }
+
+    /// Continue a running analysis for a part of the frame.
+    /// Returns true while the analysis is running, so the UI should keep repainting.
+    #[cfg(target_arch = "wasm32")]
+    pub fn poll(&mut self) -> bool {
+        let Some(analysis) = &mut self.running else {
+            return false;
+        };
+        let start = Instant::now();
+        let objects = analysis.pool.objects();
+        while analysis.next_index < objects.len() && start.elapsed() < FRAME_TIME_BUDGET {
+            let analysis_result = analyse_object(&analysis.pool, &objects[analysis.next_index]);
+            analysis.builder.add(analysis_result);
+            analysis.next_index += 1;
+        }
+        if analysis.next_index < objects.len() {
+            return true;
+        }
+
+        let analysis = self.running.take().unwrap();
+        self.report = Some(analysis.builder.finish(&analysis.pool));
+        false
+    }
+
+    pub fn is_running(&self) -> bool {
+        self.running.is_some()
+    }
+
+    /// The most recent finished analysis, may be of an older version of the pool while a new
+    /// analysis is running
+    pub fn get_report(&self) -> Option<&AnalysisReport> {
+        self.report.as_ref()
+    }
+}
@@ -0,0 +1,248 @@
+//! Copyright 2024 - The Open-Agriculture Developers
+//! SPDX-License-Identifier: GPL-3.0-or-later
+//! Authors: Daan Steenbergen
+
+use ag_iso_stack::object_pool::object::Object;
+use ag_iso_stack::object_pool::{ObjectId, ObjectPool};
+use std::collections::{HashMap, HashSet};
+
+/// The objects that differ between two versions of the pool, enough to turn one version into
+/// the other. The undo history keeps these instead of copies of the whole pool, so unchanged
+/// objects like large pictures aren't stored again for every step.
+#[derive(Clone, Default)]
+pub struct PoolDelta {
+    /// The objects as they are in the target version, `None` if they don't exist there
+    objects: Vec<(ObjectId, Option<Object>)>,
+    /// The order of the objects in the target version, if it differs
+    order: Option<Vec<ObjectId>>,
+}
+
+impl PoolDelta {
+    /// The changes turning `from` into `to`
+    pub fn between(from: &ObjectPool, to: &ObjectPool) -> Self {
+        let from_objects: HashMap<ObjectId, &Object> =
+            from.objects().iter().map(|obj| (obj.id(), obj)).collect();
+        let to_ids: HashSet<ObjectId> = to.objects().iter().map(|obj| obj.id()).collect();
+
+        let mut objects: Vec<(ObjectId, Option<Object>)> = from_objects
+            .keys()
+            .filter(|id| !to_ids.contains(id))
+            .map(|id| (*id, None))
+            .collect();
+        for obj in to.objects() {
+            if from_objects.get(&obj.id()) != Some(&obj) {
+                objects.push((obj.id(), Some(obj.clone())));
+            }
+        }
+
+        let from_order = from.objects().iter().map(|obj| obj.id());
+        let to_order = to.objects().iter().map(|obj| obj.id());
+        let order = (!from_order.eq(to_order.clone())).then(|| to_order.collect());
+        PoolDelta { objects, order }
+    }
+
+    /// The changes turning `from` into `to`, when only the objects in `changed` may differ and
+    /// the order may only differ if `reordered`. Only those objects are compared, instead of
+    /// the whole pool.
+    pub fn between_changed(
+        from: &ObjectPool,
+        to: &ObjectPool,
+        changed: &[ObjectId],
+        reordered: bool,
+    ) -> Self {
+        let changed: HashSet<ObjectId> = changed.iter().copied().collect();
+        let changed_objects = |pool: &ObjectPool| -> HashMap<ObjectId, &Object> {
+            pool.objects()
+                .iter()
+                .filter(|obj| changed.contains(&obj.id()))
+                .map(|obj| (obj.id(), obj))
+                .collect()
+        };
+        let from_objects = changed_objects(from);
+        let to_objects = changed_objects(to);
+
+        let mut objects: Vec<(ObjectId, Option<Object>)> = Vec::new();
+        for id in &changed {
+            match (from_objects.get(id), to_objects.get(id)) {
+                (Some(_), None) => objects.push((*id, None)),
+                (from_object, Some(to_object)) if from_object != Some(to_object) => {
+                    objects.push((*id, Some((*to_object).clone())))
+                }
+                _ => {}
+            }
+        }
+
+        let order = if reordered {
+            let from_order = from.objects().iter().map(|obj| obj.id());
+            let to_order = to.objects().iter().map(|obj| obj.id());
+            (!from_order.eq(to_order.clone())).then(|| to_order.collect())
+        } else {
+            None
+        };
+        PoolDelta { objects, order }
+    }
+
+    /// Whether the delta changes nothing
+    pub fn is_empty(&self) -> bool {
+        self.objects.is_empty() && self.order.is_none()
+    }
+
+    /// The objects that are added, removed or changed
+    pub fn changed_ids(&self) -> impl Iterator<Item = ObjectId> + '_ {
+        self.objects.iter().map(|(id, _)| *id)
+    }
+
+    /// Whether the order of the objects changes, which includes adding and removing objects
+    pub fn reorders(&self) -> bool {
+        self.order.is_some()
+    }
+
+    /// Apply the changes to the pool, returning the changes that turn it back
+    pub fn apply(self, pool: &mut ObjectPool) -> PoolDelta {
+        let order = self
+            .order
+            .as_ref()
+            .map(|_| pool.objects().iter().map(|obj| obj.id()).collect());
+        let objects = self
+            .objects
+            .iter()
+            .map(|(id, _)| (*id, pool.object_by_id(*id).cloned()))
+            .collect();
+        let reverse = PoolDelta { objects, order };
+
+        // Removed first, an object may get the ID of a removed one
+        for (id, object) in &self.objects {
+            if object.is_none() && pool.object_by_id(*id).is_some() {
+                pool.remove(*id);
+            }
+        }
+        for (id, object) in self.objects {
+            let Some(object) = object else {
+                continue;
+            };
+            match pool.object_mut_by_id(id) {
+                Some(target) => *target = object,
+                None => pool.add(object),
+            }
+        }
+        if let Some(order) = self.order {
+            let ranks: HashMap<ObjectId, usize> = order
+                .into_iter()
+                .enumerate()
+                .map(|(rank, id)| (id, rank))
+                .collect();
+            pool.objects_mut()
+                .sort_by_key(|obj| ranks.get(&obj.id()).copied().unwrap_or(usize::MAX));
+        }
+        reverse
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::object_defaults::default_object;
+    use ag_iso_stack::object_pool::ObjectType;
+
+    fn object(object_type: ObjectType, id: u16) -> Object {
+        let mut object = default_object(object_type);
+        assert!(object.mut_id().set_value(id).is_ok());
+        object
+    }
+
+    fn pool(objects: Vec<Object>) -> ObjectPool {
+        let mut pool = ObjectPool::default();
+        for object in objects {
+            pool.add(object);
+        }
+        pool
+    }
+
+    /// Check that the delta between the pools turns the first into the second and back
+    fn assert_round_trip(a: &ObjectPool, b: &ObjectPool) {
+        let mut pool = a.clone();
+        let reverse = PoolDelta::between(a, b).apply(&mut pool);
+        assert!(pool == *b);
+        reverse.apply(&mut pool);
+        assert!(pool == *a);
+    }
+
+    fn objects() -> Vec<Object> {
+        vec![
+            object(ObjectType::WorkingSet, 0),
+            object(ObjectType::DataMask, 1),
+            object(ObjectType::Container, 2),
+            object(ObjectType::OutputNumber, 3),
+        ]
+    }
+
+    #[test]
+    fn equal_pools() {
+        let a = pool(objects());
+        let delta = PoolDelta::between(&a, &a.clone());
+        assert!(delta.is_empty());
+        assert_round_trip(&a, &a.clone());
+    }
+
+    #[test]
+    fn changed_object() {
+        let a = pool(objects());
+        let mut b = a.clone();
+        if let Some(Object::OutputNumber(o)) = b.object_mut_by_id(ObjectId::new(3).unwrap()) {
+            o.value = 42;
+        }
+        let delta = PoolDelta::between(&a, &b);
+        assert_eq!(
+            delta.changed_ids().collect::<Vec<_>>(),
+            vec![ObjectId::new(3).unwrap()]
+        );
+        assert!(!delta.reorders());
+        assert_round_trip(&a, &b);
+    }
+
+    #[test]
+    fn added_and_removed_objects() {
+        let a = pool(objects());
+        let mut objects = objects();
+        objects.remove(2);
+        objects.insert(1, object(ObjectType::Polygon, 7));
+        objects.push(object(ObjectType::Container, 8));
+        let b = pool(objects);
+        assert!(PoolDelta::between(&a, &b).reorders());
+        assert_round_trip(&a, &b);
+        assert_round_trip(&b, &a);
+    }
+
+    #[test]
+    fn reordered_and_renumbered_objects() {
+        let a = pool(objects());
+        let mut objects = objects();
+        objects.reverse();
+        assert_round_trip(&a, &pool(objects.clone()));
+
+        // An object taking the ID of a removed one
+        objects.retain(|object| object.id().value() != 2);
+        assert!(objects[0].mut_id().set_value(2).is_ok());
+        assert_round_trip(&a, &pool(objects));
+    }
+
+    #[test]
+    fn between_changed_objects() {
+        let a = pool(objects());
+        let mut b = a.clone();
+        if let Some(Object::OutputNumber(o)) = b.object_mut_by_id(ObjectId::new(3).unwrap()) {
+            o.value = 42;
+        }
+        b.add(object(ObjectType::Container, 9));
+        let ids = [2, 3, 9].map(|id| ObjectId::new(id).unwrap());
+
+        let mut pool = a.clone();
+        let delta = PoolDelta::between_changed(&a, &b, &ids, true);
+        let mut changed: Vec<ObjectId> = delta.changed_ids().collect();
+        changed.sort_by_key(|id| id.value());
+        // Object 2 is listed but didn't change
+        assert_eq!(changed, ids[1..].to_vec());
+        delta.apply(&mut pool);
+        assert!(pool == b);
+    }
+}
@@ -0,0 +1,356 @@
+//! Copyright 2024 - The Open-Agriculture Developers
+//! SPDX-License-Identifier: GPL-3.0-or-later
+//! Authors: Daan Steenbergen
+
+use ag_iso_stack::object_pool::object::Object;
+use ag_iso_stack::object_pool::{ObjectId, ObjectPool};
+use serde::{Deserialize, Serialize};
+use std::collections::hash_map::DefaultHasher;
+use std::collections::HashMap;
+use std::hash::{Hash, Hasher};
+
+/// Longest value shown for an attribute, longer values like picture data are shortened
+const MAX_VALUE_LENGTH: usize = 60;
+
+/// An attribute that differs between two versions of an object
+#[derive(Serialize, Deserialize, Clone, PartialEq, Debug)]
+pub struct AttributeChange {
+    pub attribute: String,
+    pub old: String,
+    pub new: String,
+}
+
+/// An object that exists in both pools but differs
+#[derive(Serialize, Deserialize, Clone, PartialEq, Debug)]
+pub struct ObjectDiff {
+    pub id: u16,
+    pub object_type: String,
+    pub attributes: Vec<AttributeChange>,
+}
+
+/// The differences between two versions of a pool, e.g. of two commits, so a CI pipeline can
+/// check policies like "no object IDs changed" or "the pool grew less than 10%". Serializes to
+/// JSON with [`PoolDiff::to_json`].
+#[derive(Serialize, Deserialize, Clone, PartialEq, Debug, Default)]
+pub struct PoolDiff {
+    /// IDs of the objects only in the new pool
+    pub added: Vec<u16>,
+    /// IDs of the objects only in the old pool
+    pub removed: Vec<u16>,
+    pub changed: Vec<ObjectDiff>,
+    /// Removed objects that are equal to an added object apart from the ID, as old and new ID
+    pub renumbered: Vec<(u16, u16)>,
+    /// Whether the objects are in a different order
+    pub reordered: bool,
+    pub old_object_count: usize,
+    pub new_object_count: usize,
+    /// Size of the pools as IOP files in bytes
+    pub old_size: usize,
+    pub new_size: usize,
+}
+
+impl PoolDiff {
+    /// Whether the pools are equal
+    pub fn is_empty(&self) -> bool {
+        self.added.is_empty()
+            && self.removed.is_empty()
+            && self.changed.is_empty()
+            && !self.reordered
+    }
+
+    /// Whether an object of the old pool no longer has its ID: it is removed or renumbered
+    pub fn ids_changed(&self) -> bool {
+        !self.removed.is_empty()
+    }
+
+    /// Growth of the IOP file in percent, negative if it shrank
+    pub fn size_growth_percent(&self) -> f64 {
+        if self.old_size == 0 {
+            return if self.new_size == 0 {
+                0.0
+            } else {
+                f64::INFINITY
+            };
+        }
+        (self.new_size as f64 - self.old_size as f64) * 100.0 / self.old_size as f64
+    }
+
+    pub fn to_json(&self) -> Result<Vec<u8>, serde_json::Error> {
+        serde_json::to_vec_pretty(self)
+    }
+}
+
+/// Compare two versions of a pool, objects are matched by their ID.
+/// The lists of the result are ordered by ID.
+pub fn compare_pools(old: &ObjectPool, new: &ObjectPool) -> PoolDiff {
+    let old_objects: HashMap<ObjectId, &Object> =
+        old.objects().iter().map(|obj| (obj.id(), obj)).collect();
+    let new_objects: HashMap<ObjectId, &Object> =
+        new.objects().iter().map(|obj| (obj.id(), obj)).collect();
+
+    let mut diff = PoolDiff {
+        old_object_count: old.objects().len(),
+        new_object_count: new.objects().len(),
+        old_size: old.as_iop().len(),
+        new_size: new.as_iop().len(),
+        ..PoolDiff::default()
+    };
+    for object in old.objects() {
+        if !new_objects.contains_key(&object.id()) {
+            diff.removed.push(object.id().value());
+        }
+    }
+    for object in new.objects() {
+        match old_objects.get(&object.id()) {
+            None => diff.added.push(object.id().value()),
+            Some(old_object) if *old_object != object => diff.changed.push(ObjectDiff {
+                id: object.id().value(),
+                object_type: format!("{:?}", object.object_type()),
+                attributes: attribute_changes(old_object, object),
+            }),
+            Some(_) => {}
+        }
+    }
+    diff.added.sort_unstable();
+    diff.removed.sort_unstable();
+    diff.changed.sort_by_key(|object| object.id);
+
+    // Added objects are bucketed by type and a hash of their data without the ID, so each
+    // removed object is only compared with the few added objects that may be equal to it
+    let mut candidates: HashMap<(u8, u64), Vec<(u16, Vec<u8>)>> = HashMap::new();
+    for added in &diff.added {
+        let Some(object) = ObjectId::new(*added)
+            .ok()
+            .and_then(|id| new_objects.get(&id))
+        else {
+            continue;
+        };
+        let data = data_without_id(object);
+        candidates
+            .entry((object.object_type() as u8, hash(&data)))
+            .or_default()
+            .push((*added, data));
+    }
+    for removed in &diff.removed {
+        let Some(object) = ObjectId::new(*removed)
+            .ok()
+            .and_then(|id| old_objects.get(&id))
+        else {
+            continue;
+        };
+        let data = data_without_id(object);
+        let Some(bucket) = candidates.get_mut(&(object.object_type() as u8, hash(&data))) else {
+            continue;
+        };
+        // An added object is the renumbered version of one removed object at most
+        if let Some(index) = bucket.iter().position(|(_, added)| *added == data) {
+            let (to, _) = bucket.remove(index);
+            diff.renumbered.push((*removed, to));
+        }
+    }
+
+    let common_order = |pool: &ObjectPool, other: &HashMap<ObjectId, &Object>| -> Vec<ObjectId> {
+        pool.objects()
+            .iter()
+            .map(|object| object.id())
+            .filter(|id| other.contains_key(id))
+            .collect()
+    };
+    diff.reordered = common_order(old, &new_objects) != common_order(new, &old_objects);
+    diff
+}
+
+/// The object as IOP data without its leading ID, equal for objects that only differ in ID
+fn data_without_id(object: &Object) -> Vec<u8> {
+    object.write().split_off(2)
+}
+
+fn hash(data: &[u8]) -> u64 {
+    let mut hasher = DefaultHasher::new();
+    data.hash(&mut hasher);
+    hasher.finish()
+}
+
+/// The attributes that differ between two versions of an object, in the order of the object
+pub fn attribute_changes(old: &Object, new: &Object) -> Vec<AttributeChange> {
+    if old.object_type() != new.object_type() {
+        return vec![AttributeChange {
+            attribute: "type".to_string(),
+            old: format!("{:?}", old.object_type()),
+            new: format!("{:?}", new.object_type()),
+        }];
+    }
+    let old_attributes = attributes(old);
+    attributes(new)
+        .into_iter()
+        .zip(old_attributes)
+        .filter(|((_, new), (_, old))| new != old)
+        .map(|((attribute, new), (_, old))| AttributeChange {
+            attribute,
+            old: shorten(old),
+            new: shorten(new),
+        })
+        .collect()
+}
+
+/// The name and value of every attribute of the object, read from its debug description
+fn attributes(object: &Object) -> Vec<(String, String)> {
+    let description = format!("{:?}", object);
+    let (Some(start), Some(end)) = (description.find('{'), description.rfind('}')) else {
+        return vec![("value".to_string(), description)];
+    };
+
+    let mut fields = Vec::new();
+    let mut field = String::new();
+    let mut depth = 0;
+    let mut in_string = false;
+    let mut escaped = false;
+    for c in description[start + 1..end].chars() {
+        if in_string {
+            if escaped {
+                escaped = false;
+            } else if c == '\\' {
+                escaped = true;
+            } else if c == '"' {
+                in_string = false;
+            }
+        } else {
+            match c {
+                '"' => in_string = true,
+                '{' | '[' | '(' => depth += 1,
+                '}' | ']' | ')' => depth -= 1,
+                ',' if depth == 0 => {
+                    fields.push(std::mem::take(&mut field));
+                    continue;
+                }
+                _ => {}
+            }
+        }
+        field.push(c);
+    }
+    fields.push(field);
+
+    fields
+        .iter()
+        .filter_map(|field| field.trim().split_once(": "))
+        .map(|(name, value)| (name.to_string(), value.to_string()))
+        .collect()
+}
+
+/// Shorten a long value, mentioning its full length
+fn shorten(value: String) -> String {
+    let length = value.chars().count();
+    if length <= MAX_VALUE_LENGTH {
+        return value;
+    }
+    let start: String = value.chars().take(MAX_VALUE_LENGTH).collect();
+    format!("{}\u{2026} ({} characters)", start, length)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::object_defaults::default_object;
+    use ag_iso_stack::object_pool::ObjectType;
+
+    fn object(object_type: ObjectType, id: u16) -> Object {
+        let mut object = default_object(object_type);
+        assert!(object.mut_id().set_value(id).is_ok());
+        object
+    }
+
+    fn pool(objects: Vec<Object>) -> ObjectPool {
+        let mut pool = ObjectPool::default();
+        for object in objects {
+            pool.add(object);
+        }
+        pool
+    }
+
+    #[test]
+    fn equal_pools() {
+        let old = pool(vec![
+            object(ObjectType::Container, 1),
+            object(ObjectType::OutputNumber, 2),
+        ]);
+        let diff = compare_pools(&old, &old.clone());
+        assert!(diff.is_empty());
+        assert!(!diff.ids_changed());
+    }
+
+    #[test]
+    fn added_and_removed() {
+        let old = pool(vec![
+            object(ObjectType::Container, 1),
+            object(ObjectType::OutputNumber, 2),
+        ]);
+        let new = pool(vec![
+            object(ObjectType::Container, 1),
+            object(ObjectType::Polygon, 3),
+        ]);
+        let diff = compare_pools(&old, &new);
+        assert_eq!(diff.added, vec![3]);
+        assert_eq!(diff.removed, vec![2]);
+        assert!(diff.renumbered.is_empty());
+        assert!(diff.changed.is_empty());
+        assert!(!diff.reordered);
+        assert!(diff.ids_changed());
+    }
+
+    #[test]
+    fn changed() {
+        let old = pool(vec![object(ObjectType::OutputNumber, 1)]);
+        let mut changed = object(ObjectType::OutputNumber, 1);
+        if let Object::OutputNumber(o) = &mut changed {
+            o.value += 1;
+        }
+        let diff = compare_pools(&old, &pool(vec![changed]));
+        assert_eq!(diff.changed.len(), 1);
+        assert_eq!(diff.changed[0].id, 1);
+        assert_eq!(diff.changed[0].attributes.len(), 1);
+        assert_eq!(diff.changed[0].attributes[0].attribute, "value");
+        assert!(!diff.ids_changed());
+    }
+
+    #[test]
+    fn renumbered() {
+        let old = pool(vec![
+            object(ObjectType::Container, 1),
+            object(ObjectType::Container, 2),
+            object(ObjectType::OutputNumber, 3),
+        ]);
+        let new = pool(vec![
+            object(ObjectType::Container, 10),
+            object(ObjectType::Container, 11),
+            object(ObjectType::OutputNumber, 12),
+        ]);
+        let diff = compare_pools(&old, &new);
+        assert_eq!(diff.removed, vec![1, 2, 3]);
+        assert_eq!(diff.added, vec![10, 11, 12]);
+        // Each added object is matched once, even when removed objects are equal
+        assert_eq!(diff.renumbered, vec![(1, 10), (2, 11), (3, 12)]);
+    }
+
+    #[test]
+    fn reordered() {
+        let old = pool(vec![
+            object(ObjectType::Container, 1),
+            object(ObjectType::OutputNumber, 2),
+            object(ObjectType::Polygon, 3),
+        ]);
+        let new = pool(vec![
+            object(ObjectType::Polygon, 3),
+            object(ObjectType::Container, 1),
+            object(ObjectType::OutputNumber, 2),
+        ]);
+        let diff = compare_pools(&old, &new);
+        assert!(diff.reordered);
+        assert!(diff.added.is_empty() && diff.removed.is_empty() && diff.changed.is_empty());
+        assert!(!diff.is_empty());
+
+        // Adding an object at the end doesn't reorder the others
+        let mut grown = old.clone();
+        grown.add(object(ObjectType::Container, 4));
+        assert!(!compare_pools(&old, &grown).reordered);
+    }
+}
@@ -0,0 +1,196 @@
+//! Copyright 2024 - The Open-Agriculture Developers
+//! SPDX-License-Identifier: GPL-3.0-or-later
+//! Authors: Daan Steenbergen
+
+use ag_iso_stack::object_pool::object::Object;
+use ag_iso_stack::object_pool::{ObjectId, ObjectPool, ObjectType};
+use std::cell::{Cell, RefCell};
+use std::collections::HashMap;
+
+/// What the index remembers of an object, needed to remove it from the index again
+#[derive(Clone)]
+struct IndexEntry {
+    object_type: ObjectType,
+    references: Vec<ObjectId>,
+}
+
+/// Lookup tables for the objects of a pool, so the UI doesn't have to scan all objects each frame.
+/// Only the objects that changed are re-indexed when the pool is edited, the positions only when
+/// the order of the objects changed.
+#[derive(Default, Clone)]
+pub struct PoolIndex {
+    /// Position of every object in the object list of the pool
+    positions: HashMap<ObjectId, usize>,
+    entries: HashMap<ObjectId, IndexEntry>,
+    /// Objects of each type, in the order of the pool
+    by_type: Vec<(ObjectType, Vec<ObjectId>)>,
+    /// Objects referencing each object, once per reference
+    parents: HashMap<ObjectId, Vec<ObjectId>>,
+    /// Lower case name of the objects, objects without one are named again when names are
+    /// looked up. Names are given by the project, which also knows when they change.
+    names: RefCell<HashMap<ObjectId, String>>,
+    /// Incremented whenever a name is forgotten, to find out if a name lookup is outdated
+    name_generation: Cell<u64>,
+}
+
+impl PoolIndex {
+    pub fn new(pool: &ObjectPool) -> Self {
+        let mut index = PoolIndex::default();
+        index.update_positions(pool);
+        for obj in pool.objects() {
+            index.insert(obj);
+        }
+        index
+    }
+
+    fn update_positions(&mut self, pool: &ObjectPool) {
+        self.positions = pool
+            .objects()
+            .iter()
+            .enumerate()
+            .map(|(position, obj)| (obj.id(), position))
+            .collect();
+    }
+
+    /// Bring the index up to date with the pool after the given objects were added, removed or
+    /// changed, e.g. the changed objects of a [`crate::pool_delta::PoolDelta`]
+    pub fn update(&mut self, pool: &ObjectPool, changed: &[ObjectId], reordered: bool) {
+        for id in changed {
+            self.remove(*id);
+        }
+        self.forget_names(changed);
+        if reordered {
+            self.update_positions(pool);
+        }
+        for id in changed {
+            if let Some(obj) = self
+                .position(*id)
+                .and_then(|position| pool.objects().get(position))
+            {
+                self.insert(obj);
+            }
+        }
+        if reordered {
+            for (_, ids) in self.by_type.iter_mut() {
+                ids.sort_by_key(|id| self.positions.get(id).copied().unwrap_or(usize::MAX));
+            }
+        }
+    }
+
+    fn insert(&mut self, obj: &Object) {
+        let id = obj.id();
+        let object_type = obj.object_type();
+        let positions = &self.positions;
+        match self.by_type.iter_mut().find(|(t, _)| *t == object_type) {
+            Some((_, ids)) => {
+                // Kept in the order of the pool
+                let at = ids.partition_point(|other| positions.get(other) < positions.get(&id));
+                ids.insert(at, id);
+            }
+            None => self.by_type.push((object_type, vec![id])),
+        }
+
+        let references = obj.referenced_objects();
+        for referenced in &references {
+            self.parents.entry(*referenced).or_default().push(id);
+        }
+        self.entries.insert(
+            id,
+            IndexEntry {
+                object_type,
+                references,
+            },
+        );
+    }
+
+    fn remove(&mut self, id: ObjectId) {
+        let Some(entry) = self.entries.remove(&id) else {
+            return;
+        };
+        if let Some((_, ids)) = self
+            .by_type
+            .iter_mut()
+            .find(|(t, _)| *t == entry.object_type)
+        {
+            ids.retain(|other| *other != id);
+        }
+        for referenced in entry.references {
+            if let Some(parents) = self.parents.get_mut(&referenced) {
+                parents.retain(|parent| *parent != id);
+                if parents.is_empty() {
+                    self.parents.remove(&referenced);
+                }
+            }
+        }
+    }
+
+    /// Position of the object in the object list of the pool
+    pub fn position(&self, id: ObjectId) -> Option<usize> {
+        self.positions.get(&id).copied()
+    }
+
+    /// Objects of the given type, in the order of the pool
+    pub fn ids_by_type(&self, object_type: ObjectType) -> &[ObjectId] {
+        self.by_type
+            .iter()
+            .find(|(t, _)| *t == object_type)
+            .map_or(&[], |(_, ids)| ids.as_slice())
+    }
+
+    /// Objects that are referenced but not in the pool
+    pub fn missing(&self) -> Vec<ObjectId> {
+        self.parents
+            .keys()
+            .filter(|id| !self.positions.contains_key(id))
+            .copied()
+            .collect()
+    }
+
+    /// Objects referencing the given object, an object is listed once for every reference
+    pub fn parents(&self, id: ObjectId) -> &[ObjectId] {
+        self.parents
+            .get(&id)
+            .map_or(&[], |parents| parents.as_slice())
+    }
+
+    /// Forget the names of the objects, e.g. because they were renamed
+    pub fn forget_names(&self, ids: &[ObjectId]) {
+        let mut names = self.names.borrow_mut();
+        for id in ids {
+            names.remove(id);
+        }
+        self.name_generation.set(self.name_generation.get() + 1);
+    }
+
+    /// Forget the names of all objects, e.g. because the naming rules changed
+    pub fn forget_all_names(&self) {
+        self.names.borrow_mut().clear();
+        self.name_generation.set(self.name_generation.get() + 1);
+    }
+
+    /// Incremented whenever a name may have changed
+    pub fn name_generation(&self) -> u64 {
+        self.name_generation.get()
+    }
+
+    /// Objects whose lower case name is accepted by the filter, in the order of the pool.
+    /// Objects that have no name in the index yet are named with `name_of`.
+    pub fn filter_by_name(
+        &self,
+        pool: &ObjectPool,
+        name_of: impl Fn(&Object) -> String,
+        filter: impl Fn(ObjectId, &str) -> bool,
+    ) -> Vec<ObjectId> {
+        let mut names = self.names.borrow_mut();
+        pool.objects()
+            .iter()
+            .filter(|obj| {
+                let name = names
+                    .entry(obj.id())
+                    .or_insert_with(|| name_of(obj).to_lowercase());
+                filter(obj.id(), name)
+            })
+            .map(|obj| obj.id())
+            .collect()
+    }
+}
@@ -0,0 +1,90 @@
+//! Copyright 2024 - The Open-Agriculture Developers
+//! SPDX-License-Identifier: GPL-3.0-or-later
+//! Authors: Daan Steenbergen
+
+use crate::vt_compatibility::vt_version_number;
+use crate::EditorProject;
+use serde::{Deserialize, Serialize};
+
+/// An object of the pool as described in the manifest
+#[derive(Serialize, Deserialize, Clone, PartialEq, Debug)]
+pub struct ManifestObject {
+    pub id: u16,
+    pub object_type: String,
+    pub name: String,
+    /// Name used for the object in exported headers and modules
+    pub export_name: String,
+    /// Size the object takes on the screen in pixels, zero for objects that are not shown
+    pub width: u16,
+    pub height: u16,
+    /// Size of the object in the IOP file in bytes
+    pub bytes: usize,
+    /// IDs of the objects referencing this object
+    pub parents: Vec<u16>,
+    /// IDs of the objects this object references, in the order of the object
+    pub children: Vec<u16>,
+}
+
+/// The structure of the pool for external tooling, like test scripts and documentation
+/// generators, so they don't need to parse IOP. Serializes to JSON with
+/// [`PoolManifest::to_json`].
+#[derive(Serialize, Deserialize, Clone, PartialEq, Debug)]
+pub struct PoolManifest {
+    /// VT version the pool is made for
+    pub vt_version: u8,
+    pub object_count: usize,
+    /// Size of the pool as IOP file in bytes
+    pub total_bytes: usize,
+    /// The objects in the order of the pool
+    pub objects: Vec<ManifestObject>,
+}
+
+impl PoolManifest {
+    /// Describe the pool of the project
+    pub fn new(project: &EditorProject) -> Self {
+        let pool = project.get_pool();
+        let objects: Vec<ManifestObject> = pool
+            .objects()
+            .iter()
+            .map(|object| {
+                let info = project.get_object_info(object);
+                let mut parents: Vec<u16> = project
+                    .get_parents(object.id())
+                    .iter()
+                    .map(|parent| parent.id().value())
+                    .collect();
+                parents.sort_unstable();
+                parents.dedup();
+                let mut children: Vec<u16> = Vec::new();
+                for child in object.referenced_objects() {
+                    if !children.contains(&child.value()) {
+                        children.push(child.value());
+                    }
+                }
+                let (width, height) = pool.content_size(object);
+                ManifestObject {
+                    id: object.id().value(),
+                    object_type: format!("{:?}", object.object_type()),
+                    name: info.get_name(object),
+                    export_name: info.get_export_name(object),
+                    width,
+                    height,
+                    bytes: object.write().len(),
+                    parents,
+                    children,
+                }
+            })
+            .collect();
+
+        PoolManifest {
+            vt_version: vt_version_number(project.get_vt_version()),
+            object_count: objects.len(),
+            total_bytes: objects.iter().map(|object| object.bytes).sum(),
+            objects,
+        }
+    }
+
+    pub fn to_json(&self) -> Result<Vec<u8>, serde_json::Error> {
+        serde_json::to_vec_pretty(self)
+    }
+}
@@ -0,0 +1,77 @@
+//! Copyright 2024 - The Open-Agriculture Developers
+//! SPDX-License-Identifier: GPL-3.0-or-later
+//! Authors: Daan Steenbergen
+
+use ag_iso_stack::object_pool::object::Object;
+use ag_iso_stack::object_pool::{ObjectId, ObjectPool};
+use std::collections::{BTreeSet, HashMap};
+
+/// How the copies of a pool differ for an object
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum MergeKind {
+    /// Only their copy added, changed or removed the object, their version is taken
+    Theirs,
+    /// Both copies changed the object differently, or one changed and the other removed it
+    Conflict,
+    /// Both copies added a different object with the same ID, theirs is added with a new ID
+    IdConflict,
+}
+
+/// Which version of a conflicting object is kept
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum MergeChoice {
+    Mine,
+    Theirs,
+}
+
+/// An object that has to be merged, with both versions, `None` if a copy doesn't have it
+#[derive(Clone, PartialEq, Debug)]
+pub struct MergeItem {
+    pub id: ObjectId,
+    pub kind: MergeKind,
+    pub mine: Option<Object>,
+    pub theirs: Option<Object>,
+}
+
+/// The objects to merge to bring the changes of their copy of the pool into mine, both edited
+/// from the same original. Objects only changed in my copy need nothing and are left out.
+/// Items are ordered by ID.
+pub fn plan_merge(original: &ObjectPool, mine: &ObjectPool, theirs: &ObjectPool) -> Vec<MergeItem> {
+    let ids: BTreeSet<u16> = [original, mine, theirs]
+        .iter()
+        .flat_map(|pool| pool.objects().iter().map(|object| object.id().value()))
+        .collect();
+
+    ids.into_iter()
+        .filter_map(|id| ObjectId::new(id).ok())
+        .filter_map(|id| {
+            let base = original.object_by_id(id);
+            let mine = mine.object_by_id(id);
+            let theirs = theirs.object_by_id(id);
+            let kind = if mine == theirs || theirs == base {
+                return None;
+            } else if mine == base {
+                MergeKind::Theirs
+            } else if base.is_none() {
+                MergeKind::IdConflict
+            } else {
+                MergeKind::Conflict
+            };
+            Some(MergeItem {
+                id,
+                kind,
+                mine: mine.cloned(),
+                theirs: theirs.cloned(),
+            })
+        })
+        .collect()
+}
+
+/// The choice for every conflict if the same version is kept for all of them
+pub fn choose_all(items: &[MergeItem], choice: MergeChoice) -> HashMap<ObjectId, MergeChoice> {
+    items
+        .iter()
+        .filter(|item| item.kind == MergeKind::Conflict)
+        .map(|item| (item.id, choice))
+        .collect()
+}
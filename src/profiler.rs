@@ -0,0 +1,194 @@
+//! Copyright 2024 - The Open-Agriculture Developers
+//! SPDX-License-Identifier: GPL-3.0-or-later
+//! Authors: Daan Steenbergen
+
+use eframe::egui;
+use std::collections::VecDeque;
+use std::time::Duration;
+use web_time::Instant;
+
+/// Number of frames the averages and the frame time graph are computed over
+const HISTORY_LENGTH: usize = 120;
+
+/// Frame time budget to stay at 60 frames per second
+const FRAME_BUDGET: Duration = Duration::from_micros(16_667);
+
+/// Part of the application of which the time spent each frame is measured
+#[derive(Clone, Copy, PartialEq, Eq)]
+pub enum ProfileSection {
+    Hierarchy,
+    Canvas,
+    Properties,
+    Validation,
+    Other,
+}
+
+impl ProfileSection {
+    const ALL: [ProfileSection; 5] = [
+        ProfileSection::Hierarchy,
+        ProfileSection::Canvas,
+        ProfileSection::Properties,
+        ProfileSection::Validation,
+        ProfileSection::Other,
+    ];
+
+    fn name(self) -> &'static str {
+        match self {
+            ProfileSection::Hierarchy => "Hierarchy UI",
+            ProfileSection::Canvas => "Canvas render",
+            ProfileSection::Properties => "Property panel",
+            ProfileSection::Validation => "Validation",
+            ProfileSection::Other => "Other",
+        }
+    }
+
+    fn colour(self) -> egui::Color32 {
+        match self {
+            ProfileSection::Hierarchy => egui::Color32::from_rgb(86, 156, 214),
+            ProfileSection::Canvas => egui::Color32::from_rgb(220, 160, 60),
+            ProfileSection::Properties => egui::Color32::from_rgb(120, 190, 100),
+            ProfileSection::Validation => egui::Color32::from_rgb(190, 110, 200),
+            ProfileSection::Other => egui::Color32::GRAY,
+        }
+    }
+}
+
+/// Time spent in every section during a single frame
+#[derive(Clone, Copy, Default)]
+struct FrameSample {
+    total: Duration,
+    sections: [Duration; ProfileSection::ALL.len()],
+}
+
+/// Measures the frame time broken down by section and shows it in an overlay
+pub struct FrameProfiler {
+    frame_start: Option<Instant>,
+    current: FrameSample,
+    history: VecDeque<FrameSample>,
+}
+
+impl Default for FrameProfiler {
+    fn default() -> Self {
+        FrameProfiler {
+            frame_start: None,
+            current: FrameSample::default(),
+            history: VecDeque::with_capacity(HISTORY_LENGTH),
+        }
+    }
+}
+
+impl FrameProfiler {
+    pub fn begin_frame(&mut self) {
+        self.frame_start = Some(Instant::now());
+        self.current = FrameSample::default();
+    }
+
+    /// Add the time elapsed since `start` to a section of the current frame
+    pub fn record(&mut self, section: ProfileSection, start: Instant) {
+        self.current.sections[section as usize] += start.elapsed();
+    }
+
+    /// Finish the current frame, time not recorded in a section is attributed to `Other`
+    pub fn end_frame(&mut self) {
+        let Some(frame_start) = self.frame_start.take() else {
+            return;
+        };
+        self.current.total = frame_start.elapsed();
+        let measured: Duration = self.current.sections.iter().sum();
+        self.current.sections[ProfileSection::Other as usize] +=
+            self.current.total.saturating_sub(measured);
+
+        if self.history.len() == HISTORY_LENGTH {
+            self.history.pop_front();
+        }
+        self.history.push_back(self.current);
+    }
+
+    fn average(&self, f: impl Fn(&FrameSample) -> Duration) -> Duration {
+        if self.history.is_empty() {
+            return Duration::ZERO;
+        }
+        self.history.iter().map(f).sum::<Duration>() / self.history.len() as u32
+    }
+
+    fn maximum(&self, f: impl Fn(&FrameSample) -> Duration) -> Duration {
+        self.history.iter().map(f).max().unwrap_or_default()
+    }
+
+    /// Show the overlay with the measured frame times
+    pub fn show_overlay(&self, ctx: &egui::Context) {
+        egui::Area::new(egui::Id::new("performance_hud"))
+            .anchor(egui::Align2::RIGHT_BOTTOM, egui::vec2(-8.0, -8.0))
+            .order(egui::Order::Foreground)
+            .interactable(false)
+            .show(ctx, |ui| {
+                egui::Frame::popup(ui.style()).show(ui, |ui| {
+                    let average_total = self.average(|sample| sample.total);
+                    ui.strong(format!(
+                        "Frame: {:.2} ms avg, {:.2} ms max",
+                        ms(average_total),
+                        ms(self.maximum(|sample| sample.total))
+                    ));
+
+                    egui::Grid::new("performance_hud_sections")
+                        .num_columns(3)
+                        .show(ui, |ui| {
+                            for section in ProfileSection::ALL {
+                                let idx = section as usize;
+                                ui.colored_label(section.colour(), section.name());
+                                ui.monospace(format!(
+                                    "{:6.2} ms",
+                                    ms(self.average(|sample| sample.sections[idx]))
+                                ));
+                                ui.monospace(format!(
+                                    "max {:6.2} ms",
+                                    ms(self.maximum(|sample| sample.sections[idx]))
+                                ));
+                                ui.end_row();
+                            }
+                        });
+
+                    self.render_graph(ui);
+                });
+            });
+    }
+
+    /// Stacked bar per frame, the line marks the frame budget
+    fn render_graph(&self, ui: &mut egui::Ui) {
+        let size = egui::vec2(HISTORY_LENGTH as f32 * 2.0, 60.0);
+        let (rect, _) = ui.allocate_exact_size(size, egui::Sense::hover());
+        let painter = ui.painter_at(rect);
+        painter.rect_filled(rect, 0.0, ui.visuals().extreme_bg_color);
+
+        // Scale to twice the budget so frames over budget stand out
+        let scale = rect.height() / (2.0 * FRAME_BUDGET.as_secs_f32());
+        let bar_width = rect.width() / HISTORY_LENGTH as f32;
+        for (idx, sample) in self.history.iter().enumerate() {
+            let x = rect.left() + idx as f32 * bar_width;
+            let mut y = rect.bottom();
+            for section in ProfileSection::ALL {
+                let height = sample.sections[section as usize].as_secs_f32() * scale;
+                painter.rect_filled(
+                    egui::Rect::from_min_max(
+                        egui::pos2(x, y - height),
+                        egui::pos2(x + bar_width, y),
+                    ),
+                    0.0,
+                    section.colour(),
+                );
+                y -= height;
+            }
+        }
+
+        let budget_y = rect.bottom() - FRAME_BUDGET.as_secs_f32() * scale;
+        painter.hline(
+            rect.x_range(),
+            budget_y,
+            egui::Stroke::new(1.0, egui::Color32::RED),
+        );
+    }
+}
+
+fn ms(duration: Duration) -> f32 {
+    duration.as_secs_f32() * 1000.0
+}
@@ -0,0 +1,82 @@
+//! Copyright 2024 - The Open-Agriculture Developers
+//! SPDX-License-Identifier: GPL-3.0-or-later
+//! Authors: Daan Steenbergen
+
+use ag_iso_stack::object_pool::object::Object;
+use ag_iso_stack::object_pool::{NullableObjectId, ObjectId, ObjectPool};
+use std::collections::HashMap;
+use std::rc::Rc;
+
+/// Change of the project, sent to the listeners once the change is committed
+#[derive(Clone, Debug, PartialEq)]
+pub enum ProjectEvent {
+    ObjectAdded(ObjectId),
+    ObjectRemoved(ObjectId),
+    /// One or more attributes of the object changed
+    AttributeChanged(ObjectId),
+    SelectionChanged(NullableObjectId),
+}
+
+/// Called for every event of the project it is subscribed to
+pub type EventListener = Rc<dyn Fn(&ProjectEvent)>;
+
+/// Handle to remove a listener again
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct SubscriptionId(pub(crate) u64);
+
+/// The listeners subscribed to a project
+#[derive(Default, Clone)]
+pub(crate) struct EventListeners {
+    next_id: u64,
+    listeners: Vec<(SubscriptionId, EventListener)>,
+}
+
+impl EventListeners {
+    pub fn subscribe(&mut self, listener: EventListener) -> SubscriptionId {
+        let id = SubscriptionId(self.next_id);
+        self.next_id += 1;
+        self.listeners.push((id, listener));
+        id
+    }
+
+    pub fn unsubscribe(&mut self, id: SubscriptionId) {
+        self.listeners.retain(|(other, _)| *other != id);
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.listeners.is_empty()
+    }
+
+    /// Copy of the listeners, so they can be called while listeners are (un)subscribed
+    pub fn snapshot(&self) -> Vec<EventListener> {
+        self.listeners
+            .iter()
+            .map(|(_, listener)| listener.clone())
+            .collect()
+    }
+}
+
+/// Events describing the changes from the old to the new version of the pool
+pub(crate) fn pool_changes(old: &ObjectPool, new: &ObjectPool) -> Vec<ProjectEvent> {
+    let old_objects: HashMap<ObjectId, &Object> =
+        old.objects().iter().map(|obj| (obj.id(), obj)).collect();
+    let new_objects: HashMap<ObjectId, &Object> =
+        new.objects().iter().map(|obj| (obj.id(), obj)).collect();
+
+    let mut events: Vec<ProjectEvent> = old
+        .objects()
+        .iter()
+        .filter(|obj| !new_objects.contains_key(&obj.id()))
+        .map(|obj| ProjectEvent::ObjectRemoved(obj.id()))
+        .collect();
+    for obj in new.objects() {
+        match old_objects.get(&obj.id()) {
+            None => events.push(ProjectEvent::ObjectAdded(obj.id())),
+            Some(old_obj) if *old_obj != obj => {
+                events.push(ProjectEvent::AttributeChanged(obj.id()))
+            }
+            Some(_) => {}
+        }
+    }
+    events
+}
@@ -2,10 +2,13 @@
 //! SPDX-License-Identifier: GPL-3.0-or-later
 //! Authors: Daan Steenbergen
 
-use crate::ObjectInfo;
+use crate::{
+    demo_script::DemoScript, guidelines::GuidelineSettings, image_import::ImageSource,
+    simulation::AuxAssignment, ObjectInfo,
+};
 use ag_iso_stack::object_pool::{object::Object, ObjectId, ObjectPool};
 use serde::{Deserialize, Serialize};
-use std::collections::HashMap;
+use std::collections::{BTreeMap, HashMap};
 
 /// Project file format version
 const PROJECT_FILE_VERSION: u32 = 1;
@@ -25,6 +28,36 @@ pub struct ProjectFile {
 
     /// Project-level settings
     settings: ProjectSettings,
+
+    /// Named copies of the pool made by the user
+    #[serde(default)]
+    snapshots: Vec<NamedSnapshot>,
+}
+
+/// A named copy of the object pool, e.g. "before redesigning alarm masks"
+#[derive(Serialize, Deserialize, Clone)]
+pub struct NamedSnapshot {
+    pub name: String,
+
+    /// Seconds since the unix epoch at which the snapshot was made
+    pub created_at: u64,
+
+    /// The object pool data as IOP bytes
+    object_pool_data: Vec<u8>,
+}
+
+impl NamedSnapshot {
+    pub fn new(name: String, pool: &ObjectPool) -> Self {
+        NamedSnapshot {
+            name,
+            created_at: crate::autosave::unix_time(),
+            object_pool_data: pool.as_iop(),
+        }
+    }
+
+    pub fn load_pool(&self) -> ObjectPool {
+        ObjectPool::from_iop(self.object_pool_data.clone())
+    }
 }
 
 /// Metadata for a single object
@@ -33,8 +66,36 @@ pub struct ObjectMetadata {
     /// Custom name for the object
     pub name: Option<String>,
 
+    /// Identifier used instead of the name in exported headers and code
+    #[serde(default)]
+    pub export_alias: Option<String>,
+
     /// Notes or comments about the object
     pub notes: Option<String>,
+
+    /// Tags to group objects across the hierarchy
+    #[serde(default)]
+    pub tags: Vec<String>,
+
+    /// Text of the object per language code
+    #[serde(default)]
+    pub translations: BTreeMap<String, String>,
+
+    /// Whether editing the object and its children requires unlocking it first
+    #[serde(default)]
+    pub restricted: bool,
+
+    /// Drawing commands previewed on a graphics context
+    #[serde(default)]
+    pub graphics_commands: String,
+
+    /// Whether the object is shown blinking in the preview
+    #[serde(default)]
+    pub blinking: bool,
+
+    /// Image file the picture graphic was imported from
+    #[serde(default)]
+    pub image_source: Option<ImageSource>,
 }
 
 /// Project-level settings
@@ -45,6 +106,39 @@ pub struct ProjectSettings {
 
     /// Last selected object ID
     pub last_selected: Option<u16>,
+
+    /// Recently used colour indices, most recent first
+    #[serde(default)]
+    pub recent_colours: Vec<u8>,
+
+    /// Colour indices curated by the user as the palette of this project
+    #[serde(default)]
+    pub palette_colours: Vec<u8>,
+
+    /// Auxiliary input assignments used by the simulation
+    #[serde(default)]
+    pub aux_assignments: Vec<AuxAssignment>,
+
+    /// Demo script replayed in the simulation
+    #[serde(default)]
+    pub demo_script: DemoScript,
+
+    /// HMI guideline rules checked for the pool
+    #[serde(default)]
+    pub guidelines: GuidelineSettings,
+
+    /// Expanded nodes of the hierarchy, as paths of object IDs from the working set
+    #[serde(default)]
+    pub expanded_nodes: Vec<Vec<u16>>,
+
+    /// Smart names are preceded by the name of the mask or container showing the object
+    #[serde(default)]
+    pub parent_naming: bool,
+
+    /// VT version the pool is made for, objects and attributes of later versions are not offered.
+    /// Not stored by older projects, which are made for the version their objects require.
+    #[serde(default)]
+    pub vt_version: Option<u8>,
 }
 
 impl ProjectFile {
@@ -52,15 +146,21 @@ impl ProjectFile {
     pub fn new(
         pool: &ObjectPool,
         object_info: &HashMap<ObjectId, ObjectInfo>,
-        mask_size: u16,
-        selected: Option<ObjectId>,
+        settings: ProjectSettings,
     ) -> Self {
         // Convert ObjectInfo map to ObjectMetadata map
         let mut object_metadata = HashMap::new();
         for (id, info) in object_info {
             let metadata = ObjectMetadata {
                 name: info.name.clone(),
-                notes: None, // Future feature
+                export_alias: info.export_alias.clone(),
+                notes: (!info.notes.is_empty()).then(|| info.notes.clone()),
+                tags: info.tags.clone(),
+                translations: info.get_translations().clone(),
+                restricted: info.restricted,
+                graphics_commands: info.graphics_commands.clone(),
+                blinking: info.blinking,
+                image_source: info.image_source.clone(),
             };
             object_metadata.insert(id.value(), metadata);
         }
@@ -69,13 +169,22 @@ impl ProjectFile {
             version: PROJECT_FILE_VERSION,
             object_pool_data: pool.as_iop(),
             object_metadata,
-            settings: ProjectSettings {
-                mask_size,
-                last_selected: selected.map(|id| id.value()),
-            },
+            settings,
+            snapshots: Vec::new(),
         }
     }
 
+    /// Store named snapshots of the pool in the project
+    pub fn with_snapshots(mut self, snapshots: Vec<NamedSnapshot>) -> Self {
+        self.snapshots = snapshots;
+        self
+    }
+
+    /// Get the named snapshots of the pool
+    pub fn get_snapshots(&self) -> &[NamedSnapshot] {
+        &self.snapshots
+    }
+
     /// Load object pool from project file
     /// Returns an error if the object pool data is corrupted or invalid
     pub fn load_pool(&self) -> Result<ObjectPool, String> {
@@ -115,6 +224,118 @@ impl ProjectFile {
     pub fn from_bytes(bytes: &[u8]) -> Result<Self, serde_json::Error> {
         serde_json::from_slice(bytes)
     }
+
+    /// Read what can still be read of a project file that can't be deserialized, e.g. because it
+    /// is corrupted or written by a newer version. Sections that can't be read are left out and
+    /// described in the returned list. Returns an error if the object pool can't be found.
+    pub fn recover(bytes: &[u8]) -> Result<(Self, Vec<String>), String> {
+        let mut dropped = Vec::new();
+        let Ok(serde_json::Value::Object(mut sections)) = serde_json::from_slice(bytes) else {
+            let object_pool_data =
+                find_pool_data(bytes).ok_or("No object pool data found in the file")?;
+            dropped.push("Everything but the object pool, the file is not valid JSON".to_string());
+            return Ok((
+                ProjectFile {
+                    version: PROJECT_FILE_VERSION,
+                    object_pool_data,
+                    object_metadata: HashMap::new(),
+                    settings: ProjectSettings::default(),
+                    snapshots: Vec::new(),
+                },
+                dropped,
+            ));
+        };
+
+        let object_pool_data: Vec<u8> = sections
+            .remove("object_pool_data")
+            .and_then(|data| serde_json::from_value(data).ok())
+            .ok_or("The object pool data can't be read")?;
+        if let Some(version) = sections
+            .get("version")
+            .and_then(|version| version.as_u64())
+            .filter(|version| *version > PROJECT_FILE_VERSION as u64)
+        {
+            dropped.push(format!(
+                "Data only known to version {} of the project file format",
+                version
+            ));
+        }
+
+        let mut object_metadata = HashMap::new();
+        match sections.remove("object_metadata") {
+            Some(serde_json::Value::Object(entries)) => {
+                let count = entries.len();
+                for (id, entry) in entries {
+                    if let (Ok(id), Ok(metadata)) =
+                        (id.parse::<u16>(), serde_json::from_value(entry))
+                    {
+                        object_metadata.insert(id, metadata);
+                    }
+                }
+                if object_metadata.len() < count {
+                    dropped.push(format!(
+                        "Names, notes and other metadata of {} objects",
+                        count - object_metadata.len()
+                    ));
+                }
+            }
+            Some(_) => dropped.push("Names, notes and other metadata of all objects".to_string()),
+            None => {}
+        }
+
+        let settings = match sections.remove("settings").map(serde_json::from_value) {
+            Some(Ok(settings)) => settings,
+            Some(Err(_)) => {
+                dropped.push(
+                    "Project settings, like the mask size, colours and auxiliary assignments"
+                        .to_string(),
+                );
+                ProjectSettings::default()
+            }
+            None => ProjectSettings::default(),
+        };
+
+        let mut snapshots = Vec::new();
+        match sections.remove("snapshots") {
+            Some(serde_json::Value::Array(entries)) => {
+                let count = entries.len();
+                snapshots.extend(
+                    entries
+                        .into_iter()
+                        .filter_map(|entry| serde_json::from_value(entry).ok()),
+                );
+                if snapshots.len() < count {
+                    dropped.push(format!("{} snapshots", count - snapshots.len()));
+                }
+            }
+            Some(_) => dropped.push("All snapshots".to_string()),
+            None => {}
+        }
+
+        Ok((
+            ProjectFile {
+                version: PROJECT_FILE_VERSION,
+                object_pool_data,
+                object_metadata,
+                settings,
+                snapshots,
+            },
+            dropped,
+        ))
+    }
+}
+
+/// Find the object pool data in the text of a project file that isn't valid JSON, e.g. because
+/// it was cut off after the pool data
+fn find_pool_data(bytes: &[u8]) -> Option<Vec<u8>> {
+    let text = String::from_utf8_lossy(bytes);
+    let rest = &text[text.find("\"object_pool_data\"")?..];
+    let open = rest.find('[')?;
+    let close = open + rest[open..].find(']')?;
+    rest[open + 1..close]
+        .split(',')
+        .map(|byte| byte.trim().parse().ok())
+        .collect()
 }
 
 impl Default for ProjectSettings {
@@ -122,6 +343,81 @@ impl Default for ProjectSettings {
         ProjectSettings {
             mask_size: 500,
             last_selected: None,
+            recent_colours: Vec::new(),
+            palette_colours: Vec::new(),
+            aux_assignments: Vec::new(),
+            demo_script: DemoScript::default(),
+            guidelines: GuidelineSettings::default(),
+            expanded_nodes: Vec::new(),
+            parent_naming: false,
+            vt_version: None,
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::object_defaults::default_object;
+    use ag_iso_stack::object_pool::ObjectType;
+
+    fn project_bytes() -> (Vec<u8>, Vec<u8>) {
+        let mut pool = ObjectPool::default();
+        pool.add(default_object(ObjectType::WorkingSet));
+        let file = ProjectFile::new(&pool, &HashMap::new(), ProjectSettings::default());
+        (file.to_bytes().unwrap(), pool.as_iop())
+    }
+
+    #[test]
+    fn recover_truncated_file() {
+        let (bytes, pool_data) = project_bytes();
+        let text = String::from_utf8(bytes).unwrap();
+        let cut = text.find("\"object_metadata\"").unwrap();
+        let (recovered, dropped) = ProjectFile::recover(&text.as_bytes()[..cut]).unwrap();
+        assert_eq!(recovered.object_pool_data, pool_data);
+        assert_eq!(
+            dropped,
+            vec!["Everything but the object pool, the file is not valid JSON".to_string()]
+        );
+
+        // Nothing can be recovered when the pool data itself is cut off
+        let cut = text.find("\"object_pool_data\"").unwrap() + "\"object_pool_data\": [".len();
+        assert!(ProjectFile::recover(&text.as_bytes()[..cut + 1]).is_err());
+    }
+
+    #[test]
+    fn recover_newer_version() {
+        let (bytes, pool_data) = project_bytes();
+        let mut json: serde_json::Value = serde_json::from_slice(&bytes).unwrap();
+        json["version"] = serde_json::json!(PROJECT_FILE_VERSION + 1);
+        json["settings"] = serde_json::json!("changed in a newer version");
+        json["snapshots"] = serde_json::json!([{ "name": "missing fields" }]);
+        json["object_metadata"] = serde_json::json!({
+            "0": { "name": "Working set", "notes": null },
+            "1": { "name": 5 },
+        });
+        let bytes = serde_json::to_vec(&json).unwrap();
+        assert!(ProjectFile::from_bytes(&bytes).is_err());
+
+        let (recovered, dropped) = ProjectFile::recover(&bytes).unwrap();
+        assert_eq!(recovered.version, PROJECT_FILE_VERSION);
+        assert_eq!(recovered.object_pool_data, pool_data);
+        assert_eq!(
+            recovered.object_metadata[&0].name.as_deref(),
+            Some("Working set")
+        );
+        assert_eq!(
+            dropped,
+            vec![
+                format!(
+                    "Data only known to version {} of the project file format",
+                    PROJECT_FILE_VERSION + 1
+                ),
+                "Names, notes and other metadata of 1 objects".to_string(),
+                "Project settings, like the mask size, colours and auxiliary assignments"
+                    .to_string(),
+                "1 snapshots".to_string(),
+            ]
+        );
+    }
+}
@@ -0,0 +1,198 @@
+//! Copyright 2024 - The Open-Agriculture Developers
+//! SPDX-License-Identifier: GPL-3.0-or-later
+//! Authors: Daan Steenbergen
+
+use ag_iso_stack::object_pool::object::Object;
+use ag_iso_stack::object_pool::{NullableObjectId, ObjectId, ObjectRef};
+use std::collections::HashMap;
+
+/// The objects references are rewritten from, and what they are rewritten to
+enum Targets<'a> {
+    /// References to `from` are redirected to `to`, or removed without a new object
+    Single {
+        from: ObjectId,
+        to: Option<ObjectId>,
+    },
+    /// References are redirected from every key to its value at once
+    Map(&'a HashMap<ObjectId, ObjectId>),
+}
+
+/// Rewrites the references of an object to other objects, counting the rewrites.
+/// Removing references is done where possible, required references are left.
+struct Redirect<'a> {
+    targets: Targets<'a>,
+    count: usize,
+}
+
+impl Redirect<'_> {
+    /// What a reference to the object becomes: `None` if it is left as it is, `Some(None)` if
+    /// it is removed
+    fn target(&self, id: ObjectId) -> Option<Option<ObjectId>> {
+        match &self.targets {
+            Targets::Single { from, to } => (id == *from).then_some(*to),
+            Targets::Map(map) => map.get(&id).map(|to| Some(*to)),
+        }
+    }
+
+    fn id(&mut self, id: &mut ObjectId) {
+        if let Some(Some(to)) = self.target(*id) {
+            *id = to;
+            self.count += 1;
+        }
+    }
+
+    fn nullable(&mut self, id: &mut NullableObjectId) {
+        if let Some(to) = id.0.and_then(|id| self.target(id)) {
+            *id = NullableObjectId(to);
+            self.count += 1;
+        }
+    }
+
+    fn ids(&mut self, ids: &mut Vec<ObjectId>) {
+        let len = ids.len();
+        ids.retain(|id| self.target(*id) != Some(None));
+        self.count += len - ids.len();
+        ids.iter_mut().for_each(|id| self.id(id));
+    }
+
+    fn nullables(&mut self, ids: &mut [NullableObjectId]) {
+        ids.iter_mut().for_each(|id| self.nullable(id));
+    }
+
+    fn refs(&mut self, refs: &mut Vec<ObjectRef>) {
+        let len = refs.len();
+        refs.retain(|object_ref| self.target(object_ref.id) != Some(None));
+        self.count += len - refs.len();
+        refs.iter_mut()
+            .for_each(|object_ref| self.id(&mut object_ref.id));
+    }
+}
+
+/// Make the object reference `to` wherever it references `from`.
+/// Returns the number of references that were changed.
+pub(crate) fn redirect_references(object: &mut Object, from: ObjectId, to: ObjectId) -> usize {
+    let mut r = Redirect {
+        targets: Targets::Single { from, to: Some(to) },
+        count: 0,
+    };
+    rewrite_references(object, &mut r);
+    r.count
+}
+
+/// Make the object reference the new ID of every object in the map at once, so IDs can be
+/// swapped. Returns the number of references that were changed.
+pub(crate) fn remap_references(object: &mut Object, map: &HashMap<ObjectId, ObjectId>) -> usize {
+    let mut r = Redirect {
+        targets: Targets::Map(map),
+        count: 0,
+    };
+    rewrite_references(object, &mut r);
+    r.count
+}
+
+/// Remove the references of the object to `id`: they are dropped from lists of children and
+/// cleared where they are optional, list items are cleared to keep the indices of the others.
+/// References that are required are left as they are.
+/// Returns the number of references that were removed.
+pub(crate) fn remove_references(object: &mut Object, id: ObjectId) -> usize {
+    let mut r = Redirect {
+        targets: Targets::Single { from: id, to: None },
+        count: 0,
+    };
+    rewrite_references(object, &mut r);
+    r.count
+}
+
+fn rewrite_references(object: &mut Object, r: &mut Redirect) {
+    match object {
+        Object::WorkingSet(o) => {
+            r.id(&mut o.active_mask);
+            r.refs(&mut o.object_refs);
+        }
+        Object::DataMask(o) => {
+            r.nullable(&mut o.soft_key_mask);
+            r.refs(&mut o.object_refs);
+        }
+        Object::AlarmMask(o) => {
+            r.nullable(&mut o.soft_key_mask);
+            r.refs(&mut o.object_refs);
+        }
+        Object::Container(o) => r.refs(&mut o.object_refs),
+        Object::SoftKeyMask(o) => r.ids(&mut o.objects),
+        Object::Key(o) => r.refs(&mut o.object_refs),
+        Object::Button(o) => r.refs(&mut o.object_refs),
+        Object::InputBoolean(o) => {
+            r.id(&mut o.foreground_colour);
+            r.nullable(&mut o.variable_reference);
+        }
+        Object::InputString(o) => {
+            r.id(&mut o.font_attributes);
+            r.nullable(&mut o.input_attributes);
+            r.nullable(&mut o.variable_reference);
+        }
+        Object::InputNumber(o) => {
+            r.id(&mut o.font_attributes);
+            r.nullable(&mut o.variable_reference);
+        }
+        Object::InputList(o) => {
+            r.nullable(&mut o.variable_reference);
+            r.nullables(&mut o.list_items);
+        }
+        Object::OutputString(o) => {
+            r.id(&mut o.font_attributes);
+            r.nullable(&mut o.variable_reference);
+        }
+        Object::OutputNumber(o) => {
+            r.id(&mut o.font_attributes);
+            r.nullable(&mut o.variable_reference);
+        }
+        Object::OutputList(o) => {
+            r.nullable(&mut o.variable_reference);
+            r.nullables(&mut o.list_items);
+        }
+        Object::OutputLine(o) => r.id(&mut o.line_attributes),
+        Object::OutputRectangle(o) => {
+            r.id(&mut o.line_attributes);
+            r.nullable(&mut o.fill_attributes);
+        }
+        Object::OutputEllipse(o) => {
+            r.id(&mut o.line_attributes);
+            r.nullable(&mut o.fill_attributes);
+        }
+        Object::OutputPolygon(o) => {
+            r.id(&mut o.line_attributes);
+            r.nullable(&mut o.fill_attributes);
+        }
+        Object::OutputMeter(o) => r.nullable(&mut o.variable_reference),
+        Object::OutputLinearBarGraph(o) => {
+            r.nullable(&mut o.variable_reference);
+            r.nullable(&mut o.target_value_variable_reference);
+        }
+        Object::OutputArchedBarGraph(o) => {
+            r.nullable(&mut o.variable_reference);
+            r.nullable(&mut o.target_value_variable_reference);
+        }
+        Object::FillAttributes(o) => r.nullable(&mut o.fill_pattern),
+        Object::ObjectPointer(o) => r.nullable(&mut o.value),
+        Object::AuxiliaryFunctionType1(o) => r.refs(&mut o.object_refs),
+        Object::AuxiliaryInputType1(o) => r.refs(&mut o.object_refs),
+        Object::AuxiliaryFunctionType2(o) => r.refs(&mut o.object_refs),
+        Object::AuxiliaryInputType2(o) => r.refs(&mut o.object_refs),
+        Object::AuxiliaryControlDesignatorType2(o) => r.nullable(&mut o.auxiliary_object_id),
+        Object::WindowMask(o) => {
+            r.id(&mut o.name);
+            r.nullable(&mut o.window_title);
+            r.nullable(&mut o.window_icon);
+            r.ids(&mut o.objects);
+            r.refs(&mut o.object_refs);
+        }
+        Object::ExternalObjectDefinition(o) => r.nullables(&mut o.objects),
+        Object::ExternalObjectPointer(o) => {
+            r.nullable(&mut o.default_object_id);
+            r.nullable(&mut o.external_reference_name_id);
+        }
+        Object::WorkingSetSpecialControls(o) => r.nullable(&mut o.id_of_colour_palette),
+        // The other objects don't reference objects, or their references are not edited yet
+        _ => {}
+    }
+}
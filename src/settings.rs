@@ -0,0 +1,1270 @@
+//! Copyright 2024 - The Open-Agriculture Developers
+//! SPDX-License-Identifier: GPL-3.0-or-later
+//! Authors: Daan Steenbergen
+
+use crate::mask_size::mask_size_selector;
+use crate::simulation::{FocusStyle, MaskTransition};
+use crate::smart_naming::{CaseConvention, NamingRules, NumberingStyle};
+use ag_iso_stack::object_pool::ObjectType;
+use eframe::egui;
+use serde::{Deserialize, Serialize};
+
+pub const UNDO_SHORTCUT: egui::KeyboardShortcut =
+    egui::KeyboardShortcut::new(egui::Modifiers::CTRL, egui::Key::Z);
+pub const REDO_SHORTCUT: egui::KeyboardShortcut =
+    egui::KeyboardShortcut::new(egui::Modifiers::CTRL, egui::Key::Y);
+pub const FILTER_SHORTCUT: egui::KeyboardShortcut =
+    egui::KeyboardShortcut::new(egui::Modifiers::CTRL, egui::Key::F);
+pub const RENAME_SHORTCUT: egui::KeyboardShortcut =
+    egui::KeyboardShortcut::new(egui::Modifiers::NONE, egui::Key::F2);
+pub const GO_TO_SHORTCUT: egui::KeyboardShortcut =
+    egui::KeyboardShortcut::new(egui::Modifiers::CTRL, egui::Key::G);
+pub const PERFORMANCE_HUD_SHORTCUT: egui::KeyboardShortcut =
+    egui::KeyboardShortcut::new(egui::Modifiers::NONE, egui::Key::F12);
+/// Copying and pasting arrive as clipboard events, these are only shown in the settings
+const COPY_SHORTCUT: egui::KeyboardShortcut =
+    egui::KeyboardShortcut::new(egui::Modifiers::COMMAND, egui::Key::C);
+const PASTE_SHORTCUT: egui::KeyboardShortcut =
+    egui::KeyboardShortcut::new(egui::Modifiers::COMMAND, egui::Key::V);
+
+const SHOW_DEVELOPMENT_POPUP: &str = "Show development notice on startup";
+const RUST_EXPORT_MASK_ENUMS: &str = "Include an enum per mask in Rust export";
+const HEADER_NOTES: &str = "Include object notes as comments in C header export";
+const HEADER_STYLE: &str = "C header export style";
+const HEADER_GROUP_BY_TYPE: &str = "Group C header export by object type";
+const HEADER_CONSTANTS: &str = "Include mask size and VT version in C header export";
+const SHOW_PERFORMANCE_HUD: &str = "Show performance overlay";
+const RESTORE_LAST_SESSION: &str = "Reopen the last project on startup";
+const LINK_IMPORTED_IMAGES: &str = "Link imported images to their files";
+const DATA_MASK_SIZE: &str = "Data mask size (px)";
+const APPLY_SMART_NAMING_ON_IMPORT: &str = "Propose smart names on import";
+const NAMING_PREFIXES: &str = "Name prefixes per type";
+const NAMING_NUMBERING: &str = "Number the names of";
+const NAMING_NUMBER_WIDTH: &str = "Digits of the number";
+const NAMING_CASE: &str = "Case of the names";
+const ID_RANGES: &str = "Object ID ranges per type";
+const CANVAS_THEME: &str = "Canvas theme";
+const EXPORT_THEME: &str = "Export image style";
+const EXPORT_MARGIN: &str = "Export image margin (px)";
+const CUSTOM_THEME_COLOURS: &str = "Custom theme colours";
+const LOCALIZED_NUMBERS: &str = "Format numbers for the working set language";
+const ANIMATIONS: &str = "Animate flashing and blinking";
+const LOW_POWER: &str = "Low-power mode";
+const AUTOSAVE_ENABLED: &str = "Autosave modified projects";
+const AUTOSAVE_INTERVAL: &str = "Autosave interval (s)";
+const AUTOSAVE_KEEP: &str = "Autosaves kept per project";
+const AUTOSAVE_DIRECTORY: &str = "Autosave directory";
+const MASK_TRANSITION: &str = "Mask transition";
+const MASK_TRANSITION_DURATION: &str = "Mask transition duration (ms)";
+const FOCUS_STYLE: &str = "Input focus style";
+const FOCUS_COLOUR: &str = "Input focus colour";
+
+/// General application settings
+#[derive(Serialize, Deserialize, Clone, PartialEq)]
+#[serde(default)]
+pub struct GeneralSettings {
+    /// Show the development notice when the application starts
+    pub show_development_popup: bool,
+
+    /// Add an enum for every mask when exporting a Rust module
+    pub rust_export_mask_enums: bool,
+
+    /// Write the notes of the objects as comments above their defines in the C header
+    pub header_notes: bool,
+
+    /// How the object IDs are declared in the C header
+    pub header_style: HeaderStyle,
+
+    /// Group the object IDs in the C header per object type, under a comment banner
+    pub header_group_by_type: bool,
+
+    /// Add the data mask size and the required VT version to the C header
+    pub header_constants: bool,
+
+    /// Show the overlay with the frame time per part of the application
+    pub show_performance_hud: bool,
+
+    /// Reopen the project of the last session on startup, with its selection and layout
+    pub restore_last_session: bool,
+
+    /// Remember the file a picture graphic was imported from, to re-import it when it changes
+    pub link_imported_images: bool,
+}
+
+impl Default for GeneralSettings {
+    fn default() -> Self {
+        GeneralSettings {
+            show_development_popup: true,
+            rust_export_mask_enums: true,
+            header_notes: false,
+            header_style: HeaderStyle::Defines,
+            header_group_by_type: false,
+            header_constants: false,
+            show_performance_hud: false,
+            restore_last_session: false,
+            link_imported_images: true,
+        }
+    }
+}
+
+/// How the object IDs are declared in the exported C header
+#[derive(Serialize, Deserialize, Clone, Copy, PartialEq, Eq, Debug)]
+pub enum HeaderStyle {
+    /// `#define` per object, for C and C++
+    Defines,
+    /// C++ `enum class` of the objects, one per object type when grouped
+    EnumClass,
+    /// C++ `constexpr` constant per object
+    Constexpr,
+}
+
+impl HeaderStyle {
+    pub const ALL: [HeaderStyle; 3] = [
+        HeaderStyle::Defines,
+        HeaderStyle::EnumClass,
+        HeaderStyle::Constexpr,
+    ];
+
+    pub fn name(self) -> &'static str {
+        match self {
+            HeaderStyle::Defines => "#define",
+            HeaderStyle::EnumClass => "C++ enum class",
+            HeaderStyle::Constexpr => "C++ constexpr",
+        }
+    }
+}
+
+/// Properties of the terminal the pools are designed for
+#[derive(Serialize, Deserialize, Clone, PartialEq)]
+#[serde(default)]
+pub struct TerminalProfileSettings {
+    /// Width and height of the data mask area of the terminal
+    pub data_mask_size: u16,
+    /// How the terminal indicates the input that has focus
+    pub focus_style: FocusStyle,
+    /// Colour of the focus indication, as unmultiplied sRGBA
+    pub focus_colour: [u8; 4],
+}
+
+impl TerminalProfileSettings {
+    pub fn focus_colour(&self) -> egui::Color32 {
+        to_color32(self.focus_colour)
+    }
+}
+
+impl Default for TerminalProfileSettings {
+    fn default() -> Self {
+        TerminalProfileSettings {
+            data_mask_size: 480,
+            focus_style: FocusStyle::Border,
+            focus_colour: [255, 200, 0, 255],
+        }
+    }
+}
+
+/// Settings of the automatic object naming
+#[derive(Serialize, Deserialize, Clone, PartialEq)]
+#[serde(default)]
+pub struct NamingSettings {
+    /// Automatically apply smart naming to objects when importing IOP files
+    pub apply_smart_naming_on_import: bool,
+
+    /// Patterns of the names given by smart naming
+    pub rules: NamingRules,
+}
+
+impl Default for NamingSettings {
+    fn default() -> Self {
+        NamingSettings {
+            apply_smart_naming_on_import: true, // Default to true for better UX
+            rules: NamingRules::default(),
+        }
+    }
+}
+
+/// IDs given to new objects of a type, e.g. 1000 to 1999 for data masks
+#[derive(Serialize, Deserialize, Clone, PartialEq)]
+pub struct IdRange {
+    /// The object type, by its number in the standard
+    pub object_type: u8,
+    pub start: u16,
+    /// Last ID of the range, `None` for all IDs from the start on
+    pub end: Option<u16>,
+}
+
+impl IdRange {
+    pub fn object_type(&self) -> Option<ObjectType> {
+        ObjectType::values()
+            .into_iter()
+            .find(|object_type| *object_type as u8 == self.object_type)
+    }
+
+    /// The IDs of the range, the NULL object ID 65535 is never part of it
+    pub fn ids(&self) -> std::ops::RangeInclusive<u16> {
+        let end = self.end.unwrap_or(u16::MAX - 1).min(u16::MAX - 1);
+        self.start.max(1)..=end
+    }
+}
+
+/// Settings of the IDs given to new, duplicated, pasted and inserted objects
+#[derive(Serialize, Deserialize, Clone, PartialEq, Default)]
+#[serde(default)]
+pub struct IdSettings {
+    /// Ranges for the types that are numbered on their own, other types get the next free ID
+    pub ranges: Vec<IdRange>,
+}
+
+impl IdSettings {
+    pub fn range(&self, object_type: ObjectType) -> Option<&IdRange> {
+        self.ranges
+            .iter()
+            .find(|range| range.object_type == object_type as u8)
+    }
+}
+
+/// Settings of the automatic saving of modified projects
+#[derive(Serialize, Deserialize, Clone, PartialEq)]
+#[serde(default)]
+pub struct AutosaveSettings {
+    /// Periodically save modified projects to their own autosave file
+    pub enabled: bool,
+
+    /// Time between autosaves of a modified project
+    pub interval_secs: u32,
+
+    /// Number of autosaves kept per project, the oldest is overwritten by a new autosave
+    pub keep: u8,
+
+    /// Directory for the autosaves, `None` for the application data directory.
+    /// Not used on the web, where autosaves are kept in the local storage of the browser.
+    pub directory: Option<String>,
+}
+
+impl Default for AutosaveSettings {
+    fn default() -> Self {
+        AutosaveSettings {
+            enabled: true,
+            interval_secs: 60,
+            keep: 3,
+            directory: None,
+        }
+    }
+}
+
+/// Settings of the simulation of the pool
+#[derive(Serialize, Deserialize, Clone, PartialEq)]
+#[serde(default)]
+pub struct SimulationSettings {
+    /// Animation shown when the simulation changes the active mask
+    pub mask_transition: MaskTransition,
+    pub mask_transition_duration_ms: u16,
+}
+
+impl Default for SimulationSettings {
+    fn default() -> Self {
+        SimulationSettings {
+            mask_transition: MaskTransition::None,
+            mask_transition_duration_ms: 300,
+        }
+    }
+}
+
+/// Colours used to draw the mask canvas and exported documentation images.
+/// Colours are stored as unmultiplied sRGBA.
+#[derive(Serialize, Deserialize, Clone, Copy, PartialEq)]
+pub struct CanvasTheme {
+    /// Area surrounding the mask
+    pub surround: [u8; 4],
+    /// Outline of the mask area
+    pub guide: [u8; 4],
+    /// Highlight of the object under the pointer
+    pub hover: [u8; 4],
+    /// Highlight of the selected object
+    pub selection: [u8; 4],
+}
+
+impl CanvasTheme {
+    pub const LIGHT: CanvasTheme = CanvasTheme {
+        surround: [235, 235, 235, 255],
+        guide: [150, 150, 150, 255],
+        hover: [255, 150, 0, 220],
+        selection: [0, 110, 220, 255],
+    };
+
+    pub const DARK: CanvasTheme = CanvasTheme {
+        surround: [30, 30, 30, 255],
+        guide: [100, 100, 100, 255],
+        hover: [255, 255, 0, 200],
+        selection: [90, 170, 255, 255],
+    };
+
+    pub fn surround_colour(&self) -> egui::Color32 {
+        to_color32(self.surround)
+    }
+
+    pub fn guide_colour(&self) -> egui::Color32 {
+        to_color32(self.guide)
+    }
+
+    pub fn hover_colour(&self) -> egui::Color32 {
+        to_color32(self.hover)
+    }
+
+    pub fn selection_colour(&self) -> egui::Color32 {
+        to_color32(self.selection)
+    }
+}
+
+fn to_color32([r, g, b, a]: [u8; 4]) -> egui::Color32 {
+    egui::Color32::from_rgba_unmultiplied(r, g, b, a)
+}
+
+/// Which canvas theme is used, independent of the theme of the application itself
+#[derive(Serialize, Deserialize, Clone, Copy, PartialEq, Eq, Debug)]
+pub enum CanvasThemeMode {
+    /// Follow the light/dark theme of the application
+    FollowApplication,
+    Light,
+    Dark,
+    Custom,
+}
+
+/// Appearance of the mask canvas and exported images
+#[derive(Serialize, Deserialize, Clone, PartialEq)]
+#[serde(default)]
+pub struct CanvasSettings {
+    pub theme: CanvasThemeMode,
+    pub export_theme: CanvasThemeMode,
+    pub custom_theme: CanvasTheme,
+    /// Margin around exported images, filled with the surround colour of the export theme
+    pub export_margin: u8,
+    /// Render numbers with the decimal separator of the first working set language
+    pub localized_numbers: bool,
+    /// Animate flashing text and blinking objects, off to show them in their normal state, e.g.
+    /// to take screenshots
+    pub animations: bool,
+    /// Repaint less often, without animations or anti-aliasing, e.g. for the web version on a
+    /// laptop running on battery
+    pub low_power: bool,
+}
+
+impl Default for CanvasSettings {
+    fn default() -> Self {
+        CanvasSettings {
+            theme: CanvasThemeMode::FollowApplication,
+            export_theme: CanvasThemeMode::Light,
+            custom_theme: CanvasTheme::LIGHT,
+            export_margin: 0,
+            localized_numbers: false,
+            animations: true,
+            low_power: false,
+        }
+    }
+}
+
+impl CanvasSettings {
+    fn resolve(&self, mode: CanvasThemeMode, dark_mode: bool) -> CanvasTheme {
+        match mode {
+            CanvasThemeMode::FollowApplication if dark_mode => CanvasTheme::DARK,
+            CanvasThemeMode::FollowApplication => CanvasTheme::LIGHT,
+            CanvasThemeMode::Light => CanvasTheme::LIGHT,
+            CanvasThemeMode::Dark => CanvasTheme::DARK,
+            CanvasThemeMode::Custom => self.custom_theme,
+        }
+    }
+
+    /// Get the theme of the mask canvas
+    pub fn canvas_theme(&self, dark_mode: bool) -> CanvasTheme {
+        self.resolve(self.theme, dark_mode)
+    }
+
+    /// Get the theme of exported documentation images
+    pub fn export_theme(&self, dark_mode: bool) -> CanvasTheme {
+        self.resolve(self.export_theme, dark_mode)
+    }
+}
+
+/// Application wide settings of the designer, stored outside of the projects
+#[derive(Serialize, Deserialize, Clone, PartialEq, Default)]
+#[serde(default)]
+pub struct DesignerSettings {
+    pub general: GeneralSettings,
+    pub terminal: TerminalProfileSettings,
+    pub canvas: CanvasSettings,
+    pub naming: NamingSettings,
+    pub ids: IdSettings,
+    pub autosave: AutosaveSettings,
+    pub simulation: SimulationSettings,
+
+    /// Paths of the recently opened pools and projects, most recent first
+    pub recent_files: Vec<String>,
+
+    /// State of the last session, see [`GeneralSettings::restore_last_session`]
+    pub session: SessionState,
+}
+
+/// The open project and layout of a session, kept up to date while the designer runs so it
+/// can be restored on the next start
+#[derive(Serialize, Deserialize, Clone, PartialEq, Default)]
+#[serde(default)]
+pub struct SessionState {
+    /// File of the open project, `None` if no project or an unsaved project is open
+    pub project_path: Option<String>,
+    pub selected_object: Option<u16>,
+    pub mask_size: Option<u16>,
+    pub left_panel_width: Option<f32>,
+    pub right_panel_width: Option<f32>,
+}
+
+/// Amount of recently opened files that are remembered
+const MAX_RECENT_FILES: usize = 10;
+
+impl DesignerSettings {
+    /// Move a file to the top of the recently opened files
+    pub fn add_recent_file(&mut self, path: &str) {
+        self.recent_files.retain(|recent| recent != path);
+        self.recent_files.insert(0, path.to_string());
+        self.recent_files.truncate(MAX_RECENT_FILES);
+    }
+
+    pub fn remove_recent_file(&mut self, path: &str) {
+        self.recent_files.retain(|recent| recent != path);
+    }
+
+    /// Load the settings, falling back to the defaults if there are no stored settings
+    pub fn load() -> Self {
+        match storage::read() {
+            Ok(Some(data)) => match serde_json::from_slice(&data) {
+                Ok(settings) => return settings,
+                Err(e) => log::error!("Failed to parse settings: {}", e),
+            },
+            Ok(None) => {}
+            Err(e) => log::error!("Failed to read settings: {}", e),
+        }
+        DesignerSettings::default()
+    }
+
+    /// Store the settings so they are restored on the next start
+    pub fn save(&self) {
+        let result = serde_json::to_vec_pretty(self)
+            .map_err(|e| e.to_string())
+            .and_then(|data| storage::write(&data));
+        if let Err(e) = result {
+            log::error!("Failed to save settings: {}", e);
+        }
+    }
+}
+
+/// Directory of the designer in the user configuration directory
+#[cfg(not(target_arch = "wasm32"))]
+pub(crate) fn app_data_dir() -> Option<std::path::PathBuf> {
+    use std::path::PathBuf;
+
+    let config_dir = std::env::var_os("APPDATA")
+        .map(PathBuf::from)
+        .or_else(|| std::env::var_os("XDG_CONFIG_HOME").map(PathBuf::from))
+        .or_else(|| std::env::var_os("HOME").map(|home| PathBuf::from(home).join(".config")))?;
+    Some(config_dir.join("AgIsoTerminalDesigner"))
+}
+
+/// The settings are a file in the user configuration directory on native targets
+#[cfg(not(target_arch = "wasm32"))]
+mod storage {
+    /// Location of the settings file in the user configuration directory
+    fn settings_path() -> Result<std::path::PathBuf, String> {
+        super::app_data_dir()
+            .map(|dir| dir.join("settings.json"))
+            .ok_or_else(|| "No configuration directory".to_string())
+    }
+
+    /// The stored settings, `None` if there are none yet
+    pub fn read() -> Result<Option<Vec<u8>>, String> {
+        let Ok(path) = settings_path() else {
+            return Ok(None);
+        };
+        match std::fs::read(path) {
+            Ok(data) => Ok(Some(data)),
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(None),
+            Err(e) => Err(e.to_string()),
+        }
+    }
+
+    pub fn write(data: &[u8]) -> Result<(), String> {
+        let path = settings_path()?;
+        if let Some(dir) = path.parent() {
+            std::fs::create_dir_all(dir).map_err(|e| e.to_string())?;
+        }
+        std::fs::write(&path, data).map_err(|e| e.to_string())
+    }
+}
+
+/// The settings are kept in the local storage of the browser on the web
+#[cfg(target_arch = "wasm32")]
+mod storage {
+    const KEY: &str = "settings";
+
+    fn local_storage() -> Result<web_sys::Storage, String> {
+        web_sys::window()
+            .and_then(|window| window.local_storage().ok()?)
+            .ok_or_else(|| "No local storage".to_string())
+    }
+
+    /// The stored settings, `None` if there are none yet
+    pub fn read() -> Result<Option<Vec<u8>>, String> {
+        local_storage()?
+            .get_item(KEY)
+            .map(|data| data.map(String::into_bytes))
+            .map_err(|e| format!("{:?}", e))
+    }
+
+    pub fn write(data: &[u8]) -> Result<(), String> {
+        local_storage()?
+            .set_item(KEY, &String::from_utf8_lossy(data))
+            .map_err(|e| format!("{:?}", e))
+    }
+}
+
+#[derive(Clone, Copy, PartialEq, Eq)]
+enum SettingsCategory {
+    General,
+    TerminalProfile,
+    Canvas,
+    Shortcuts,
+    Naming,
+    ObjectIds,
+    Autosave,
+    Simulation,
+}
+
+impl SettingsCategory {
+    const ALL: [SettingsCategory; 8] = [
+        SettingsCategory::General,
+        SettingsCategory::TerminalProfile,
+        SettingsCategory::Canvas,
+        SettingsCategory::Shortcuts,
+        SettingsCategory::Naming,
+        SettingsCategory::ObjectIds,
+        SettingsCategory::Autosave,
+        SettingsCategory::Simulation,
+    ];
+
+    fn name(self) -> &'static str {
+        match self {
+            SettingsCategory::General => "General",
+            SettingsCategory::TerminalProfile => "Terminal profile",
+            SettingsCategory::Canvas => "Canvas",
+            SettingsCategory::Shortcuts => "Shortcuts",
+            SettingsCategory::Naming => "Naming",
+            SettingsCategory::ObjectIds => "Object IDs",
+            SettingsCategory::Autosave => "Autosave",
+            SettingsCategory::Simulation => "Simulation",
+        }
+    }
+
+    /// Labels of the settings in this category, used for searching
+    fn labels(self) -> Vec<&'static str> {
+        match self {
+            SettingsCategory::General => vec![
+                SHOW_DEVELOPMENT_POPUP,
+                RUST_EXPORT_MASK_ENUMS,
+                HEADER_NOTES,
+                HEADER_STYLE,
+                HEADER_GROUP_BY_TYPE,
+                HEADER_CONSTANTS,
+                SHOW_PERFORMANCE_HUD,
+                RESTORE_LAST_SESSION,
+                LINK_IMPORTED_IMAGES,
+            ],
+            SettingsCategory::TerminalProfile => {
+                vec![DATA_MASK_SIZE, FOCUS_STYLE, FOCUS_COLOUR]
+            }
+            SettingsCategory::Canvas => vec![
+                CANVAS_THEME,
+                EXPORT_THEME,
+                EXPORT_MARGIN,
+                CUSTOM_THEME_COLOURS,
+                LOCALIZED_NUMBERS,
+                ANIMATIONS,
+                LOW_POWER,
+            ],
+            SettingsCategory::Shortcuts => shortcuts().iter().map(|(label, _)| *label).collect(),
+            SettingsCategory::Naming => vec![
+                APPLY_SMART_NAMING_ON_IMPORT,
+                NAMING_NUMBERING,
+                NAMING_NUMBER_WIDTH,
+                NAMING_CASE,
+                NAMING_PREFIXES,
+            ],
+            SettingsCategory::ObjectIds => vec![ID_RANGES],
+            SettingsCategory::Autosave => vec![
+                AUTOSAVE_ENABLED,
+                AUTOSAVE_INTERVAL,
+                AUTOSAVE_KEEP,
+                AUTOSAVE_DIRECTORY,
+            ],
+            SettingsCategory::Simulation => vec![MASK_TRANSITION, MASK_TRANSITION_DURATION],
+        }
+    }
+
+    /// Whether the settings of this category differ between the two settings
+    fn differs(self, a: &DesignerSettings, b: &DesignerSettings) -> bool {
+        match self {
+            SettingsCategory::General => a.general != b.general,
+            SettingsCategory::TerminalProfile => a.terminal != b.terminal,
+            SettingsCategory::Canvas => a.canvas != b.canvas,
+            SettingsCategory::Shortcuts => false,
+            SettingsCategory::Naming => a.naming != b.naming,
+            SettingsCategory::ObjectIds => a.ids != b.ids,
+            SettingsCategory::Autosave => a.autosave != b.autosave,
+            SettingsCategory::Simulation => a.simulation != b.simulation,
+        }
+    }
+
+    /// Copy the settings of this category from one settings to the other
+    fn copy(self, from: &DesignerSettings, to: &mut DesignerSettings) {
+        match self {
+            SettingsCategory::General => to.general = from.general.clone(),
+            SettingsCategory::TerminalProfile => to.terminal = from.terminal.clone(),
+            SettingsCategory::Canvas => to.canvas = from.canvas.clone(),
+            SettingsCategory::Shortcuts => {}
+            SettingsCategory::Naming => to.naming = from.naming.clone(),
+            SettingsCategory::ObjectIds => to.ids = from.ids.clone(),
+            SettingsCategory::Autosave => to.autosave = from.autosave.clone(),
+            SettingsCategory::Simulation => to.simulation = from.simulation.clone(),
+        }
+    }
+}
+
+/// All keyboard shortcuts of the designer with a description
+fn shortcuts() -> [(&'static str, egui::KeyboardShortcut); 8] {
+    [
+        ("Undo", UNDO_SHORTCUT),
+        ("Redo", REDO_SHORTCUT),
+        ("Filter objects by name", FILTER_SHORTCUT),
+        ("Rename selected object", RENAME_SHORTCUT),
+        (
+            "Go to object by name, header identifier or ID",
+            GO_TO_SHORTCUT,
+        ),
+        ("Toggle performance overlay", PERFORMANCE_HUD_SHORTCUT),
+        ("Copy selected object to the clipboard", COPY_SHORTCUT),
+        ("Paste objects from the clipboard", PASTE_SHORTCUT),
+    ]
+}
+
+fn render_theme_mode_selector(ui: &mut egui::Ui, label: &str, mode: &mut CanvasThemeMode) {
+    egui::ComboBox::from_label(label)
+        .selected_text(theme_mode_name(*mode))
+        .show_ui(ui, |ui| {
+            for option in [
+                CanvasThemeMode::FollowApplication,
+                CanvasThemeMode::Light,
+                CanvasThemeMode::Dark,
+                CanvasThemeMode::Custom,
+            ] {
+                ui.selectable_value(mode, option, theme_mode_name(option));
+            }
+        });
+}
+
+fn render_naming_prefixes(ui: &mut egui::Ui, rules: &mut NamingRules) {
+    ui.label(NAMING_PREFIXES).on_hover_text(
+        "Replace the readable type name at the start of generated names, e.g. BTN for buttons",
+    );
+    let mut removed = None;
+    egui::Grid::new("settings_naming_prefixes")
+        .num_columns(3)
+        .striped(true)
+        .show(ui, |ui| {
+            for (index, (object_type, prefix)) in rules.prefixes.iter_mut().enumerate() {
+                let name = type_name(*object_type);
+                egui::ComboBox::from_id_salt(("naming_prefix_type", index))
+                    .selected_text(name)
+                    .show_ui(ui, |ui| {
+                        for t in ObjectType::values() {
+                            ui.selectable_value(object_type, t as u8, format!("{:?}", t));
+                        }
+                    });
+                ui.text_edit_singleline(prefix);
+                if ui
+                    .small_button("🗑")
+                    .on_hover_text("Remove prefix")
+                    .clicked()
+                {
+                    removed = Some(index);
+                }
+                ui.end_row();
+            }
+        });
+    if let Some(index) = removed {
+        rules.prefixes.remove(index);
+    }
+    if ui.button("Add prefix").clicked() {
+        let object_type = ObjectType::values()
+            .into_iter()
+            .find(|t| !rules.prefixes.iter().any(|(other, _)| *other == *t as u8))
+            .unwrap_or(ObjectType::DataMask);
+        rules.prefixes.push((
+            object_type as u8,
+            crate::smart_naming::get_object_type_name(object_type).to_string(),
+        ));
+    }
+}
+
+/// Name of an object type stored by its number in the settings
+fn type_name(object_type: u8) -> String {
+    ObjectType::values()
+        .into_iter()
+        .find(|t| *t as u8 == object_type)
+        .map_or("Unknown".to_string(), |t| format!("{:?}", t))
+}
+
+fn render_id_ranges(ui: &mut egui::Ui, ids: &mut IdSettings) {
+    ui.label(ID_RANGES).on_hover_text(
+        "New objects of a type with a range get the first free ID in it, also when duplicating, \
+        pasting or inserting templates. The ranges are used when renumbering as well.",
+    );
+    let mut removed = None;
+    egui::Grid::new("settings_id_ranges")
+        .num_columns(4)
+        .striped(true)
+        .show(ui, |ui| {
+            for (index, range) in ids.ranges.iter_mut().enumerate() {
+                let name = type_name(range.object_type);
+                egui::ComboBox::from_id_salt(("id_range_type", index))
+                    .selected_text(name)
+                    .show_ui(ui, |ui| {
+                        for object_type in ObjectType::values() {
+                            ui.selectable_value(
+                                &mut range.object_type,
+                                object_type as u8,
+                                format!("{:?}", object_type),
+                            );
+                        }
+                    });
+                ui.add(egui::DragValue::new(&mut range.start).range(1..=u16::MAX - 1));
+                ui.horizontal(|ui| {
+                    let mut open_ended = range.end.is_none();
+                    if ui.checkbox(&mut open_ended, "No end").changed() {
+                        range.end = if open_ended {
+                            None
+                        } else {
+                            Some(range.start.saturating_add(999).min(u16::MAX - 1))
+                        };
+                    }
+                    if let Some(end) = &mut range.end {
+                        ui.add(egui::DragValue::new(end).range(range.start..=u16::MAX - 1));
+                    }
+                });
+                if ui.small_button("🗑").on_hover_text("Remove range").clicked() {
+                    removed = Some(index);
+                }
+                ui.end_row();
+            }
+        });
+    if let Some(index) = removed {
+        ids.ranges.remove(index);
+    }
+
+    if ui.button("Add range").clicked() {
+        let object_type = ObjectType::values()
+            .into_iter()
+            .find(|t| ids.range(*t).is_none())
+            .unwrap_or(ObjectType::DataMask);
+        let start = (object_type as u16).saturating_mul(1000).max(1);
+        ids.ranges.push(IdRange {
+            object_type: object_type as u8,
+            start,
+            end: Some(start.saturating_add(999).min(u16::MAX - 1)),
+        });
+    }
+
+    for (index, range) in ids.ranges.iter().enumerate() {
+        let overlaps = ids.ranges[index + 1..].iter().any(|other| {
+            range.ids().start() <= other.ids().end() && other.ids().start() <= range.ids().end()
+        });
+        if overlaps {
+            ui.colored_label(
+                ui.visuals().warn_fg_color,
+                format!(
+                    "The range of {} overlaps another range",
+                    type_name(range.object_type)
+                ),
+            );
+        }
+    }
+}
+
+fn theme_mode_name(mode: CanvasThemeMode) -> &'static str {
+    match mode {
+        CanvasThemeMode::FollowApplication => "Follow application",
+        CanvasThemeMode::Light => "Light",
+        CanvasThemeMode::Dark => "Dark",
+        CanvasThemeMode::Custom => "Custom",
+    }
+}
+
+fn matches_search(search: &str, label: &str) -> bool {
+    search.is_empty() || label.to_lowercase().contains(&search.to_lowercase())
+}
+
+/// Dialog to edit the designer settings.
+/// Changes are made to a draft and only take effect once the category is applied.
+pub struct SettingsDialog {
+    category: SettingsCategory,
+    search: String,
+    draft: DesignerSettings,
+}
+
+impl SettingsDialog {
+    pub fn new(settings: &DesignerSettings) -> Self {
+        SettingsDialog {
+            category: SettingsCategory::General,
+            search: String::new(),
+            draft: settings.clone(),
+        }
+    }
+
+    /// Show the dialog, applied changes are written to `settings`.
+    /// Returns false once the dialog is closed, unapplied changes are discarded.
+    pub fn show(&mut self, ctx: &egui::Context, settings: &mut DesignerSettings) -> bool {
+        let mut open = true;
+
+        let modal = egui::Modal::new(egui::Id::new("settings_dialog")).show(ctx, |ui| {
+            ui.set_width(520.0);
+            ui.heading("Settings");
+            ui.add(
+                egui::TextEdit::singleline(&mut self.search)
+                    .hint_text("Search settings...")
+                    .desired_width(f32::INFINITY),
+            );
+            ui.separator();
+
+            if self.search.is_empty() {
+                ui.horizontal_top(|ui| {
+                    ui.vertical(|ui| {
+                        ui.set_width(130.0);
+                        for category in SettingsCategory::ALL {
+                            let text = if category.differs(&self.draft, settings) {
+                                format!("{} \u{2022}", category.name())
+                            } else {
+                                category.name().to_string()
+                            };
+                            ui.selectable_value(&mut self.category, category, text);
+                        }
+                    });
+                    ui.separator();
+                    ui.vertical(|ui| {
+                        self.render_category(ui, self.category, settings);
+                    });
+                });
+            } else {
+                let search = self.search.clone();
+                let mut any_match = false;
+                for category in SettingsCategory::ALL {
+                    if category
+                        .labels()
+                        .iter()
+                        .any(|label| matches_search(&search, label))
+                    {
+                        any_match = true;
+                        ui.strong(category.name());
+                        self.render_category(ui, category, settings);
+                        ui.add_space(8.0);
+                    }
+                }
+                if !any_match {
+                    ui.weak("No settings match the search");
+                }
+            }
+
+            ui.separator();
+            ui.horizontal(|ui| {
+                let any_changes = SettingsCategory::ALL
+                    .iter()
+                    .any(|category| category.differs(&self.draft, settings));
+                if ui
+                    .add_enabled(any_changes, egui::Button::new("Apply all"))
+                    .clicked()
+                {
+                    // Files may have been opened while the dialog was open
+                    let recent_files = std::mem::take(&mut settings.recent_files);
+                    let session = std::mem::take(&mut settings.session);
+                    *settings = self.draft.clone();
+                    settings.recent_files = recent_files;
+                    settings.session = session;
+                    settings.save();
+                }
+                if ui.button("Close").clicked() {
+                    open = false;
+                }
+            });
+        });
+
+        if modal.should_close() {
+            open = false;
+        }
+        open
+    }
+
+    fn render_category(
+        &mut self,
+        ui: &mut egui::Ui,
+        category: SettingsCategory,
+        settings: &mut DesignerSettings,
+    ) {
+        let search = self.search.clone();
+        let draft = &mut self.draft;
+        match category {
+            SettingsCategory::General => {
+                if matches_search(&search, SHOW_DEVELOPMENT_POPUP) {
+                    ui.checkbox(
+                        &mut draft.general.show_development_popup,
+                        SHOW_DEVELOPMENT_POPUP,
+                    );
+                }
+                if matches_search(&search, RUST_EXPORT_MASK_ENUMS) {
+                    ui.checkbox(
+                        &mut draft.general.rust_export_mask_enums,
+                        RUST_EXPORT_MASK_ENUMS,
+                    )
+                    .on_hover_text(
+                        "Add an enum for every data and alarm mask listing the objects shown on it",
+                    );
+                }
+                if matches_search(&search, HEADER_NOTES) {
+                    ui.checkbox(&mut draft.general.header_notes, HEADER_NOTES)
+                        .on_hover_text(
+                            "Write the notes of an object as a comment above its define, e.g. for \
+                            code reviews",
+                        );
+                }
+                if matches_search(&search, HEADER_STYLE) {
+                    egui::ComboBox::from_label(HEADER_STYLE)
+                        .selected_text(draft.general.header_style.name())
+                        .show_ui(ui, |ui| {
+                            for style in HeaderStyle::ALL {
+                                ui.selectable_value(
+                                    &mut draft.general.header_style,
+                                    style,
+                                    style.name(),
+                                );
+                            }
+                        })
+                        .response
+                        .on_hover_text("Declare the object IDs as defines or as C++ constants");
+                }
+                if matches_search(&search, HEADER_GROUP_BY_TYPE) {
+                    ui.checkbox(
+                        &mut draft.general.header_group_by_type,
+                        HEADER_GROUP_BY_TYPE,
+                    )
+                    .on_hover_text(
+                        "List the objects per type under a comment banner, with an enum class \
+                        per type",
+                    );
+                }
+                if matches_search(&search, HEADER_CONSTANTS) {
+                    ui.checkbox(&mut draft.general.header_constants, HEADER_CONSTANTS)
+                        .on_hover_text(
+                            "Add constants for the data mask size and the lowest VT version \
+                            supporting all objects of the pool",
+                        );
+                }
+                if matches_search(&search, SHOW_PERFORMANCE_HUD) {
+                    ui.checkbox(
+                        &mut draft.general.show_performance_hud,
+                        SHOW_PERFORMANCE_HUD,
+                    )
+                    .on_hover_text(
+                        "Show the frame time per part of the application, useful when reporting slowdowns",
+                    );
+                }
+                #[cfg(not(target_arch = "wasm32"))]
+                if matches_search(&search, RESTORE_LAST_SESSION) {
+                    ui.checkbox(
+                        &mut draft.general.restore_last_session,
+                        RESTORE_LAST_SESSION,
+                    )
+                    .on_hover_text(
+                        "Open the project of the last session again, with the selected object, \
+                        mask size and panel widths",
+                    );
+                }
+                #[cfg(not(target_arch = "wasm32"))]
+                if matches_search(&search, LINK_IMPORTED_IMAGES) {
+                    ui.checkbox(
+                        &mut draft.general.link_imported_images,
+                        LINK_IMPORTED_IMAGES,
+                    )
+                    .on_hover_text(
+                        "Store the path of imported images in the project, relative to the \
+                        project file, so changed images can be re-imported from the File menu",
+                    );
+                }
+            }
+            SettingsCategory::TerminalProfile => {
+                if matches_search(&search, DATA_MASK_SIZE) {
+                    ui.horizontal(|ui| {
+                        mask_size_selector(
+                            ui,
+                            "terminal_data_mask_size",
+                            &mut draft.terminal.data_mask_size,
+                        );
+                        ui.label(DATA_MASK_SIZE).on_hover_text(
+                            "Virtual mask size used for imported pools that do not require a \
+                            larger mask",
+                        );
+                    });
+                }
+                if matches_search(&search, FOCUS_STYLE) {
+                    egui::ComboBox::from_label(FOCUS_STYLE)
+                        .selected_text(draft.terminal.focus_style.name())
+                        .show_ui(ui, |ui| {
+                            for style in FocusStyle::ALL {
+                                ui.selectable_value(
+                                    &mut draft.terminal.focus_style,
+                                    style,
+                                    style.name(),
+                                );
+                            }
+                        })
+                        .response
+                        .on_hover_text(
+                            "How the terminal indicates the input that has focus in the \
+                            simulation, to check it doesn't clash with the layout",
+                        );
+                }
+                if matches_search(&search, FOCUS_COLOUR) {
+                    ui.horizontal(|ui| {
+                        ui.color_edit_button_srgba_unmultiplied(&mut draft.terminal.focus_colour);
+                        ui.label(FOCUS_COLOUR);
+                    });
+                }
+            }
+            SettingsCategory::Canvas => {
+                if matches_search(&search, CANVAS_THEME) {
+                    render_theme_mode_selector(ui, CANVAS_THEME, &mut draft.canvas.theme);
+                }
+                if matches_search(&search, EXPORT_THEME) {
+                    render_theme_mode_selector(ui, EXPORT_THEME, &mut draft.canvas.export_theme);
+                }
+                if matches_search(&search, EXPORT_MARGIN) {
+                    ui.add(
+                        egui::Slider::new(&mut draft.canvas.export_margin, 0..=64)
+                            .text(EXPORT_MARGIN),
+                    );
+                }
+                if matches_search(&search, LOCALIZED_NUMBERS) {
+                    ui.checkbox(&mut draft.canvas.localized_numbers, LOCALIZED_NUMBERS)
+                        .on_hover_text(
+                            "Preview number objects with the decimal separator (comma or point) \
+                            of the first language of the working set",
+                        );
+                }
+                if matches_search(&search, ANIMATIONS) {
+                    ui.checkbox(&mut draft.canvas.animations, ANIMATIONS)
+                        .on_hover_text(
+                            "Animate flashing text, blinking objects and mask transitions like \
+                            the VT. Turn off to show everything in its normal state, e.g. to take \
+                            screenshots.",
+                        );
+                }
+                if matches_search(&search, LOW_POWER) {
+                    ui.checkbox(&mut draft.canvas.low_power, LOW_POWER)
+                        .on_hover_text(
+                            "Save battery by repainting less often, without animations and \
+                            with less detailed rendering. Recommended for the web version on \
+                            laptops running on battery.",
+                        );
+                }
+                if matches_search(&search, CUSTOM_THEME_COLOURS) {
+                    ui.label(CUSTOM_THEME_COLOURS);
+                    let theme = &mut draft.canvas.custom_theme;
+                    egui::Grid::new("settings_custom_theme")
+                        .num_columns(2)
+                        .show(ui, |ui| {
+                            for (label, colour) in [
+                                ("Surround", &mut theme.surround),
+                                ("Guides", &mut theme.guide),
+                                ("Hover", &mut theme.hover),
+                                ("Selection", &mut theme.selection),
+                            ] {
+                                ui.label(label);
+                                ui.color_edit_button_srgba_unmultiplied(colour);
+                                ui.end_row();
+                            }
+                        });
+                }
+            }
+            SettingsCategory::Shortcuts => {
+                egui::Grid::new("settings_shortcuts")
+                    .num_columns(2)
+                    .striped(true)
+                    .show(ui, |ui| {
+                        for (label, shortcut) in shortcuts() {
+                            if matches_search(&search, label) {
+                                ui.label(label);
+                                ui.label(ui.ctx().format_shortcut(&shortcut));
+                                ui.end_row();
+                            }
+                        }
+                    });
+                return;
+            }
+            SettingsCategory::Naming => {
+                if matches_search(&search, APPLY_SMART_NAMING_ON_IMPORT) {
+                    ui.checkbox(
+                        &mut draft.naming.apply_smart_naming_on_import,
+                        APPLY_SMART_NAMING_ON_IMPORT,
+                    )
+                    .on_hover_text(
+                        "Propose smart names for the objects when importing IOP files, to \
+                        review before they are applied",
+                    );
+                }
+                let rules = &mut draft.naming.rules;
+                if matches_search(&search, NAMING_NUMBERING) {
+                    egui::ComboBox::from_label(NAMING_NUMBERING)
+                        .selected_text(rules.numbering.name())
+                        .show_ui(ui, |ui| {
+                            for numbering in NumberingStyle::ALL {
+                                ui.selectable_value(
+                                    &mut rules.numbering,
+                                    numbering,
+                                    numbering.name(),
+                                );
+                            }
+                        });
+                }
+                if matches_search(&search, NAMING_NUMBER_WIDTH) {
+                    ui.add(
+                        egui::Slider::new(&mut rules.number_width, 1..=5).text(NAMING_NUMBER_WIDTH),
+                    )
+                    .on_hover_text("Pad the numbers with zeros, e.g. 01, 02, ...");
+                }
+                if matches_search(&search, NAMING_CASE) {
+                    egui::ComboBox::from_label(NAMING_CASE)
+                        .selected_text(rules.case.name())
+                        .show_ui(ui, |ui| {
+                            for case in CaseConvention::ALL {
+                                ui.selectable_value(&mut rules.case, case, case.name());
+                            }
+                        });
+                }
+                if matches_search(&search, NAMING_PREFIXES) {
+                    render_naming_prefixes(ui, rules);
+                }
+                ui.weak(format!(
+                    "Example: {}, {}, {}",
+                    rules.format_name(ObjectType::DataMask, 1),
+                    rules.format_name(ObjectType::DataMask, 2),
+                    rules.format_name(ObjectType::Button, 3)
+                ));
+            }
+            SettingsCategory::ObjectIds => {
+                if matches_search(&search, ID_RANGES) {
+                    render_id_ranges(ui, &mut draft.ids);
+                }
+            }
+            SettingsCategory::Autosave => {
+                if matches_search(&search, AUTOSAVE_ENABLED) {
+                    ui.checkbox(&mut draft.autosave.enabled, AUTOSAVE_ENABLED)
+                        .on_hover_text(
+                            "Periodically save modified projects to an autosave file in the \
+                            autosave directory, separate for every project",
+                        );
+                }
+                if matches_search(&search, AUTOSAVE_INTERVAL) {
+                    ui.add(
+                        egui::Slider::new(&mut draft.autosave.interval_secs, 10..=600)
+                            .text(AUTOSAVE_INTERVAL),
+                    );
+                }
+                if matches_search(&search, AUTOSAVE_KEEP) {
+                    ui.add(egui::Slider::new(&mut draft.autosave.keep, 1..=20).text(AUTOSAVE_KEEP))
+                        .on_hover_text(
+                            "Keep the earlier autosaves of a project next to the latest one, \
+                            the oldest is overwritten by a new autosave",
+                        );
+                }
+                #[cfg(not(target_arch = "wasm32"))]
+                if matches_search(&search, AUTOSAVE_DIRECTORY) {
+                    ui.horizontal(|ui| {
+                        ui.label(AUTOSAVE_DIRECTORY);
+                        let directory = draft.autosave.directory.clone().unwrap_or_else(|| {
+                            crate::autosave::default_autosave_dir()
+                                .display()
+                                .to_string()
+                        });
+                        ui.monospace(directory);
+                    });
+                    ui.horizontal(|ui| {
+                        if ui.button("Choose...").clicked() {
+                            if let Some(path) = rfd::FileDialog::new().pick_folder() {
+                                draft.autosave.directory = Some(path.display().to_string());
+                            }
+                        }
+                        if ui
+                            .add_enabled(
+                                draft.autosave.directory.is_some(),
+                                egui::Button::new("Use default"),
+                            )
+                            .clicked()
+                        {
+                            draft.autosave.directory = None;
+                        }
+                    });
+                }
+            }
+            SettingsCategory::Simulation => {
+                if matches_search(&search, MASK_TRANSITION) {
+                    egui::ComboBox::from_label(MASK_TRANSITION)
+                        .selected_text(draft.simulation.mask_transition.name())
+                        .show_ui(ui, |ui| {
+                            for transition in MaskTransition::ALL {
+                                ui.selectable_value(
+                                    &mut draft.simulation.mask_transition,
+                                    transition,
+                                    transition.name(),
+                                );
+                            }
+                        })
+                        .response
+                        .on_hover_text(
+                            "Animate changes of the active mask in the simulation, to show the \
+                            intended flow in demos. The VT itself switches instantly.",
+                        );
+                }
+                if matches_search(&search, MASK_TRANSITION_DURATION) {
+                    ui.add(
+                        egui::Slider::new(
+                            &mut draft.simulation.mask_transition_duration_ms,
+                            50..=2000,
+                        )
+                        .text(MASK_TRANSITION_DURATION),
+                    );
+                }
+            }
+        }
+
+        ui.horizontal(|ui| {
+            let changed = category.differs(&self.draft, settings);
+            if ui
+                .add_enabled(changed, egui::Button::new("Apply"))
+                .on_hover_text(format!("Apply the {} settings", category.name()))
+                .clicked()
+            {
+                category.copy(&self.draft, settings);
+                settings.save();
+            }
+            if ui
+                .add_enabled(changed, egui::Button::new("Reset"))
+                .on_hover_text("Discard the unapplied changes")
+                .clicked()
+            {
+                category.copy(settings, &mut self.draft);
+            }
+        });
+    }
+}
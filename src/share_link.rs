@@ -0,0 +1,62 @@
+//! Copyright 2024 - The Open-Agriculture Developers
+//! SPDX-License-Identifier: GPL-3.0-or-later
+//! Authors: Daan Steenbergen
+
+use base64::engine::general_purpose::URL_SAFE_NO_PAD;
+use base64::Engine as _;
+use miniz_oxide::inflate::TINFLStatus;
+
+/// Start of the URL fragment holding a shared project, e.g. `#project=eJzt...`
+const FRAGMENT_PREFIX: &str = "project=";
+
+/// Longest fragment put in a link, longer links are cut off by chat and mail clients
+pub const MAX_SHARE_FRAGMENT_LENGTH: usize = 32 * 1024;
+
+/// Largest project opened from a link, so a crafted link can't make the page run out of memory
+const MAX_SHARED_PROJECT_SIZE: usize = 8 * MAX_SHARE_FRAGMENT_LENGTH;
+
+/// Compression level of the project data, the highest since links are made rarely
+const COMPRESSION_LEVEL: u8 = 10;
+
+/// URL fragment holding the project, given in the project file format (.aitp).
+/// Returns an error if the project is too large to share in a link.
+pub fn encode_share_fragment(project: &[u8]) -> Result<String, String> {
+    let compressed = miniz_oxide::deflate::compress_to_vec(project, COMPRESSION_LEVEL);
+    let fragment = format!("{}{}", FRAGMENT_PREFIX, URL_SAFE_NO_PAD.encode(compressed));
+    if fragment.len() > MAX_SHARE_FRAGMENT_LENGTH {
+        return Err(format!(
+            "The project needs a link of {} kB, at most {} kB fit in a link. Share the project \
+            file instead.",
+            fragment.len() / 1024,
+            MAX_SHARE_FRAGMENT_LENGTH / 1024
+        ));
+    }
+    Ok(fragment)
+}
+
+/// The project (in the project file format) shared in the URL fragment, with or without the
+/// leading `#`. Returns `None` if the fragment doesn't hold a project.
+pub fn decode_share_fragment(fragment: &str) -> Option<Result<Vec<u8>, String>> {
+    let encoded = fragment
+        .strip_prefix('#')
+        .unwrap_or(fragment)
+        .strip_prefix(FRAGMENT_PREFIX)?;
+    Some(
+        URL_SAFE_NO_PAD
+            .decode(encoded)
+            .map_err(|e| format!("The link is damaged: {}", e))
+            .and_then(|compressed| {
+                miniz_oxide::inflate::decompress_to_vec_with_limit(
+                    &compressed,
+                    MAX_SHARED_PROJECT_SIZE,
+                )
+                .map_err(|e| match e.status {
+                    TINFLStatus::HasMoreOutput => format!(
+                        "The project in the link is larger than {} kB",
+                        MAX_SHARED_PROJECT_SIZE / 1024
+                    ),
+                    status => format!("The link is damaged: {:?}", status),
+                })
+            }),
+    )
+}
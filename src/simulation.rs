@@ -0,0 +1,1162 @@
+//! Copyright 2024 - The Open-Agriculture Developers
+//! SPDX-License-Identifier: GPL-3.0-or-later
+//! Authors: Daan Steenbergen
+
+use ag_iso_stack::object_pool::object::*;
+use ag_iso_stack::object_pool::object_attributes::*;
+use ag_iso_stack::object_pool::{NullableObjectId, ObjectId, ObjectPool, ObjectType};
+use eframe::egui;
+use serde::{Deserialize, Serialize};
+
+use crate::{DemoAction, DemoScript, EditorProject, PoolSnapshot};
+use std::sync::Arc;
+use std::time::Duration;
+use web_time::Instant;
+
+/// Maximum amount of lines kept in the simulation log
+const MAX_LOG_LINES: usize = 200;
+
+/// Maximum depth of macros triggering other macros, protects against endless loops
+const MAX_MACRO_DEPTH: usize = 8;
+
+/// Highest value of an analogue auxiliary input
+const AUX_ANALOGUE_MAX: u32 = 0xFAFF;
+
+/// Time in seconds a simulated momentary button press is held
+const AUX_PRESS_DURATION: f32 = 0.5;
+
+/// Time in seconds of a simulated analogue sweep in one direction
+const AUX_SWEEP_DURATION: f32 = 2.0;
+
+/// Assignment of an auxiliary input to an auxiliary function, as an operator would make it on
+/// the VT. The variable is driven by the function in the simulation, standing in for the
+/// implement application that would react to the function.
+#[derive(Serialize, Deserialize, Clone, PartialEq)]
+pub struct AuxAssignment {
+    pub function: u16,
+    pub input: Option<u16>,
+    pub variable: Option<u16>,
+}
+
+/// Input actuation being replayed on the variable of an auxiliary function
+enum AuxActuation {
+    Press {
+        variable: ObjectId,
+        remaining: f32,
+    },
+    Sweep {
+        variable: ObjectId,
+        elapsed: f32,
+        return_to_zero: bool,
+    },
+}
+
+/// Animation shown in the preview when the simulation changes the active mask.
+/// The VT itself switches instantly, the animation only helps to show the intended flow.
+#[derive(Serialize, Deserialize, Clone, Copy, PartialEq, Eq, Debug)]
+pub enum MaskTransition {
+    None,
+    Crossfade,
+    /// The new mask slides in from the right
+    Slide,
+}
+
+impl MaskTransition {
+    pub const ALL: [MaskTransition; 3] = [
+        MaskTransition::None,
+        MaskTransition::Crossfade,
+        MaskTransition::Slide,
+    ];
+
+    pub fn name(self) -> &'static str {
+        match self {
+            MaskTransition::None => "None",
+            MaskTransition::Crossfade => "Crossfade",
+            MaskTransition::Slide => "Slide",
+        }
+    }
+}
+
+/// How a VT indicates the input object that has focus. Terminals differ in this, so the
+/// designer can preview the style of the terminal the pool is made for.
+#[derive(Serialize, Deserialize, Clone, Copy, PartialEq, Eq, Debug)]
+pub enum FocusStyle {
+    /// Frame around the input
+    Border,
+    /// Line below the input
+    Underline,
+    /// Translucent fill over the input
+    Highlight,
+}
+
+impl FocusStyle {
+    pub const ALL: [FocusStyle; 3] = [
+        FocusStyle::Border,
+        FocusStyle::Underline,
+        FocusStyle::Highlight,
+    ];
+
+    pub fn name(self) -> &'static str {
+        match self {
+            FocusStyle::Border => "Border",
+            FocusStyle::Underline => "Underline",
+            FocusStyle::Highlight => "Highlight",
+        }
+    }
+
+    /// Paint the focus indication of an input shown at `rect`
+    pub fn paint(self, painter: &egui::Painter, rect: egui::Rect, colour: egui::Color32) {
+        match self {
+            FocusStyle::Border => {
+                painter.rect_stroke(
+                    rect,
+                    0.0,
+                    egui::Stroke::new(2.0, colour),
+                    egui::epaint::StrokeKind::Outside,
+                );
+            }
+            FocusStyle::Underline => {
+                painter.line_segment(
+                    [
+                        rect.left_bottom() + egui::vec2(0.0, 2.0),
+                        rect.right_bottom() + egui::vec2(0.0, 2.0),
+                    ],
+                    egui::Stroke::new(2.0, colour),
+                );
+            }
+            FocusStyle::Highlight => {
+                painter.rect_filled(rect, 0.0, colour.gamma_multiply(0.4));
+            }
+        }
+    }
+}
+
+/// A change of the active mask, animated until the transition duration has passed
+struct MaskChange {
+    from: ObjectId,
+    to: ObjectId,
+    at: Instant,
+    /// Time the previous mask was shown
+    previous_shown_for: Duration,
+}
+
+/// A demo script being played, with the step that is done next and the time since the previous
+/// step
+struct DemoPlayback {
+    script: DemoScript,
+    step: usize,
+    elapsed: f32,
+}
+
+/// Entry dialog that is open while the operator edits an input object
+enum InputDialog {
+    Number {
+        object_id: ObjectId,
+        text: String,
+        /// Minimum and maximum of the displayed value
+        min: f64,
+        max: f64,
+        offset: i32,
+        scale: f32,
+        decimals: u8,
+    },
+    String {
+        object_id: ObjectId,
+        text: String,
+        max_length: usize,
+        validation: Option<(ValidationType, String)>,
+    },
+    List {
+        object_id: ObjectId,
+        items: Vec<NullableObjectId>,
+        selected: u8,
+    },
+}
+
+/// Value entered by the operator in an entry dialog
+enum EnteredValue {
+    Number(u32),
+    String(String),
+}
+
+/// Simulates the behaviour of a VT for the operator inputs of a pool.
+/// The simulation works on a snapshot of the pool, which is only copied once the simulation
+/// changes it, so the design is never changed by it.
+pub struct Simulation {
+    pool: PoolSnapshot,
+    input_dialog: Option<InputDialog>,
+    /// Input object that has focus on the simulated VT
+    focused: Option<ObjectId>,
+    log: Vec<String>,
+    aux_actuations: Vec<AuxActuation>,
+    transition: MaskTransition,
+    transition_duration: Duration,
+    /// Time the simulation started or the active mask last changed
+    mask_shown_since: Instant,
+    last_mask_change: Option<MaskChange>,
+    demo: Option<DemoPlayback>,
+}
+
+impl Simulation {
+    pub fn new(pool: PoolSnapshot) -> Self {
+        Simulation {
+            pool,
+            input_dialog: None,
+            focused: None,
+            log: Vec::new(),
+            aux_actuations: Vec::new(),
+            transition: MaskTransition::None,
+            transition_duration: Duration::ZERO,
+            mask_shown_since: Instant::now(),
+            last_mask_change: None,
+            demo: None,
+        }
+    }
+
+    /// Set the animation shown when the active mask changes
+    pub fn set_mask_transition(&mut self, transition: MaskTransition, duration: Duration) {
+        self.transition = transition;
+        self.transition_duration = duration;
+    }
+
+    /// The masks and progress from 0 to 1 of the active mask transition that is being animated
+    pub fn mask_transition_progress(&self) -> Option<(MaskTransition, ObjectId, ObjectId, f32)> {
+        let change = self.last_mask_change.as_ref()?;
+        let elapsed = change.at.elapsed();
+        if self.transition == MaskTransition::None || elapsed >= self.transition_duration {
+            return None;
+        }
+        let progress = elapsed.as_secs_f32() / self.transition_duration.as_secs_f32();
+        Some((self.transition, change.from, change.to, progress))
+    }
+
+    /// Describe the last change of the active mask and how long the previous mask was shown
+    pub fn mask_timing(&self) -> Option<String> {
+        self.last_mask_change.as_ref().map(|change| {
+            format!(
+                "Mask {} \u{2192} {} after {:.1} s",
+                change.from.value(),
+                change.to.value(),
+                change.previous_shown_for.as_secs_f32()
+            )
+        })
+    }
+
+    /// Get the pool as it is currently shown by the simulated VT
+    pub fn get_pool(&self) -> &ObjectPool {
+        &self.pool
+    }
+
+    /// Get the input object that has focus, if it is still shown on the active mask
+    pub fn get_focused(&self) -> Option<ObjectId> {
+        self.focused
+            .filter(|id| self.focusable_inputs().contains(id))
+    }
+
+    /// Move the focus to the next (or previous) enabled input of the active mask, like the
+    /// navigation keys of a VT
+    pub fn move_focus(&mut self, forward: bool) {
+        let inputs = self.focusable_inputs();
+        if inputs.is_empty() {
+            return;
+        }
+        let next = match self
+            .get_focused()
+            .and_then(|id| inputs.iter().position(|i| *i == id))
+        {
+            Some(idx) if forward => (idx + 1) % inputs.len(),
+            Some(idx) => (idx + inputs.len() - 1) % inputs.len(),
+            None if forward => 0,
+            None => inputs.len() - 1,
+        };
+        self.focused = Some(inputs[next]);
+    }
+
+    /// The enabled input objects of the active mask, in the order they are shown
+    fn focusable_inputs(&self) -> Vec<ObjectId> {
+        let mut inputs = Vec::new();
+        if let Some(mask) = self
+            .pool
+            .working_set_object()
+            .and_then(|working_set| self.pool.object_by_id(working_set.active_mask))
+        {
+            self.collect_focusable_inputs(mask, &mut inputs);
+        }
+        inputs
+    }
+
+    fn collect_focusable_inputs(&self, object: &Object, inputs: &mut Vec<ObjectId>) {
+        let object_refs = match object {
+            Object::DataMask(o) => &o.object_refs,
+            Object::AlarmMask(o) => &o.object_refs,
+            Object::Container(o) if !o.hidden => &o.object_refs,
+            Object::InputNumber(o) if o.options2.enabled => return inputs.push(object.id()),
+            Object::InputString(o) if o.enabled => return inputs.push(object.id()),
+            Object::InputList(o) if o.options.enabled => return inputs.push(object.id()),
+            Object::InputBoolean(o) if o.enabled => return inputs.push(object.id()),
+            _ => return,
+        };
+        for object_ref in object_refs {
+            if let Some(child) = self.pool.object_by_id(object_ref.id) {
+                if !inputs.contains(&child.id()) {
+                    self.collect_focusable_inputs(child, inputs);
+                }
+            }
+        }
+    }
+
+    /// Get the log of simulated events, oldest first
+    pub fn get_log(&self) -> &[String] {
+        &self.log
+    }
+
+    fn log(&mut self, message: String) {
+        self.log.push(message);
+        if self.log.len() > MAX_LOG_LINES {
+            self.log.remove(0);
+        }
+    }
+
+    /// Handle the operator clicking on an object, opening the entry dialog for input objects
+    pub fn activate_object(&mut self, object_id: ObjectId) {
+        if self.input_dialog.is_some() {
+            return;
+        }
+
+        let dialog = match self.pool.object_by_id(object_id) {
+            Some(Object::InputNumber(o)) if o.options2.enabled => {
+                let raw = self.number_value(o.variable_reference).unwrap_or(o.value);
+                let to_displayed = |raw: u32| (raw as f64 + o.offset as f64) * o.scale as f64;
+                let (min, max) = {
+                    let (a, b) = (to_displayed(o.min_value), to_displayed(o.max_value));
+                    (a.min(b), a.max(b))
+                };
+                Some(InputDialog::Number {
+                    object_id,
+                    text: format!("{:.*}", o.nr_of_decimals as usize, to_displayed(raw)),
+                    min,
+                    max,
+                    offset: o.offset,
+                    scale: o.scale,
+                    decimals: o.nr_of_decimals,
+                })
+            }
+            Some(Object::InputString(o)) if o.enabled => {
+                let value = self
+                    .string_value(o.variable_reference)
+                    .unwrap_or_else(|| o.value.clone());
+                let validation = match o.input_attributes.into() {
+                    Some(id) => match self.pool.object_by_id(id) {
+                        Some(Object::InputAttributes(a)) => {
+                            Some((a.validation_type, a.validation_string.clone()))
+                        }
+                        _ => None,
+                    },
+                    None => None,
+                };
+                Some(InputDialog::String {
+                    object_id,
+                    max_length: value.chars().count(),
+                    text: value.trim_end().to_string(),
+                    validation,
+                })
+            }
+            Some(Object::InputList(o)) if o.options.enabled => Some(InputDialog::List {
+                object_id,
+                items: o.list_items.clone(),
+                selected: self
+                    .number_value(o.variable_reference)
+                    .map_or(o.value, |v| v as u8),
+            }),
+            Some(Object::InputBoolean(o)) if o.enabled => {
+                // A VT toggles a boolean input directly without an entry dialog
+                let value = self
+                    .number_value(o.variable_reference)
+                    .map_or(o.value, |v| v != 0);
+                self.set_number_value(object_id, !value as u32);
+                None
+            }
+            Some(
+                Object::InputNumber(_)
+                | Object::InputString(_)
+                | Object::InputList(_)
+                | Object::InputBoolean(_),
+            ) => {
+                self.log(format!("Input {} is disabled", object_id.value()));
+                None
+            }
+            _ => None,
+        };
+
+        if self.focusable_inputs().contains(&object_id) {
+            self.focused = Some(object_id);
+        }
+        if let Some(dialog) = dialog {
+            self.fire_event(object_id, Event::OnInputFieldSelection, 0);
+            self.input_dialog = Some(dialog);
+        }
+    }
+
+    fn number_value(&self, variable: NullableObjectId) -> Option<u32> {
+        match self.pool.object_by_id(variable.0?) {
+            Some(Object::NumberVariable(v)) => Some(v.value),
+            _ => None,
+        }
+    }
+
+    fn string_value(&self, variable: NullableObjectId) -> Option<String> {
+        match self.pool.object_by_id(variable.0?) {
+            Some(Object::StringVariable(v)) => Some(v.value.clone()),
+            _ => None,
+        }
+    }
+
+    /// Write the entered value of a numeric input (number, list or boolean) to its variable
+    /// or to the object itself when it has no variable
+    fn set_number_value(&mut self, object_id: ObjectId, value: u32) {
+        let variable = match self.pool.object_by_id(object_id) {
+            Some(Object::InputNumber(o)) => o.variable_reference,
+            Some(Object::InputList(o)) => o.variable_reference,
+            Some(Object::InputBoolean(o)) => o.variable_reference,
+            _ => return,
+        };
+        let changed = match variable.0 {
+            Some(variable_id) => {
+                match Arc::make_mut(&mut self.pool).object_mut_by_id(variable_id) {
+                    Some(Object::NumberVariable(v)) => {
+                        std::mem::replace(&mut v.value, value) != value
+                    }
+                    _ => false,
+                }
+            }
+            None => match Arc::make_mut(&mut self.pool).object_mut_by_id(object_id) {
+                Some(Object::InputNumber(o)) => std::mem::replace(&mut o.value, value) != value,
+                Some(Object::InputList(o)) => {
+                    std::mem::replace(&mut o.value, value as u8) != value as u8
+                }
+                Some(Object::InputBoolean(o)) => {
+                    std::mem::replace(&mut o.value, value != 0) != (value != 0)
+                }
+                _ => false,
+            },
+        };
+
+        self.log(format!("Input {} set to {}", object_id.value(), value));
+        self.fire_event(object_id, Event::OnEntryOfValue, 0);
+        if changed {
+            self.fire_event(object_id, Event::OnEntryOfNewValue, 0);
+            self.fire_event(object_id, Event::OnChangeValue, 0);
+        }
+    }
+
+    /// Write the entered value of a string input to its variable or to the object itself
+    fn set_string_value(&mut self, object_id: ObjectId, value: String) {
+        let variable = match self.pool.object_by_id(object_id) {
+            Some(Object::InputString(o)) => o.variable_reference,
+            _ => return,
+        };
+        let changed = match variable.0 {
+            Some(variable_id) => {
+                match Arc::make_mut(&mut self.pool).object_mut_by_id(variable_id) {
+                    Some(Object::StringVariable(v)) => {
+                        std::mem::replace(&mut v.value, value.clone()) != value
+                    }
+                    _ => false,
+                }
+            }
+            None => match Arc::make_mut(&mut self.pool).object_mut_by_id(object_id) {
+                Some(Object::InputString(o)) => {
+                    std::mem::replace(&mut o.value, value.clone()) != value
+                }
+                _ => false,
+            },
+        };
+
+        self.log(format!(
+            "Input {} set to \"{}\"",
+            object_id.value(),
+            value.trim_end()
+        ));
+        self.fire_event(object_id, Event::OnEntryOfValue, 0);
+        if changed {
+            self.fire_event(object_id, Event::OnEntryOfNewValue, 0);
+            self.fire_event(object_id, Event::OnChangeValue, 0);
+        }
+    }
+
+    /// Run the macros an object has linked to an event
+    fn fire_event(&mut self, object_id: ObjectId, event: Event, depth: usize) {
+        let macro_refs: Vec<MacroRef> = match self.pool.object_by_id(object_id) {
+            Some(Object::Key(o)) => o.macro_refs.clone(),
+            Some(Object::Button(o)) => o.macro_refs.clone(),
+            Some(Object::InputNumber(o)) => o.macro_refs.clone(),
+            Some(Object::InputString(o)) => o.macro_refs.clone(),
+            Some(Object::InputList(o)) => o.macro_refs.clone(),
+            Some(Object::InputBoolean(o)) => o.macro_refs.clone(),
+            _ => return,
+        };
+
+        for macro_ref in macro_refs.iter().filter(|m| m.event_id == event) {
+            self.log(format!(
+                "{:?} of object {} runs macro {}",
+                event,
+                object_id.value(),
+                macro_ref.macro_id
+            ));
+            self.run_macro(macro_ref.macro_id, depth + 1);
+        }
+    }
+
+    /// Execute the commands of a macro on the simulated pool.
+    /// Only commands that change values are simulated, the others are logged.
+    fn run_macro(&mut self, macro_id: u8, depth: usize) {
+        if depth > MAX_MACRO_DEPTH {
+            self.log(format!(
+                "Macro {} not run: macros are nested too deep",
+                macro_id
+            ));
+            return;
+        }
+        let commands = match ObjectId::new(macro_id as u16)
+            .ok()
+            .and_then(|id| self.pool.object_by_id(id))
+        {
+            Some(Object::Macro(m)) => m.commands.clone(),
+            _ => {
+                self.log(format!("Missing macro object {}", macro_id));
+                return;
+            }
+        };
+
+        let id_at = |bytes: &[u8], idx: usize| {
+            ObjectId::new(u16::from_le_bytes([bytes[idx], bytes[idx + 1]])).ok()
+        };
+
+        let mut remaining = commands.as_slice();
+        while let Some(&code) = remaining.first() {
+            match code {
+                // Change Numeric Value
+                0xA8 if remaining.len() >= 8 => {
+                    let value = u32::from_le_bytes([
+                        remaining[4],
+                        remaining[5],
+                        remaining[6],
+                        remaining[7],
+                    ]);
+                    if let Some(target) = id_at(remaining, 1) {
+                        self.change_numeric_value(target, value, depth);
+                    }
+                    remaining = &remaining[8..];
+                }
+                // Change String Value
+                0xB3 if remaining.len() >= 5 => {
+                    let length = u16::from_le_bytes([remaining[3], remaining[4]]) as usize;
+                    if remaining.len() < 5 + length {
+                        break;
+                    }
+                    let value = String::from_utf8_lossy(&remaining[5..5 + length]).to_string();
+                    if let Some(target) = id_at(remaining, 1) {
+                        self.change_string_value(target, value, depth);
+                    }
+                    remaining = &remaining[5 + length..];
+                }
+                // Change Active Mask
+                0xAD if remaining.len() >= 8 => {
+                    if let (Some(working_set), Some(mask)) =
+                        (id_at(remaining, 1), id_at(remaining, 3))
+                    {
+                        self.change_active_mask(working_set, mask);
+                    }
+                    remaining = &remaining[8..];
+                }
+                _ => {
+                    self.log(format!(
+                        "Macro {}: command 0x{:02X} is not simulated, remaining commands skipped",
+                        macro_id, code
+                    ));
+                    break;
+                }
+            }
+        }
+    }
+
+    fn change_active_mask(&mut self, working_set: ObjectId, mask: ObjectId) {
+        if let Some(Object::WorkingSet(ws)) =
+            Arc::make_mut(&mut self.pool).object_mut_by_id(working_set)
+        {
+            let from = std::mem::replace(&mut ws.active_mask, mask);
+            self.log(format!("Active mask changed to {}", mask.value()));
+            if from != mask {
+                self.last_mask_change = Some(MaskChange {
+                    from,
+                    to: mask,
+                    at: Instant::now(),
+                    previous_shown_for: self.mask_shown_since.elapsed(),
+                });
+                self.mask_shown_since = Instant::now();
+            }
+        }
+    }
+
+    fn change_numeric_value(&mut self, target: ObjectId, value: u32, depth: usize) {
+        match Arc::make_mut(&mut self.pool).object_mut_by_id(target) {
+            Some(Object::NumberVariable(v)) => v.value = value,
+            Some(Object::InputNumber(o)) => o.value = value,
+            Some(Object::OutputNumber(o)) => o.value = value,
+            Some(Object::InputList(o)) => o.value = value as u8,
+            Some(Object::InputBoolean(o)) => o.value = value != 0,
+            _ => {
+                self.log(format!(
+                    "Change Numeric Value: object {} not supported",
+                    target.value()
+                ));
+                return;
+            }
+        }
+        self.log(format!("Object {} changed to {}", target.value(), value));
+        self.fire_event(target, Event::OnChangeValue, depth);
+    }
+
+    fn change_string_value(&mut self, target: ObjectId, value: String, depth: usize) {
+        match Arc::make_mut(&mut self.pool).object_mut_by_id(target) {
+            Some(Object::StringVariable(v)) => v.value = value.clone(),
+            Some(Object::InputString(o)) => o.value = value.clone(),
+            Some(Object::OutputString(o)) => o.value = value.clone(),
+            _ => {
+                self.log(format!(
+                    "Change String Value: object {} not supported",
+                    target.value()
+                ));
+                return;
+            }
+        }
+        self.log(format!(
+            "Object {} changed to \"{}\"",
+            target.value(),
+            value
+        ));
+        self.fire_event(target, Event::OnChangeValue, depth);
+    }
+
+    /// Set a number variable without triggering any events
+    fn set_variable_value(&mut self, variable: ObjectId, value: u32) {
+        if let Some(Object::NumberVariable(v)) =
+            Arc::make_mut(&mut self.pool).object_mut_by_id(variable)
+        {
+            v.value = value;
+        }
+    }
+
+    /// Replay the actuation of the input assigned to an auxiliary function
+    fn actuate_aux_function(&mut self, function: ObjectId, variable: ObjectId, sweep: bool) {
+        let function_type = match self.pool.object_by_id(function) {
+            Some(Object::AuxiliaryFunctionType2(f)) => f.function_attributes.function_type,
+            _ => return,
+        };
+
+        // Only one actuation per variable at a time
+        self.aux_actuations.retain(|actuation| match actuation {
+            AuxActuation::Press { variable: v, .. } | AuxActuation::Sweep { variable: v, .. } => {
+                *v != variable
+            }
+        });
+
+        if sweep {
+            let return_to_zero = !matches!(
+                function_type,
+                AuxiliaryFunctionType::AnalogueMaintains
+                    | AuxiliaryFunctionType::QuadratureAnalogueMaintains
+                    | AuxiliaryFunctionType::CombinedAnalogueMaintainsWithLatch
+            );
+            self.log(format!("Auxiliary function {} swept", function.value()));
+            self.aux_actuations.push(AuxActuation::Sweep {
+                variable,
+                elapsed: 0.0,
+                return_to_zero,
+            });
+        } else if matches!(
+            function_type,
+            AuxiliaryFunctionType::BooleanLatching | AuxiliaryFunctionType::DualBooleanLatching
+        ) {
+            let value = self
+                .number_value(NullableObjectId(Some(variable)))
+                .unwrap_or(0);
+            let toggled = if value == 0 { 1 } else { 0 };
+            self.log(format!(
+                "Auxiliary function {} latched to {}",
+                function.value(),
+                toggled
+            ));
+            self.set_variable_value(variable, toggled);
+        } else {
+            self.log(format!("Auxiliary function {} pressed", function.value()));
+            self.set_variable_value(variable, 1);
+            self.aux_actuations.push(AuxActuation::Press {
+                variable,
+                remaining: AUX_PRESS_DURATION,
+            });
+        }
+    }
+
+    /// Start playing a demo script from its first step
+    pub fn play_demo(&mut self, script: DemoScript) {
+        self.log("Demo started".to_string());
+        self.demo = Some(DemoPlayback {
+            script,
+            step: 0,
+            elapsed: 0.0,
+        });
+    }
+
+    pub fn stop_demo(&mut self) {
+        if self.demo.take().is_some() {
+            self.log("Demo stopped".to_string());
+        }
+    }
+
+    /// The step of the demo script that is done next, if a demo is playing
+    pub fn get_demo_step(&self) -> Option<usize> {
+        self.demo.as_ref().map(|demo| demo.step)
+    }
+
+    /// Advance the playing demo script by `dt` seconds, doing the steps whose delay has passed
+    fn advance_demo(&mut self, dt: f32) {
+        let Some(demo) = &mut self.demo else {
+            return;
+        };
+        demo.elapsed += dt;
+        let mut actions = Vec::new();
+        let mut finished = false;
+        while let Some(step) = demo.script.steps.get(demo.step) {
+            let delay = step.delay_ms as f32 / 1000.0;
+            if demo.elapsed < delay {
+                break;
+            }
+            demo.elapsed -= delay;
+            actions.push(step.action.clone());
+            demo.step += 1;
+            if demo.step == demo.script.steps.len() {
+                // A loop without any delay would never end the frame
+                let total_delay: u32 = demo.script.steps.iter().map(|s| s.delay_ms).sum();
+                if demo.script.looping && total_delay > 0 {
+                    demo.step = 0;
+                } else {
+                    finished = true;
+                    break;
+                }
+            }
+        }
+        for action in actions {
+            self.run_demo_action(&action);
+        }
+        if finished
+            || self
+                .demo
+                .as_ref()
+                .is_some_and(|d| d.script.steps.is_empty())
+        {
+            self.demo = None;
+            self.log("Demo finished".to_string());
+        }
+    }
+
+    /// Do a step of a demo script on the simulated VT
+    fn run_demo_action(&mut self, action: &DemoAction) {
+        let Ok(target) = ObjectId::new(action.target()) else {
+            self.log(format!("{}: no object chosen", action.name()));
+            return;
+        };
+        match action {
+            DemoAction::SetNumber { value, .. } => self.change_numeric_value(target, *value, 0),
+            DemoAction::SetString { value, .. } => {
+                self.change_string_value(target, value.clone(), 0)
+            }
+            DemoAction::PressKey { .. } => {
+                self.log(format!("Key {} pressed", target.value()));
+                self.fire_event(target, Event::OnKeyPress, 0);
+                self.fire_event(target, Event::OnKeyRelease, 0);
+            }
+            DemoAction::ActivateMask { .. } => {
+                match self.pool.working_set_object().map(|ws| ws.id) {
+                    Some(working_set) => self.change_active_mask(working_set, target),
+                    None => self.log("Activate mask: no working set".to_string()),
+                }
+            }
+        }
+    }
+
+    /// Render the editor of the demo script of the project, with buttons to play it.
+    /// The script is stored in the project, so it survives restarting the simulation.
+    pub fn render_demo_control(&mut self, ui: &mut egui::Ui, project: &EditorProject) {
+        let mut script = project.get_demo_script();
+        ui.horizontal(|ui| {
+            if self.demo.is_some() {
+                if ui.button("\u{23F9} Stop").clicked() {
+                    self.stop_demo();
+                }
+            } else if ui
+                .add_enabled(!script.steps.is_empty(), egui::Button::new("\u{25B6} Play"))
+                .clicked()
+            {
+                self.play_demo(script.clone());
+            }
+        });
+        if script.render_editor(ui, project, self.get_demo_step()) {
+            project.set_demo_script(script);
+        }
+    }
+
+    /// Advance the replayed auxiliary input actuations and the playing demo script by `dt`
+    /// seconds.
+    /// Returns true while actuations, a demo or a mask transition are in progress and the preview
+    /// should keep repainting.
+    pub fn update(&mut self, dt: f32) -> bool {
+        self.advance_demo(dt);
+        let mut values = Vec::new();
+        self.aux_actuations.retain_mut(|actuation| match actuation {
+            AuxActuation::Press {
+                variable,
+                remaining,
+            } => {
+                *remaining -= dt;
+                if *remaining <= 0.0 {
+                    values.push((*variable, 0));
+                    false
+                } else {
+                    true
+                }
+            }
+            AuxActuation::Sweep {
+                variable,
+                elapsed,
+                return_to_zero,
+            } => {
+                *elapsed += dt;
+                let progress = *elapsed / AUX_SWEEP_DURATION;
+                let (fraction, done) = if *return_to_zero {
+                    ((1.0 - (progress - 1.0).abs()).max(0.0), progress >= 2.0)
+                } else {
+                    (progress.min(1.0), progress >= 1.0)
+                };
+                values.push((*variable, (fraction * AUX_ANALOGUE_MAX as f32) as u32));
+                !done
+            }
+        });
+
+        for (variable, value) in values {
+            self.set_variable_value(variable, value);
+        }
+        !self.aux_actuations.is_empty()
+            || self.demo.is_some()
+            || self.mask_transition_progress().is_some()
+    }
+
+    /// Render the panel to assign auxiliary inputs to functions and replay input actuations.
+    /// The assignments are stored in the project, so they survive restarting the simulation.
+    pub fn render_aux_control(&mut self, ui: &mut egui::Ui, project: &EditorProject) {
+        let functions = project.get_objects_by_type(ObjectType::AuxiliaryFunctionType2);
+        if functions.is_empty() {
+            ui.weak("No auxiliary functions in the pool");
+            return;
+        }
+        let inputs = project.get_objects_by_type(ObjectType::AuxiliaryInputType2);
+        let variables = project.get_objects_by_type(ObjectType::NumberVariable);
+
+        let mut assignments = project.get_aux_assignments();
+        let mut changed = false;
+        let mut actuation = None;
+
+        egui::Grid::new("aux_assignments")
+            .striped(true)
+            .num_columns(4)
+            .show(ui, |ui| {
+                ui.strong("Function");
+                ui.strong("Input");
+                ui.strong("Variable");
+                ui.end_row();
+
+                for function in &functions {
+                    let function_id = function.id().value();
+                    let idx = match assignments.iter().position(|a| a.function == function_id) {
+                        Some(idx) => idx,
+                        None => {
+                            assignments.push(AuxAssignment {
+                                function: function_id,
+                                input: None,
+                                variable: None,
+                            });
+                            assignments.len() - 1
+                        }
+                    };
+                    let assignment = &mut assignments[idx];
+                    let function_type = match function {
+                        Object::AuxiliaryFunctionType2(f) => {
+                            Some(f.function_attributes.function_type)
+                        }
+                        _ => None,
+                    };
+
+                    ui.label(format!(
+                        "{}: {}",
+                        function_id,
+                        project.get_object_info(function).get_name(function)
+                    ));
+
+                    let before = assignment.clone();
+                    egui::ComboBox::from_id_salt(("aux_input", function_id))
+                        .selected_text(
+                            assignment
+                                .input
+                                .map_or("Unassigned".to_string(), |id| id.to_string()),
+                        )
+                        .show_ui(ui, |ui| {
+                            ui.selectable_value(&mut assignment.input, None, "Unassigned");
+                            for input in &inputs {
+                                let matching = match input {
+                                    Object::AuxiliaryInputType2(i) => {
+                                        Some(i.function_attributes.function_type) == function_type
+                                    }
+                                    _ => false,
+                                };
+                                let mut label = format!(
+                                    "{}: {}",
+                                    input.id().value(),
+                                    project.get_object_info(input).get_name(input)
+                                );
+                                if !matching {
+                                    label.push_str(" \u{26A0} (other function type)");
+                                }
+                                ui.selectable_value(
+                                    &mut assignment.input,
+                                    Some(input.id().value()),
+                                    label,
+                                );
+                            }
+                        });
+                    egui::ComboBox::from_id_salt(("aux_variable", function_id))
+                        .selected_text(
+                            assignment
+                                .variable
+                                .map_or("None".to_string(), |id| id.to_string()),
+                        )
+                        .show_ui(ui, |ui| {
+                            ui.selectable_value(&mut assignment.variable, None, "None");
+                            for variable in &variables {
+                                ui.selectable_value(
+                                    &mut assignment.variable,
+                                    Some(variable.id().value()),
+                                    format!(
+                                        "{}: {}",
+                                        variable.id().value(),
+                                        project.get_object_info(variable).get_name(variable)
+                                    ),
+                                );
+                            }
+                        });
+                    changed |= *assignment != before;
+
+                    ui.horizontal(|ui| {
+                        let variable = assignment
+                            .variable
+                            .filter(|_| assignment.input.is_some())
+                            .and_then(|id| ObjectId::new(id).ok());
+                        if ui
+                            .add_enabled(variable.is_some(), egui::Button::new("Press"))
+                            .on_hover_text("Simulate pressing the assigned input")
+                            .clicked()
+                        {
+                            actuation = variable.map(|v| (function.id(), v, false));
+                        }
+                        if ui
+                            .add_enabled(variable.is_some(), egui::Button::new("Sweep"))
+                            .on_hover_text("Simulate moving the assigned input over its full range")
+                            .clicked()
+                        {
+                            actuation = variable.map(|v| (function.id(), v, true));
+                        }
+                    });
+                    ui.end_row();
+                }
+            });
+
+        if changed {
+            project.set_aux_assignments(assignments);
+        }
+        if let Some((function, variable, sweep)) = actuation {
+            self.actuate_aux_function(function, variable, sweep);
+        }
+    }
+
+    /// Show the entry dialog of the input object that is being edited, if any
+    pub fn show_input_dialog(&mut self, ctx: &egui::Context) {
+        let Some(dialog) = &mut self.input_dialog else {
+            return;
+        };
+
+        let mut result = None;
+        let mut cancelled = false;
+
+        let modal = egui::Modal::new(egui::Id::new("simulation_input_dialog")).show(ctx, |ui| {
+            match dialog {
+                InputDialog::Number {
+                    object_id,
+                    text,
+                    min,
+                    max,
+                    offset,
+                    scale,
+                    decimals,
+                } => {
+                    ui.heading(format!("Input number {}", object_id.value()));
+                    ui.label(format!(
+                        "Range: {:.*} to {:.*}",
+                        *decimals as usize, min, *decimals as usize, max
+                    ));
+                    ui.add(egui::TextEdit::singleline(text).desired_width(160.0));
+
+                    egui::Grid::new("simulation_keypad").show(ui, |ui| {
+                        for row in [
+                            ["7", "8", "9"],
+                            ["4", "5", "6"],
+                            ["1", "2", "3"],
+                            ["-", "0", "."],
+                        ] {
+                            for key in row {
+                                if ui.add_sized([48.0, 32.0], egui::Button::new(key)).clicked() {
+                                    text.push_str(key);
+                                }
+                            }
+                            ui.end_row();
+                        }
+                    });
+                    ui.horizontal(|ui| {
+                        if ui.button("Back").clicked() {
+                            text.pop();
+                        }
+                        if ui.button("Clear").clicked() {
+                            text.clear();
+                        }
+                    });
+
+                    let parsed = text.trim().replace(',', ".").parse::<f64>();
+                    let error = match parsed {
+                        Ok(_) if *scale == 0.0 => Some("The scale of the object is 0"),
+                        Ok(value) if value < *min || value > *max => Some("Value is out of range"),
+                        Ok(_) => None,
+                        Err(_) => Some("Not a valid number"),
+                    };
+                    if let Some(error) = error {
+                        ui.colored_label(egui::Color32::RED, error);
+                    }
+
+                    ui.horizontal(|ui| {
+                        if ui
+                            .add_enabled(error.is_none(), egui::Button::new("Enter"))
+                            .clicked()
+                        {
+                            if let Ok(value) = parsed {
+                                let raw = (value / *scale as f64 - *offset as f64).round();
+                                result =
+                                    Some((*object_id, EnteredValue::Number(raw.max(0.0) as u32)));
+                            }
+                        }
+                        if ui.button("Cancel").clicked() {
+                            cancelled = true;
+                        }
+                    });
+                }
+                InputDialog::String {
+                    object_id,
+                    text,
+                    max_length,
+                    validation,
+                } => {
+                    ui.heading(format!("Input string {}", object_id.value()));
+                    ui.label(format!("Maximum length: {}", max_length));
+                    let response = ui.add(
+                        egui::TextEdit::singleline(text)
+                            .char_limit(*max_length)
+                            .desired_width(240.0),
+                    );
+                    if response.changed() {
+                        if let Some((validation_type, characters)) = validation {
+                            text.retain(|c| {
+                                characters.contains(c)
+                                    == (*validation_type == ValidationType::ValidCharacters)
+                            });
+                        }
+                    }
+                    if let Some((validation_type, characters)) = validation {
+                        let kind = if *validation_type == ValidationType::ValidCharacters {
+                            "Allowed"
+                        } else {
+                            "Not allowed"
+                        };
+                        ui.weak(format!("{} characters: {}", kind, characters));
+                    }
+
+                    ui.horizontal(|ui| {
+                        if ui.button("Enter").clicked() {
+                            // A VT pads the value to the length of the string
+                            let padding = max_length.saturating_sub(text.chars().count());
+                            result = Some((
+                                *object_id,
+                                EnteredValue::String(format!("{}{}", text, " ".repeat(padding))),
+                            ));
+                        }
+                        if ui.button("Cancel").clicked() {
+                            cancelled = true;
+                        }
+                    });
+                }
+                InputDialog::List {
+                    object_id,
+                    items,
+                    selected,
+                } => {
+                    ui.heading(format!("Input list {}", object_id.value()));
+                    for (idx, item) in items.iter().enumerate() {
+                        let label = match item.0.and_then(|id| self.pool.object_by_id(id)) {
+                            Some(obj) => {
+                                format!("{}: {:?} {}", idx, obj.object_type(), obj.id().value())
+                            }
+                            None => format!("{}: (empty)", idx),
+                        };
+                        ui.selectable_value(selected, idx as u8, label);
+                    }
+                    ui.horizontal(|ui| {
+                        if ui.button("Enter").clicked() {
+                            result = Some((*object_id, EnteredValue::Number(*selected as u32)));
+                        }
+                        if ui.button("Cancel").clicked() {
+                            cancelled = true;
+                        }
+                    });
+                }
+            }
+        });
+
+        if modal.should_close() {
+            cancelled = true;
+        }
+
+        if let Some((object_id, value)) = result {
+            self.input_dialog = None;
+            match value {
+                EnteredValue::Number(number) => self.set_number_value(object_id, number),
+                EnteredValue::String(string) => self.set_string_value(object_id, string),
+            }
+            self.fire_event(object_id, Event::OnInputFieldDeselection, 0);
+        } else if cancelled {
+            if let Some(object_id) = self.input_dialog.take().map(|dialog| match dialog {
+                InputDialog::Number { object_id, .. }
+                | InputDialog::String { object_id, .. }
+                | InputDialog::List { object_id, .. } => object_id,
+            }) {
+                self.log(format!("Input {} cancelled", object_id.value()));
+                self.fire_event(object_id, Event::OnESC, 0);
+                self.fire_event(object_id, Event::OnInputFieldDeselection, 0);
+            }
+        }
+    }
+}
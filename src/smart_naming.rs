@@ -2,7 +2,8 @@
 //! SPDX-License-Identifier: GPL-3.0-or-later
 //! Authors: Daan Steenbergen
 
-use ag_iso_stack::object_pool::{object::Object, ObjectPool, ObjectType};
+use ag_iso_stack::object_pool::{object::Object, ObjectId, ObjectPool, ObjectType};
+use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
 
 /// ISOBUS maximum object count (16-bit IDs)
@@ -63,10 +64,196 @@ pub fn get_object_type_name(object_type: ObjectType) -> &'static str {
     }
 }
 
+/// How the objects of a type are numbered
+#[derive(Serialize, Deserialize, Clone, Copy, PartialEq, Eq, Debug)]
+pub enum NumberingStyle {
+    /// The first object has no number, e.g. "Key", "Key 2"
+    OmitFirst,
+    /// Every object has a number, e.g. "Key 1", "Key 2"
+    Always,
+}
+
+impl NumberingStyle {
+    pub const ALL: [NumberingStyle; 2] = [NumberingStyle::OmitFirst, NumberingStyle::Always];
+
+    pub fn name(self) -> &'static str {
+        match self {
+            NumberingStyle::OmitFirst => "From the second object",
+            NumberingStyle::Always => "Every object",
+        }
+    }
+}
+
+/// How the words of a generated name are written
+#[derive(Serialize, Deserialize, Clone, Copy, PartialEq, Eq, Debug)]
+pub enum CaseConvention {
+    /// Words as they are, separated by spaces, e.g. "Data Mask 2"
+    AsIs,
+    /// e.g. "DATA_MASK_2"
+    UpperSnake,
+    /// e.g. "data_mask_2"
+    LowerSnake,
+    /// e.g. "DataMask2"
+    Pascal,
+}
+
+impl CaseConvention {
+    pub const ALL: [CaseConvention; 4] = [
+        CaseConvention::AsIs,
+        CaseConvention::UpperSnake,
+        CaseConvention::LowerSnake,
+        CaseConvention::Pascal,
+    ];
+
+    pub fn name(self) -> &'static str {
+        match self {
+            CaseConvention::AsIs => "As written",
+            CaseConvention::UpperSnake => "UPPER_SNAKE_CASE",
+            CaseConvention::LowerSnake => "lower_snake_case",
+            CaseConvention::Pascal => "PascalCase",
+        }
+    }
+
+    fn join(self, words: &[&str]) -> String {
+        match self {
+            CaseConvention::AsIs => words.join(" "),
+            CaseConvention::UpperSnake => words.join("_").to_uppercase(),
+            CaseConvention::LowerSnake => words.join("_").to_lowercase(),
+            CaseConvention::Pascal => words
+                .iter()
+                .map(|word| {
+                    let mut chars = word.chars();
+                    match chars.next() {
+                        Some(first) => {
+                            first.to_uppercase().to_string() + &chars.as_str().to_lowercase()
+                        }
+                        None => String::new(),
+                    }
+                })
+                .collect(),
+        }
+    }
+}
+
+/// Patterns of the generated names, to match the naming conventions of existing headers
+#[derive(Serialize, Deserialize, Clone, PartialEq, Debug)]
+#[serde(default)]
+pub struct NamingRules {
+    /// Prefix of the names of an object type, by the number of the type. Types without a
+    /// prefix use their readable name, e.g. "Data Mask".
+    pub prefixes: Vec<(u8, String)>,
+    pub numbering: NumberingStyle,
+    /// Minimum number of digits of the number, padded with zeros
+    pub number_width: u8,
+    pub case: CaseConvention,
+}
+
+impl Default for NamingRules {
+    fn default() -> Self {
+        NamingRules {
+            prefixes: Vec::new(),
+            numbering: NumberingStyle::OmitFirst,
+            number_width: 1,
+            case: CaseConvention::AsIs,
+        }
+    }
+}
+
+impl NamingRules {
+    /// The prefix of the names of the object type
+    pub fn prefix(&self, object_type: ObjectType) -> &str {
+        self.prefixes
+            .iter()
+            .find(|(t, _)| *t == object_type as u8)
+            .map_or(get_object_type_name(object_type), |(_, prefix)| prefix)
+    }
+
+    /// The name of the given number for an object of the type
+    pub fn format_name(&self, object_type: ObjectType, number: usize) -> String {
+        self.format_name_in_context(None, object_type, number)
+    }
+
+    /// The name of the given number for an object of the type, preceded by the name of the mask
+    /// or container showing it if given, e.g. "Main Button 2"
+    pub fn format_name_in_context(
+        &self,
+        context: Option<&str>,
+        object_type: ObjectType,
+        number: usize,
+    ) -> String {
+        let digits = format!("{:0width$}", number, width = self.number_width as usize);
+        let mut words: Vec<&str> = context
+            .unwrap_or_default()
+            .split(|c: char| c.is_whitespace() || c == '_')
+            .chain(
+                self.prefix(object_type)
+                    .split(|c: char| c.is_whitespace() || c == '_'),
+            )
+            .filter(|word| !word.is_empty())
+            .collect();
+        if number > 1 || self.numbering == NumberingStyle::Always {
+            words.push(&digits);
+        }
+        self.case.join(&words)
+    }
+}
+
+/// A smart name proposed for an object, shown for review before it is applied
+#[derive(Clone)]
+pub struct SmartRename {
+    pub object: ObjectId,
+    pub name: String,
+    pub new_name: String,
+    /// The current name looks chosen by someone, so it is kept unless opted in
+    pub intentional: bool,
+}
+
+/// Types of the objects whose name precedes the names of the objects they show, when naming in
+/// the context of the parent
+pub const CONTEXT_OBJECT_TYPES: &[ObjectType] = &[
+    ObjectType::DataMask,
+    ObjectType::AlarmMask,
+    ObjectType::WindowMask,
+    ObjectType::Container,
+];
+
+/// Whether a name looks chosen by someone rather than generated, e.g. "Engine speed" rather than
+/// "Output Number 3", "OUTPUT_NUMBER_03" or "12: OutputNumber". Only the letters are compared,
+/// so numbering and case conventions don't matter. A generated name may be preceded by the
+/// name of the mask or container showing the object, given as `context`.
+pub fn looks_intentional(
+    name: &str,
+    object_type: ObjectType,
+    context: Option<&str>,
+    rules: &NamingRules,
+) -> bool {
+    let letters = |text: &str| -> String {
+        text.chars()
+            .filter(|c| c.is_alphabetic())
+            .flat_map(char::to_lowercase)
+            .collect()
+    };
+    let type_name = get_object_type_name(object_type);
+    let generated = [
+        letters(rules.prefix(object_type)),
+        letters(type_name),
+        letters(&format!("{:?}", object_type)),
+        format!("object{}", letters(type_name)),
+    ];
+    let name = letters(name);
+    let name = context
+        .map(letters)
+        .and_then(|context| name.strip_prefix(&context).map(str::to_string))
+        .filter(|rest| !rest.is_empty())
+        .unwrap_or(name);
+    !generated.contains(&name)
+}
+
 /// Generates a smart default name for an object based on its type and context
 pub fn generate_smart_default_name(
     object_type: ObjectType,
     existing_names: &HashMap<String, ObjectType>,
+    rules: &NamingRules,
 ) -> String {
     // Count existing objects of the same type
     let same_type_count = existing_names
@@ -77,10 +264,25 @@ pub fn generate_smart_default_name(
     // Generate numbered name
     let mut counter = same_type_count + 1;
     loop {
-        let mut candidate = format!("{}", get_object_type_name(object_type));
-        if counter > 1 {
-            candidate.push_str(&format!(" {}", counter));
+        let candidate = rules.format_name(object_type, counter);
+        if !existing_names.contains_key(&candidate) {
+            return candidate;
         }
+        counter += 1;
+    }
+}
+
+/// Generates a smart name for an object shown by the mask or container named `context`, numbered
+/// within that context, e.g. "Main Button" and "Main Button 2"
+pub fn generate_smart_name_in_context(
+    object_type: ObjectType,
+    context: &str,
+    existing_names: &HashMap<String, ObjectType>,
+    rules: &NamingRules,
+) -> String {
+    let mut counter = 1;
+    loop {
+        let candidate = rules.format_name_in_context(Some(context), object_type, counter);
         if !existing_names.contains_key(&candidate) {
             return candidate;
         }
@@ -0,0 +1,312 @@
+//! Copyright 2024 - The Open-Agriculture Developers
+//! SPDX-License-Identifier: GPL-3.0-or-later
+//! Authors: Daan Steenbergen
+
+use crate::{default_object, EditorProject, ObjectInfo};
+use ag_iso_stack::object_pool::object::*;
+use ag_iso_stack::object_pool::object_attributes::*;
+use ag_iso_stack::object_pool::{NullableObjectId, ObjectId, ObjectPool, ObjectRef, ObjectType};
+
+// Colours of the standard VT palette
+const BLACK: u8 = 0;
+const WHITE: u8 = 1;
+const SILVER: u8 = 7;
+const GREY: u8 = 8;
+const RED: u8 = 12;
+
+/// A project the designer ships with, to start a new design from
+pub struct StarterTemplate {
+    pub name: &'static str,
+    pub description: &'static str,
+    build: fn(&mut PoolBuilder),
+}
+
+impl StarterTemplate {
+    /// Create a new project from the template
+    pub fn create_project(&self) -> EditorProject {
+        let mut builder = PoolBuilder::default();
+        (self.build)(&mut builder);
+        let project = EditorProject::from(builder.pool);
+        let mut object_info = project.object_info.borrow_mut();
+        for (id, name) in builder.names {
+            if let Some(object) = project.get_object(id) {
+                object_info
+                    .entry(id)
+                    .or_insert_with(|| ObjectInfo::new(object))
+                    .set_name(name);
+            }
+        }
+        drop(object_info);
+        project
+    }
+}
+
+pub static STARTER_TEMPLATES: [StarterTemplate; 3] = [
+    StarterTemplate {
+        name: "Numeric keypad",
+        description: "Data mask with a number input and an on-screen keypad of buttons",
+        build: build_numeric_keypad,
+    },
+    StarterTemplate {
+        name: "Alarm mask",
+        description: "Home data mask and a high priority alarm mask with an acknowledge key",
+        build: build_alarm_mask,
+    },
+    StarterTemplate {
+        name: "Settings page",
+        description: "Data mask with setting inputs and soft keys to go back, save and reset",
+        build: build_settings_page,
+    },
+];
+
+/// Adds named objects to a pool with consecutive IDs
+#[derive(Default)]
+struct PoolBuilder {
+    pool: ObjectPool,
+    next_id: u16,
+    names: Vec<(ObjectId, String)>,
+}
+
+impl PoolBuilder {
+    fn add(&mut self, name: &str, mut object: Object) -> ObjectId {
+        let id = ObjectId::new(self.next_id).unwrap();
+        self.next_id += 1;
+        object.mut_id().set_value(id.value()).ok();
+        self.pool.add(object);
+        self.names.push((id, name.to_string()));
+        id
+    }
+
+    /// Replace an object added before, e.g. once the objects it references are added
+    fn set(&mut self, object: Object) {
+        if let Some(existing) = self.pool.object_mut_by_id(object.id()) {
+            *existing = object;
+        }
+    }
+
+    fn font(&mut self, name: &str, size: NonProportionalFontSize, colour: u8) -> ObjectId {
+        let mut object = default_object(ObjectType::FontAttributes);
+        if let Object::FontAttributes(o) = &mut object {
+            o.font_size = FontSize::NonProportional(size);
+            o.font_colour = colour;
+        }
+        self.add(name, object)
+    }
+
+    fn text(&mut self, name: &str, value: &str, size: (u16, u16), font: ObjectId) -> ObjectId {
+        let mut object = default_object(ObjectType::OutputString);
+        if let Object::OutputString(o) = &mut object {
+            (o.width, o.height) = size;
+            o.font_attributes = font;
+            o.options.transparent = true;
+            o.justification = Alignment {
+                horizontal: HorizontalAlignment::Middle,
+                vertical: VerticalAlignment::Middle,
+            };
+            o.value = value.to_string();
+        }
+        self.add(name, object)
+    }
+
+    /// Working set showing the application name, with the given active mask
+    fn working_set(&mut self, title: &str, active_mask: ObjectId, font: ObjectId) {
+        let label = self.text("Working set label", title, (60, 60), font);
+        let mut object = default_object(ObjectType::WorkingSet);
+        if let Object::WorkingSet(o) = &mut object {
+            o.background_colour = WHITE;
+            o.active_mask = active_mask;
+            o.object_refs = refs(&[(label, 0, 0)]);
+        }
+        self.add("Working set", object);
+    }
+
+    fn soft_key(&mut self, name: &str, label: &str, key_code: u8, font: ObjectId) -> ObjectId {
+        let label = self.text(&format!("{} label", name), label, (60, 60), font);
+        let mut object = default_object(ObjectType::Key);
+        if let Object::Key(o) = &mut object {
+            o.background_colour = SILVER;
+            o.key_code = key_code;
+            o.object_refs = refs(&[(label, 0, 0)]);
+        }
+        self.add(name, object)
+    }
+
+    fn soft_key_mask(&mut self, name: &str, keys: Vec<ObjectId>) -> ObjectId {
+        let mut object = default_object(ObjectType::SoftKeyMask);
+        if let Object::SoftKeyMask(o) = &mut object {
+            o.background_colour = GREY;
+            o.objects = keys;
+        }
+        self.add(name, object)
+    }
+}
+
+fn refs(objects: &[(ObjectId, i16, i16)]) -> Vec<ObjectRef> {
+    objects
+        .iter()
+        .map(|&(id, x, y)| ObjectRef {
+            id,
+            offset: Point { x, y },
+        })
+        .collect()
+}
+
+fn build_numeric_keypad(builder: &mut PoolBuilder) {
+    let mask = builder.add("Keypad mask", default_object(ObjectType::DataMask));
+    let title_font = builder.font("Title font", NonProportionalFontSize::Px12x16, BLACK);
+    let value_font = builder.font("Value font", NonProportionalFontSize::Px16x24, BLACK);
+    let key_font = builder.font("Key font", NonProportionalFontSize::Px16x24, BLACK);
+    builder.working_set("Keypad", mask, title_font);
+
+    let title = builder.text("Title", "Enter value", (440, 30), title_font);
+    let variable = builder.add("Value variable", default_object(ObjectType::NumberVariable));
+    let mut input = default_object(ObjectType::InputNumber);
+    if let Object::InputNumber(o) = &mut input {
+        o.width = 440;
+        o.height = 40;
+        o.background_colour = WHITE;
+        o.font_attributes = value_font;
+        o.variable_reference = NullableObjectId(Some(variable));
+        o.max_value = 99999;
+        o.justification = Alignment {
+            horizontal: HorizontalAlignment::Right,
+            vertical: VerticalAlignment::Middle,
+        };
+        o.options2.enabled = true;
+    }
+    let input = builder.add("Value input", input);
+
+    let mut objects = vec![(title, 20, 10), (input, 20, 50)];
+    let labels = ["7", "8", "9", "4", "5", "6", "1", "2", "3", "C", "0", "OK"];
+    for (idx, label) in labels.iter().enumerate() {
+        let name = format!("Key {}", label);
+        let text = builder.text(&format!("{} label", name), label, (140, 80), key_font);
+        let mut button = default_object(ObjectType::Button);
+        if let Object::Button(o) = &mut button {
+            o.width = 140;
+            o.height = 80;
+            o.background_colour = SILVER;
+            o.border_colour = GREY;
+            o.key_code = idx as u8 + 1;
+            o.object_refs = refs(&[(text, 0, 0)]);
+        }
+        let button = builder.add(&name, button);
+        let (column, row) = ((idx % 3) as i16, (idx / 3) as i16);
+        objects.push((button, 20 + column * 150, 100 + row * 90));
+    }
+
+    let mut data_mask = default_object(ObjectType::DataMask);
+    if let Object::DataMask(o) = &mut data_mask {
+        o.id = mask;
+        o.background_colour = WHITE;
+        o.object_refs = refs(&objects);
+    }
+    builder.set(data_mask);
+}
+
+fn build_alarm_mask(builder: &mut PoolBuilder) {
+    let home = builder.add("Home mask", default_object(ObjectType::DataMask));
+    let title_font = builder.font("Title font", NonProportionalFontSize::Px12x16, BLACK);
+    let alarm_font = builder.font("Alarm font", NonProportionalFontSize::Px24x32, WHITE);
+    let message_font = builder.font("Message font", NonProportionalFontSize::Px12x16, WHITE);
+    builder.working_set("Alarm", home, title_font);
+
+    let home_title = builder.text("Home title", "Home", (440, 30), title_font);
+    let mut data_mask = default_object(ObjectType::DataMask);
+    if let Object::DataMask(o) = &mut data_mask {
+        o.id = home;
+        o.background_colour = WHITE;
+        o.object_refs = refs(&[(home_title, 20, 10)]);
+    }
+    builder.set(data_mask);
+
+    let acknowledge = builder.soft_key("Acknowledge key", "ACK", 0, title_font);
+    let soft_keys = builder.soft_key_mask("Alarm soft keys", vec![acknowledge]);
+    let alarm_title = builder.text("Alarm title", "ALARM", (440, 60), alarm_font);
+    let message = builder.text(
+        "Alarm message",
+        "Describe the alarm and what to do",
+        (440, 80),
+        message_font,
+    );
+    let mut alarm_mask = default_object(ObjectType::AlarmMask);
+    if let Object::AlarmMask(o) = &mut alarm_mask {
+        o.background_colour = RED;
+        o.soft_key_mask = NullableObjectId(Some(soft_keys));
+        // Highest priority, with the acoustic signal of the highest priority
+        o.priority = 0;
+        o.acoustic_signal = 0;
+        o.object_refs = refs(&[(alarm_title, 20, 60), (message, 20, 160)]);
+    }
+    builder.add("Alarm mask", alarm_mask);
+}
+
+fn build_settings_page(builder: &mut PoolBuilder) {
+    let mask = builder.add("Settings mask", default_object(ObjectType::DataMask));
+    let title_font = builder.font("Title font", NonProportionalFontSize::Px12x16, BLACK);
+    let label_font = builder.font("Label font", NonProportionalFontSize::Px8x12, BLACK);
+    builder.working_set("Settings", mask, title_font);
+
+    let title = builder.text("Title", "Settings", (440, 30), title_font);
+    let mut objects = vec![(title, 20, 10)];
+    for (row, setting) in ["Working width (m)", "Target rate", "Section count"]
+        .iter()
+        .enumerate()
+    {
+        let y = 60 + row as i16 * 50;
+        let label = builder.text(
+            &format!("{} label", setting),
+            setting,
+            (240, 40),
+            label_font,
+        );
+        let variable = builder.add(
+            &format!("{} variable", setting),
+            default_object(ObjectType::NumberVariable),
+        );
+        let mut input = default_object(ObjectType::InputNumber);
+        if let Object::InputNumber(o) = &mut input {
+            o.width = 180;
+            o.height = 40;
+            o.background_colour = WHITE;
+            o.font_attributes = title_font;
+            o.variable_reference = NullableObjectId(Some(variable));
+            o.max_value = 9999;
+            o.justification = Alignment {
+                horizontal: HorizontalAlignment::Right,
+                vertical: VerticalAlignment::Middle,
+            };
+            o.options2.enabled = true;
+        }
+        let input = builder.add(&format!("{} input", setting), input);
+        objects.push((label, 20, y));
+        objects.push((input, 280, y));
+    }
+
+    let mut enabled = default_object(ObjectType::InputBoolean);
+    if let Object::InputBoolean(o) = &mut enabled {
+        o.width = 40;
+        o.background_colour = WHITE;
+        o.foreground_colour = title_font;
+    }
+    let enabled_label = builder.text("Auto mode label", "Auto mode", (240, 40), label_font);
+    let enabled = builder.add("Auto mode input", enabled);
+    objects.push((enabled_label, 20, 210));
+    objects.push((enabled, 280, 210));
+
+    let keys = vec![
+        builder.soft_key("Back key", "Back", 1, label_font),
+        builder.soft_key("Save key", "Save", 2, label_font),
+        builder.soft_key("Reset key", "Reset", 3, label_font),
+    ];
+    let soft_keys = builder.soft_key_mask("Settings soft keys", keys);
+
+    let mut data_mask = default_object(ObjectType::DataMask);
+    if let Object::DataMask(o) = &mut data_mask {
+        o.id = mask;
+        o.background_colour = WHITE;
+        o.soft_key_mask = NullableObjectId(Some(soft_keys));
+        o.object_refs = refs(&objects);
+    }
+    builder.set(data_mask);
+}
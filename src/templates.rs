@@ -0,0 +1,152 @@
+//! Copyright 2024 - The Open-Agriculture Developers
+//! SPDX-License-Identifier: GPL-3.0-or-later
+//! Authors: Daan Steenbergen
+
+use serde::{Deserialize, Serialize};
+
+/// A named group of objects that can be inserted into any project.
+/// The objects are stored in the clipboard format, so inserting a template works like pasting.
+#[derive(Serialize, Deserialize, Clone)]
+pub struct Template {
+    pub name: String,
+    /// Type of the object the template was saved from, shown in the library
+    pub root_type: String,
+    pub object_count: usize,
+    /// The objects as made by [`crate::EditorProject::copy_objects`], the root object first
+    pub objects: String,
+}
+
+impl Template {
+    /// Name of the file or storage entry of the template
+    fn file_name(&self) -> String {
+        file_name(&self.name)
+    }
+}
+
+fn file_name(name: &str) -> String {
+    let stem: String = name
+        .chars()
+        .map(|c| {
+            if c.is_alphanumeric() || c == '-' || c == '_' {
+                c
+            } else {
+                '_'
+            }
+        })
+        .collect();
+    format!("{}.json", stem)
+}
+
+/// All templates in the library, sorted by name
+pub fn list_templates() -> Vec<Template> {
+    let mut templates: Vec<Template> = storage::list()
+        .iter()
+        .filter(|name| name.ends_with(".json"))
+        .filter_map(|name| {
+            let data = storage::read(name)?;
+            match serde_json::from_str(&data) {
+                Ok(template) => Some(template),
+                Err(e) => {
+                    log::error!("Failed to read template {}: {}", name, e);
+                    None
+                }
+            }
+        })
+        .collect();
+    templates.sort_by_key(|template| template.name.to_lowercase());
+    templates
+}
+
+/// Add a template to the library, replacing a template with the same name
+pub fn save_template(template: &Template) -> Result<(), String> {
+    let data = serde_json::to_string_pretty(template).map_err(|e| e.to_string())?;
+    storage::write(&template.file_name(), &data)
+}
+
+pub fn delete_template(name: &str) -> Result<(), String> {
+    storage::remove(&file_name(name))
+}
+
+/// Whether the library has a template with the name, saving would replace it
+pub fn template_exists(name: &str) -> bool {
+    storage::read(&file_name(name)).is_some()
+}
+
+/// Templates are files in the templates directory on native targets
+#[cfg(not(target_arch = "wasm32"))]
+mod storage {
+    use std::path::PathBuf;
+
+    fn dir() -> PathBuf {
+        crate::settings::app_data_dir()
+            .unwrap_or_else(|| std::env::temp_dir().join("AgIsoTerminalDesigner"))
+            .join("templates")
+    }
+
+    pub fn write(file_name: &str, data: &str) -> Result<(), String> {
+        let dir = dir();
+        std::fs::create_dir_all(&dir)
+            .and_then(|_| std::fs::write(dir.join(file_name), data))
+            .map_err(|e| e.to_string())
+    }
+
+    pub fn read(file_name: &str) -> Option<String> {
+        std::fs::read_to_string(dir().join(file_name)).ok()
+    }
+
+    pub fn remove(file_name: &str) -> Result<(), String> {
+        std::fs::remove_file(dir().join(file_name)).map_err(|e| e.to_string())
+    }
+
+    pub fn list() -> Vec<String> {
+        let Ok(entries) = std::fs::read_dir(dir()) else {
+            return Vec::new();
+        };
+        entries
+            .filter_map(|entry| entry.ok()?.file_name().into_string().ok())
+            .collect()
+    }
+}
+
+/// Templates are kept in the local storage of the browser on the web
+#[cfg(target_arch = "wasm32")]
+mod storage {
+    const KEY_PREFIX: &str = "template/";
+
+    fn local_storage() -> Option<web_sys::Storage> {
+        web_sys::window()?.local_storage().ok()?
+    }
+
+    fn key(file_name: &str) -> String {
+        format!("{}{}", KEY_PREFIX, file_name)
+    }
+
+    pub fn write(file_name: &str, data: &str) -> Result<(), String> {
+        local_storage()
+            .ok_or_else(|| "No local storage".to_string())?
+            .set_item(&key(file_name), data)
+            .map_err(|e| format!("{:?}", e))
+    }
+
+    pub fn read(file_name: &str) -> Option<String> {
+        local_storage()?.get_item(&key(file_name)).ok()?
+    }
+
+    pub fn remove(file_name: &str) -> Result<(), String> {
+        local_storage()
+            .ok_or_else(|| "No local storage".to_string())?
+            .remove_item(&key(file_name))
+            .map_err(|e| format!("{:?}", e))
+    }
+
+    pub fn list() -> Vec<String> {
+        let Some(storage) = local_storage() else {
+            return Vec::new();
+        };
+        let length = storage.length().unwrap_or(0);
+        (0..length)
+            .filter_map(|idx| storage.key(idx).ok()?)
+            .filter_map(|key| key.strip_prefix(KEY_PREFIX).map(str::to_string))
+            .collect()
+    }
+}
@@ -0,0 +1,95 @@
+//! Copyright 2024 - The Open-Agriculture Developers
+//! SPDX-License-Identifier: GPL-3.0-or-later
+//! Authors: Daan Steenbergen
+
+use ag_iso_stack::object_pool::{ObjectId, ObjectPool, ObjectType};
+
+/// An object of a type the designer can't fully work with yet
+#[derive(Clone)]
+pub struct UnsupportedObject {
+    pub id: ObjectId,
+    pub object_type: ObjectType,
+    /// What the designer can't do with the object
+    pub limitation: &'static str,
+}
+
+/// Describe what the designer can't do with objects of a type, `None` if the type is supported
+pub fn limitation(object_type: ObjectType) -> Option<&'static str> {
+    let editable = !matches!(
+        object_type,
+        ObjectType::KeyGroup
+            | ObjectType::ColourMap
+            | ObjectType::ObjectLabelReferenceList
+            | ObjectType::Animation
+            | ObjectType::GraphicData
+            | ObjectType::WorkingSetSpecialControls
+            | ObjectType::ScaledGraphic
+    );
+    let rendered = !matches!(
+        object_type,
+        ObjectType::InputString
+            | ObjectType::InputList
+            | ObjectType::OutputList
+            | ObjectType::OutputEllipse
+            | ObjectType::OutputPolygon
+            | ObjectType::OutputMeter
+            | ObjectType::OutputLinearBarGraph
+            | ObjectType::OutputArchedBarGraph
+            | ObjectType::AuxiliaryFunctionType2
+            | ObjectType::AuxiliaryInputType2
+            | ObjectType::AuxiliaryControlDesignatorType2
+            | ObjectType::Animation
+            | ObjectType::ScaledGraphic
+    );
+    match (editable, rendered) {
+        (true, true) => None,
+        (false, true) => Some("Can't be edited"),
+        (true, false) => Some("Not shown on the canvas"),
+        (false, false) => Some("Can't be edited or shown on the canvas"),
+    }
+}
+
+/// Find the objects of the pool the designer can't edit or render, in pool order
+pub fn find_unsupported_objects(pool: &ObjectPool) -> Vec<UnsupportedObject> {
+    pool.objects()
+        .iter()
+        .filter_map(|object| {
+            limitation(object.object_type()).map(|limitation| UnsupportedObject {
+                id: object.id(),
+                object_type: object.object_type(),
+                limitation,
+            })
+        })
+        .collect()
+}
+
+/// Plain text report of the unsupported objects, to paste into an issue
+pub fn unsupported_objects_report(objects: &[UnsupportedObject]) -> String {
+    let mut types: Vec<(ObjectType, &'static str, usize)> = Vec::new();
+    for object in objects {
+        match types.iter_mut().find(|(t, _, _)| *t == object.object_type) {
+            Some((_, _, count)) => *count += 1,
+            None => types.push((object.object_type, object.limitation, 1)),
+        }
+    }
+
+    let mut report = format!(
+        "AgIsoTerminalDesigner {}: unsupported objects in the pool\n\n",
+        env!("CARGO_PKG_VERSION")
+    );
+    for (object_type, limitation, count) in types {
+        let ids: Vec<String> = objects
+            .iter()
+            .filter(|object| object.object_type == object_type)
+            .map(|object| object.id.value().to_string())
+            .collect();
+        report.push_str(&format!(
+            "- {:?} ({}): {}. Objects: {}\n",
+            object_type,
+            count,
+            limitation,
+            ids.join(", ")
+        ));
+    }
+    report
+}
@@ -0,0 +1,66 @@
+//! Copyright 2024 - The Open-Agriculture Developers
+//! SPDX-License-Identifier: GPL-3.0-or-later
+//! Authors: Daan Steenbergen
+
+use ag_iso_stack::object_pool::vt_version::VtVersion;
+use ag_iso_stack::object_pool::{ObjectPool, ObjectType};
+
+/// Lowest VT version the designer makes pools for
+pub const BASE_VT_VERSION: u8 = 3;
+
+/// Latest VT version the designer knows the objects and attributes of
+pub const LATEST_VT_VERSION: u8 = 6;
+
+/// The VT version with the given number, numbers outside of the known versions are limited to
+/// version 2 and the latest version
+pub fn vt_version(number: u8) -> VtVersion {
+    match number {
+        0..=2 => VtVersion::Version2,
+        3 => VtVersion::Version3,
+        4 => VtVersion::Version4,
+        5 => VtVersion::Version5,
+        _ => VtVersion::Version6,
+    }
+}
+
+pub fn vt_version_number(version: VtVersion) -> u8 {
+    match version {
+        VtVersion::Version3 => 3,
+        VtVersion::Version4 => 4,
+        VtVersion::Version5 => 5,
+        VtVersion::Version6 => 6,
+        _ => 2,
+    }
+}
+
+/// The VT version that introduced the object type, object types of version 3 and before are
+/// reported as version 3
+pub fn object_type_vt_version(object_type: ObjectType) -> u8 {
+    match object_type {
+        ObjectType::WindowMask
+        | ObjectType::KeyGroup
+        | ObjectType::GraphicsContext
+        | ObjectType::OutputList
+        | ObjectType::ExtendedInputAttributes
+        | ObjectType::ColourMap
+        | ObjectType::ObjectLabelReferenceList => 4,
+        ObjectType::ExternalObjectDefinition
+        | ObjectType::ExternalReferenceName
+        | ObjectType::ExternalObjectPointer
+        | ObjectType::Animation => 5,
+        ObjectType::ColourPalette
+        | ObjectType::GraphicData
+        | ObjectType::WorkingSetSpecialControls
+        | ObjectType::ScaledGraphic => 6,
+        _ => BASE_VT_VERSION,
+    }
+}
+
+/// The lowest VT version that supports all object types of the pool
+pub fn required_vt_version(pool: &ObjectPool) -> u8 {
+    pool.objects()
+        .iter()
+        .map(|object| object_type_vt_version(object.object_type()))
+        .max()
+        .unwrap_or(BASE_VT_VERSION)
+}